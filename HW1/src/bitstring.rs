@@ -73,22 +73,28 @@ fn crossover(
 fn parent_selection(
     population: &Vec<String>,
     fitness_fn: &impl Fitness,
+    num_dims: usize,
     rng: &mut ChaCha8Rng,
 ) -> (String, String) {
     // Determine the "wheel size"
     let total_fitness: f64 = population
         .iter()
-        .map(|member| fitness_fn.fitness(member))
+        .map(|member| fitness_fn.fitness_bitstring(member, num_dims))
         .sum();
 
     // Helper function to select one parent
-    fn select_parent(population: &Vec<String>, fitness_fn: &impl Fitness, mut pick: f64) -> String {
+    fn select_parent(
+        population: &Vec<String>,
+        fitness_fn: &impl Fitness,
+        num_dims: usize,
+        mut pick: f64,
+    ) -> String {
         let mut parent = String::new();
 
         // Iterate through the wheel until we find the slot that matches the pick
         for member in population {
             // Get the fitness of the current member
-            let fitness = fitness_fn.fitness(member);
+            let fitness = fitness_fn.fitness_bitstring(member, num_dims);
 
             // If the pick is less than or equal to the fitness parent is found
             if pick <= fitness {
@@ -104,18 +110,22 @@ fn parent_selection(
 
     // Select first parent on the wheel
     let pick1 = rng.random_range(0.0..total_fitness);
-    let parent1 = select_parent(population, fitness_fn, pick1);
+    let parent1 = select_parent(population, fitness_fn, num_dims, pick1);
 
     // Select second parent
     let pick2 = rng.random_range(0.0..total_fitness);
-    let parent2 = select_parent(population, fitness_fn, pick2);
+    let parent2 = select_parent(population, fitness_fn, num_dims, pick2);
 
     (parent1, parent2)
 }
 
 // Calculate population statistics
-fn calculate_stats(population: &[String], fitness_fn: &impl Fitness) -> (f64, f64, f64) {
-    let fitnesses: Vec<f64> = population.iter().map(|m| fitness_fn.fitness(m)).collect();
+fn calculate_stats(
+    population: &[String],
+    fitness_fn: &impl Fitness,
+    num_dims: usize,
+) -> (f64, f64, f64) {
+    let fitnesses: Vec<f64> = population.iter().map(|m| fitness_fn.fitness_bitstring(m, num_dims)).collect();
 
     let max_fitness = fitnesses.iter().cloned().fold(0.0, f64::max);
     let avg_fitness = fitnesses.iter().sum::<f64>() / population.len() as f64;
@@ -140,8 +150,13 @@ fn calculate_stats(population: &[String], fitness_fn: &impl Fitness) -> (f64, f6
 }
 
 // Check for convergence (pct identical individuals exceeds threshold or avg fitness exceeds threshold)
-fn check_convergence(population: &[String], fitness_fn: &impl Fitness, threshold: f64) -> bool {
-    let (_, avg_fitness, pct_identical) = calculate_stats(population, fitness_fn);
+fn check_convergence(
+    population: &[String],
+    fitness_fn: &impl Fitness,
+    num_dims: usize,
+    threshold: f64,
+) -> bool {
+    let (_, avg_fitness, pct_identical) = calculate_stats(population, fitness_fn, num_dims);
     if avg_fitness >= threshold {
         return true;
     }
@@ -154,6 +169,7 @@ pub fn sga(
     fitness_fn: &impl Fitness,
     pop_size: usize,
     mem_size: usize,
+    num_dims: usize,
     mutation_rate: f64,
     crossover_rate: f64,
     max_iters: usize,
@@ -168,11 +184,11 @@ pub fn sga(
 
     for gen_number in 0..max_iters {
         // Calculate and print statistics
-        let (max_fitness, avg_fitness, pct_identical) = calculate_stats(&population, fitness_fn);
+        let (max_fitness, avg_fitness, pct_identical) = calculate_stats(&population, fitness_fn, num_dims);
         println!("{gen_number} {max_fitness:.2} {avg_fitness:.2} {pct_identical:.2}");
 
         // Check for convergence
-        if check_convergence(&population, fitness_fn, convergence_threshold) {
+        if check_convergence(&population, fitness_fn, num_dims, convergence_threshold) {
             println!("Converged at generation {gen_number}");
             return population;
         }
@@ -183,7 +199,7 @@ pub fn sga(
         // Generate offspring pairs until we have a full new population
         while new_population.len() < pop_size {
             // Select parents
-            let (parent1, parent2) = parent_selection(&population, fitness_fn, rng);
+            let (parent1, parent2) = parent_selection(&population, fitness_fn, num_dims, rng);
 
             // Crossover
             let (mut child1, mut child2) = crossover(&parent1, &parent2, crossover_rate, rng);
@@ -1,4 +1,26 @@
-// Define a trait for fitness functions for reusability
-pub trait Fitness {
-    fn fitness(&self, bitstring: &str) -> f64;
+use crate::decoder::{BinaryDecoder, Decoder};
+
+// Define a trait for objective functions for reusability. Every problem is an objective over a
+// real-valued phenotype (higher is better), whatever representation the phenotype comes from.
+pub trait Objective {
+    fn fitness(&self, member: &[f64]) -> f64;
+
+    // Range the phenotype's values lie in
+    fn bounds(&self) -> (f64, f64);
+}
+
+// An objective as the GA sees it: composed with the decoder bitstrings go through on their way
+// to it. Problems decode evenly into their bounds unless they pick another decoder.
+pub trait Fitness: Objective {
+    fn decoder(&self) -> impl Decoder {
+        BinaryDecoder { bounds: self.bounds() }
+    }
+
+    fn decode_bitstring(&self, bitstring: &str, num_dims: usize) -> Vec<f64> {
+        self.decoder().decode_bitstring(bitstring, num_dims)
+    }
+
+    fn fitness_bitstring(&self, bitstring: &str, num_dims: usize) -> f64 {
+        self.fitness(&self.decode_bitstring(bitstring, num_dims))
+    }
 }
@@ -1,9 +1,11 @@
 mod bitstring;
+mod decoder;
 mod fitness;
 mod maxones;
 mod rosenbrock;
 
 use crate::bitstring::sga;
+use crate::fitness::Fitness;
 use crate::maxones::MaxOnes;
 use crate::rosenbrock::Rosenbrock;
 
@@ -16,7 +18,7 @@ fn main() {
 
     // Test the algorithms
     print!("max_ones ");
-    sga(&MaxOnes, 100, 32, 0.01, 0.5, 1e6 as usize, 0.85, &mut rng);
+    sga(&MaxOnes, 100, 32, 32, 0.01, 0.5, 1e6 as usize, 0.85, &mut rng);
 
     // Reseed so running order does not matter
     rng = ChaCha8Rng::seed_from_u64(5132);
@@ -27,6 +29,7 @@ fn main() {
         &rosenbrock,
         500,
         48,
+        2,
         0.00125,
         0.25,
         1e6 as usize,
@@ -37,12 +40,12 @@ fn main() {
     // Compute and print average x and y values of final population
     let avg_x = final_rosenbrock_pop
         .iter()
-        .map(|member| rosenbrock.decode(member).0)
+        .map(|member| rosenbrock.decode_bitstring(member, 2)[0])
         .sum::<f64>()
         / final_rosenbrock_pop.len() as f64;
     let avg_y = final_rosenbrock_pop
         .iter()
-        .map(|member| rosenbrock.decode(member).0)
+        .map(|member| rosenbrock.decode_bitstring(member, 2)[1])
         .sum::<f64>()
         / final_rosenbrock_pop.len() as f64;
     println!("Average member: ({avg_x:.4}, {avg_y:.4})");
@@ -1,10 +1,22 @@
-use crate::fitness::Fitness;
+use crate::decoder::{Decoder, PerBit};
+use crate::fitness::{Fitness, Objective};
 
 // MaxOnes fitness implementation
 pub struct MaxOnes;
 
+impl Objective for MaxOnes {
+    // Fraction of genes that are "on" (>= 0.5)
+    fn fitness(&self, member: &[f64]) -> f64 {
+        member.iter().filter(|&&gene| gene >= 0.5).count() as f64 / member.len() as f64
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+}
+
 impl Fitness for MaxOnes {
-    fn fitness(&self, bitstring: &str) -> f64 {
-        bitstring.chars().filter(|c| *c == '1').count() as f64 / bitstring.len() as f64
+    fn decoder(&self) -> impl Decoder {
+        PerBit
     }
 }
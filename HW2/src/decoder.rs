@@ -0,0 +1,44 @@
+// Maps a bitstring genome to the real-valued phenotype an Objective is evaluated on, so one
+// objective serves any bitstring representation
+pub trait Decoder {
+    fn decode_bitstring(&self, bitstring: &str, num_dims: usize) -> Vec<f64>;
+}
+
+// Every bit is a dimension of its own and decodes to 0.0 or 1.0, for selection problems such
+// as MaxOnes. The dimension count is ignored.
+pub struct PerBit;
+
+impl Decoder for PerBit {
+    fn decode_bitstring(&self, bitstring: &str, _num_dims: usize) -> Vec<f64> {
+        bitstring
+            .chars()
+            .map(|c| if c == '1' { 1.0 } else { 0.0 })
+            .collect()
+    }
+}
+
+// Splits a bitstring into num_dims equal segments and maps each onto evenly spaced values in
+// bounds
+pub struct BinaryDecoder {
+    pub bounds: (f64, f64),
+}
+
+impl Decoder for BinaryDecoder {
+    fn decode_bitstring(&self, bitstring: &str, num_dims: usize) -> Vec<f64> {
+        if num_dims == 0 || !bitstring.len().is_multiple_of(num_dims) {
+            panic!("Bitstring length must be a multiple of the {num_dims} dimensions");
+        }
+        let bits_per_dim = bitstring.len() / num_dims;
+        let (low, high) = self.bounds;
+        let max_val = 2_f64.powi(bits_per_dim as i32) - 1.0;
+
+        // Convert each segment (base 2) to an integer, then scale it into the bounds
+        (0..num_dims)
+            .map(|dim| {
+                let bits = &bitstring[dim * bits_per_dim..(dim + 1) * bits_per_dim];
+                let value = i64::from_str_radix(bits, 2).unwrap() as f64;
+                low + value / max_val * (high - low)
+            })
+            .collect()
+    }
+}
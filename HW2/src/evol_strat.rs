@@ -1,4 +1,4 @@
-use crate::fitness::Fitness;
+use crate::fitness::Objective;
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
 
@@ -24,19 +24,19 @@ fn init_population(
 
 // Ignore warning
 #[allow(clippy::too_many_arguments)]
-pub fn evolution_strategy<F: Fitness>(
+// Genes start uniformly within the objective's bounds
+pub fn evolution_strategy<F: Objective>(
     fitness_fn: &F,
     mu: usize,
     lambda: usize,
     mem_size: usize,
-    mem_range: (f64, f64),
     sigma: f64,
     tau: f64,
     max_gens: usize,
     rng: &mut ChaCha8Rng,
 ) -> Vec<Vec<f64>> {
     // Initialize population
-    let mut population = init_population(mu, mem_size, mem_range, sigma, rng);
+    let mut population = init_population(mu, mem_size, fitness_fn.bounds(), sigma, rng);
     let mut cumulative_evals = 0;
 
     for generation_number in 1..=max_gens {
@@ -1,11 +1,17 @@
 mod bitstring;
+mod decoder;
 mod evol_strat;
 mod fitness;
 mod himmelblau;
+// HW1's problems, which run on the same GA and ES as Himmelblau but aren't part of this study
+#[allow(dead_code)]
+mod maxones;
+#[allow(dead_code)]
+mod rosenbrock;
 
 use crate::bitstring::sga;
 use crate::evol_strat::evolution_strategy;
-use crate::fitness::Fitness;
+use crate::fitness::{Fitness, Objective};
 use crate::himmelblau::Himmelblau;
 
 use rand::prelude::*;
@@ -18,8 +24,7 @@ fn main() {
         &Himmelblau,
         15,            // mu: parent population size
         100,           // lambda: offspring population size
-        2,             // Member size
-        (-10.0, 10.0), // Gene range
+        2,             // Member size, genes start within Himmelblau's bounds
         0.1,           // Initial mutation standard deviation
         0.1,           // Tau for sigma adaptation
         1000,          // Max generations
@@ -32,6 +37,7 @@ fn main() {
         &Himmelblau,
         100,          //population size (mu = lambda)
         20,           // member size (in bits)
+        2,            // dimensions the bits decode to
         0.00125,      // mutation rate
         0.25,         // crossover rate
         1e6 as usize, // max evaluations
@@ -46,8 +52,9 @@ fn main() {
     }
 
     for member in final_ea_pop {
-        let fitness = Himmelblau.fitness_bitstring(&member);
-        let (x, y) = Himmelblau.decode_bitstring(&member);
+        let fitness = Himmelblau.fitness_bitstring(&member, 2);
+        let decoded = Himmelblau.decode_bitstring(&member, 2);
+        let (x, y) = (decoded[0], decoded[1]);
         println!("EA Member: ({x:.4}, {y:.4}), Fitness: {fitness}");
     }
 }
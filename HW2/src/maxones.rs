@@ -1,19 +1,21 @@
-use crate::fitness::Fitness;
+use crate::decoder::{Decoder, PerBit};
+use crate::fitness::{Fitness, Objective};
 
 pub struct MaxOnes;
 
-impl Fitness for MaxOnes {
-    fn fitness(&self, _member: &[f64]) -> f64 {
-        // Not used for bitstring
-        0.0
+impl Objective for MaxOnes {
+    // Fraction of genes that are "on" (>= 0.5), so real-valued members work too
+    fn fitness(&self, member: &[f64]) -> f64 {
+        member.iter().filter(|&&gene| gene >= 0.5).count() as f64 / member.len() as f64
     }
 
-    fn fitness_bitstring(&self, bitstring: &str) -> f64 {
-        let ones: f64 = bitstring.chars().filter(|&c| c == '1').count() as f64;
-        ones / bitstring.len() as f64
+    fn bounds(&self) -> (f64, f64) {
+        (0.0, 1.0)
     }
+}
 
-    fn decode_bitstring(&self, _bitstring: &str) -> (f64, f64) {
-        (0.0, 0.0)
+impl Fitness for MaxOnes {
+    fn decoder(&self) -> impl Decoder {
+        PerBit
     }
-}
\ No newline at end of file
+}
@@ -1,8 +1,8 @@
-use crate::fitness::Fitness;
+use crate::fitness::{Fitness, Objective};
 
 pub struct Rosenbrock;
 
-impl Fitness for Rosenbrock {
+impl Objective for Rosenbrock {
     fn fitness(&self, member: &[f64]) -> f64 {
         let x = member[0];
         let y = member[1];
@@ -12,29 +12,9 @@ impl Fitness for Rosenbrock {
         1.0 / (1.0 + rosenbrock_value)
     }
 
-    fn fitness_bitstring(&self, bitstring: &str) -> f64 {
-        let (x, y) = self.decode_bitstring(bitstring);
-        // Use the standard Rosenbrock function formula
-        let rosenbrock_value = (1.0 - x).powi(2) + 100.0 * (y - x.powi(2)).powi(2);
-        // Convert to fitness in the range (0, 1], higher is better, maximum at global optima
-        1.0 / (1.0 + rosenbrock_value)
+    fn bounds(&self) -> (f64, f64) {
+        (-2.0, 2.0)
     }
+}
 
-    fn decode_bitstring(&self, bitstring: &str) -> (f64, f64) {
-        if bitstring.len() % 2 != 0 {
-            panic!("Bitstring length must be even for Rosenbrock decoding");
-        }
-        let x_bits = &bitstring[..bitstring.len() / 2];
-        let y_bits = &bitstring[bitstring.len() / 2..];
-
-        // Convert binary strings (base 2) to integers, then to floats
-        let mut x = i64::from_str_radix(x_bits, 2).unwrap() as f64;
-        let mut y = i64::from_str_radix(y_bits, 2).unwrap() as f64;
-
-        let max_val = 2_f64.powi(x_bits.len() as i32) - 1.0;
-        x = (x / max_val) * 4.0 - 2.0; // Scale to [-2, 2]
-        y = (y / max_val) * 4.0 - 2.0; // Scale to [-2, 2]
-
-        (x, y)
-    }
-}
\ No newline at end of file
+impl Fitness for Rosenbrock {}
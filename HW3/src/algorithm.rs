@@ -0,0 +1,36 @@
+use crate::stopping::StopReason;
+use rand::Rng;
+
+// A generational algorithm advanced one generation at a time, so callers such as the tuning
+// runner can drive the real GA and ES loops under their own budget and timeout control
+pub trait Algorithm {
+    type Individual;
+
+    // Runs one generation, returning the reason the run stopped if it did
+    fn step<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<StopReason>;
+
+    // Generations completed so far
+    fn generation(&self) -> usize;
+
+    // Best fitness seen so far
+    fn best_fitness(&self) -> f64;
+
+    // Fitness evaluations used so far
+    fn evaluations(&self) -> usize;
+
+    // Prints the end-of-run summary and hands back the final population
+    fn finish(self, reason: StopReason) -> Vec<Self::Individual>;
+
+    // Steps until the algorithm stops
+    fn run<R: Rng + ?Sized>(mut self, rng: &mut R) -> Vec<Self::Individual>
+    where
+        Self: Sized,
+    {
+        let reason = loop {
+            if let Some(reason) = self.step(rng) {
+                break reason;
+            }
+        };
+        self.finish(reason)
+    }
+}
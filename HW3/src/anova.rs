@@ -0,0 +1,402 @@
+use crate::parameter_tuning::TuningResult;
+use crate::statistics;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+// One source of variation in an ANOVA table
+#[derive(Debug, Clone, Serialize)]
+pub struct Effect {
+    // A parameter name, or "a x b" for the interaction of two
+    pub source: String,
+    pub df: usize,
+    pub sum_sq: f64,
+    pub mean_sq: f64,
+    // None without residual degrees of freedom to test against
+    pub f: Option<f64>,
+    pub p: Option<f64>,
+    // Share of the total sum of squares
+    pub eta_squared: f64,
+}
+
+// Factorial ANOVA of one response over the parameters a session varied in a factorial way
+#[derive(Debug, Clone, Serialize)]
+pub struct Anova {
+    // "score" or "max_fitness"
+    pub response: String,
+    pub runs: usize,
+    // Main effects, then two-way interactions, each by decreasing sum of squares
+    pub effects: Vec<Effect>,
+    // Variation between runs of a combination, plus any interactions left out of the model
+    pub residual_df: usize,
+    pub residual_sum_sq: f64,
+    pub total_sum_sq: f64,
+    // Each factor's parameters at its level of the best marginal mean response
+    pub best_levels: Vec<(String, f64)>,
+}
+
+// Mean response at one level of a factor, over all runs at that level whatever the other
+// parameters were, with a 95% t interval of that mean
+#[derive(Debug, Clone, Serialize)]
+pub struct LevelMean {
+    pub level: f64,
+    pub runs: usize,
+    pub mean: f64,
+    // Both equal to the mean for a single run
+    pub ci_low: f64,
+    pub ci_high: f64,
+}
+
+// Marginal means of one response across the levels of one factor
+#[derive(Debug, Clone, Serialize)]
+pub struct MainEffect {
+    pub parameter: String,
+    pub response: String,
+    // By increasing level
+    pub levels: Vec<LevelMean>,
+}
+
+impl MainEffect {
+    // Difference between the best and the worst level's mean
+    pub fn range(&self) -> f64 {
+        let means = self.levels.iter().map(|level| level.mean);
+        means.clone().fold(f64::NEG_INFINITY, f64::max) - means.fold(f64::INFINITY, f64::min)
+    }
+}
+
+// Mean response in every cell of two factors' levels, marginalized over any others
+#[derive(Debug, Clone, Serialize)]
+pub struct Heatmap {
+    pub response: String,
+    pub row: String,
+    pub column: String,
+    pub row_levels: Vec<f64>,
+    pub column_levels: Vec<f64>,
+    // means[i][j] at row level i and column level j, None for a cell without runs
+    pub means: Vec<Vec<Option<f64>>>,
+}
+
+// A factor's level index in every run, and its levels
+struct Factor {
+    name: String,
+    levels: Vec<f64>,
+    indices: Vec<usize>,
+    // The parameters the factor stands for, its own first, then any that restate it
+    aliased: Vec<String>,
+}
+
+// Parameters that act as factors, those varied in a factorial way. In a sampled design every
+// combination has its own value of each parameter, so such parameters only count as a
+// factor when the session varied nothing else, as a one-way sweep. A parameter that only
+// restates an earlier one (mu following lambda, say) is left out, as its effect would be
+// the same. When every combination is a level of the one factor left and other parameters
+// restated it, as in a sampled design, the effect belongs to the combination rather than to
+// any one of them, so the factor is named after all of them.
+fn factors(results: &[&TuningResult]) -> Vec<Factor> {
+    let names: BTreeSet<&String> = results.iter().flat_map(|r| r.parameters.keys()).collect();
+    let combinations: BTreeSet<String> =
+        results.iter().map(|r| format!("{:?}", BTreeMap::from_iter(&r.parameters))).collect();
+    let mut factors: Vec<Factor> = Vec::new();
+    for name in names {
+        let values: Vec<f64> =
+            results.iter().map(|r| r.parameters.get(name).copied().unwrap_or(f64::NAN)).collect();
+        let mut levels: Vec<f64> = values.clone();
+        levels.sort_by(f64::total_cmp);
+        levels.dedup_by(|a, b| a.total_cmp(b).is_eq());
+        if levels.len() < 2 {
+            continue;
+        }
+        let indices: Vec<usize> = values
+            .iter()
+            .map(|value| levels.iter().position(|level| level.total_cmp(value).is_eq()))
+            .map(Option::unwrap_or_default)
+            .collect();
+        let restated = factors.iter_mut().find(|factor| {
+            factor.levels.len() == levels.len() && same_partition(&factor.indices, &indices)
+        });
+        match restated {
+            Some(factor) => factor.aliased.push(name.clone()),
+            None => factors.push(Factor {
+                name: name.clone(),
+                levels,
+                indices,
+                aliased: vec![name.clone()],
+            }),
+        }
+    }
+    if factors.len() > 1 {
+        factors.retain(|factor| factor.levels.len() < combinations.len());
+    }
+    for factor in &mut factors {
+        if factor.aliased.len() > 1 && factor.levels.len() == combinations.len() {
+            factor.name = format!("combination of {}", factor.aliased.join(", "));
+        }
+    }
+    factors
+}
+
+// Whether two level assignments group the runs the same way
+fn same_partition(a: &[usize], b: &[usize]) -> bool {
+    let mut pairs: BTreeMap<usize, usize> = BTreeMap::new();
+    a.iter().zip(b).all(|(&x, &y)| *pairs.entry(x).or_insert(y) == y)
+}
+
+// Mean response of the runs at each value of `key`, with their counts
+fn cell_means<K: Ord>(
+    responses: &[f64],
+    key: impl Fn(usize) -> K,
+) -> BTreeMap<K, (f64, usize)> {
+    let mut cells: BTreeMap<K, (f64, usize)> = BTreeMap::new();
+    for (run, &response) in responses.iter().enumerate() {
+        let cell = cells.entry(key(run)).or_insert((0.0, 0));
+        cell.0 += response;
+        cell.1 += 1;
+    }
+    cells.values_mut().for_each(|(sum, count)| *sum /= *count as f64);
+    cells
+}
+
+// Effects of the factorial parameters on `response` (higher is better), None when the
+// results have no such parameter. Main effects come from the marginal means; two-way
+// interactions are only fitted when every combination of the factors' levels was run, as
+// in screening designs they are aliased with one another. Sums of squares are exact for
+// balanced designs and approximate when some runs are missing.
+pub fn factorial_anova(
+    results: &[&TuningResult],
+    response_name: &str,
+    response: impl Fn(&TuningResult) -> f64,
+) -> Option<Anova> {
+    let factors = factors(results);
+    if factors.is_empty() {
+        return None;
+    }
+    let responses: Vec<f64> = results.iter().map(|r| response(r)).collect();
+    let n = responses.len();
+    let grand = responses.iter().sum::<f64>() / n as f64;
+    let total_sum_sq: f64 = responses.iter().map(|y| (y - grand).powi(2)).sum();
+
+    let marginals: Vec<BTreeMap<usize, (f64, usize)>> =
+        factors.iter().map(|factor| cell_means(&responses, |run| factor.indices[run])).collect();
+    let mut main_effects: Vec<Effect> = factors
+        .iter()
+        .zip(&marginals)
+        .map(|(factor, means)| {
+            let sum_sq =
+                means.values().map(|&(mean, count)| count as f64 * (mean - grand).powi(2)).sum();
+            effect(factor.name.clone(), factor.levels.len() - 1, sum_sq)
+        })
+        .collect();
+
+    let full_factorial = {
+        let cells: BTreeSet<Vec<usize>> = (0..n)
+            .map(|run| factors.iter().map(|factor| factor.indices[run]).collect())
+            .collect();
+        cells.len() == factors.iter().map(|factor| factor.levels.len()).product::<usize>()
+    };
+    let mut interactions = Vec::new();
+    for a in 0..factors.len() {
+        for b in (a + 1..factors.len()).filter(|_| full_factorial) {
+            let (fa, fb) = (&factors[a], &factors[b]);
+            let cells = cell_means(&responses, |run| (fa.indices[run], fb.indices[run]));
+            let sum_sq = cells
+                .iter()
+                .map(|(&(i, j), &(mean, count))| {
+                    let deviation = mean - marginals[a][&i].0 - marginals[b][&j].0 + grand;
+                    count as f64 * deviation.powi(2)
+                })
+                .sum();
+            let df = (fa.levels.len() - 1) * (fb.levels.len() - 1);
+            interactions.push(effect(format!("{} x {}", fa.name, fb.name), df, sum_sq));
+        }
+    }
+
+    let model_df: usize = main_effects.iter().chain(&interactions).map(|e| e.df).sum();
+    let model_sum_sq: f64 = main_effects.iter().chain(&interactions).map(|e| e.sum_sq).sum();
+    let residual_df = (n - 1).saturating_sub(model_df);
+    let residual_sum_sq = (total_sum_sq - model_sum_sq).max(0.0);
+    let residual_mean_sq = (residual_df > 0).then(|| residual_sum_sq / residual_df as f64);
+    for effects in [&mut main_effects, &mut interactions] {
+        for effect in effects.iter_mut() {
+            effect.eta_squared =
+                if total_sum_sq > 0.0 { effect.sum_sq / total_sum_sq } else { 0.0 };
+            if let Some(residual) = residual_mean_sq.filter(|&ms| ms > 0.0) {
+                let f = effect.mean_sq / residual;
+                effect.f = Some(f);
+                effect.p =
+                    Some(statistics::f_test_p_value(f, effect.df as f64, residual_df as f64));
+            }
+        }
+        effects.sort_by(|a, b| b.sum_sq.total_cmp(&a.sum_sq));
+    }
+
+    // The value of each parameter a factor stands for at its best level, so parameters that
+    // restate it come out consistent with it
+    let mut best_levels = Vec::new();
+    for (factor, means) in factors.iter().zip(&marginals) {
+        let Some((&best, _)) = means.iter().max_by(|a, b| a.1.0.total_cmp(&b.1.0)) else {
+            continue;
+        };
+        let run = factor.indices.iter().position(|&index| index == best).unwrap_or_default();
+        for name in &factor.aliased {
+            let value = results[run].parameters.get(name).copied().unwrap_or(f64::NAN);
+            best_levels.push((name.clone(), value));
+        }
+    }
+    Some(Anova {
+        response: response_name.to_string(),
+        runs: n,
+        effects: main_effects.into_iter().chain(interactions).collect(),
+        residual_df,
+        residual_sum_sq,
+        total_sum_sq,
+        best_levels,
+    })
+}
+
+// Main effects of the same factors factorial_anova tests, largest range of level means
+// first, so the parameter driving the differences leads
+pub fn main_effects(
+    results: &[&TuningResult],
+    response_name: &str,
+    response: impl Fn(&TuningResult) -> f64,
+) -> Vec<MainEffect> {
+    let responses: Vec<f64> = results.iter().map(|r| response(r)).collect();
+    let mut effects: Vec<MainEffect> = factors(results)
+        .into_iter()
+        .map(|factor| {
+            let levels = factor
+                .levels
+                .iter()
+                .enumerate()
+                .map(|(index, &level)| {
+                    let values: Vec<f64> = (0..responses.len())
+                        .filter(|&run| factor.indices[run] == index)
+                        .map(|run| responses[run])
+                        .collect();
+                    level_mean(level, &values)
+                })
+                .collect();
+            MainEffect { parameter: factor.name, response: response_name.to_string(), levels }
+        })
+        .collect();
+    effects.sort_by(|a, b| b.range().total_cmp(&a.range()));
+    effects
+}
+
+// A heatmap for every pair of the factors factorial_anova tests
+pub fn heatmaps(
+    results: &[&TuningResult],
+    response_name: &str,
+    response: impl Fn(&TuningResult) -> f64,
+) -> Vec<Heatmap> {
+    let responses: Vec<f64> = results.iter().map(|r| response(r)).collect();
+    let factors = factors(results);
+    let mut heatmaps = Vec::new();
+    for (a, row) in factors.iter().enumerate() {
+        for column in &factors[a + 1..] {
+            let cells = cell_means(&responses, |run| (row.indices[run], column.indices[run]));
+            let means = (0..row.levels.len())
+                .map(|i| {
+                    (0..column.levels.len()).map(|j| cells.get(&(i, j)).map(|cell| cell.0)).collect()
+                })
+                .collect();
+            heatmaps.push(Heatmap {
+                response: response_name.to_string(),
+                row: row.name.clone(),
+                column: column.name.clone(),
+                row_levels: row.levels.clone(),
+                column_levels: column.levels.clone(),
+                means,
+            });
+        }
+    }
+    heatmaps
+}
+
+fn level_mean(level: f64, values: &[f64]) -> LevelMean {
+    let n = values.len();
+    let mean = values.iter().sum::<f64>() / n as f64;
+    let half_width = if n > 1 {
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+        statistics::t_critical(0.95, (n - 1) as f64) * (variance / n as f64).sqrt()
+    } else {
+        0.0
+    };
+    LevelMean { level, runs: n, mean, ci_low: mean - half_width, ci_high: mean + half_width }
+}
+
+// Writes each algorithm's main effects as CSV, one row per parameter level
+pub fn save_main_effects(effects: &[(&str, &[MainEffect])], path: &str) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    writeln!(file, "algorithm,response,parameter,level,runs,mean,ci_low,ci_high")?;
+    for (algorithm, effects) in effects {
+        for effect in effects.iter() {
+            for level in &effect.levels {
+                writeln!(
+                    file,
+                    "{algorithm},{},{},{},{},{},{},{}",
+                    effect.response,
+                    effect.parameter,
+                    level.level,
+                    level.runs,
+                    level.mean,
+                    level.ci_low,
+                    level.ci_high
+                )?;
+            }
+        }
+    }
+    file.flush()
+}
+
+// Writes a heatmap as a CSV matrix: a header of the column parameter's levels, then a row per
+// level of the row parameter, with an empty cell where no run had that pair of levels
+pub fn save_heatmap(heatmap: &Heatmap, path: &str) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    let levels: Vec<String> = heatmap.column_levels.iter().map(f64::to_string).collect();
+    writeln!(file, "{}\\{},{}", heatmap.row, heatmap.column, levels.join(","))?;
+    for (level, means) in heatmap.row_levels.iter().zip(&heatmap.means) {
+        let cells: Vec<String> =
+            means.iter().map(|mean| mean.map_or(String::new(), |mean| mean.to_string())).collect();
+        writeln!(file, "{level},{}", cells.join(","))?;
+    }
+    file.flush()
+}
+
+fn effect(source: String, df: usize, sum_sq: f64) -> Effect {
+    let mean_sq = sum_sq / df.max(1) as f64;
+    Effect { source, df, sum_sq, mean_sq, f: None, p: None, eta_squared: 0.0 }
+}
+
+// The ANOVA table, with the best level of each factor under it
+pub fn print(anova: &Anova) {
+    println!("\nEffects on {} (factorial ANOVA over {} runs):", anova.response, anova.runs);
+    // Wide enough for a combination of several parameters
+    let width = anova.effects.iter().map(|effect| effect.source.len()).fold(36, usize::max);
+    println!(
+        "  {:<width$} {:>4} {:>12} {:>9} {:>8} {:>6}",
+        "source", "df", "sum sq", "F", "p", "eta^2"
+    );
+    let optional = |value: Option<f64>, precision: usize| {
+        value.map_or("-".to_string(), |value| format!("{value:.precision$}"))
+    };
+    for effect in &anova.effects {
+        println!(
+            "  {:<width$} {:>4} {:>12.4e} {:>9} {:>8} {:>6.3}",
+            effect.source,
+            effect.df,
+            effect.sum_sq,
+            optional(effect.f, 2),
+            optional(effect.p, 4),
+            effect.eta_squared
+        );
+    }
+    println!(
+        "  {:<width$} {:>4} {:>12.4e}",
+        "residual", anova.residual_df, anova.residual_sum_sq
+    );
+    let best: Vec<String> =
+        anova.best_levels.iter().map(|(name, level)| format!("{name} {level}")).collect();
+    println!("Best level of each parameter: {}", best.join(", "));
+}
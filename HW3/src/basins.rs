@@ -0,0 +1,91 @@
+use std::fmt;
+
+// Smallest fraction of a population a cluster needs to count as an optimum it covers
+pub const MIN_CLUSTER_SHARE: f64 = 0.02;
+
+// Final members grouped by the known optimum they sit on, for problems with several optima
+// where the best fitness alone doesn't say which of them a run found
+#[derive(Debug, Clone)]
+pub struct BasinCounts {
+    pub optima: Vec<Vec<f64>>,
+    // Members within the tolerance of each optimum, in the order of optima
+    pub counts: Vec<usize>,
+    // Members further than the tolerance from every optimum
+    pub unassigned: usize,
+}
+
+impl BasinCounts {
+    // Assigns each member to its nearest optimum by euclidean distance, if it lies within
+    // tolerance of it
+    pub fn classify(members: &[Vec<f64>], optima: &[Vec<f64>], tolerance: f64) -> Self {
+        let mut counts = vec![0; optima.len()];
+        let mut unassigned = 0;
+        for member in members {
+            let nearest = optima
+                .iter()
+                .map(|optimum| distance(member, optimum))
+                .enumerate()
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            match nearest {
+                Some((i, dist)) if dist <= tolerance => counts[i] += 1,
+                _ => unassigned += 1,
+            }
+        }
+        Self { optima: optima.to_vec(), counts, unassigned }
+    }
+
+    // Number of optima with at least one member
+    pub fn found(&self) -> usize {
+        self.counts.iter().filter(|&&count| count > 0).count()
+    }
+}
+
+fn distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt()
+}
+
+// "found/total optima: [x, y]: count, ..., none: count"
+impl fmt::Display for BasinCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{} optima found:", self.found(), self.optima.len())?;
+        for (optimum, count) in self.optima.iter().zip(&self.counts) {
+            let coordinates: Vec<String> = optimum.iter().map(|x| format!("{x:.4}")).collect();
+            write!(f, " [{}]: {count},", coordinates.join(", "))?;
+        }
+        write!(f, " none: {}", self.unassigned)
+    }
+}
+
+// One cluster of a population: its best member and how many members it holds
+#[derive(Debug, Clone, Copy)]
+pub struct Cluster {
+    pub leader: usize,
+    pub size: usize,
+}
+
+// A simple clustering pass over a population: members are taken best fitness first, and each
+// one further than radius from every leader so far leads a cluster of its own, while the rest
+// join the first leader within radius
+pub fn clusters(members: &[Vec<f64>], fitnesses: &[f64], radius: f64) -> Vec<Cluster> {
+    let mut order: Vec<usize> = (0..members.len()).collect();
+    order.sort_by(|&a, &b| fitnesses[b].total_cmp(&fitnesses[a]));
+    let mut clusters: Vec<Cluster> = Vec::new();
+    for i in order {
+        let home = clusters
+            .iter_mut()
+            .find(|cluster| distance(&members[i], &members[cluster.leader]) <= radius);
+        match home {
+            Some(cluster) => cluster.size += 1,
+            None => clusters.push(Cluster { leader: i, size: 1 }),
+        }
+    }
+    clusters
+}
+
+// Distinct optima a population covers without knowing where the optima are: its clusters
+// holding at least MIN_CLUSTER_SHARE of the members (and at least two), so that stray mutants
+// don't count as optima held
+pub fn distinct_optima(members: &[Vec<f64>], fitnesses: &[f64], radius: f64) -> usize {
+    let min_size = ((MIN_CLUSTER_SHARE * members.len() as f64).ceil() as usize).max(2);
+    clusters(members, fitnesses, radius).iter().filter(|cluster| cluster.size >= min_size).count()
+}
@@ -0,0 +1,236 @@
+use crate::fitness::Fitness;
+use crate::parameter_tuning::{SampledSearch, Sampling, TuningConfig, TuningResult};
+use crate::timeout_runner::TimeoutRunner;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use std::f64::consts::PI;
+
+// Gaussian process regression on the unit cube with a squared exponential kernel. Targets are
+// standardized before fitting, so the prior has zero mean and unit variance. Also the
+// kriging and RBF models of the surrogate-assisted ES.
+pub struct GaussianProcess {
+    length_scale: f64,
+    points: Vec<Vec<f64>>,
+    // (K + noise I)^-1 y, for the posterior mean
+    alpha: Vec<f64>,
+    // Lower Cholesky factor of K + noise I, for the posterior variance
+    cholesky: Vec<Vec<f64>>,
+    y_mean: f64,
+    y_std: f64,
+}
+
+impl GaussianProcess {
+    const LENGTH_SCALE: f64 = 0.25;
+    // Scores are averages of a few noisy runs, so observations aren't interpolated exactly
+    const NOISE: f64 = 0.05;
+
+    fn kernel(&self, a: &[f64], b: &[f64]) -> f64 {
+        let squared: f64 = a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum();
+        (-squared / (2.0 * self.length_scale.powi(2))).exp()
+    }
+
+    // noise is the variance of the observations relative to that of the standardized targets
+    pub fn fit(points: &[Vec<f64>], values: &[f64], length_scale: f64, noise: f64) -> Self {
+        let n = values.len() as f64;
+        let y_mean = values.iter().sum::<f64>() / n;
+        let y_std = (values.iter().map(|v| (v - y_mean).powi(2)).sum::<f64>() / n).sqrt();
+        let y_std = if y_std > 0.0 { y_std } else { 1.0 };
+        let y: Vec<f64> = values.iter().map(|v| (v - y_mean) / y_std).collect();
+
+        let mut model = Self {
+            length_scale,
+            points: points.to_vec(),
+            alpha: Vec::new(),
+            cholesky: Vec::new(),
+            y_mean,
+            y_std,
+        };
+        let covariance: Vec<Vec<f64>> = points
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                let noise = |j| if i == j { noise } else { 0.0 };
+                points.iter().enumerate().map(|(j, b)| model.kernel(a, b) + noise(j)).collect()
+            })
+            .collect();
+        model.cholesky = cholesky(&covariance);
+        model.alpha = solve_upper(&model.cholesky, &solve_lower(&model.cholesky, &y));
+        model
+    }
+
+    // Posterior mean at `x`, in the units of the fitted values
+    pub fn mean(&self, x: &[f64]) -> f64 {
+        self.predict_with(x).0
+    }
+
+    // Posterior mean and standard deviation at `x`, in the units of the fitted values
+    pub fn predict(&self, x: &[f64]) -> (f64, f64) {
+        let (mean, k) = self.predict_with(x);
+        let v = solve_lower(&self.cholesky, &k);
+        let variance = (1.0 - v.iter().map(|v| v * v).sum::<f64>()).max(0.0);
+        (mean, variance.sqrt() * self.y_std)
+    }
+
+    // Posterior mean and the kernel between `x` and every fitted point
+    fn predict_with(&self, x: &[f64]) -> (f64, Vec<f64>) {
+        let k: Vec<f64> = self.points.iter().map(|p| self.kernel(p, x)).collect();
+        let mean: f64 = k.iter().zip(&self.alpha).map(|(a, b)| a * b).sum();
+        (self.y_mean + mean * self.y_std, k)
+    }
+}
+
+fn cholesky(matrix: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let n = matrix.len();
+    let mut lower = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let dot: f64 = (0..j).map(|k| lower[i][k] * lower[j][k]).sum();
+            lower[i][j] = if i == j {
+                // Clamped so near-duplicate points can't make the factorization fail
+                (matrix[i][i] - dot).max(1e-12).sqrt()
+            } else {
+                (matrix[i][j] - dot) / lower[j][j]
+            };
+        }
+    }
+    lower
+}
+
+// Solves L x = b
+fn solve_lower(lower: &[Vec<f64>], b: &[f64]) -> Vec<f64> {
+    let mut x = vec![0.0; b.len()];
+    for i in 0..b.len() {
+        let dot: f64 = (0..i).map(|k| lower[i][k] * x[k]).sum();
+        x[i] = (b[i] - dot) / lower[i][i];
+    }
+    x
+}
+
+// Solves L^T x = b
+fn solve_upper(lower: &[Vec<f64>], b: &[f64]) -> Vec<f64> {
+    let mut x = vec![0.0; b.len()];
+    for i in (0..b.len()).rev() {
+        let dot: f64 = (i + 1..b.len()).map(|k| lower[k][i] * x[k]).sum();
+        x[i] = (b[i] - dot) / lower[i][i];
+    }
+    x
+}
+
+// Standard normal CDF, via the Abramowitz and Stegun erf approximation (error below 1.5e-7)
+fn normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / 2f64.sqrt();
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 { 0.5 * (1.0 + erf) } else { 0.5 * (1.0 - erf) }
+}
+
+// Expected amount by which a point predicted as N(mean, std^2) beats `best`
+fn expected_improvement(mean: f64, std: f64, best: f64) -> f64 {
+    if std <= 0.0 {
+        return (mean - best).max(0.0);
+    }
+    let z = (mean - best) / std;
+    let density = (-0.5 * z * z).exp() / (2.0 * PI).sqrt();
+    (mean - best) * normal_cdf(z) + std * density
+}
+
+// Sequential model-based tuning: after a small Sobol design, each further combination is the
+// one a Gaussian process fitted to the average scores so far expects to improve most on the
+// best of them. Uses the same parameter ranges as the sampled designs, and `samples`
+// combinations per algorithm in total.
+pub struct BayesianTuner {
+    pub search: SampledSearch,
+    // Combinations placed by the Sobol design before the surrogate takes over
+    pub initial: usize,
+    // Random points the expected improvement is maximized over, per proposal
+    pub candidates: usize,
+}
+
+impl BayesianTuner {
+    pub fn new(search: SampledSearch) -> Self {
+        Self { search, initial: 8, candidates: 2000 }
+    }
+
+    pub fn run<F: Fitness + Clone + Send + Sync + 'static>(
+        &self,
+        fitness_fn: F,
+        config: &TuningConfig,
+    ) -> Vec<TuningResult> {
+        let mut results = Vec::new();
+
+        let mut sga_grid = Vec::new();
+        let mut rng = ChaCha8Rng::seed_from_u64(self.search.seed);
+        self.optimize(SampledSearch::SGA_AXES, &mut rng, |u| {
+            sga_grid.push(self.search.sga_at(u));
+            let combination = sga_grid.len() - 1;
+            let fitness_fn = fitness_fn.clone();
+            let runs = TimeoutRunner::run_sga_combination(fitness_fn, &sga_grid, combination, config);
+            Self::record(&mut results, runs)
+        });
+
+        let mut es_grid = Vec::new();
+        let mut rng = ChaCha8Rng::seed_from_u64(self.search.seed.wrapping_add(1));
+        self.optimize(SampledSearch::ES_AXES, &mut rng, |u| {
+            es_grid.push(self.search.es_at(u));
+            let combination = es_grid.len() - 1;
+            let fitness_fn = fitness_fn.clone();
+            let runs = TimeoutRunner::run_es_combination(fitness_fn, &es_grid, combination, config);
+            Self::record(&mut results, runs)
+        });
+
+        results
+    }
+
+    // Keeps a combination's runs and returns their average score for the surrogate, None if
+    // none ran because the session's budget is spent
+    fn record(results: &mut Vec<TuningResult>, runs: Vec<TuningResult>) -> Option<f64> {
+        if runs.is_empty() {
+            return None;
+        }
+        let average = runs.iter().map(|r| r.score).sum::<f64>() / runs.len() as f64;
+        results.extend(runs);
+        Some(average)
+    }
+
+    // Maximizes `evaluate` over the unit cube of dimension `dims` within the sample budget,
+    // stopping early once `evaluate` returns None
+    fn optimize<R: Rng + ?Sized>(
+        &self,
+        dims: usize,
+        rng: &mut R,
+        mut evaluate: impl FnMut(&[f64]) -> Option<f64>,
+    ) {
+        let initial = self.initial.min(self.search.samples);
+        let mut points = Sampling::Sobol.points(initial, dims, rng);
+        let mut values = Vec::new();
+        for u in &points {
+            let Some(value) = evaluate(u) else { return };
+            values.push(value);
+        }
+
+        while points.len() < self.search.samples {
+            let model = GaussianProcess::fit(
+                &points,
+                &values,
+                GaussianProcess::LENGTH_SCALE,
+                GaussianProcess::NOISE,
+            );
+            let best = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            let next = (0..self.candidates)
+                .map(|_| (0..dims).map(|_| rng.random()).collect::<Vec<f64>>())
+                .map(|u| {
+                    let (mean, std) = model.predict(&u);
+                    (expected_improvement(mean, std, best), u)
+                })
+                .max_by(|a, b| a.0.total_cmp(&b.0))
+                .map(|(_, u)| u)
+                .expect("at least one candidate");
+            let Some(value) = evaluate(&next) else { return };
+            values.push(value);
+            points.push(next);
+        }
+    }
+}
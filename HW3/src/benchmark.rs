@@ -0,0 +1,230 @@
+use hw3::algorithm::Algorithm;
+use hw3::bitgenome::BitGenome;
+use hw3::bitstring::{GAParameters, GeneticAlgorithm};
+use hw3::bytegenome::ByteGenome;
+use hw3::fitness::{Fitness, Objective};
+use hw3::genome::Genome;
+use hw3::repair::NoRepair;
+use hw3::rosenbrock::Rosenbrock;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+const NUM_DIMS: usize = 10;
+const GENOME_BITS: usize = 16 * NUM_DIMS;
+const POOL_SIZE: usize = 1000;
+const ITERATIONS: usize = 200_000;
+const RUN_GENERATIONS: usize = 200;
+
+// The system allocator, counting allocations (reallocations included) while a benchmark has
+// switched counting on. Off, it costs one relaxed load per allocation.
+struct CountingAllocator;
+
+static COUNTING: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+impl CountingAllocator {
+    fn count(size: usize) {
+        if COUNTING.load(Ordering::Relaxed) {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            ALLOCATED_BYTES.fetch_add(size, Ordering::Relaxed);
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::count(layout.size());
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        Self::count(new_size);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+// Time, allocations and bytes allocated by one piece of work
+#[derive(Debug, Clone, Copy)]
+struct Measurement {
+    time: Duration,
+    allocations: usize,
+    bytes: usize,
+}
+
+impl Measurement {
+    fn of(work: impl FnOnce()) -> Self {
+        ALLOCATIONS.store(0, Ordering::Relaxed);
+        ALLOCATED_BYTES.store(0, Ordering::Relaxed);
+        COUNTING.store(true, Ordering::Relaxed);
+        let start = Instant::now();
+        work();
+        let time = start.elapsed();
+        COUNTING.store(false, Ordering::Relaxed);
+        Self {
+            time,
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            bytes: ALLOCATED_BYTES.load(Ordering::Relaxed),
+        }
+    }
+
+    // The same work measured over `count` repetitions, per repetition
+    fn per(self, count: usize) -> Self {
+        Self {
+            time: self.time / count as u32,
+            allocations: self.allocations / count,
+            bytes: self.bytes / count,
+        }
+    }
+}
+
+// Cost per operation for one genome representation
+struct OperatorTimings {
+    mutate: Measurement,
+    crossover: Measurement,
+    evaluate: Measurement,
+}
+
+// Times mutation, crossover, and decode + evaluation on a pool of random genomes
+fn time_operators<G: Genome>(rng: &mut ChaCha8Rng) -> OperatorTimings {
+    let bounds = Rosenbrock.bounds();
+    let pool: Vec<G> = (0..POOL_SIZE)
+        .map(|_| G::random(GENOME_BITS, bounds, rng))
+        .collect();
+
+    let params = GAParameters {
+        mutation_rate: 0.01,
+        ..GAParameters::default()
+    };
+    let mutate = Measurement::of(|| {
+        for i in 0..ITERATIONS {
+            black_box(pool[i % POOL_SIZE].mutate(&params, bounds, rng));
+        }
+    });
+
+    let crossover = Measurement::of(|| {
+        for i in 0..ITERATIONS {
+            let other = &pool[(i + 1) % POOL_SIZE];
+            black_box(pool[i % POOL_SIZE].crossover(other, 1.0, rng));
+        }
+    });
+
+    let decoder = Rosenbrock.decoder();
+    let evaluate = Measurement::of(|| {
+        for i in 0..ITERATIONS {
+            let x = pool[i % POOL_SIZE].phenotype(&decoder, NUM_DIMS);
+            black_box(Rosenbrock.fitness(&x));
+        }
+    });
+
+    OperatorTimings {
+        mutate: mutate.per(ITERATIONS),
+        crossover: crossover.per(ITERATIONS),
+        evaluate: evaluate.per(ITERATIONS),
+    }
+}
+
+// A whole GA run on the HW3 Rosenbrock settings, without the status lines, and its final
+// population's phenotypes
+fn time_run<G: Genome>() -> (Measurement, Vec<Vec<f64>>) {
+    let params = GAParameters {
+        mem_size: GENOME_BITS,
+        num_dims: NUM_DIMS,
+        max_iters: RUN_GENERATIONS,
+        ..GAParameters::default()
+    };
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let mut population = Vec::new();
+    let measurement = Measurement::of(|| {
+        population =
+            GeneticAlgorithm::<G, _, _>::new(&Rosenbrock, &params, &NoRepair, &mut rng)
+                .quiet()
+                .run(&mut rng);
+    });
+    let decoder = Rosenbrock.decoder();
+    let phenotypes = population.iter().map(|m| m.phenotype(&decoder, NUM_DIMS)).collect();
+    (measurement, phenotypes)
+}
+
+fn speedup(before: Duration, after: Duration) -> f64 {
+    before.as_secs_f64() / after.as_secs_f64().max(f64::EPSILON)
+}
+
+// One row per representation: time, allocations and kilobytes allocated, and the speedup
+// over the String genome
+fn print_rows(name: &str, rows: [(&str, Measurement); 3]) {
+    let baseline = rows[0].1.time;
+    for (genome, m) in rows {
+        println!(
+            "{:<12} {:<11} {:>12?} {:>10} {:>12.1} {:>8.1}x",
+            name,
+            genome,
+            m.time,
+            m.allocations,
+            m.bytes as f64 / 1024.0,
+            speedup(baseline, m.time)
+        );
+    }
+}
+
+// Compares the '0'/'1' String genome against the byte-per-bit ByteGenome and the packed
+// BitGenome on 160-bit Rosenbrock genomes, per operator and over a whole run. Run with
+// `cargo run --release -- demo bench` for meaningful numbers.
+pub fn run_genome_benchmark() {
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+
+    // Every representation must decode to the same phenotype
+    let sample = String::random(GENOME_BITS, Rosenbrock.bounds(), &mut rng);
+    let decoder = Rosenbrock.decoder();
+    let expected = Rosenbrock.decode_bitstring(&sample, NUM_DIMS);
+    let matches = Rosenbrock.decode_bitgenome(&BitGenome::from_bitstring(&sample), NUM_DIMS)
+        == expected
+        && ByteGenome::from_bitstring(&sample).phenotype(&decoder, NUM_DIMS) == expected;
+    println!("Decoded phenotypes match: {matches}");
+
+    let string_timings = time_operators::<String>(&mut rng);
+    let byte_timings = time_operators::<ByteGenome>(&mut rng);
+    let packed_timings = time_operators::<BitGenome>(&mut rng);
+
+    println!("\n=== {GENOME_BITS}-bit Rosenbrock genome benchmark ({ITERATIONS} ops each) ===");
+    println!(
+        "{:<12} {:<11} {:>12} {:>10} {:>12} {:>9}",
+        "operator", "genome", "time/op", "allocs/op", "KiB/op", "speedup"
+    );
+    let rows = |operator: fn(&OperatorTimings) -> Measurement| {
+        [
+            ("String", operator(&string_timings)),
+            ("ByteGenome", operator(&byte_timings)),
+            ("BitGenome", operator(&packed_timings)),
+        ]
+    };
+    print_rows("mutate", rows(|t| t.mutate));
+    print_rows("crossover", rows(|t| t.crossover));
+    print_rows("evaluate", rows(|t| t.evaluate));
+
+    // ByteGenome draws from the RNG as the String genome does, so the two runs must agree
+    let (string_run, string_final) = time_run::<String>();
+    let (byte_run, byte_final) = time_run::<ByteGenome>();
+    let (packed_run, _) = time_run::<BitGenome>();
+    println!("\n=== GA run, pop 100, {RUN_GENERATIONS} generations ===");
+    println!(
+        "{:<12} {:<11} {:>12} {:>10} {:>12} {:>9}",
+        "", "genome", "time", "allocs", "KiB", "speedup"
+    );
+    print_rows(
+        "run",
+        [("String", string_run), ("ByteGenome", byte_run), ("BitGenome", packed_run)],
+    );
+    println!("String and ByteGenome runs match: {}", string_final == byte_final);
+}
@@ -0,0 +1,195 @@
+use crate::bitstring::GAParameters;
+use crate::decoder::{Decoder, Encoding, gray_to_binary};
+use crate::genome::{Genome, hash_of};
+use rand::prelude::*;
+use rand_distr::Geometric;
+
+// Bitstring packed 64 bits per word (bit i lives in words[i / 64] at position i % 64), with
+// the same operator semantics as the '0'/'1' String genome but without per-character
+// allocation or text parsing on decode
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGenome {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitGenome {
+    pub fn from_bitstring(bitstring: &str) -> Self {
+        let mut genome = BitGenome {
+            words: vec![0; bitstring.len().div_ceil(64)],
+            len: bitstring.len(),
+        };
+        for (i, c) in bitstring.chars().enumerate() {
+            if c == '1' {
+                genome.flip(i);
+            }
+        }
+        genome
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    pub fn flip(&mut self, i: usize) {
+        self.words[i / 64] ^= 1 << (i % 64);
+    }
+
+    // Reads bits [start, start + width) as an unsigned integer, first bit most significant.
+    // width must be between 1 and 64.
+    pub fn read_uint(&self, start: usize, width: usize) -> u64 {
+        let word = start / 64;
+        let offset = start % 64;
+        let mut raw = self.words[word] >> offset;
+        if offset + width > 64 {
+            raw |= self.words[word + 1] << (64 - offset);
+        }
+        // raw holds the first bit in its least significant position, so reverse the order
+        raw.reverse_bits() >> (64 - width)
+    }
+
+    // Splits the genome into num_dims equal segments and scales each one linearly into bounds,
+    // exactly like decoder::decode_binary does for strings
+    pub fn decode(&self, num_dims: usize, bounds: (f64, f64), encoding: Encoding) -> Vec<f64> {
+        if num_dims == 0 {
+            panic!("Number of dimensions must be greater than 0");
+        }
+        if !self.len.is_multiple_of(num_dims) {
+            panic!("Bitstring length must be divisible by number of dimensions");
+        }
+
+        let segment_len = self.len / num_dims;
+        let max_val = 2_f64.powi(segment_len as i32) - 1.0;
+        (0..num_dims)
+            .map(|i| {
+                let mut value = self.read_uint(segment_len * i, segment_len);
+                if encoding == Encoding::Gray {
+                    value = gray_to_binary(value);
+                }
+                (value as f64 / max_val) * (bounds.1 - bounds.0) + bounds.0
+            })
+            .collect()
+    }
+
+    // Clears any bits past len in the last word so equality and hashing stay exact
+    fn mask_tail(&mut self) {
+        let used = self.len % 64;
+        if used != 0
+            && let Some(last) = self.words.last_mut()
+        {
+            *last &= (1 << used) - 1;
+        }
+    }
+}
+
+impl Genome for BitGenome {
+    fn random<R: Rng + ?Sized>(len: usize, _bounds: (f64, f64), rng: &mut R) -> Self {
+        let mut genome = BitGenome {
+            words: (0..len.div_ceil(64)).map(|_| rng.random()).collect(),
+            len,
+        };
+        genome.mask_tail();
+        genome
+    }
+
+    // Each bit flips independently with probability mutation_rate. Rather than drawing one
+    // number per bit, the gaps between flipped bits are drawn from a geometric distribution.
+    fn mutate<R: Rng + ?Sized>(
+        &self,
+        params: &GAParameters,
+        bounds: (f64, f64),
+        rng: &mut R,
+    ) -> Self {
+        self.mutate_with_loci(params, bounds, rng).0
+    }
+
+    fn mutate_with_loci<R: Rng + ?Sized>(
+        &self,
+        params: &GAParameters,
+        _bounds: (f64, f64),
+        rng: &mut R,
+    ) -> (Self, Option<Vec<usize>>) {
+        let mutation_rate = params.mutation_rate;
+        let mut mutated = self.clone();
+        if mutation_rate <= 0.0 {
+            return (mutated, Some(Vec::new()));
+        }
+        if mutation_rate >= 1.0 {
+            for word in mutated.words.iter_mut() {
+                *word = !*word;
+            }
+            mutated.mask_tail();
+            return (mutated, Some((0..self.len).collect()));
+        }
+
+        let gaps = Geometric::new(mutation_rate).unwrap();
+        let mut flipped = Vec::new();
+        let mut i = rng.sample(gaps) as usize;
+        while i < mutated.len {
+            mutated.flip(i);
+            flipped.push(i);
+            i = i.saturating_add(1 + rng.sample(gaps) as usize);
+        }
+        (mutated, Some(flipped))
+    }
+
+    // Single point crossover, swapping whole words past the crossover point
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        other: &Self,
+        crossover_rate: f64,
+        rng: &mut R,
+    ) -> (Self, Self) {
+        if self.len != other.len {
+            panic!("Parents must be of the same length");
+        }
+        let random: f64 = rng.random();
+        if random >= crossover_rate || self.len < 2 {
+            return (self.clone(), other.clone());
+        }
+
+        let crossover_point = rng.random_range(1..self.len);
+        let mut offspring1 = self.clone();
+        let mut offspring2 = other.clone();
+        let word = crossover_point / 64;
+        let low_mask: u64 = (1 << (crossover_point % 64)) - 1;
+        offspring1.words[word] = (self.words[word] & low_mask) | (other.words[word] & !low_mask);
+        offspring2.words[word] = (other.words[word] & low_mask) | (self.words[word] & !low_mask);
+        offspring1.words[word + 1..].copy_from_slice(&other.words[word + 1..]);
+        offspring2.words[word + 1..].copy_from_slice(&self.words[word + 1..]);
+        (offspring1, offspring2)
+    }
+
+    fn length(&self) -> usize {
+        self.len
+    }
+
+    // Hamming distance, a popcount per word
+    fn distance(&self, other: &Self) -> f64 {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .map(|(a, b)| (a ^ b).count_ones())
+            .sum::<u32>() as f64
+    }
+
+    fn fingerprint(&self) -> u64 {
+        hash_of(self)
+    }
+
+    fn phenotype(&self, decoder: &impl Decoder, num_dims: usize) -> Vec<f64> {
+        decoder.decode_bitgenome(self, num_dims)
+    }
+
+    fn from_phenotype(phenotype: &[f64], decoder: &impl Decoder, len: usize) -> Option<Self> {
+        decoder.encode_bitstring(phenotype, len).map(|bits| BitGenome::from_bitstring(&bits))
+    }
+}
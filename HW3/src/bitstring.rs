@@ -1,53 +1,155 @@
+use crate::algorithm::Algorithm;
+use crate::cache::CacheStats;
+use crate::cancellation::CancellationToken;
+use crate::constraint::{Constrained, ViolationStats};
+use crate::diversity::{
+    DiversityMonitor, DiversityRestoration, DuplicateFilter, DuplicatePolicy,
+    max_pairwise_distance,
+};
+use crate::decoder::Decoder;
 use crate::fitness::Fitness;
+use crate::genome::{Genome, RealCrossover, hash_of};
+use crate::integer_genome::IntegerMutation;
+use crate::logging::{status_observer, trace_observer};
+use crate::observer::{
+    ConsoleObserver, GenerationStats, Observer, RunSummary, RunningStats, StatsConfig,
+};
+use crate::parameter_tuning::ParameterGrid;
+use crate::population::Population;
+use crate::rate_control::{RateControl, RateController, ScheduleClock};
+use crate::repair::{NoRepair, Repair, RepairStats};
+use crate::seeds::SeedSequence;
+use crate::niching::{Clearing, FitnessSharing, Replacement, deterministic_crowding};
+use crate::selection::{FitnessScaling, ParentSelector, SelectionMethod};
+use crate::speciation::{Speciation, SpeciesSet};
+use crate::stopping::{StopMonitor, StopReason, StoppingCriteria};
+use crate::timing::{OperatorTimer, Phase};
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
+use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
 pub struct GAParameters {
     pub pop_size: usize,
     pub mem_size: usize,
+    pub num_dims: usize,
     pub mutation_rate: f64,
+    pub mutation_control: RateControl,
+    pub integer_mutation: IntegerMutation,
+    pub insertion_rate: f64,
+    pub deletion_rate: f64,
     pub crossover_rate: f64,
+    pub crossover_control: RateControl,
+    // Recombination of real-valued genomes; other representations have their own crossover
+    pub real_crossover: RealCrossover,
+    // Learning rate of the log-normal mutation of rates carried by SelfAdaptive genomes
+    pub rate_tau: f64,
+    // Whether the mutation and crossover schedules count generations or evaluations
+    pub schedule_clock: ScheduleClock,
+    pub selection: SelectionMethod,
+    pub truncation_fraction: f64,
+    pub scaling: FitnessScaling,
+    pub sharing: Option<FitnessSharing>,
+    // Applied to the (shared, if sharing is on) fitnesses parents are selected on
+    pub clearing: Option<Clearing>,
+    // Groups members into species by genotypic distance; parents are then selected on
+    // fitness shared within each species, before any sharing or clearing
+    pub speciation: Option<Speciation>,
+    pub replacement: Replacement,
+    pub diversity_restoration: Option<DiversityRestoration>,
+    pub duplicates: DuplicatePolicy,
     pub max_iters: usize,
     pub convergence_threshold: f64,
+    pub resamples: usize,
+    pub stopping: StoppingCriteria,
+    pub stats: StatsConfig,
+    // Writes the run as JSON lines to this file in place of the console status lines
+    pub log_path: Option<String>,
+    // Seed the caller's RNG was created from, recorded in the run log for replay
+    pub seed: Option<u64>,
+    // Writes a CSV of per-generation metrics to this file, alongside the other output
+    pub trace_path: Option<String>,
 }
 
-// Creates a population of random bitstrings with specified size and member length
-fn init_population(params: &GAParameters, rng: &mut ChaCha8Rng) -> Vec<String> {
+impl Default for GAParameters {
+    fn default() -> Self {
+        Self {
+            pop_size: 100,
+            mem_size: 160,
+            num_dims: 10,
+            mutation_rate: 0.01,
+            mutation_control: RateControl::Constant,
+            integer_mutation: IntegerMutation::Uniform,
+            insertion_rate: 0.05,
+            deletion_rate: 0.05,
+            crossover_rate: 0.75,
+            crossover_control: RateControl::Constant,
+            real_crossover: RealCrossover::SinglePoint,
+            rate_tau: 0.2,
+            schedule_clock: ScheduleClock::Generations,
+            selection: SelectionMethod::Tournament { size: 3 },
+            truncation_fraction: 0.5,
+            scaling: FitnessScaling::None,
+            sharing: None,
+            clearing: None,
+            speciation: None,
+            replacement: Replacement::Generational,
+            diversity_restoration: None,
+            duplicates: DuplicatePolicy::Allow,
+            max_iters: 1000,
+            convergence_threshold: 0.95,
+            resamples: 1,
+            stopping: StoppingCriteria::default(),
+            stats: StatsConfig::default(),
+            log_path: None,
+            seed: None,
+            trace_path: None,
+        }
+    }
+}
+
+// Creates a population of random genomes with specified size and member length
+pub fn init_population<G: Genome, R: Rng + ?Sized>(
+    params: &GAParameters,
+    bounds: (f64, f64),
+    rng: &mut R,
+) -> Vec<G> {
     let mut population = Vec::new();
     for _ in 0..params.pop_size {
-        let mut member: String = String::new();
-        for _ in 0..params.mem_size {
-            let bit = if rng.random() { '1' } else { '0' };
-            member.push(bit);
-        }
-        population.push(member);
+        population.push(G::random(params.mem_size, bounds, rng));
     }
     population
 }
 
-// Given a bitstring, flips each bit with a probability equal to mutation_rate
-fn mutate(bitstring: &str, mutation_rate: f64, rng: &mut ChaCha8Rng) -> String {
+// Given a bitstring, flips each bit with a probability equal to mutation_rate. Also returns
+// the positions of the flipped bits.
+fn mutate<R: Rng + ?Sized>(
+    bitstring: &str,
+    mutation_rate: f64,
+    rng: &mut R,
+) -> (String, Vec<usize>) {
     let mut mutated = String::new();
-    for c in bitstring.chars() {
+    let mut flipped = Vec::new();
+    for (i, c) in bitstring.chars().enumerate() {
         let random: f64 = rng.random();
         let bit = if random < mutation_rate {
+            flipped.push(i);
             if c == '1' { '0' } else { '1' }
         } else {
             c
         };
         mutated.push(bit);
     }
-    mutated
+    (mutated, flipped)
 }
 
 // Perform single point crossover on parents. Becuase we are storing these as strings, we
 // can use string formmatting to do this in a straightforward manner.
-fn crossover(
+fn crossover<R: Rng + ?Sized>(
     parent1: &str,
     parent2: &str,
     crossover_rate: f64,
-    rng: &mut ChaCha8Rng,
+    rng: &mut R,
 ) -> (String, String) {
     // Basic error checking for parent lengths
     if parent1.len() != parent2.len() {
@@ -79,172 +181,792 @@ fn crossover(
     (offspring1, offspring2)
 }
 
-// Tournament selection
-fn tournament_selection(
-    population: &[String],
-    num_dims: usize,
-    fitness_fn: &impl Fitness,
-    tournament_size: usize,
-    rng: &mut ChaCha8Rng,
-) -> String {
-    let mut best_individual = String::new();
-    let mut best_fitness = f64::MIN;
+// Bitstrings of '0'/'1' characters, decoded by the fitness function
+impl Genome for String {
+    fn random<R: Rng + ?Sized>(len: usize, _bounds: (f64, f64), rng: &mut R) -> Self {
+        let mut member: String = String::new();
+        for _ in 0..len {
+            let bit = if rng.random() { '1' } else { '0' };
+            member.push(bit);
+        }
+        member
+    }
+
+    fn mutate<R: Rng + ?Sized>(
+        &self,
+        params: &GAParameters,
+        _bounds: (f64, f64),
+        rng: &mut R,
+    ) -> Self {
+        mutate(self, params.mutation_rate, rng).0
+    }
+
+    fn mutate_with_loci<R: Rng + ?Sized>(
+        &self,
+        params: &GAParameters,
+        _bounds: (f64, f64),
+        rng: &mut R,
+    ) -> (Self, Option<Vec<usize>>) {
+        let (mutated, flipped) = mutate(self, params.mutation_rate, rng);
+        (mutated, Some(flipped))
+    }
+
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        other: &Self,
+        crossover_rate: f64,
+        rng: &mut R,
+    ) -> (Self, Self) {
+        crossover(self, other, crossover_rate, rng)
+    }
 
-    // Randomly select tournament_size individuals and pick the best one
-    for _ in 0..tournament_size {
-        let random_index = rng.random_range(0..population.len());
-        let individual = &population[random_index];
-        let fitness = fitness_fn.fitness_bitstring(individual, num_dims);
+    // Flips the characters of the existing string, drawing as mutate does
+    fn mutate_in_place<R: Rng + ?Sized>(
+        &mut self,
+        params: &GAParameters,
+        _bounds: (f64, f64),
+        rng: &mut R,
+    ) {
+        let mut bits = std::mem::take(self).into_bytes();
+        for bit in &mut bits {
+            let random: f64 = rng.random();
+            if random < params.mutation_rate {
+                *bit = if *bit == b'1' { b'0' } else { b'1' };
+            }
+        }
+        *self = String::from_utf8(bits).expect("bitstrings are ASCII");
+    }
 
-        if fitness > best_fitness {
-            best_fitness = fitness;
-            best_individual = individual.clone();
+    // Copies the parents' slices into the offspring strings, drawing as crossover does
+    fn crossover_into<R: Rng + ?Sized>(
+        &self,
+        other: &Self,
+        params: &GAParameters,
+        rng: &mut R,
+        (offspring1, offspring2): (&mut Self, &mut Self),
+    ) {
+        if self.len() != other.len() {
+            panic!("Parents must be of the same length");
         }
+        let random: f64 = rng.random();
+        let crossover_point = if random >= params.crossover_rate {
+            self.len()
+        } else {
+            rng.random_range(1..self.len())
+        };
+        offspring1.clear();
+        offspring1.push_str(&self[..crossover_point]);
+        offspring1.push_str(&other[crossover_point..]);
+        offspring2.clear();
+        offspring2.push_str(&other[..crossover_point]);
+        offspring2.push_str(&self[crossover_point..]);
+    }
+
+    fn length(&self) -> usize {
+        self.len()
+    }
+
+    // Hamming distance
+    fn distance(&self, other: &Self) -> f64 {
+        self.bytes().zip(other.bytes()).filter(|(a, b)| a != b).count() as f64
+    }
+
+    fn fingerprint(&self) -> u64 {
+        hash_of(self)
+    }
+
+    fn phenotype(&self, decoder: &impl Decoder, num_dims: usize) -> Vec<f64> {
+        decoder.decode_bitstring(self, num_dims)
+    }
+
+    fn from_phenotype(phenotype: &[f64], decoder: &impl Decoder, len: usize) -> Option<Self> {
+        decoder.encode_bitstring(phenotype, len)
     }
 
-    best_individual
+    fn repair(&mut self, repair: &impl Repair) -> bool {
+        repair.repair_bitstring(self)
+    }
 }
 
-// Tournament selection for two parents
-fn parent_selection(
-    population: &[String],
+// Fitness of a genome, averaged over params.resamples evaluations drawing any noise from
+// stream
+pub fn evaluate<G: Genome>(
+    genome: &G,
+    fitness_fn: &impl Fitness,
     num_dims: usize,
+    resamples: usize,
+    stream: SeedSequence,
+) -> f64 {
+    let phenotype = genome.phenotype(&fitness_fn.decoder(), num_dims);
+    fitness_fn.evaluate_population_seeded_resampled(&[phenotype], &[stream], resamples)[0]
+}
+
+// Fitness of every member, evaluated once per generation as one batch and shared by the
+// statistics, convergence check and parent selection. Member i draws any noise from
+// seeds.individual(i).
+pub fn evaluate_population<G: Genome>(
+    population: &[G],
     fitness_fn: &impl Fitness,
-    rng: &mut ChaCha8Rng,
-) -> (String, String) {
-    let tournament_size = 3; // Common tournament size, can be adjusted
+    num_dims: usize,
+    resamples: usize,
+    seeds: SeedSequence,
+) -> Vec<f64> {
+    let decoder = fitness_fn.decoder();
+    let phenotypes: Vec<Vec<f64>> =
+        population.iter().map(|member| member.phenotype(&decoder, num_dims)).collect();
+    let streams = seeds.individuals(phenotypes.len());
+    fitness_fn.evaluate_population_seeded_resampled(&phenotypes, &streams, resamples)
+}
 
-    let parent1 = tournament_selection(population, num_dims, fitness_fn, tournament_size, rng);
-    let parent2 = tournament_selection(population, num_dims, fitness_fn, tournament_size, rng);
+// Parent selector for the generation, built from the population's fitness (shared first
+// when fitness sharing is on, then cleared when clearing is)
+fn parent_selector<G: Genome, R: Rng + ?Sized>(
+    population: &[G],
+    fitnesses: &[f64],
+    params: &GAParameters,
+    fitness_fn: &impl Fitness,
+    rng: &mut R,
+) -> ParentSelector {
+    let shared = match &params.sharing {
+        Some(sharing) => {
+            sharing.shared_fitnesses(population, fitnesses, fitness_fn, params.num_dims)
+        }
+        None => fitnesses.to_vec(),
+    };
+    let selected_on = match &params.clearing {
+        Some(clearing) => {
+            clearing.cleared_fitnesses(population, &shared, fitness_fn, params.num_dims)
+        }
+        None => shared,
+    };
+    ParentSelector::new(params, &selected_on, rng)
+}
 
-    (parent1, parent2)
+// Population statistics for one generation
+struct PopulationStats {
+    max_fitness: f64,
+    avg_fitness: f64,
+    std_fitness: f64,
+    // Fraction of individuals that have an identical copy in the population
+    pct_identical: Option<f64>,
+    // Largest euclidean distance between two individuals in decoded space
+    diversity: Option<f64>,
+    best_index: usize,
 }
 
-// Calculate population statistics
-fn calculate_stats(
-    population: &[String],
+// Statistics over the generation's precomputed fitnesses in one pass, plus the metrics
+// `config` turns on; `rng` draws the pairs when diversity is sampled
+fn calculate_stats<G: Genome, R: Rng + ?Sized>(
+    population: &[G],
+    fitnesses: &[f64],
     fitness_fn: &impl Fitness,
     num_dims: usize,
-) -> (f64, f64, f64, f64) {
-    let fitnesses: Vec<f64> = population
-        .iter()
-        .map(|m| fitness_fn.fitness_bitstring(m, num_dims))
-        .collect();
-
-    let max_fitness = fitnesses.iter().cloned().fold(0.0, f64::max);
-    let avg_fitness = fitnesses.iter().sum::<f64>() / population.len() as f64;
-
-    // Calculate percentage of identical individuals
-    let mut unique_count = 0;
-    for (i, member1) in population.iter().enumerate() {
-        let mut is_unique = true;
-        for (j, member2) in population.iter().enumerate() {
-            if i != j && member1 == member2 {
-                is_unique = false;
-                break;
-            }
-        }
-        if is_unique {
-            unique_count += 1;
-        }
-    }
-    let pct_identical = (population.len() - unique_count) as f64 / population.len() as f64;
+    config: StatsConfig,
+    rng: &mut R,
+) -> PopulationStats {
+    let running = RunningStats::of(fitnesses);
+    let (best_index, max_fitness) = running.max().unwrap_or((0, 0.0));
+    let max_fitness = max_fitness.max(0.0);
+    let (avg_fitness, std_fitness) = (running.mean(), running.std());
+
+    let pct_identical = config.identity.then(|| identical_fraction(population));
+    let diversity = config.diversity.then(|| {
+        // Decode each member once rather than once per pair
+        let decoder = fitness_fn.decoder();
+        let decoded: Vec<Vec<f64>> =
+            population.iter().map(|m| m.phenotype(&decoder, num_dims)).collect();
+        max_pairwise_distance(&decoded, config.diversity_pairs, rng)
+    });
+
+    PopulationStats { max_fitness, avg_fitness, std_fitness, pct_identical, diversity, best_index }
+}
 
-    // Calculate diversity as max euclidean distance in decoded space
-    let mut diversity = 0.0;
-    for i in 0..population.len() {
-        for j in (i + 1)..population.len() {
-            let decoded1 = fitness_fn.decode_bitstring(&population[i], num_dims);
-            let decoded2 = fitness_fn.decode_bitstring(&population[j], num_dims);
-            let mut dist_sq = 0.0;
-            for k in 0..num_dims {
-                dist_sq += (decoded1[k] - decoded2[k]).powi(2);
-            }
-            let dist = dist_sq.sqrt();
-            if dist > diversity {
-                diversity = dist;
-            }
-        }
+// Fraction of individuals that have an identical copy in the population, counted by
+// fingerprint; two different genomes sharing a 64-bit hash are rare enough to ignore
+fn identical_fraction<G: Genome>(population: &[G]) -> f64 {
+    let mut copies: HashMap<u64, usize> = HashMap::with_capacity(population.len());
+    for member in population {
+        *copies.entry(member.fingerprint()).or_default() += 1;
     }
+    let unique_count = copies.values().filter(|&&count| count == 1).count();
+    (population.len() - unique_count) as f64 / population.len() as f64
+}
 
-    (max_fitness, avg_fitness, pct_identical, diversity)
+// Minimum, average, and maximum genome length in the population
+fn length_stats<G: Genome>(population: &[G]) -> (usize, f64, usize) {
+    let lengths: Vec<usize> = population.iter().map(|m| m.length()).collect();
+    let min_len = lengths.iter().copied().min().unwrap_or(0);
+    let max_len = lengths.iter().copied().max().unwrap_or(0);
+    let avg_len = lengths.iter().sum::<usize>() as f64 / lengths.len().max(1) as f64;
+    (min_len, avg_len, max_len)
 }
 
 // Check for convergence (pct identical individuals exceeds threshold or avg fitness exceeds threshold)
-fn check_convergence(
-    population: &[String],
-    fitness_fn: &impl Fitness,
-    num_dims: usize,
-    threshold: f64,
-) -> bool {
-    let (_, avg_fitness, pct_identical, _) = calculate_stats(population, fitness_fn, num_dims);
-    if avg_fitness >= threshold {
+// The identical-copies test only applies when identity statistics are being collected
+fn check_convergence(stats: &PopulationStats, threshold: f64) -> bool {
+    if stats.avg_fitness >= threshold {
         return true;
     }
-    pct_identical >= threshold
+    stats.pct_identical.is_some_and(|pct_identical| pct_identical >= threshold)
 }
 
-pub fn sga(
+pub fn sga<R: Rng + ?Sized>(
     fitness_fn: &impl Fitness,
     params: &GAParameters,
-    rng: &mut ChaCha8Rng,
+    rng: &mut R,
 ) -> Vec<String> {
-    // Initialize population
-    let mut population = init_population(params, rng);
-    let mut cumulative_evals = 0;
-
-    // Print algorithm parameters
-    println!(
-        "Running Dejong Rosenbrock GA with Pop={} MemberSize={} Mutation={} Crossover={}",
-        params.pop_size, params.mem_size, params.mutation_rate, params.crossover_rate
-    );
+    ga(fitness_fn, params, &NoRepair, rng)
+}
+
+// SGA where every offspring is passed through a repair operator after crossover and mutation
+pub fn sga_with_repair<R: Rng + ?Sized>(
+    fitness_fn: &impl Fitness,
+    params: &GAParameters,
+    repair: &impl Repair,
+    rng: &mut R,
+) -> Vec<String> {
+    ga(fitness_fn, params, repair, rng)
+}
+
+// Generational GA over any genome representation. mem_size is the genome length, and
+// num_dims is passed to the fitness function when decoding.
+pub fn ga<G: Genome, R: Rng + ?Sized>(
+    fitness_fn: &impl Fitness,
+    params: &GAParameters,
+    repair: &impl Repair,
+    rng: &mut R,
+) -> Vec<G> {
+    GeneticAlgorithm::new(fitness_fn, params, repair, rng).run(rng)
+}
+
+// State of a GA run between generations, advanced through the Algorithm trait
+pub struct GeneticAlgorithm<'a, G: Genome, F: Fitness, P: Repair> {
+    fitness_fn: &'a F,
+    params: &'a GAParameters,
+    repair: &'a P,
+    bounds: (f64, f64),
+    population: Population<G>,
+    // The previous generation, kept to be refilled with the next one's offspring so the
+    // population's buffers aren't reallocated every generation
+    offspring: Population<G>,
+    // Genomes retired from the previous generation, overwritten by crossover as children
+    spare: Vec<G>,
+    generation: usize,
+    cumulative_evals: usize,
+    repair_stats: RepairStats,
+    // Offspring that came out identical to a parent and took its fitness
+    unchanged_offspring: usize,
+    monitor: StopMonitor,
+    mutation_controller: RateController,
+    crossover_controller: RateController,
+    diversity_monitor: DiversityMonitor,
+    duplicate_filter: DuplicateFilter,
+    cancellation: CancellationToken,
+    // Cache counts when the run started, as the cache may outlive it
+    cache_start: Option<CacheStats>,
+    observers: Vec<Box<dyn Observer<G> + 'a>>,
+    best_individual: Option<G>,
+    // Constraints on the decoded members, if any
+    constraints: Option<&'a dyn Constrained>,
+    species: Option<SpeciesSet<G>>,
+    timer: OperatorTimer,
+    // Draws the pairs sampled for diversity, apart from the run's own stream so sampling
+    // doesn't change the run
+    diversity_rng: ChaCha8Rng,
+    // Streams of the evaluations, one per generation and member, so noise doesn't depend on
+    // the order or threads members are evaluated on
+    evaluation_seeds: SeedSequence,
+}
+
+impl<'a, G: Genome, F: Fitness, P: Repair> GeneticAlgorithm<'a, G, F, P> {
+    pub fn new<R: Rng + ?Sized>(
+        fitness_fn: &'a F,
+        params: &'a GAParameters,
+        repair: &'a P,
+        rng: &mut R,
+    ) -> Self {
+        // Initialize population
+        let bounds = fitness_fn.bounds();
+        let population = Population::new(init_population(params, bounds, rng));
+
+        let mut observers = vec![status_observer(
+            params.log_path.as_deref(),
+            "GA",
+            ParameterGrid::params_to_map_ga(params),
+            params.seed,
+            ConsoleObserver::ga(params),
+        )];
+        if let Some(trace) = trace_observer(params.trace_path.as_deref()) {
+            observers.push(Box::new(trace));
+        }
 
-    for gen_number in 0..params.max_iters {
-        // Calculate and print statistics
-        let (max_fitness, avg_fitness, _, diversity) =
-            calculate_stats(&population, fitness_fn, params.mem_size / 2);
-        cumulative_evals += params.pop_size;
-        println!(
-            "Dejong Rosenbrock GA {} {} {} {} {} {} {} {} {}",
-            params.pop_size, params.pop_size, params.mutation_rate, params.crossover_rate, gen_number, cumulative_evals, max_fitness, avg_fitness, diversity
+        Self {
+            fitness_fn,
+            params,
+            repair,
+            bounds,
+            offspring: Population::with_capacity(params.pop_size),
+            spare: Vec::with_capacity(params.pop_size),
+            population,
+            generation: 0,
+            cumulative_evals: 0,
+            repair_stats: RepairStats::default(),
+            unchanged_offspring: 0,
+            monitor: StopMonitor::new(&params.stopping, fitness_fn.optimum_value()),
+            mutation_controller: rate_controller(
+                params.mutation_control,
+                params.mutation_rate,
+                params,
+            ),
+            crossover_controller: rate_controller(
+                params.crossover_control,
+                params.crossover_rate,
+                params,
+            ),
+            diversity_monitor: DiversityMonitor::new(params.diversity_restoration),
+            duplicate_filter: DuplicateFilter::new(params.duplicates, params.pop_size),
+            cancellation: CancellationToken::never(),
+            cache_start: fitness_fn.cache_stats(),
+            observers,
+            best_individual: None,
+            constraints: None,
+            species: params.speciation.map(SpeciesSet::new),
+            timer: OperatorTimer::new(params.stats.timing),
+            diversity_rng: SeedSequence::new(params.seed.unwrap_or_default())
+                .operator("diversity")
+                .rng(),
+            evaluation_seeds: SeedSequence::new(params.seed.unwrap_or_default())
+                .operator("evaluation"),
+        }
+    }
+
+    // Stops the run at the next check once the token is cancelled
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    // Replaces part of the random initial population with known individuals, e.g. a previous
+    // run's final population: round(fraction * pop_size) members, or as many seeds as there
+    // are, taken from the front of `seeds`. Seeds of the wrong length are skipped. The random
+    // members are drawn either way, so the RNG stream doesn't depend on the seeds.
+    pub fn with_seeds(mut self, seeds: &[G], fraction: f64) -> Self {
+        let count = (fraction.clamp(0.0, 1.0) * self.population.len() as f64).round() as usize;
+        let mem_size = self.params.mem_size;
+        let valid: Vec<&G> = seeds
+            .iter()
+            .filter(|seed| G::VARIABLE_LENGTH || seed.length() == mem_size)
+            .collect();
+        let seeded = count.min(valid.len());
+        for (i, &seed) in valid.iter().take(seeded).enumerate() {
+            self.population.replace(i, seed.clone());
+        }
+        if !seeds.is_empty() {
+            let skipped = seeds.len() - valid.len();
+            let message = format!(
+                "Seeded {seeded} of {} initial members; {skipped} seeds had the wrong length",
+                self.population.len()
+            );
+            self.report(&message);
+        }
+        self
+    }
+
+    // Decides tournament selection by Deb's feasibility rules on the decoded members and
+    // reports violation statistics each generation
+    pub fn with_constraints(mut self, constraints: &'a dyn Constrained) -> Self {
+        self.constraints = Some(constraints);
+        self
+    }
+
+    // Registers an observer alongside the console output
+    pub fn with_observer(mut self, observer: impl Observer<G> + 'a) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    // Removes all observers, including the console output, for runs driven in bulk
+    pub fn quiet(mut self) -> Self {
+        self.observers.clear();
+        self
+    }
+
+    // The generation the next step evaluates, for callers that draw the run as it goes
+    pub fn population(&self) -> &[G] {
+        self.population.members()
+    }
+
+    // This run's lookups in the problem's fitness cache, if it has one
+    fn cache_stats(&self) -> Option<CacheStats> {
+        self.fitness_fn.cache_stats().map(|stats| stats.since(self.cache_start))
+    }
+
+    // Passes an operator report to every observer
+    fn report(&mut self, message: &str) {
+        for observer in &mut self.observers {
+            observer.on_message(message);
+        }
+    }
+}
+
+impl<G: Genome, F: Fitness, P: Repair> Algorithm for GeneticAlgorithm<'_, G, F, P> {
+    type Individual = G;
+
+    fn step<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<StopReason> {
+        let (fitness_fn, params, bounds) = (self.fitness_fn, self.params, self.bounds);
+        let gen_number = self.generation;
+        if gen_number >= params.max_iters {
+            return Some(StopReason::MaxGenerations);
+        }
+        if self.cancellation.is_cancelled() {
+            return Some(StopReason::Cancelled);
+        }
+        self.timer.start_generation();
+
+        // Evaluate the members whose fitness isn't known yet, then calculate statistics and
+        // pass them to the observers
+        let started = self.timer.start();
+        let seeds = self.evaluation_seeds.generation(gen_number);
+        let (num_dims, resamples) = (params.num_dims, params.resamples);
+        let evaluated =
+            self.population.evaluate(fitness_fn, num_dims, resamples, seeds, &self.cancellation);
+        self.cumulative_evals += evaluated.unwrap_or_else(|partial| partial) * resamples;
+        if evaluated.is_err() {
+            return Some(StopReason::Cancelled);
+        }
+        let fitnesses = self.population.fitnesses().to_vec();
+        self.timer.stop(Phase::Evaluation, started);
+        let started = self.timer.start();
+        let stats_config = StatsConfig {
+            // Diversity restoration reports and triggers on identical copies
+            identity: params.stats.identity || params.diversity_restoration.is_some(),
+            ..params.stats
+        };
+        let population_stats = calculate_stats(
+            self.population.members(),
+            &fitnesses,
+            fitness_fn,
+            params.num_dims,
+            stats_config,
+            &mut self.diversity_rng,
         );
+        let max_fitness = population_stats.max_fitness;
+        let violations = self.constraints.map(|constraints| {
+            let decoder = fitness_fn.decoder();
+            self.population
+                .members()
+                .iter()
+                .map(|member| member.phenotype(&decoder, params.num_dims))
+                .map(|phenotype| constraints.total_violation(&phenotype))
+                .collect::<Vec<f64>>()
+        });
+        let stats = GenerationStats {
+            generation: gen_number,
+            evaluations: self.cumulative_evals,
+            max_fitness,
+            avg_fitness: population_stats.avg_fitness,
+            std_fitness: population_stats.std_fitness,
+            diversity: population_stats.diversity,
+            cache: self.cache_stats(),
+            boundary_hits: None,
+            violations: violations.as_deref().map(ViolationStats::of),
+            qd: None,
+        };
+        let best = &self.population.members()[population_stats.best_index];
+        let improved = max_fitness > self.monitor.best_fitness;
+        for observer in &mut self.observers {
+            observer.on_generation(&stats, best);
+        }
+        if improved {
+            self.best_individual = Some(best.clone());
+            for observer in &mut self.observers {
+                observer.on_improvement(&stats, best);
+            }
+        }
+        self.timer.stop(Phase::Statistics, started);
+        if G::VARIABLE_LENGTH {
+            let (min_len, avg_len, max_len) = length_stats(self.population.members());
+            self.report(&format!("Genome length min={min_len} avg={avg_len:.2} max={max_len}"));
+        }
+        if !self.mutation_controller.is_constant() {
+            self.report(&format!("Mutation rate control: {}", self.mutation_controller.describe()));
+        }
+        if !self.crossover_controller.is_constant() {
+            let description = self.crossover_controller.describe();
+            self.report(&format!("Crossover rate control: {description}"));
+        }
 
-        // Check for convergence
-        if check_convergence(&population, fitness_fn, params.mem_size / 2, params.convergence_threshold) {
-            println!("Converged at generation {gen_number}");
-            return population;
+        // Check for convergence, then the stagnation and target rules
+        let monitor_stop = self.monitor.update(max_fitness);
+        if check_convergence(&population_stats, params.convergence_threshold) {
+            return Some(StopReason::Converged);
+        }
+        if monitor_stop.is_some() {
+            return monitor_stop;
         }
 
-        // Create new generation
-        let mut new_population = Vec::new();
+        // Restore diversity if the population has stagnated or filled with copies
+        let pct_identical = population_stats.pct_identical.unwrap_or(0.0);
+        if self.diversity_monitor.update(self.monitor.stagnant_generations, pct_identical) {
+            self.report(&format!(
+                "Diversity restoration at generation {gen_number} ({:.2}% identical): {}",
+                pct_identical * 100.0,
+                self.diversity_monitor.describe()
+            ));
+        }
 
-        // Generate offspring pairs until we have a full new population
+        // Speciate, so parents are selected on fitness shared within each species
+        let species_fitnesses = self.species.as_mut().map(|species| {
+            species.speciate(self.population.members(), &fitnesses, |a, b| a.distance(b));
+            species.shared_fitnesses(&fitnesses)
+        });
+        if let Some(species) = &self.species {
+            let description = species.describe();
+            self.report(&format!("Speciation: {description}"));
+        }
+
+        // Create new generation, mutating with the controlled (and possibly boosted) rate
+        let population = self.population.members();
+        let mut new_population =
+            std::mem::replace(&mut self.offspring, Population::new(Vec::new()));
+        new_population.clear_into(&mut self.spare);
+        let gen_params = GAParameters {
+            mutation_rate: self.diversity_monitor.mutation_rate(self.mutation_controller.value()),
+            crossover_rate: self.crossover_controller.value(),
+            ..params.clone()
+        };
+        let selected_on = species_fitnesses.as_deref().unwrap_or(&fitnesses);
+        let started = self.timer.start();
+        let mut selector = parent_selector(population, selected_on, params, fitness_fn, rng);
+        if let Some(violations) = violations {
+            selector = selector.with_violations(violations);
+        }
+        self.timer.stop(Phase::Selection, started);
+
+        // Generate offspring pairs until we have a full new population; children evaluated
+        // during reproduction draw from streams of their own
+        let offspring_seeds = self.evaluation_seeds.generation(gen_number).operator("offspring");
+        let mut pairs = 0;
         while new_population.len() < params.pop_size {
-            // Select parents
-            let (parent1, parent2) = parent_selection(&population, params.mem_size / 2, fitness_fn, rng);
+            if self.cancellation.is_cancelled() {
+                return Some(StopReason::Cancelled);
+            }
 
-            // Crossover
-            let (mut child1, mut child2) = crossover(&parent1, &parent2, params.crossover_rate, rng);
+            // Select parents
+            let started = self.timer.start();
+            let (index1, index2) = (selector.next(rng), selector.next(rng));
+            let (parent1, parent2) = (&population[index1], &population[index2]);
+            self.timer.stop(Phase::Selection, started);
+
+            // Crossover, into retired genomes while there are any
+            let started = self.timer.start();
+            let mut child1 = self.spare.pop().unwrap_or_else(|| parent1.clone());
+            let mut child2 = self.spare.pop().unwrap_or_else(|| parent2.clone());
+            parent1.crossover_into(parent2, &gen_params, rng, (&mut child1, &mut child2));
+            self.timer.stop(Phase::Crossover, started);
 
             // Mutation
-            child1 = mutate(&child1, params.mutation_rate, rng);
-            child2 = mutate(&child2, params.mutation_rate, rng);
+            let started = self.timer.start();
+            child1.mutate_in_place(&gen_params, bounds, rng);
+            child2.mutate_in_place(&gen_params, bounds, rng);
+            self.timer.stop(Phase::Mutation, started);
+
+            // Repair
+            self.repair_stats.record(child1.repair(self.repair));
+            self.repair_stats.record(child2.repair(self.repair));
+
+            // Children evaluated during reproduction join the new population with their
+            // fitness. A child that crossover, mutation and repair all left as a copy of a
+            // parent already has one: the parent's.
+            let mut child_fitnesses = [&child1, &child2].map(|child| {
+                if child == parent1 {
+                    Some(fitnesses[index1])
+                } else if child == parent2 {
+                    Some(fitnesses[index2])
+                } else {
+                    None
+                }
+            });
+            self.unchanged_offspring += child_fitnesses.iter().filter(|f| f.is_some()).count();
+            let pair_seeds = [0, 1].map(|child| offspring_seeds.individual(2 * pairs + child));
+            pairs += 1;
+            let evaluate_member = |member: &G, stream| {
+                evaluate(member, fitness_fn, params.num_dims, params.resamples, stream)
+            };
+
+            // Success feedback: did each child beat the better of its parents?
+            if self.mutation_controller.needs_success_feedback()
+                || self.crossover_controller.needs_success_feedback()
+            {
+                let started = self.timer.start();
+                let parent_best = fitnesses[index1].max(fitnesses[index2]);
+                let unevaluated = child_fitnesses.iter().filter(|f| f.is_none()).count();
+                self.cumulative_evals += unevaluated * params.resamples;
+                child_fitnesses = [(&child1, 0), (&child2, 1)].map(|(child, i)| {
+                    let known = child_fitnesses[i];
+                    Some(known.unwrap_or_else(|| evaluate_member(child, pair_seeds[i])))
+                });
+                let improved = child_fitnesses.map(|fitness| fitness > Some(parent_best));
+                for controller in [&mut self.mutation_controller, &mut self.crossover_controller] {
+                    for improved in improved {
+                        controller.record_offspring(improved);
+                    }
+                }
+                self.timer.stop(Phase::Evaluation, started);
+            }
 
-            // Add children to new population
-            new_population.push(child1);
-            if new_population.len() < params.pop_size {
-                new_population.push(child2);
+            // Deterministic crowding: each child must beat its most similar parent to survive,
+            // timed as evaluation since evaluating the children dominates it
+            let children = if let Replacement::DeterministicCrowding { distance } =
+                params.replacement
+            {
+                let started = self.timer.start();
+                let unevaluated = child_fitnesses.iter().filter(|f| f.is_none()).count();
+                self.cumulative_evals += unevaluated * params.resamples;
+                let fitness1 =
+                    child_fitnesses[0].unwrap_or_else(|| evaluate_member(&child1, pair_seeds[0]));
+                let fitness2 =
+                    child_fitnesses[1].unwrap_or_else(|| evaluate_member(&child2, pair_seeds[1]));
+                let survivors = deterministic_crowding(
+                    [(parent1.clone(), fitnesses[index1]), (parent2.clone(), fitnesses[index2])],
+                    [(child1, fitness1), (child2, fitness2)],
+                    |a, b| distance.between(a, b, fitness_fn, params.num_dims),
+                );
+                self.timer.stop(Phase::Evaluation, started);
+                survivors.map(|(member, fitness)| (member, Some(fitness)))
+            } else {
+                [(child1, child_fitnesses[0]), (child2, child_fitnesses[1])]
+            };
+
+            // Add children to new population, subject to the duplicate policy; a re-mutated
+            // child no longer has the fitness it was evaluated with
+            for (child, fitness) in children {
+                if new_population.len() >= params.pop_size {
+                    break;
+                }
+                let remutated = self.duplicate_filter.generation.remutated;
+                let remutate = |member: &G| member.mutate(&gen_params, bounds, rng);
+                let next_generation = new_population.members();
+                if let Some(child) = self.duplicate_filter.admit(child, next_generation, remutate)
+                {
+                    let unchanged = self.duplicate_filter.generation.remutated == remutated;
+                    new_population.push(child, fitness.filter(|_| unchanged));
+                }
             }
         }
+        if self.duplicate_filter.is_active() {
+            let stats = self.duplicate_filter.generation;
+            self.report(&format!(
+                "Duplicate offspring: {} found, {} rejected, {} re-mutated",
+                stats.found, stats.rejected, stats.remutated
+            ));
+            self.duplicate_filter.end_generation();
+        }
 
         // Ensure we have exactly pop_size individuals (handle odd pop_size case)
-        while new_population.len() > params.pop_size {
-            new_population.pop();
+        new_population.truncate(params.pop_size);
+
+        // Random immigrants take the place of randomly chosen offspring
+        let immigrants = self.diversity_monitor.immigrants(params.pop_size);
+        for i in rand::seq::index::sample(rng, params.pop_size, immigrants) {
+            new_population.replace(i, G::random(params.mem_size, bounds, rng));
         }
 
-        // Full replacement: new population replaces old population
-        population = new_population;
+        // New population replaces old population (under crowding it already holds the
+        // surviving parents), and the old one is kept for the next generation's offspring
+        self.offspring = std::mem::replace(&mut self.population, new_population);
+        self.generation += 1;
+        self.mutation_controller.end_generation(self.generation, self.cumulative_evals);
+        self.crossover_controller.end_generation(self.generation, self.cumulative_evals);
+        self.diversity_monitor.end_generation();
+        None
     }
-    println!("Max iterations reached");
-    population
+
+    fn generation(&self) -> usize {
+        self.generation
+    }
+
+    fn best_fitness(&self) -> f64 {
+        self.monitor.best_fitness
+    }
+
+    fn evaluations(&self) -> usize {
+        self.cumulative_evals
+    }
+
+    fn finish(mut self, reason: StopReason) -> Vec<G> {
+        let summary = RunSummary {
+            reason,
+            generations: self.generation,
+            evaluations: self.cumulative_evals,
+            best_fitness: self.monitor.best_fitness,
+            stagnant_generations: self.monitor.stagnant_generations,
+        };
+        for observer in &mut self.observers {
+            observer.on_termination(&summary, self.best_individual.as_ref());
+        }
+        if let Some(report) = self.repair_stats.report() {
+            self.report(&report);
+        }
+        if self.unchanged_offspring > 0 {
+            let saved = self.unchanged_offspring * self.params.resamples;
+            self.report(&format!(
+                "Unchanged offspring: {} copies of a parent took its fitness instead of being \
+                 evaluated ({saved} evaluations saved)",
+                self.unchanged_offspring
+            ));
+        }
+        if let Some(cache) = self.cache_stats() {
+            self.report(&format!("Fitness cache: {cache}"));
+        }
+        if self.duplicate_filter.is_active() {
+            let total = self.duplicate_filter.total;
+            self.report(&format!(
+                "Duplicate offspring over the run: {} found, {} rejected, {} re-mutated",
+                total.found, total.rejected, total.remutated
+            ));
+        }
+        if self.diversity_monitor.activations > 0 {
+            let activations = self.diversity_monitor.activations;
+            self.report(&format!("Diversity restoration fired {activations} times"));
+        }
+        if let Some(report) = self.timer.finish() {
+            self.report(&report);
+        }
+        self.population.into_members()
+    }
+}
+
+// Controller for one of the GA's rates, on the clock the parameters ask for
+fn rate_controller(control: RateControl, initial: f64, params: &GAParameters) -> RateController {
+    let controller = RateController::new(control, initial, params.max_iters);
+    match params.schedule_clock {
+        ScheduleClock::Generations => controller,
+        ScheduleClock::Evaluations => {
+            controller.on_evaluations(params.max_iters * params.pop_size * params.resamples)
+        }
+    }
+}
+
+// One plain generational step (selection, crossover, mutation) without the rate control,
+// repair and diversity machinery of `ga`, for runners that drive the loop themselves
+pub fn next_generation<G: Genome, R: Rng + ?Sized>(
+    population: &[G],
+    fitnesses: &[f64],
+    params: &GAParameters,
+    fitness_fn: &impl Fitness,
+    rng: &mut R,
+) -> Vec<G> {
+    let bounds = fitness_fn.bounds();
+    let mut selector = parent_selector(population, fitnesses, params, fitness_fn, rng);
+    let mut new_population = Vec::with_capacity(params.pop_size);
+    while new_population.len() < params.pop_size {
+        let (parent1, parent2) = (&population[selector.next(rng)], &population[selector.next(rng)]);
+        let (child1, child2) = parent1.crossover_with_params(parent2, params, rng);
+        new_population.push(child1.mutate(params, bounds, rng));
+        if new_population.len() < params.pop_size {
+            new_population.push(child2.mutate(params, bounds, rng));
+        }
+    }
+    new_population
 }
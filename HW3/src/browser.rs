@@ -0,0 +1,142 @@
+use crate::algorithm::Algorithm;
+use crate::bitstring::{GAParameters, GeneticAlgorithm};
+use crate::decoder::DEFAULT_BITS_PER_DIM;
+use crate::evol_strat::{ESParameters, EvolutionStrategy};
+use crate::fitness::{Fitness, Objective};
+use crate::genome::Genome;
+use crate::himmelblau::Himmelblau;
+use crate::repair::NoRepair;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::sync::LazyLock;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// GA and ES runs on Himmelblau advanced one generation per call, for a web page that animates
+// the population converging on the four optima: it calls step once per frame and redraws
+// points. Only the core runs here, no threads, file IO or clock, so this works in a browser;
+// the wasm feature exports both types to JavaScript through wasm-bindgen.
+
+const NUM_DIMS: usize = 2;
+const GENERATIONS: usize = 200;
+
+// Parameters live as long as the runs borrowing them, which JavaScript may keep indefinitely
+static GA_PARAMS: LazyLock<GAParameters> = LazyLock::new(|| GAParameters {
+    pop_size: 60,
+    mem_size: DEFAULT_BITS_PER_DIM * NUM_DIMS,
+    num_dims: NUM_DIMS,
+    max_iters: GENERATIONS,
+    ..GAParameters::default()
+});
+
+static ES_PARAMS: LazyLock<ESParameters> = LazyLock::new(|| ESParameters {
+    mu: 15,
+    lambda: 60,
+    mem_size: NUM_DIMS,
+    mem_range: Himmelblau.bounds(),
+    tau: 1.0 / (2.0 * NUM_DIMS as f64).sqrt(),
+    max_gens: GENERATIONS,
+    ..ESParameters::default()
+});
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub struct HimmelblauGa {
+    ga: GeneticAlgorithm<'static, String, Himmelblau, NoRepair>,
+    rng: ChaCha8Rng,
+    stopped: bool,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl HimmelblauGa {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(seed: u32) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed.into());
+        let ga = GeneticAlgorithm::new(&Himmelblau, &GA_PARAMS, &NoRepair, &mut rng).quiet();
+        Self { ga, rng, stopped: false }
+    }
+
+    // Runs one generation, false once the run has stopped; later calls do nothing
+    pub fn step(&mut self) -> bool {
+        self.stopped = self.stopped || self.ga.step(&mut self.rng).is_some();
+        !self.stopped
+    }
+
+    pub fn generation(&self) -> usize {
+        self.ga.generation()
+    }
+
+    pub fn best_fitness(&self) -> f64 {
+        self.ga.best_fitness()
+    }
+
+    // The decoded population as x0, y0, x1, y1, ...
+    pub fn points(&self) -> Vec<f64> {
+        let decoder = Himmelblau.decoder();
+        let members = self.ga.population().iter();
+        members.flat_map(|member| member.phenotype(&decoder, NUM_DIMS)).collect()
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub struct HimmelblauEs {
+    es: EvolutionStrategy<'static, Himmelblau, NoRepair>,
+    rng: ChaCha8Rng,
+    stopped: bool,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl HimmelblauEs {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new(seed: u32) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed.into());
+        let es = EvolutionStrategy::new(&Himmelblau, &ES_PARAMS, &NoRepair, &mut rng).quiet();
+        Self { es, rng, stopped: false }
+    }
+
+    // Runs one generation, false once the run has stopped; later calls do nothing
+    pub fn step(&mut self) -> bool {
+        self.stopped = self.stopped || self.es.step(&mut self.rng).is_some();
+        !self.stopped
+    }
+
+    pub fn generation(&self) -> usize {
+        self.es.generation()
+    }
+
+    pub fn best_fitness(&self) -> f64 {
+        self.es.best_fitness()
+    }
+
+    // The parents' positions as x0, y0, x1, y1, ..., without their step sizes
+    pub fn points(&self) -> Vec<f64> {
+        let members = self.es.population().iter();
+        members.flat_map(|member| member[..NUM_DIMS].to_vec()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ga_steps_to_an_optimum() {
+        let mut ga = HimmelblauGa::new(5000);
+        while ga.step() {
+            assert_eq!(ga.points().len(), GA_PARAMS.pop_size * NUM_DIMS);
+        }
+        assert!(!ga.step());
+        assert!((1..=GENERATIONS).contains(&ga.generation()));
+        assert!(ga.best_fitness() > 0.99);
+    }
+
+    #[test]
+    fn es_steps_to_an_optimum() {
+        let mut es = HimmelblauEs::new(5000);
+        while es.step() {
+            assert_eq!(es.points().len(), ES_PARAMS.mu * NUM_DIMS);
+        }
+        assert!(!es.step());
+        assert!((1..=GENERATIONS).contains(&es.generation()));
+        assert!(es.best_fitness() > 0.99);
+    }
+}
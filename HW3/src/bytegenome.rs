@@ -0,0 +1,166 @@
+use crate::bitstring::GAParameters;
+use crate::decoder::{Decoder, Encoding, gray_to_binary};
+use crate::genome::{Genome, hash_of};
+use rand::prelude::*;
+
+// Bitstring held one bit per byte (0 or 1). Mutation flips bytes of a copy in place and
+// crossover copies slices, so neither builds a string character by character. Both draw from
+// the RNG exactly as the '0'/'1' String genome does, so a run evolves the same population.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ByteGenome(pub Vec<u8>);
+
+impl ByteGenome {
+    pub fn from_bitstring(bitstring: &str) -> Self {
+        ByteGenome(bitstring.bytes().map(|c| u8::from(c == b'1')).collect())
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    // Splits the genome into num_dims equal segments and scales each one linearly into bounds,
+    // exactly like decoder::decode_binary does for strings
+    pub fn decode(&self, num_dims: usize, bounds: (f64, f64), encoding: Encoding) -> Vec<f64> {
+        if num_dims == 0 {
+            panic!("Number of dimensions must be greater than 0");
+        }
+        if !self.len().is_multiple_of(num_dims) {
+            panic!("Bitstring length must be divisible by number of dimensions");
+        }
+
+        let segment_len = self.len() / num_dims;
+        let max_val = 2_f64.powi(segment_len as i32) - 1.0;
+        self.0
+            .chunks(segment_len)
+            .map(|segment| {
+                let mut value = segment.iter().fold(0, |value, &bit| value << 1 | bit as u64);
+                if encoding == Encoding::Gray {
+                    value = gray_to_binary(value);
+                }
+                (value as f64 / max_val) * (bounds.1 - bounds.0) + bounds.0
+            })
+            .collect()
+    }
+}
+
+impl Genome for ByteGenome {
+    fn random<R: Rng + ?Sized>(len: usize, _bounds: (f64, f64), rng: &mut R) -> Self {
+        ByteGenome((0..len).map(|_| u8::from(rng.random::<bool>())).collect())
+    }
+
+    fn mutate<R: Rng + ?Sized>(
+        &self,
+        params: &GAParameters,
+        bounds: (f64, f64),
+        rng: &mut R,
+    ) -> Self {
+        self.mutate_with_loci(params, bounds, rng).0
+    }
+
+    // Each bit flips independently with probability mutation_rate
+    fn mutate_with_loci<R: Rng + ?Sized>(
+        &self,
+        params: &GAParameters,
+        _bounds: (f64, f64),
+        rng: &mut R,
+    ) -> (Self, Option<Vec<usize>>) {
+        let mut mutated = self.clone();
+        let mut flipped = Vec::new();
+        for (i, bit) in mutated.0.iter_mut().enumerate() {
+            let random: f64 = rng.random();
+            if random < params.mutation_rate {
+                *bit ^= 1;
+                flipped.push(i);
+            }
+        }
+        (mutated, Some(flipped))
+    }
+
+    // Single point crossover, copying the tails past the crossover point
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        other: &Self,
+        crossover_rate: f64,
+        rng: &mut R,
+    ) -> (Self, Self) {
+        if self.len() != other.len() {
+            panic!("Parents must be of the same length");
+        }
+        let random: f64 = rng.random();
+        if random >= crossover_rate {
+            return (self.clone(), other.clone());
+        }
+
+        let crossover_point = rng.random_range(1..self.len());
+        let mut offspring1 = self.clone();
+        let mut offspring2 = other.clone();
+        offspring1.0[crossover_point..].copy_from_slice(&other.0[crossover_point..]);
+        offspring2.0[crossover_point..].copy_from_slice(&self.0[crossover_point..]);
+        (offspring1, offspring2)
+    }
+
+    // Flips the bytes where they are, drawing as mutate does
+    fn mutate_in_place<R: Rng + ?Sized>(
+        &mut self,
+        params: &GAParameters,
+        _bounds: (f64, f64),
+        rng: &mut R,
+    ) {
+        for bit in &mut self.0 {
+            let random: f64 = rng.random();
+            if random < params.mutation_rate {
+                *bit ^= 1;
+            }
+        }
+    }
+
+    // Copies the parents' slices into the offspring's buffers, drawing as crossover does
+    fn crossover_into<R: Rng + ?Sized>(
+        &self,
+        other: &Self,
+        params: &GAParameters,
+        rng: &mut R,
+        (offspring1, offspring2): (&mut Self, &mut Self),
+    ) {
+        if self.len() != other.len() {
+            panic!("Parents must be of the same length");
+        }
+        let random: f64 = rng.random();
+        let crossover_point = if random >= params.crossover_rate {
+            self.len()
+        } else {
+            rng.random_range(1..self.len())
+        };
+        offspring1.0.clear();
+        offspring1.0.extend_from_slice(&self.0[..crossover_point]);
+        offspring1.0.extend_from_slice(&other.0[crossover_point..]);
+        offspring2.0.clear();
+        offspring2.0.extend_from_slice(&other.0[..crossover_point]);
+        offspring2.0.extend_from_slice(&self.0[crossover_point..]);
+    }
+
+    fn length(&self) -> usize {
+        self.len()
+    }
+
+    // Hamming distance
+    fn distance(&self, other: &Self) -> f64 {
+        self.0.iter().zip(&other.0).filter(|(a, b)| a != b).count() as f64
+    }
+
+    fn fingerprint(&self) -> u64 {
+        hash_of(self)
+    }
+
+    fn phenotype(&self, decoder: &impl Decoder, num_dims: usize) -> Vec<f64> {
+        decoder.decode_bytes(self, num_dims)
+    }
+
+    fn from_phenotype(phenotype: &[f64], decoder: &impl Decoder, len: usize) -> Option<Self> {
+        decoder.encode_bitstring(phenotype, len).map(|bits| ByteGenome::from_bitstring(&bits))
+    }
+}
@@ -0,0 +1,166 @@
+use crate::decoder::Decoder;
+use crate::fitness::{Fitness, Objective};
+use crate::seeds::SeedSequence;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Lookups answered from a CachedFitness and evaluations it passed on to the problem
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 { 0.0 } else { self.hits as f64 / lookups as f64 }
+    }
+
+    // Counts since `start`, so runs sharing one cache each report their own lookups
+    pub fn since(&self, start: Option<CacheStats>) -> Self {
+        let start = start.unwrap_or_default();
+        Self { hits: self.hits - start.hits, misses: self.misses - start.misses }
+    }
+}
+
+impl fmt::Display for CacheStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate)",
+            self.hits,
+            self.misses,
+            self.hit_rate() * 100.0
+        )
+    }
+}
+
+// Remembers the fitness of every phenotype the wrapped problem has evaluated, so survivors,
+// parents picked again by selection and offspring identical to their parents aren't evaluated
+// twice. The key is the exact bit pattern of the decoded genome, which decoding makes the same
+// for equal genomes. Only for deterministic problems: wrapping a NoisyFitness would freeze the
+// first noise sample of each phenotype, and resampling a cached value returns it unchanged.
+pub struct CachedFitness<F: Objective> {
+    inner: F,
+    values: Mutex<HashMap<Vec<u64>, f64>>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl<F: Objective> CachedFitness<F> {
+    pub fn new(inner: F) -> Self {
+        Self {
+            inner,
+            values: Mutex::new(HashMap::new()),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    // Only the phenotypes not seen before are passed on, as one batch with each of them once:
+    // evaluate gets the indices in members of the first member with each uncached phenotype
+    fn evaluate_uncached(
+        &self,
+        members: &[Vec<f64>],
+        evaluate: impl FnOnce(&[usize]) -> Vec<f64>,
+    ) -> Vec<f64> {
+        let keys: Vec<Vec<u64>> = members
+            .iter()
+            .map(|member| member.iter().map(|x| x.to_bits()).collect())
+            .collect();
+        let mut values: Vec<Option<f64>> = {
+            let cached = self.values.lock().unwrap();
+            keys.iter().map(|key| cached.get(key).copied()).collect()
+        };
+        // First member with each uncached phenotype, in batch order
+        let mut missing: HashMap<&Vec<u64>, usize> = HashMap::new();
+        let mut batch = Vec::new();
+        for i in (0..members.len()).filter(|&i| values[i].is_none()) {
+            missing.entry(&keys[i]).or_insert_with(|| {
+                batch.push(i);
+                batch.len() - 1
+            });
+        }
+        self.hits.fetch_add(members.len() - batch.len(), Ordering::Relaxed);
+        self.misses.fetch_add(batch.len(), Ordering::Relaxed);
+        if batch.is_empty() {
+            return values.into_iter().map(Option::unwrap).collect();
+        }
+
+        let evaluated = evaluate(&batch);
+        let mut cached = self.values.lock().unwrap();
+        for (&i, &value) in batch.iter().zip(&evaluated) {
+            cached.insert(keys[i].clone(), value);
+        }
+        for (value, key) in values.iter_mut().zip(&keys) {
+            if value.is_none() {
+                *value = Some(evaluated[missing[key]]);
+            }
+        }
+        values.into_iter().map(Option::unwrap).collect()
+    }
+}
+
+impl<F: Objective> Objective for CachedFitness<F> {
+    fn fitness(&self, member: &[f64]) -> f64 {
+        let key: Vec<u64> = member.iter().map(|x| x.to_bits()).collect();
+        if let Some(&value) = self.values.lock().unwrap().get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return value;
+        }
+        // Evaluated outside the lock so threads sharing the cache don't wait on each other
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let value = self.inner.fitness(member);
+        self.values.lock().unwrap().insert(key, value);
+        value
+    }
+
+    fn evaluate_population(&self, members: &[Vec<f64>]) -> Vec<f64> {
+        self.evaluate_uncached(members, |batch| {
+            let phenotypes: Vec<Vec<f64>> = batch.iter().map(|&i| members[i].clone()).collect();
+            self.inner.evaluate_population(&phenotypes)
+        })
+    }
+
+    // A phenotype seen again keeps the value its first stream gave it
+    fn evaluate_population_seeded(
+        &self,
+        members: &[Vec<f64>],
+        streams: &[SeedSequence],
+    ) -> Vec<f64> {
+        self.evaluate_uncached(members, |batch| {
+            let phenotypes: Vec<Vec<f64>> = batch.iter().map(|&i| members[i].clone()).collect();
+            let streams: Vec<SeedSequence> = batch.iter().map(|&i| streams[i]).collect();
+            self.inner.evaluate_population_seeded(&phenotypes, &streams)
+        })
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        self.inner.bounds()
+    }
+
+    fn optimum_value(&self) -> Option<f64> {
+        self.inner.optimum_value()
+    }
+
+    fn known_optima(&self, num_dims: usize) -> Vec<Vec<f64>> {
+        self.inner.known_optima(num_dims)
+    }
+
+    fn cache_stats(&self) -> Option<CacheStats> {
+        Some(CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        })
+    }
+}
+
+impl<F: Fitness> Fitness for CachedFitness<F> {
+    fn decoder(&self) -> impl Decoder {
+        self.inner.decoder()
+    }
+}
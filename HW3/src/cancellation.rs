@@ -0,0 +1,65 @@
+use crate::seeds::SeedSequence;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Members evaluated between checks of the token: few enough that a cancelled run stops within
+// a handful of fitness calls, enough that batch objectives still see batches
+pub const MEMBERS_PER_CHECK: usize = 8;
+
+// Shared flag asking a running algorithm to stop. Clones share the flag, so the thread that
+// owns the timeout can cancel a run that checks the token between chunks of evaluations.
+#[derive(Debug, Clone)]
+pub struct CancellationToken {
+    // None for a token that can never be cancelled
+    cancelled: Option<Arc<AtomicBool>>,
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self { cancelled: Some(Arc::default()) }
+    }
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The token of a run nobody can cancel, which evaluates its batches whole
+    pub fn never() -> Self {
+        Self { cancelled: None }
+    }
+
+    pub fn cancel(&self) {
+        if let Some(cancelled) = &self.cancelled {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.as_ref().is_some_and(|cancelled| cancelled.load(Ordering::Relaxed))
+    }
+
+    // Evaluates a seeded batch in chunks of MEMBERS_PER_CHECK items, each with its items'
+    // streams, checking for cancellation before each chunk so a cancelled run stops within one
+    // chunk. Returns the values evaluated before the run was cancelled as the error.
+    pub fn evaluate_batch<T>(
+        &self,
+        items: &[T],
+        streams: &[SeedSequence],
+        mut evaluate: impl FnMut(&[T], &[SeedSequence]) -> Vec<f64>,
+    ) -> Result<Vec<f64>, Vec<f64>> {
+        if self.cancelled.is_none() {
+            return Ok(evaluate(items, streams));
+        }
+        let mut values = Vec::with_capacity(items.len());
+        let chunks = items.chunks(MEMBERS_PER_CHECK).zip(streams.chunks(MEMBERS_PER_CHECK));
+        for (chunk, streams) in chunks {
+            if self.is_cancelled() {
+                return Err(values);
+            }
+            values.extend(evaluate(chunk, streams));
+        }
+        Ok(values)
+    }
+}
@@ -0,0 +1,357 @@
+use crate::algorithm::Algorithm;
+use crate::logging::{status_observer, trace_observer};
+use crate::observer::{ConsoleObserver, GenerationStats, Observer, RunSummary, mean_and_std};
+use crate::stopping::{StopMonitor, StopReason, StoppingCriteria};
+use rand::prelude::*;
+use std::collections::HashMap;
+
+// Function a CGP node can compute from its two inputs. Boolean functions read their inputs
+// as true when nonzero and return 0 or 1, so circuits and arithmetic share one value type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CgpFunction {
+    And,
+    Or,
+    Nand,
+    Nor,
+    Xor,
+    Add,
+    Sub,
+    Mul,
+    // Protected division: 1 where the divisor is 0
+    Div,
+}
+
+impl CgpFunction {
+    pub const BOOLEAN: [CgpFunction; 5] = [Self::And, Self::Or, Self::Nand, Self::Nor, Self::Xor];
+    pub const ARITHMETIC: [CgpFunction; 4] = [Self::Add, Self::Sub, Self::Mul, Self::Div];
+
+    fn apply(self, a: f64, b: f64) -> f64 {
+        let (x, y) = (a != 0.0, b != 0.0);
+        let bit = |value: bool| if value { 1.0 } else { 0.0 };
+        match self {
+            Self::And => bit(x && y),
+            Self::Or => bit(x || y),
+            Self::Nand => bit(!(x && y)),
+            Self::Nor => bit(!(x || y)),
+            Self::Xor => bit(x != y),
+            Self::Add => a + b,
+            Self::Sub => a - b,
+            Self::Mul => a * b,
+            Self::Div if b == 0.0 => 1.0,
+            Self::Div => a / b,
+        }
+    }
+}
+
+// A task CGP programs are scored on, over the functions it makes available
+pub trait ProgramObjective {
+    // Values fed to the program's inputs
+    fn inputs(&self) -> usize;
+
+    // Values read from the program's outputs
+    fn outputs(&self) -> usize;
+
+    fn functions(&self) -> &[CgpFunction];
+
+    fn fitness(&self, program: &CgpGenome) -> f64;
+
+    // Best possible fitness, if known, for the target stopping rule
+    fn optimum_value(&self) -> Option<f64> {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CgpParameters {
+    // Nodes in the single row of the grid
+    pub columns: usize,
+    // How many columns back a node may take its inputs from; columns allows any earlier node
+    pub levels_back: usize,
+    // Offspring of the parent each generation, the lambda of the (1+lambda) strategy
+    pub lambda: usize,
+    // Chance each gene of an offspring is mutated
+    pub mutation_rate: f64,
+    pub max_gens: usize,
+    pub stopping: StoppingCriteria,
+    // Writes the run as JSON lines to this file in place of the console status lines
+    pub log_path: Option<String>,
+    // Seed the caller's RNG was created from, recorded in the run log for replay
+    pub seed: Option<u64>,
+    // Writes a CSV of per-generation metrics to this file, alongside the other output
+    pub trace_path: Option<String>,
+}
+
+// Miller's usual settings: a single row, any earlier node as input and the (1+4)-ES
+impl Default for CgpParameters {
+    fn default() -> Self {
+        Self {
+            columns: 100,
+            levels_back: 100,
+            lambda: 4,
+            mutation_rate: 0.03,
+            max_gens: 10000,
+            stopping: StoppingCriteria::default(),
+            log_path: None,
+            seed: None,
+            trace_path: None,
+        }
+    }
+}
+
+impl CgpParameters {
+    // Parameters as recorded in the run log
+    fn to_map(&self) -> HashMap<String, f64> {
+        HashMap::from([
+            ("columns".to_string(), self.columns as f64),
+            ("levels_back".to_string(), self.levels_back as f64),
+            ("lambda".to_string(), self.lambda as f64),
+            ("mutation_rate".to_string(), self.mutation_rate),
+            ("max_gens".to_string(), self.max_gens as f64),
+        ])
+    }
+}
+
+// A Cartesian genetic program: a fixed-length integer genome of three genes per node, a
+// function index and two connections, followed by one connection per output. Connections
+// address the program's inputs first and then the nodes, and a node only connects to inputs
+// or to the levels_back columns before it, so the graph is acyclic. Nodes no output depends
+// on are inactive: mutations there change nothing and let the genome drift neutrally.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CgpGenome {
+    inputs: usize,
+    nodes: Vec<[usize; 3]>,
+    outputs: Vec<usize>,
+}
+
+impl CgpGenome {
+    // Range of addresses node `column`'s connections may take
+    fn sources(inputs: usize, column: usize, levels_back: usize) -> std::ops::Range<usize> {
+        let first_node = column.saturating_sub(levels_back);
+        let low = if first_node == 0 { 0 } else { inputs + first_node };
+        low..inputs + column
+    }
+
+    fn random<R: Rng + ?Sized>(
+        inputs: usize,
+        outputs: usize,
+        functions: usize,
+        params: &CgpParameters,
+        rng: &mut R,
+    ) -> Self {
+        let mut genome = Self {
+            inputs,
+            nodes: vec![[0; 3]; params.columns],
+            outputs: vec![0; outputs],
+        };
+        for gene in 0..genome.len() {
+            genome.randomize(gene, functions, params.levels_back, rng);
+        }
+        genome
+    }
+
+    fn len(&self) -> usize {
+        3 * self.nodes.len() + self.outputs.len()
+    }
+
+    // Gives gene `gene` a new valid value at random
+    fn randomize<R: Rng + ?Sized>(
+        &mut self,
+        gene: usize,
+        functions: usize,
+        levels_back: usize,
+        rng: &mut R,
+    ) {
+        let addresses = self.inputs + self.nodes.len();
+        if gene >= 3 * self.nodes.len() {
+            self.outputs[gene - 3 * self.nodes.len()] = rng.random_range(0..addresses);
+            return;
+        }
+        let (column, position) = (gene / 3, gene % 3);
+        self.nodes[column][position] = if position == 0 {
+            rng.random_range(0..functions)
+        } else {
+            rng.random_range(Self::sources(self.inputs, column, levels_back))
+        };
+    }
+
+    // Which nodes some output depends on
+    fn active(&self) -> Vec<bool> {
+        let mut active = vec![false; self.nodes.len()];
+        let mut stack: Vec<usize> = self.outputs.clone();
+        while let Some(address) = stack.pop() {
+            let Some(node) = address.checked_sub(self.inputs) else { continue };
+            if !active[node] {
+                active[node] = true;
+                stack.extend_from_slice(&self.nodes[node][1..]);
+            }
+        }
+        active
+    }
+
+    pub fn active_nodes(&self) -> usize {
+        self.active().iter().filter(|&&active| active).count()
+    }
+
+    // Output values for the given inputs, computing the active nodes only
+    pub fn evaluate(&self, functions: &[CgpFunction], inputs: &[f64]) -> Vec<f64> {
+        let active = self.active();
+        let mut values: Vec<f64> = inputs.to_vec();
+        values.resize(self.inputs, 0.0);
+        for (node, &[function, a, b]) in self.nodes.iter().enumerate() {
+            if active[node] {
+                values.push(functions[function].apply(values[a], values[b]));
+            } else {
+                values.push(0.0);
+            }
+        }
+        self.outputs.iter().map(|&address| values[address]).collect()
+    }
+}
+
+// Cartesian genetic programming (Miller): programs as fixed-size grids of function nodes,
+// evolved by the (1+lambda)-ES with point mutation only. Offspring as good as the parent
+// replace it, so the many inactive genes drift freely, which is where CGP's evolvability
+// comes from, unlike tree GP, whose programs grow and have no neutral genes to spare.
+pub struct Cgp<'a, F: ProgramObjective> {
+    objective: &'a F,
+    params: &'a CgpParameters,
+    parent: CgpGenome,
+    parent_fitness: f64,
+    generation: usize,
+    evaluations: usize,
+    monitor: StopMonitor,
+    observers: Vec<Box<dyn Observer<CgpGenome> + 'a>>,
+}
+
+impl<'a, F: ProgramObjective> Cgp<'a, F> {
+    pub fn new<R: Rng + ?Sized>(objective: &'a F, params: &'a CgpParameters, rng: &mut R) -> Self {
+        let functions = objective.functions().len();
+        let parent =
+            CgpGenome::random(objective.inputs(), objective.outputs(), functions, params, rng);
+        let parent_fitness = objective.fitness(&parent);
+
+        let mut observers = vec![status_observer(
+            params.log_path.as_deref(),
+            "CGP",
+            params.to_map(),
+            params.seed,
+            ConsoleObserver::cgp(params),
+        )];
+        if let Some(trace) = trace_observer(params.trace_path.as_deref()) {
+            observers.push(Box::new(trace));
+        }
+
+        Self {
+            objective,
+            params,
+            parent,
+            parent_fitness,
+            generation: 0,
+            evaluations: 1,
+            monitor: StopMonitor::new(&params.stopping, objective.optimum_value()),
+            observers,
+        }
+    }
+
+    // Removes all observers, including the console output, for runs driven in bulk
+    pub fn quiet(mut self) -> Self {
+        self.observers.clear();
+        self
+    }
+
+    // Point mutation: each gene takes a new random value with mutation_rate, and at least one
+    // gene always does, so no offspring is a plain copy
+    fn mutate<R: Rng + ?Sized>(&self, rng: &mut R) -> CgpGenome {
+        let mut child = self.parent.clone();
+        let (functions, levels_back) = (self.objective.functions().len(), self.params.levels_back);
+        let mut mutated = false;
+        for gene in 0..child.len() {
+            if rng.random_bool(self.params.mutation_rate) {
+                child.randomize(gene, functions, levels_back, rng);
+                mutated = true;
+            }
+        }
+        if !mutated {
+            let gene = rng.random_range(0..child.len());
+            child.randomize(gene, functions, levels_back, rng);
+        }
+        child
+    }
+}
+
+impl<F: ProgramObjective> Algorithm for Cgp<'_, F> {
+    type Individual = CgpGenome;
+
+    fn step<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<StopReason> {
+        if self.generation >= self.params.max_gens {
+            return Some(StopReason::MaxGenerations);
+        }
+        let offspring: Vec<CgpGenome> = (0..self.params.lambda).map(|_| self.mutate(rng)).collect();
+        let fitnesses: Vec<f64> =
+            offspring.iter().map(|program| self.objective.fitness(program)).collect();
+        self.evaluations += offspring.len();
+        self.generation += 1;
+
+        // The last of the best offspring replaces the parent unless it is worse, so neutral
+        // offspring always win
+        let best = (0..offspring.len())
+            .rev()
+            .max_by(|&a, &b| fitnesses[a].total_cmp(&fitnesses[b]))
+            .filter(|&best| fitnesses[best] >= self.parent_fitness);
+        if let Some(best) = best {
+            self.parent = offspring[best].clone();
+            self.parent_fitness = fitnesses[best];
+        }
+
+        let (avg_fitness, std_fitness) = mean_and_std(&fitnesses);
+        let stats = GenerationStats {
+            generation: self.generation,
+            evaluations: self.evaluations,
+            max_fitness: self.parent_fitness,
+            avg_fitness,
+            std_fitness,
+            diversity: None,
+            cache: None,
+            boundary_hits: None,
+            violations: None,
+            qd: None,
+        };
+        let improved = self.parent_fitness > self.monitor.best_fitness;
+        for observer in &mut self.observers {
+            observer.on_generation(&stats, &self.parent);
+            if improved {
+                observer.on_improvement(&stats, &self.parent);
+            }
+        }
+        self.monitor.update(self.parent_fitness)
+    }
+
+    fn generation(&self) -> usize {
+        self.generation
+    }
+
+    fn best_fitness(&self) -> f64 {
+        self.parent_fitness
+    }
+
+    fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+
+    fn finish(mut self, reason: StopReason) -> Vec<CgpGenome> {
+        let summary = RunSummary {
+            reason,
+            generations: self.generation,
+            evaluations: self.evaluations,
+            best_fitness: self.monitor.best_fitness,
+            stagnant_generations: self.monitor.stagnant_generations,
+        };
+        let message = format!("Active nodes: {} of {}", self.parent.active_nodes(),
+            self.parent.nodes.len());
+        for observer in &mut self.observers {
+            observer.on_message(&message);
+            observer.on_termination(&summary, Some(&self.parent));
+        }
+        vec![self.parent]
+    }
+}
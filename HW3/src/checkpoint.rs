@@ -0,0 +1,196 @@
+use crate::bitstring::GAParameters;
+use crate::evol_strat::ESParameters;
+use crate::parameter_tuning::{ParameterGrid, TuningResult, serialize_sorted};
+use crate::results_analyzer::{ESAnalysis, ResultsAnalyzer, SGAAnalysis};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// A combination that still has runs to do
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingCombination {
+    pub algorithm: String,
+    pub combination: usize,
+    pub runs_left: usize,
+    #[serde(serialize_with = "serialize_sorted")]
+    pub parameters: HashMap<String, f64>,
+}
+
+// Snapshot of a tuning session on disk. The runs themselves are in the results file, which
+// is appended to as each run finishes; the snapshot records how to restart the session and
+// how far it had got, with the analysis of the runs so far.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    // Command line of the session, replayed with --resume to recover it
+    pub args: Vec<String>,
+    pub results: String,
+    // Seconds since the Unix epoch
+    pub updated_at: u64,
+    pub completed_runs: usize,
+    pub total_runs: usize,
+    pub pending: Vec<PendingCombination>,
+    // Analysis of the completed runs, only written
+    #[serde(skip_deserializing)]
+    pub analysis: Option<PartialAnalysis>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PartialAnalysis {
+    pub sga: SGAAnalysis,
+    pub es: ESAnalysis,
+}
+
+impl SessionSnapshot {
+    pub fn load(path: &str) -> Result<Option<Self>, Box<dyn Error>> {
+        if !Path::new(path).exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+    }
+
+    // Seconds since the snapshot was written
+    pub fn age(&self) -> u64 {
+        unix_time().saturating_sub(self.updated_at)
+    }
+}
+
+// Where the snapshot of the session writing `results` lives
+pub fn path_for(results: &str) -> String {
+    Path::new(results).with_extension("checkpoint.json").display().to_string()
+}
+
+struct CheckpointState {
+    completed: Vec<TuningResult>,
+    // Every combination of the session; empty while the tuner is still choosing them
+    plan: Vec<(String, HashMap<String, f64>)>,
+    total_runs: usize,
+    last_write: Option<Instant>,
+}
+
+// Rewrites the session snapshot at most once per interval as runs finish, and removes it
+// when the session completes. Writes go to a temporary file that is renamed over the old
+// snapshot, so a crash mid-write leaves the previous one intact.
+pub struct Checkpointer {
+    path: String,
+    args: Vec<String>,
+    results: String,
+    interval: Duration,
+    num_runs: usize,
+    state: Mutex<CheckpointState>,
+}
+
+impl Checkpointer {
+    // args is the command line that recreates the session; completed holds the runs of a
+    // resumed session
+    pub fn new(
+        results: &str,
+        args: Vec<String>,
+        interval: Duration,
+        num_runs: usize,
+        completed: Vec<TuningResult>,
+    ) -> Self {
+        Self {
+            path: path_for(results),
+            args,
+            results: results.to_string(),
+            interval,
+            num_runs,
+            state: Mutex::new(CheckpointState {
+                completed,
+                plan: Vec::new(),
+                total_runs: 0,
+                last_write: None,
+            }),
+        }
+    }
+
+    // The session's combinations, once known, so the snapshot can list what is pending
+    pub fn plan(&self, sga_grid: &[GAParameters], es_grid: &[ESParameters]) {
+        let sga = sga_grid.iter().map(|p| ("SGA".to_string(), ParameterGrid::params_to_map_ga(p)));
+        let es = es_grid.iter().map(|p| ("ES".to_string(), ParameterGrid::params_to_map_es(p)));
+        let mut state = self.state.lock().unwrap();
+        state.plan = sga.chain(es).collect();
+        state.total_runs = state.plan.len() * self.num_runs;
+        self.write(&mut state);
+    }
+
+    // Total runs when the combinations are chosen as the session goes
+    pub fn expect_runs(&self, total_runs: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.total_runs = total_runs;
+        self.write(&mut state);
+    }
+
+    pub fn record(&self, result: &TuningResult) {
+        let mut state = self.state.lock().unwrap();
+        state.completed.push(result.clone());
+        if state.last_write.is_none_or(|last| last.elapsed() >= self.interval) {
+            self.write(&mut state);
+        }
+    }
+
+    // The session finished, so there is nothing left to recover
+    pub fn finish(&self) {
+        if let Err(e) = fs::remove_file(&self.path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            eprintln!("Error removing checkpoint {}: {e}", self.path);
+        }
+    }
+
+    fn write(&self, state: &mut CheckpointState) {
+        state.last_write = Some(Instant::now());
+        let snapshot = self.snapshot(state);
+        let temporary = format!("{}.tmp", self.path);
+        let written = serde_json::to_string_pretty(&snapshot)
+            .map_err(Box::<dyn Error>::from)
+            .and_then(|json| Ok(fs::write(&temporary, json)?))
+            .and_then(|()| Ok(fs::rename(&temporary, &self.path)?));
+        if let Err(e) = written {
+            eprintln!("Error writing checkpoint {}: {e}", self.path);
+        }
+    }
+
+    fn snapshot(&self, state: &CheckpointState) -> SessionSnapshot {
+        let mut combination = HashMap::new();
+        let pending = state
+            .plan
+            .iter()
+            .filter_map(|(algorithm, parameters)| {
+                let index = combination.entry(algorithm.as_str()).or_insert(0);
+                *index += 1;
+                let done = state
+                    .completed
+                    .iter()
+                    .filter(|r| r.algorithm == *algorithm && r.parameters == *parameters)
+                    .count();
+                (done < self.num_runs).then(|| PendingCombination {
+                    algorithm: algorithm.clone(),
+                    combination: *index - 1,
+                    runs_left: self.num_runs - done,
+                    parameters: parameters.clone(),
+                })
+            })
+            .collect();
+        SessionSnapshot {
+            args: self.args.clone(),
+            results: self.results.clone(),
+            updated_at: unix_time(),
+            completed_runs: state.completed.len(),
+            total_runs: state.total_runs,
+            pending,
+            analysis: Some(PartialAnalysis {
+                sga: ResultsAnalyzer::analyze_sga_results(&state.completed),
+                es: ResultsAnalyzer::analyze_es_results(&state.completed),
+            }),
+        }
+    }
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
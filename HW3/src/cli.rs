@@ -0,0 +1,545 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+#[derive(Parser, Debug)]
+#[command(version, about = "Genetic algorithms and evolution strategies on benchmark problems")]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    // Without a subcommand the `run` flags apply, so a bare invocation is the default run
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    #[command(about = "Run the GA and/or ES on one problem")]
+    Run(Box<RunArgs>),
+    #[command(about = "Sweep the GA and ES parameter grids with a timeout per run")]
+    Tune(TuneArgs),
+    #[command(about = "Run tuning jobs from a queue written by `tune --queue` until it is empty")]
+    TuneWorker(TuneWorkerArgs),
+    #[command(about = "Merge the results of a queued tuning session into one results CSV")]
+    Merge(MergeArgs),
+    #[command(about = "Summarize the results of a tuning session")]
+    Analyze(AnalyzeArgs),
+    #[command(about = "Plot convergence curves from CSV traces")]
+    Plot(PlotArgs),
+    #[command(about = "Median best-so-far curves with quartile bands from a directory of traces")]
+    Curves(CurvesArgs),
+    #[command(about = "Runtime ECDFs of the evaluations needed to reach target fitnesses")]
+    Ecdf(EcdfArgs),
+    #[command(about = "Export a directory of traces as COCO/BBOB data folders")]
+    Coco(CocoArgs),
+    #[command(about = "Rerun the recommended parameter sets at full length and report on them")]
+    Validate(ValidateArgs),
+    #[command(about = "Write a Markdown or HTML report of a tuning session")]
+    Report(ReportArgs),
+    #[command(about = "Run one of the built-in comparison experiments")]
+    Demo(DemoArgs),
+    #[command(about = "Serve fitness evaluations to `run --remote` over TCP")]
+    Worker(WorkerArgs),
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlgorithmKind {
+    Ga,
+    Es,
+    Both,
+    // The (1+1)-ES baseline on its own
+    OnePlusOne,
+    // The OpenAI natural evolution strategy on its own
+    Nes,
+    // MAP-Elites over the first two genes on its own
+    MapElites,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Problem {
+    Rosenbrock,
+    Himmelblau,
+    #[value(name = "maxones")]
+    MaxOnes,
+}
+
+impl Problem {
+    pub fn default_dims(self) -> usize {
+        match self {
+            Problem::Rosenbrock => 10,
+            Problem::Himmelblau => 2,
+            Problem::MaxOnes => 32,
+        }
+    }
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct RunArgs {
+    #[arg(long, value_enum, default_value_t = AlgorithmKind::Both, help = "Algorithm to run")]
+    pub algorithm: AlgorithmKind,
+    #[arg(long, value_enum, default_value_t = Problem::Rosenbrock, help = "Problem to optimize")]
+    pub problem: Problem,
+    #[arg(long, help = "Number of dimensions [default: 10, 2 for himmelblau, 32 for maxones]")]
+    pub dims: Option<usize>,
+    #[arg(long, default_value_t = 16, help = "GA bits per dimension")]
+    pub bits_per_dim: usize,
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["LOW", "HIGH"],
+        allow_negative_numbers = true,
+        help = "Decode GA bitstrings into [LOW, HIGH] instead of the problem's own bounds"
+    )]
+    pub decode_bounds: Option<Vec<f64>>,
+    #[arg(
+        long,
+        default_value_t = 0.1,
+        help = "Distance within which a final member counts as sitting on a known optimum"
+    )]
+    pub optimum_tolerance: f64,
+    #[arg(long, default_value_t = 0.5, help = "Distance within which final members share optima")]
+    pub cluster_radius: f64,
+    #[arg(long, default_value_t = 100, help = "GA population size, ES mu and lambda")]
+    pub pop_size: usize,
+    #[arg(long, default_value_t = 0.01, help = "GA per-bit mutation rate")]
+    pub mutation_rate: f64,
+    #[arg(long, default_value_t = 0.75, help = "GA crossover rate")]
+    pub crossover_rate: f64,
+    #[arg(long, default_value_t = 1.0, help = "ES initial step size, NES perturbation size")]
+    pub sigma: f64,
+    #[arg(long, default_value_t = 0.05, help = "NES learning rate")]
+    pub learning_rate: f64,
+    #[arg(long, default_value_t = 10, help = "MAP-Elites archive cells along each gene it maps")]
+    pub cells: usize,
+    #[arg(long, help = "Threads to evaluate on [default: available cores for NES, 1 otherwise]")]
+    pub workers: Option<usize>,
+    #[arg(long, default_value_t = 5000, help = "Random seed (each algorithm starts from it)")]
+    pub seed: u64,
+    #[arg(long, default_value_t = 1000, help = "Maximum number of generations")]
+    pub budget: usize,
+    #[arg(long, help = "Write the runs as JSON lines to this file instead of status lines")]
+    pub log: Option<String>,
+    #[arg(long, help = "Cache fitness values so repeated phenotypes aren't re-evaluated")]
+    pub cache: bool,
+    #[arg(long, help = "Time each GA and ES operator and report the breakdown at run end")]
+    pub profile: bool,
+    #[arg(long, value_name = "K", help = "Estimate diversity from K random pairs per generation")]
+    pub diversity_pairs: Option<usize>,
+    #[arg(long, value_delimiter = ',', help = "Evaluate on these workers (host:port,...)")]
+    pub remote: Vec<String>,
+    #[arg(long, help = "Members per chunk sent to a worker [default: split evenly]")]
+    pub chunk_size: Option<usize>,
+    #[arg(long, default_value_t = 30, help = "Seconds to wait for a worker before retrying")]
+    pub remote_timeout: u64,
+    #[cfg(feature = "gpu")]
+    #[arg(long, conflicts_with = "remote", help = "Evaluate whole batches on the GPU")]
+    pub gpu: bool,
+    #[arg(long, value_enum, help = "Pre-screen ES offspring with this model of the fitness")]
+    pub surrogate: Option<SurrogateKind>,
+    #[arg(long, default_value_t = 0.25, help = "Fraction of screened offspring evaluated")]
+    pub surrogate_fraction: f64,
+    #[arg(long, value_enum, help = "Recombine two ES parents with this operator before mutation")]
+    pub recombination: Option<RecombinationKind>,
+    #[arg(long, default_value_t = 0.3, help = "Alpha of the blend and arithmetic operators")]
+    pub alpha: f64,
+    #[arg(long, value_name = "RADIUS", help = "Clear fitness in niches of this phenotype radius")]
+    pub clearing: Option<f64>,
+    #[arg(long, default_value_t = 1, help = "Members per niche that keep their fitness")]
+    pub niche_capacity: usize,
+    #[arg(
+        long,
+        value_name = "THRESHOLD",
+        help = "Group GA members into species within this genotypic distance"
+    )]
+    pub speciation: Option<f64>,
+    #[arg(long, default_value_t = 15, help = "Generations a species may stagnate before culling")]
+    pub species_stagnation: usize,
+    #[arg(long, help = "Self-adapt a sigma per gene and rotation angles in the ES")]
+    pub correlated: bool,
+    #[arg(
+        long,
+        conflicts_with = "correlated",
+        help = "Control the ES sigma by cumulative step-size adaptation"
+    )]
+    pub csa: bool,
+    #[arg(long, help = "Restart the ES this many times when it converges or stagnates")]
+    pub restarts: Option<usize>,
+    #[arg(long, default_value_t = 2.0, help = "Factor the ES population grows by per restart")]
+    pub restart_factor: f64,
+    #[arg(long, value_enum, help = "Bring ES genes mutated out of the initial range back")]
+    pub bounds: Option<BoundsKind>,
+    #[arg(
+        long,
+        help = "Write per-generation CSVs to <TRACE>_es.csv and <TRACE>_ga.csv, and MAP-Elites \
+            archives to <TRACE>_me_archive.*"
+    )]
+    pub trace: Option<String>,
+    #[arg(long, help = "Plot the traces to this PNG or SVG file (traces go next to it)")]
+    pub plot: Option<String>,
+    #[arg(long, help = "Add the runs to IOHprofiler data folders <IOH>/es and <IOH>/ga")]
+    pub ioh: Option<String>,
+    #[arg(long, help = "Add the runs to COCO/BBOB data folders <COCO>/es and <COCO>/ga")]
+    pub coco: Option<String>,
+    #[arg(long, help = "Warm-start from the individuals in this JSON file (`--save-population`)")]
+    pub seed_population: Option<String>,
+    #[arg(long, default_value_t = 0.2, help = "Fraction of the initial population seeded")]
+    pub seed_fraction: f64,
+    #[arg(long, help = "Save the final populations, best first, to this JSON file")]
+    pub save_population: Option<String>,
+}
+
+impl RunArgs {
+    pub fn dims(&self) -> usize {
+        self.dims.unwrap_or(self.problem.default_dims())
+    }
+
+    // The flag that is out of range and why, for values the algorithms can't run with
+    pub fn check_ranges(&self) -> Result<(), String> {
+        if self.dims() == 0 {
+            return Err("--dims: expected at least 1".to_string());
+        }
+        if self.pop_size == 0 {
+            return Err("--pop-size: expected at least 1".to_string());
+        }
+        if self.cells == 0 {
+            return Err("--cells: expected at least 1".to_string());
+        }
+        if self.sigma.is_nan() || self.sigma <= 0.0 {
+            return Err(format!("--sigma: expected a positive step size, got {}", self.sigma));
+        }
+        for (flag, rate) in [
+            ("--mutation-rate", self.mutation_rate),
+            ("--crossover-rate", self.crossover_rate),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(format!("{flag}: expected a rate in [0, 1], got {rate}"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SurrogateKind {
+    Kriging,
+    Rbf,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecombinationKind {
+    SinglePoint,
+    Blend,
+    WholeArithmetic,
+    PartialArithmetic,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoundsKind {
+    Clamp,
+    Reflect,
+    Wrap,
+    Resample,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchKind {
+    Grid,
+    Random,
+    Lhs,
+    Sobol,
+    Bayes,
+    // Two-level screening designs over the grid's axes, or the sampled ranges
+    Fractional,
+    PlackettBurman,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct TuneArgs {
+    #[arg(long, value_enum, default_value_t = SearchKind::Grid, help = "How combinations are chosen")]
+    pub search: SearchKind,
+    #[arg(long, help = "Sweep the parameter grid in this JSON file instead of the built-in one")]
+    pub grid: Option<String>,
+    #[arg(long, default_value_t = 25, help = "Combinations per algorithm unless --search grid")]
+    pub samples: usize,
+    #[arg(long, default_value_t = 0, help = "Seed for placing sampled combinations")]
+    pub search_seed: u64,
+    #[arg(long, default_value_t = 5000, help = "Master seed every run's seed is derived from")]
+    pub seed: u64,
+    #[arg(long, alias = "workers", help = "Runs at a time [default: available parallelism]")]
+    pub jobs: Option<usize>,
+    #[arg(long, help = "Runs per parameter combination [default: 5]")]
+    pub runs: Option<usize>,
+    #[arg(long, help = "Timeout per run in seconds [default: 60]")]
+    pub timeout: Option<u64>,
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Wall-clock budget of the whole session: runs are cut short or skipped to fit it"
+    )]
+    pub total_budget: Option<u64>,
+    #[arg(long, help = "Fitness that counts as reaching the target [default: 0.05]")]
+    pub target: Option<f64>,
+    #[arg(long, help = "Distance from the optimum value that counts as success [default: 0.01]")]
+    pub success_epsilon: Option<f64>,
+    #[arg(long, help = "Distance within which final members share an optimum [default: 0.5]")]
+    pub cluster_radius: Option<f64>,
+    #[arg(long, default_value = "tuning_results.csv", help = "Results CSV")]
+    pub output: String,
+    #[arg(long, conflicts_with = "output", help = "Continue the session in this results CSV")]
+    pub resume: Option<String>,
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Csv,
+        help = "Also write the results and analysis as <OUTPUT>.json and <OUTPUT>_analysis.json"
+    )]
+    pub format: OutputFormat,
+    #[arg(long, help = "Trace every run into this directory and plot each combination")]
+    pub plot: Option<String>,
+    #[arg(
+        long,
+        conflicts_with_all = ["resume", "plot"],
+        help = "Write the combinations as jobs to this shared directory instead of running them"
+    )]
+    pub queue: Option<String>,
+    #[arg(
+        long,
+        default_value_t = 60,
+        help = "Seconds between snapshots of the session for crash recovery, 0 for every run"
+    )]
+    pub checkpoint_interval: u64,
+    #[arg(long, help = "Start over even if a crashed session left a checkpoint for <OUTPUT>")]
+    pub fresh: bool,
+    #[arg(
+        long,
+        conflicts_with = "queue",
+        help = "Estimate the session's runs, time and output files without running it"
+    )]
+    pub dry_run: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct TuneWorkerArgs {
+    #[arg(help = "Queue directory written by `tune --queue`")]
+    pub dir: String,
+    #[arg(long, help = "Runs at a time [default: available parallelism]")]
+    pub jobs: Option<usize>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct MergeArgs {
+    #[arg(help = "Queue directory written by `tune --queue`")]
+    pub dir: String,
+    #[arg(long, default_value = "tuning_results.csv", help = "Merged results CSV")]
+    pub output: String,
+    #[arg(long, help = "First put jobs claimed by workers that died back in the queue")]
+    pub requeue: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct AnalyzeArgs {
+    #[arg(default_value = "tuning_results.csv", help = "Results CSV written by `tune`")]
+    pub results: String,
+    #[arg(long, help = "Write per-combination statistics to this CSV")]
+    pub combinations: Option<String>,
+    #[arg(
+        long,
+        value_name = "STEM",
+        help = "Write mean score by parameter level to STEM_main_effects.csv, plus plots"
+    )]
+    pub main_effects: Option<String>,
+    #[arg(
+        long,
+        value_name = "STEM",
+        help = "Write mean score over each parameter pair to STEM_heatmap_*.csv, plus heatmaps"
+    )]
+    pub heatmaps: Option<String>,
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Csv,
+        help = "Print the analysis as a text summary or as JSON"
+    )]
+    pub format: OutputFormat,
+    #[arg(
+        long,
+        num_args = 1..,
+        value_name = "TRACE",
+        help = "Summarize the final archives in these MAP-Elites QD traces instead of a session"
+    )]
+    pub qd: Vec<String>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PlotArgs {
+    #[arg(help = "Output PNG or SVG file")]
+    pub output: String,
+    #[arg(required = true, help = "CSV traces of runs of one configuration")]
+    pub traces: Vec<String>,
+    #[arg(
+        long,
+        help = "The traces are MAP-Elites QD traces: plot coverage, QD-score and max fitness"
+    )]
+    pub qd: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct CurvesArgs {
+    #[arg(help = "Directory of CSV traces, e.g. from `tune --plot`")]
+    pub dir: String,
+    #[arg(long, default_value = "curves.csv", help = "Aggregated curves CSV")]
+    pub output: String,
+    #[arg(long, default_value_t = 100, help = "Evaluation counts to align the runs on")]
+    pub points: usize,
+    #[arg(long, help = "Also plot the curves to this PNG or SVG file")]
+    pub plot: Option<String>,
+    #[arg(long, default_value_t = 5, help = "Settings to plot, best final median first")]
+    pub top: usize,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct EcdfArgs {
+    #[arg(help = "Directory of CSV traces, e.g. from `tune --plot`")]
+    pub dir: String,
+    #[arg(long, default_value = "ecdf.csv", help = "ECDF CSV")]
+    pub output: String,
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "Target fitnesses, comma separated [default: evenly spaced over the traces]"
+    )]
+    pub targets: Vec<f64>,
+    #[arg(long, default_value_t = 10, help = "Number of default targets")]
+    pub num_targets: usize,
+    #[arg(long, default_value_t = 100, help = "Evaluation counts to evaluate the ECDFs at")]
+    pub points: usize,
+    #[arg(long, help = "Pool the settings of each algorithm into one ECDF")]
+    pub by_algorithm: bool,
+    #[arg(long, help = "Also plot the ECDFs to this PNG or SVG file")]
+    pub plot: Option<String>,
+    #[arg(long, default_value_t = 5, help = "Settings to plot, largest area under the ECDF first")]
+    pub top: usize,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ValidateArgs {
+    #[arg(default_value = "tuning_results.csv", help = "Results CSV written by `tune`")]
+    pub results: String,
+    #[arg(long, default_value_t = 30, help = "Runs per parameter set")]
+    pub runs: usize,
+    #[arg(long, default_value_t = 300, help = "Timeout per run in seconds")]
+    pub timeout: u64,
+    #[arg(long, default_value_t = 4, help = "Multiplies the tuned generation limits")]
+    pub budget_factor: usize,
+    #[arg(long, default_value_t = 10000, help = "Master seed of the reruns, apart from tuning's")]
+    pub seed: u64,
+    #[arg(long, alias = "workers", help = "Runs at a time [default: available parallelism]")]
+    pub jobs: Option<usize>,
+    #[arg(long, default_value = "validation_results.csv", help = "Results CSV of the reruns")]
+    pub output: String,
+    #[arg(long, default_value = "validation.md", help = "Report file, HTML if it ends in .html")]
+    pub report: String,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct CocoArgs {
+    #[arg(help = "Directory of CSV traces, e.g. from `tune --plot`")]
+    pub dir: String,
+    #[arg(long, default_value = "exdata", help = "Root of the data folders, one per setting")]
+    pub output: String,
+    #[arg(long, value_enum, default_value_t = Problem::Rosenbrock, help = "Problem of the runs")]
+    pub problem: Problem,
+    #[arg(long, help = "Dimensions of the runs [default: 10, 2 for himmelblau, 32 for maxones]")]
+    pub dims: Option<usize>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ReportArgs {
+    #[arg(default_value = "tuning_results.csv", help = "Results CSV written by `tune`")]
+    pub results: String,
+    #[arg(long, default_value = "report.md", help = "Report file, HTML if it ends in .html")]
+    pub output: String,
+    #[arg(long, help = "Trace directory of the session (`tune --plot`), for convergence plots")]
+    pub traces: Option<String>,
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        requires = "traces",
+        help = "Views of the traces to add, comma separated"
+    )]
+    pub view: Vec<AnalysisView>,
+    #[arg(long, help = "Evaluations for the fixed-budget view [default: fewest any run used]")]
+    pub budget: Option<usize>,
+    #[arg(long, help = "Fitness for the fixed-target view [default: median final best]")]
+    pub target: Option<f64>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnalysisView {
+    // Fitness distribution after a number of evaluations
+    FixedBudget,
+    // Evaluations needed to reach a fitness, with runs that never did censored
+    FixedTarget,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Demo {
+    Constrained,
+    Bench,
+    Genomes,
+    Control,
+    Gray,
+    Selection,
+    Niching,
+    Diversity,
+    Islands,
+    Compare,
+    Repair,
+    Noisy,
+    Observers,
+    Incremental,
+    Ffi,
+    SelfAdaptive,
+    HyperHeuristic,
+    Crossover,
+    MixedInteger,
+    MultiObjective,
+    Neat,
+    Mlp,
+    Cgp,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct WorkerArgs {
+    #[arg(long, default_value = "127.0.0.1:7878", help = "Address to listen on")]
+    pub listen: String,
+    #[arg(long, value_enum, default_value_t = Problem::Rosenbrock, help = "Problem to evaluate")]
+    pub problem: Problem,
+    #[arg(long, default_value_t = 0, help = "Milliseconds to sleep per evaluation")]
+    pub delay_ms: u64,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NoiseKind {
+    Gaussian,
+    Uniform,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct DemoArgs {
+    #[arg(value_enum)]
+    pub name: Demo,
+    #[arg(long, value_enum, default_value_t = NoiseKind::Gaussian, help = "Noise model (noisy)")]
+    pub noise: NoiseKind,
+    #[arg(long, default_value_t = 0.05, help = "Noise standard deviation (noisy)")]
+    pub noise_std: f64,
+    #[arg(long, default_value = "data/circle.csv", help = "CSV dataset, target last (mlp)")]
+    pub dataset: String,
+}
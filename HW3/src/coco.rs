@@ -0,0 +1,113 @@
+use crate::ioh::{InfoBlock, IohProblem, IohRun, add_info_entry};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// Precision COCO counts a run as solved at, as in the published BBOB data
+const PRECISION: f64 = 1e-8;
+// Target levels per decade of f - fopt that trigger a .dat row
+const TARGETS_PER_DECADE: f64 = 5.0;
+// Evaluation counts per decade that trigger a .tdat row
+const EVALUATIONS_PER_DECADE: f64 = 20.0;
+
+// Data folder of one algorithm in the format of COCO's BBOB experiments, as the cocopp
+// post-processing reads it: per function a bbobexp_f<id>.info file, and per function and
+// dimension a .dat file with a row each time f - fopt passes a target level and a .tdat file
+// with a row at evaluation counts spaced evenly on a log scale. COCO minimizes, so the
+// measured f is the negated fitness and f - fopt the problem's optimum value minus the
+// fitness, which is 0 at the optimum. As with IohArchive, runs are added to whatever the
+// folder already holds.
+pub struct CocoArchive {
+    root: PathBuf,
+    algorithm: String,
+    info: String,
+}
+
+impl CocoArchive {
+    pub fn new(root: &Path, algorithm: &str, info: &str) -> io::Result<Self> {
+        fs::create_dir_all(root)?;
+        Ok(Self {
+            root: root.to_path_buf(),
+            algorithm: algorithm.to_string(),
+            info: info.to_string(),
+        })
+    }
+
+    pub fn add_run(&self, problem: &IohProblem, optimum: f64, run: &IohRun) -> io::Result<()> {
+        let delta = |fitness: f64| (optimum - fitness).max(0.0);
+        let data_dir = format!("data_f{}", problem.id);
+        let stem = format!("bbobexp_f{}_DIM{}", problem.id, problem.dimension);
+        fs::create_dir_all(self.root.join(&data_dir))?;
+
+        // Rows by target level: the first improvement, then every one that reaches a lower
+        // level than the last row did
+        let mut dat_rows = Vec::new();
+        let mut level = f64::INFINITY;
+        for &(evaluations, best) in &run.improvements {
+            let reached = (delta(best).max(PRECISION).log10() * TARGETS_PER_DECADE).floor();
+            if reached < level {
+                level = reached;
+                dat_rows.push((evaluations, best));
+            }
+        }
+        // Rows by evaluation count: dimension * 10^(i / 20), as COCO's logger spaces them
+        let mut tdat_rows = Vec::new();
+        let mut last = 0;
+        for i in 0.. {
+            let evaluations =
+                (problem.dimension as f64 * 10f64.powf(i as f64 / EVALUATIONS_PER_DECADE)).ceil();
+            let evaluations = evaluations as usize;
+            if evaluations > run.evaluations {
+                break;
+            }
+            if evaluations > last
+                && let Some(best) = run.best_within(evaluations)
+            {
+                tdat_rows.push((evaluations, best));
+                last = evaluations;
+            }
+        }
+        // Both files end on the run's last evaluation
+        if let Some(best) = run.best() {
+            for rows in [&mut dat_rows, &mut tdat_rows] {
+                if rows.last().is_none_or(|&(evaluations, _)| evaluations < run.evaluations) {
+                    rows.push((run.evaluations, best));
+                }
+            }
+        }
+        for (extension, rows) in [("dat", &dat_rows), ("tdat", &tdat_rows)] {
+            let path = self.root.join(&data_dir).join(format!("{stem}.{extension}"));
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            // Only the best so far is known at each row, so it fills the columns of the
+            // current evaluation as well
+            let mut text = format!(
+                "% function evaluation | noise-free fitness - Fopt ({:.12e}) | best noise-free \
+                 fitness - Fopt | measured fitness | best measured fitness\n",
+                -optimum
+            );
+            for &(evaluations, best) in rows {
+                let (delta, value) = (delta(best), -best);
+                text.push_str(&format!(
+                    "{evaluations} {delta:+.4e} {delta:+.4e} {value:+.4e} {value:+.4e}\n"
+                ));
+            }
+            file.write_all(text.as_bytes())?;
+        }
+
+        // Each run is instance 1: the problems here have no transformed instances
+        let final_delta = run.best().map_or(f64::NAN, delta);
+        let entry = format!("1:{}|{final_delta:.1e}", run.evaluations);
+        let block = InfoBlock {
+            meta: format!(
+                "funcId = {}, DIM = {}, Precision = {PRECISION:.3e}, algId = '{}'",
+                problem.id,
+                problem.dimension,
+                self.algorithm
+            ),
+            comment: format!("% {}", self.info),
+            dat_path: format!("{data_dir}/{stem}.dat"),
+        };
+        let info = self.root.join(format!("bbobexp_f{}.info", problem.id));
+        add_info_entry(&info, problem.dimension, &block, &entry)
+    }
+}
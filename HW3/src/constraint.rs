@@ -0,0 +1,237 @@
+use crate::cache::CacheStats;
+use crate::decoder::Decoder;
+use crate::fitness::{Fitness, Objective};
+use crate::seeds::SeedSequence;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::sync::Mutex;
+
+// A constraint on the decoded solution, reporting how far a point is from feasibility
+pub trait Constraint {
+    // Returns 0.0 for feasible points and a positive violation amount otherwise
+    fn violation(&self, x: &[f64]) -> f64;
+}
+
+// A problem whose solutions can break constraints, exposing how far each one is broken. The
+// GA and ES compare members under Deb's feasibility rules when given one.
+pub trait Constrained {
+    // Violation of each constraint at x, 0.0 for the ones it satisfies
+    fn violations(&self, x: &[f64]) -> Vec<f64>;
+
+    // Sum of violations over all constraints, 0.0 exactly when x is feasible
+    fn total_violation(&self, x: &[f64]) -> f64 {
+        self.violations(x).iter().sum()
+    }
+}
+
+// Any number of constraints, all of which must hold
+#[derive(Default)]
+pub struct ConstraintSet {
+    constraints: Vec<Box<dyn Constraint + Send + Sync>>,
+}
+
+impl ConstraintSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, constraint: impl Constraint + Send + Sync + 'static) -> Self {
+        self.constraints.push(Box::new(constraint));
+        self
+    }
+}
+
+impl Constrained for ConstraintSet {
+    fn violations(&self, x: &[f64]) -> Vec<f64> {
+        self.constraints.iter().map(|c| c.violation(x)).collect()
+    }
+}
+
+// Deb's feasibility rules over (fitness, total violation) pairs: a feasible member beats an
+// infeasible one, two feasible members compare by fitness and two infeasible ones by
+// violation, the smaller being better. Greater means a is the better member.
+pub fn feasibility_order(a: (f64, f64), b: (f64, f64)) -> Ordering {
+    let (fitness_a, violation_a) = a;
+    let (fitness_b, violation_b) = b;
+    match (violation_a > 0.0, violation_b > 0.0) {
+        (false, false) => fitness_a.partial_cmp(&fitness_b).unwrap_or(Ordering::Equal),
+        (false, true) => Ordering::Greater,
+        (true, false) => Ordering::Less,
+        (true, true) => violation_b.partial_cmp(&violation_a).unwrap_or(Ordering::Equal),
+    }
+}
+
+// Constraint violation across a population for one generation
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ViolationStats {
+    // Fraction of members with no violation
+    pub feasible: f64,
+    pub mean_violation: f64,
+    pub max_violation: f64,
+}
+
+impl ViolationStats {
+    // Statistics of the members' total violations
+    pub fn of(violations: &[f64]) -> Self {
+        let n = violations.len().max(1) as f64;
+        Self {
+            feasible: violations.iter().filter(|&&v| v <= 0.0).count() as f64 / n,
+            mean_violation: violations.iter().sum::<f64>() / n,
+            max_violation: violations.iter().cloned().fold(0.0, f64::max),
+        }
+    }
+}
+
+// Feasible region is the ball of the given radius around center (same length as x)
+pub struct DiskConstraint {
+    pub center: Vec<f64>,
+    pub radius: f64,
+}
+
+impl Constraint for DiskConstraint {
+    fn violation(&self, x: &[f64]) -> f64 {
+        let dist = x
+            .iter()
+            .zip(&self.center)
+            .map(|(xi, ci)| (xi - ci).powi(2))
+            .sum::<f64>()
+            .sqrt();
+        (dist - self.radius).max(0.0)
+    }
+}
+
+// How the penalty weight applied to the total violation is chosen
+#[derive(Debug, Clone, Copy)]
+pub enum PenaltyScheme {
+    // Fixed weight for the whole run
+    Static { weight: f64 },
+    // Weight (coefficient * t)^alpha, where t counts steps of evals_per_step evaluations
+    Dynamic { coefficient: f64, alpha: f64, evals_per_step: usize },
+    // Weight is multiplied by increase after a window with no feasible evaluations and
+    // divided by decrease after a window where every evaluation was feasible
+    Adaptive { initial_weight: f64, increase: f64, decrease: f64, window: usize },
+}
+
+struct PenaltyState {
+    evaluations: usize,
+    weight: f64,
+    window_evals: usize,
+    window_feasible: usize,
+}
+
+// Wraps any fitness function and penalizes constraint violations. Fitness stays in (0, 1] by
+// dividing the raw fitness by (1 + weight * violation).
+pub struct PenalizedFitness<F: Objective> {
+    inner: F,
+    constraints: ConstraintSet,
+    scheme: PenaltyScheme,
+    state: Mutex<PenaltyState>,
+}
+
+impl<F: Objective> PenalizedFitness<F> {
+    pub fn new(inner: F, scheme: PenaltyScheme) -> Self {
+        let weight = match scheme {
+            PenaltyScheme::Static { weight } => weight,
+            PenaltyScheme::Dynamic { coefficient, alpha, .. } => coefficient.powf(alpha),
+            PenaltyScheme::Adaptive { initial_weight, .. } => initial_weight,
+        };
+        Self {
+            inner,
+            constraints: ConstraintSet::new(),
+            scheme,
+            state: Mutex::new(PenaltyState {
+                evaluations: 0,
+                weight,
+                window_evals: 0,
+                window_feasible: 0,
+            }),
+        }
+    }
+
+    pub fn with_constraint(mut self, constraint: impl Constraint + Send + Sync + 'static) -> Self {
+        self.constraints = self.constraints.with(constraint);
+        self
+    }
+
+    pub fn current_weight(&self) -> f64 {
+        self.state.lock().unwrap().weight
+    }
+
+    // Records one evaluation and returns the penalty weight to apply to it
+    fn next_weight(&self, violation: f64) -> f64 {
+        let mut state = self.state.lock().unwrap();
+        state.evaluations += 1;
+        match self.scheme {
+            PenaltyScheme::Static { .. } => {}
+            PenaltyScheme::Dynamic { coefficient, alpha, evals_per_step } => {
+                let t = 1 + state.evaluations / evals_per_step.max(1);
+                state.weight = (coefficient * t as f64).powf(alpha);
+            }
+            PenaltyScheme::Adaptive { increase, decrease, window, .. } => {
+                state.window_evals += 1;
+                if violation == 0.0 {
+                    state.window_feasible += 1;
+                }
+                if state.window_evals >= window.max(1) {
+                    if state.window_feasible == 0 {
+                        state.weight *= increase;
+                    } else if state.window_feasible == state.window_evals {
+                        state.weight /= decrease;
+                    }
+                    state.window_evals = 0;
+                    state.window_feasible = 0;
+                }
+            }
+        }
+        state.weight
+    }
+
+    fn penalize(&self, raw_fitness: f64, x: &[f64]) -> f64 {
+        let violation = self.total_violation(x);
+        let weight = self.next_weight(violation);
+        raw_fitness / (1.0 + weight * violation)
+    }
+}
+
+impl<F: Objective> Constrained for PenalizedFitness<F> {
+    fn violations(&self, x: &[f64]) -> Vec<f64> {
+        self.constraints.violations(x)
+    }
+}
+
+impl<F: Objective> Objective for PenalizedFitness<F> {
+    fn fitness(&self, member: &[f64]) -> f64 {
+        self.penalize(self.inner.fitness(member), member)
+    }
+
+    // Penalties are applied in member order, so an adaptive weight sees the same sequence of
+    // violations as with one call per member
+    fn evaluate_population(&self, members: &[Vec<f64>]) -> Vec<f64> {
+        let values = self.inner.evaluate_population(members);
+        values.into_iter().zip(members).map(|(value, x)| self.penalize(value, x)).collect()
+    }
+
+    // An adaptive weight still depends on the order batches are evaluated in
+    fn evaluate_population_seeded(
+        &self,
+        members: &[Vec<f64>],
+        streams: &[SeedSequence],
+    ) -> Vec<f64> {
+        let values = self.inner.evaluate_population_seeded(members, streams);
+        values.into_iter().zip(members).map(|(value, x)| self.penalize(value, x)).collect()
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        self.inner.bounds()
+    }
+
+    fn cache_stats(&self) -> Option<CacheStats> {
+        self.inner.cache_stats()
+    }
+}
+
+impl<F: Fitness> Fitness for PenalizedFitness<F> {
+    fn decoder(&self) -> impl Decoder {
+        self.inner.decoder()
+    }
+}
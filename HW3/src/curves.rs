@@ -0,0 +1,186 @@
+use crate::statistics::quantile;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+
+// One row of a CSV trace as written by CsvTrace
+#[derive(Debug, Clone)]
+pub struct TraceRow {
+    pub generation: usize,
+    pub evaluations: usize,
+    pub best: f64,
+    pub mean: f64,
+}
+
+// Reads the generation, evaluations, best and mean columns of a CSV trace
+pub fn read_trace(path: &str) -> Result<Vec<TraceRow>, Box<dyn Error>> {
+    let contents = fs::read_to_string(path)?;
+    let mut rows = Vec::new();
+    // Skip the header: generation,evaluations,best,mean,std,diversity
+    for line in contents.lines().skip(1).filter(|line| !line.is_empty()) {
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() < 4 {
+            return Err(format!("{path}: malformed trace line '{line}'").into());
+        }
+        rows.push(TraceRow {
+            generation: fields[0].parse()?,
+            evaluations: fields[1].parse()?,
+            best: fields[2].parse()?,
+            mean: fields[3].parse()?,
+        });
+    }
+    Ok(rows)
+}
+
+// One row of a QD trace as written by logging::QdTrace
+#[derive(Debug, Clone)]
+pub struct QdTraceRow {
+    pub generation: usize,
+    pub evaluations: usize,
+    pub coverage: f64,
+    pub qd_score: f64,
+    pub max_fitness: f64,
+}
+
+// Reads a QD trace: generation,evaluations,coverage,qd_score,max_fitness
+pub fn read_qd_trace(path: &str) -> Result<Vec<QdTraceRow>, Box<dyn Error>> {
+    let contents = fs::read_to_string(path)?;
+    let mut rows = Vec::new();
+    for line in contents.lines().skip(1).filter(|line| !line.is_empty()) {
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() < 5 {
+            return Err(format!("{path}: malformed QD trace line '{line}'").into());
+        }
+        rows.push(QdTraceRow {
+            generation: fields[0].parse()?,
+            evaluations: fields[1].parse()?,
+            coverage: fields[2].parse()?,
+            qd_score: fields[3].parse()?,
+            max_fitness: fields[4].parse()?,
+        });
+    }
+    Ok(rows)
+}
+
+// The traces of the runs of one setting
+pub type Runs = Vec<Vec<TraceRow>>;
+
+// Median and interquartile range, across runs, of the best fitness found within a number of
+// evaluations
+#[derive(Debug, Clone)]
+pub struct CurvePoint {
+    pub evaluations: usize,
+    // Runs that had evaluated their first generation by this point
+    pub runs: usize,
+    pub median: f64,
+    pub q1: f64,
+    pub q3: f64,
+}
+
+// Best fitness found so far after each generation, against the evaluations used
+pub fn best_so_far(trace: &[TraceRow]) -> Vec<(usize, f64)> {
+    trace
+        .iter()
+        .scan(f64::NEG_INFINITY, |best, row| {
+            *best = best.max(row.best);
+            Some((row.evaluations, *best))
+        })
+        .collect()
+}
+
+// Aligns the runs of one setting on `points` evenly spaced evaluation counts up to where the
+// longest run stopped. Runs differ in evaluations per generation, so generations don't line
+// up; a run that stopped early keeps its final best for the later points.
+pub fn aggregate(runs: &[Vec<TraceRow>], points: usize) -> Vec<CurvePoint> {
+    let curves: Vec<Vec<(usize, f64)>> = runs.iter().map(|run| best_so_far(run)).collect();
+    let max_evaluations = curves.iter().filter_map(|curve| curve.last()).map(|p| p.0).max();
+    let Some(max_evaluations) = max_evaluations else {
+        return Vec::new();
+    };
+    let steps = points.max(2) - 1;
+    (1..=steps)
+        .map(|step| max_evaluations * step / steps)
+        .filter_map(|evaluations| {
+            let values: Vec<f64> = curves
+                .iter()
+                .filter_map(|curve| {
+                    let reached = curve.partition_point(|&(evals, _)| evals <= evaluations);
+                    reached.checked_sub(1).map(|last| curve[last].1)
+                })
+                .collect();
+            (!values.is_empty()).then(|| CurvePoint {
+                evaluations,
+                runs: values.len(),
+                median: quantile(&values, 0.5),
+                q1: quantile(&values, 0.25),
+                q3: quantile(&values, 0.75),
+            })
+        })
+        .collect()
+}
+
+// The traces in `dir` grouped by setting: the runs `<alg>_<combination>_run<run>.csv` of a
+// tuning session share the setting `<alg>_<combination>`, and any other trace is a setting of
+// its own. Settings come back in combination order; files that aren't traces are skipped.
+pub fn read_trace_dir(dir: &str) -> Result<Vec<(String, Runs)>, Box<dyn Error>> {
+    let mut settings: BTreeMap<(String, Option<usize>), Runs> = BTreeMap::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "csv") {
+            continue;
+        }
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let trace = match read_trace(&path.display().to_string()) {
+            Ok(trace) if !trace.is_empty() => trace,
+            Ok(_) => continue,
+            Err(e) => {
+                eprintln!("Skipping {}: {e}", path.display());
+                continue;
+            }
+        };
+        let setting = stem
+            .rsplit_once("_run")
+            .filter(|(_, run)| run.parse::<usize>().is_ok())
+            .and_then(|(setting, _)| setting.rsplit_once('_'))
+            .and_then(|(algorithm, combination)| {
+                Some((algorithm.to_string(), Some(combination.parse().ok()?)))
+            })
+            .unwrap_or((stem, None));
+        settings.entry(setting).or_default().push(trace);
+    }
+    Ok(settings
+        .into_iter()
+        .map(|((name, combination), runs)| match combination {
+            Some(combination) => (format!("{name}_{combination}"), runs),
+            None => (name, runs),
+        })
+        .collect())
+}
+
+// Keeps the `count` curves with the best final median, best first
+pub fn top_by_final_median(curves: &mut Vec<(String, Vec<CurvePoint>)>, count: usize) {
+    let final_median =
+        |curve: &[CurvePoint]| curve.last().map_or(f64::NEG_INFINITY, |point| point.median);
+    curves.sort_by(|a, b| final_median(&b.1).total_cmp(&final_median(&a.1)));
+    curves.truncate(count);
+}
+
+pub fn save_curves_to_csv(
+    curves: &[(String, Vec<CurvePoint>)],
+    filename: &str,
+) -> Result<(), Box<dyn Error>> {
+    let mut file = BufWriter::new(File::create(filename)?);
+    writeln!(file, "setting,evaluations,runs,median,q1,q3")?;
+    for (setting, curve) in curves {
+        for point in curve {
+            writeln!(
+                file,
+                "{setting},{},{},{},{},{}",
+                point.evaluations, point.runs, point.median, point.q1, point.q3
+            )?;
+        }
+    }
+    file.flush()?;
+    Ok(())
+}
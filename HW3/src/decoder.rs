@@ -0,0 +1,226 @@
+use crate::bitgenome::BitGenome;
+use crate::bytegenome::ByteGenome;
+
+// Maps a bitstring genome to the real-valued phenotype an Objective is evaluated on, so one
+// objective serves binary, Gray-coded and real-valued representations alike. Real-valued
+// genomes are their own phenotype and go through no decoder.
+pub trait Decoder {
+    fn decode_bitstring(&self, bitstring: &str, num_dims: usize) -> Vec<f64>;
+
+    // Same decoding as decode_bitstring for the packed representation
+    fn decode_bitgenome(&self, genome: &BitGenome, num_dims: usize) -> Vec<f64>;
+
+    // Same decoding as decode_bitstring for the byte-per-bit representation
+    fn decode_bytes(&self, genome: &ByteGenome, num_dims: usize) -> Vec<f64>;
+
+    // Inverse of decode_bitstring: a bitstring of len bits decoding to about the phenotype,
+    // or None where the decoder can't produce one of that length. Islands of different
+    // representations translate their migrants through it.
+    fn encode_bitstring(&self, _phenotype: &[f64], _len: usize) -> Option<String> {
+        None
+    }
+}
+
+impl<D: Decoder + ?Sized> Decoder for &D {
+    fn decode_bitstring(&self, bitstring: &str, num_dims: usize) -> Vec<f64> {
+        (**self).decode_bitstring(bitstring, num_dims)
+    }
+
+    fn decode_bitgenome(&self, genome: &BitGenome, num_dims: usize) -> Vec<f64> {
+        (**self).decode_bitgenome(genome, num_dims)
+    }
+
+    fn decode_bytes(&self, genome: &ByteGenome, num_dims: usize) -> Vec<f64> {
+        (**self).decode_bytes(genome, num_dims)
+    }
+
+    fn encode_bitstring(&self, phenotype: &[f64], len: usize) -> Option<String> {
+        (**self).encode_bitstring(phenotype, len)
+    }
+}
+
+// Every bit is a dimension of its own and decodes to 0.0 or 1.0, for selection problems such
+// as MaxOnes and the knapsack. The dimension count is ignored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PerBit;
+
+impl Decoder for PerBit {
+    fn decode_bitstring(&self, bitstring: &str, _num_dims: usize) -> Vec<f64> {
+        bitstring
+            .chars()
+            .map(|c| if c == '1' { 1.0 } else { 0.0 })
+            .collect()
+    }
+
+    fn decode_bitgenome(&self, genome: &BitGenome, _num_dims: usize) -> Vec<f64> {
+        genome.decode(genome.len(), (0.0, 1.0), Encoding::Binary)
+    }
+
+    fn decode_bytes(&self, genome: &ByteGenome, _num_dims: usize) -> Vec<f64> {
+        genome.0.iter().map(|&bit| bit as f64).collect()
+    }
+
+    // Values from 0.5 up encode to 1
+    fn encode_bitstring(&self, phenotype: &[f64], len: usize) -> Option<String> {
+        (phenotype.len() == len)
+            .then(|| phenotype.iter().map(|&x| if x >= 0.5 { '1' } else { '0' }).collect())
+    }
+}
+
+// Integer encoding of each bitstring segment. With Gray code neighbouring values differ in a
+// single bit, which removes the Hamming cliffs of plain binary (e.g. 0111 -> 1000).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    Binary,
+    Gray,
+}
+
+pub fn binary_to_gray(value: u64) -> u64 {
+    value ^ (value >> 1)
+}
+
+pub fn gray_to_binary(gray: u64) -> u64 {
+    let mut value = gray;
+    let mut shift = gray >> 1;
+    while shift != 0 {
+        value ^= shift;
+        shift >>= 1;
+    }
+    value
+}
+
+// Largest segment that decodes exactly: an f64 holds integers up to 2^53
+const MAX_BITS_PER_DIM: usize = 53;
+
+// Precision of a problem's own decoder, the one the GA's default genomes are sized for
+pub const DEFAULT_BITS_PER_DIM: usize = 16;
+
+// Maps segments of bits_per_dim bits onto evenly spaced values in bounds, so the search range
+// and the precision (the step between neighbouring values) of a bitstring problem are explicit
+// and checked once rather than implied by the bitstring length. Genomes must have exactly
+// bits_per_dim bits per dimension.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BinaryDecoder {
+    pub bounds: (f64, f64),
+    pub bits_per_dim: usize,
+    pub encoding: Encoding,
+}
+
+impl BinaryDecoder {
+    pub fn new(
+        bounds: (f64, f64),
+        bits_per_dim: usize,
+        encoding: Encoding,
+    ) -> Result<Self, String> {
+        let (low, high) = bounds;
+        if !low.is_finite() || !high.is_finite() || low >= high {
+            return Err(format!(
+                "Decode bounds must be finite with low < high, got [{low}, {high}]"
+            ));
+        }
+        if !(1..=MAX_BITS_PER_DIM).contains(&bits_per_dim) {
+            return Err(format!(
+                "Bits per dimension must be between 1 and {MAX_BITS_PER_DIM}, got {bits_per_dim}"
+            ));
+        }
+        Ok(Self { bounds, bits_per_dim, encoding })
+    }
+
+    // Distance between neighbouring decoded values
+    pub fn precision(&self) -> f64 {
+        (self.bounds.1 - self.bounds.0) / (2_f64.powi(self.bits_per_dim as i32) - 1.0)
+    }
+
+    pub fn encode(&self, x: &[f64]) -> String {
+        encode_binary(x, self.bits_per_dim, self.bounds, self.encoding)
+    }
+}
+
+impl Decoder for BinaryDecoder {
+    fn decode_bitstring(&self, bitstring: &str, num_dims: usize) -> Vec<f64> {
+        if bitstring.len() != num_dims * self.bits_per_dim {
+            panic!(
+                "Bitstring of {} bits doesn't hold {num_dims} dimensions of {} bits",
+                bitstring.len(),
+                self.bits_per_dim
+            );
+        }
+        decode_binary(bitstring, num_dims, self.bounds, self.encoding)
+    }
+
+    fn decode_bitgenome(&self, genome: &BitGenome, num_dims: usize) -> Vec<f64> {
+        if genome.len() != num_dims * self.bits_per_dim {
+            panic!(
+                "Genome of {} bits doesn't hold {num_dims} dimensions of {} bits",
+                genome.len(),
+                self.bits_per_dim
+            );
+        }
+        genome.decode(num_dims, self.bounds, self.encoding)
+    }
+
+    fn decode_bytes(&self, genome: &ByteGenome, num_dims: usize) -> Vec<f64> {
+        if genome.len() != num_dims * self.bits_per_dim {
+            panic!(
+                "Genome of {} bits doesn't hold {num_dims} dimensions of {} bits",
+                genome.len(),
+                self.bits_per_dim
+            );
+        }
+        genome.decode(num_dims, self.bounds, self.encoding)
+    }
+
+    fn encode_bitstring(&self, phenotype: &[f64], len: usize) -> Option<String> {
+        (len == phenotype.len() * self.bits_per_dim).then(|| self.encode(phenotype))
+    }
+}
+
+// Splits the bitstring into num_dims equal segments and scales each one linearly into bounds
+pub fn decode_binary(
+    bitstring: &str,
+    num_dims: usize,
+    bounds: (f64, f64),
+    encoding: Encoding,
+) -> Vec<f64> {
+    if num_dims == 0 {
+        panic!("Number of dimensions must be greater than 0");
+    }
+    if !bitstring.len().is_multiple_of(num_dims) {
+        panic!("Bitstring length must be divisible by number of dimensions");
+    }
+
+    let segment_len = bitstring.len() / num_dims;
+    let max_val = 2_f64.powi(segment_len as i32) - 1.0;
+    let mut x: Vec<f64> = Vec::new();
+    for i in 0..num_dims {
+        let segment = &bitstring[segment_len * i..segment_len * (i + 1)];
+
+        // Convert binary strings (base 2) to integers, then to floats
+        let mut value = u64::from_str_radix(segment, 2).unwrap();
+        if encoding == Encoding::Gray {
+            value = gray_to_binary(value);
+        }
+        x.push((value as f64 / max_val) * (bounds.1 - bounds.0) + bounds.0);
+    }
+    x
+}
+
+// Inverse of decode_binary: quantizes each value of x to bits_per_dim bits within bounds
+pub fn encode_binary(
+    x: &[f64],
+    bits_per_dim: usize,
+    bounds: (f64, f64),
+    encoding: Encoding,
+) -> String {
+    let max_val = 2_f64.powi(bits_per_dim as i32) - 1.0;
+    let mut bitstring = String::new();
+    for &value in x {
+        let scaled = ((value - bounds.0) / (bounds.1 - bounds.0)).clamp(0.0, 1.0);
+        let mut level = (scaled * max_val).round() as u64;
+        if encoding == Encoding::Gray {
+            level = binary_to_gray(level);
+        }
+        bitstring.push_str(&format!("{level:0bits_per_dim$b}"));
+    }
+    bitstring
+}
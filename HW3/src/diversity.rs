@@ -0,0 +1,226 @@
+use crate::genome::{Genome, euclidean_distance};
+use rand::prelude::*;
+
+// What to do once the population is judged to have lost its diversity
+#[derive(Debug, Clone, Copy)]
+pub enum RestorationAction {
+    // Multiply the mutation rate by factor for the given number of generations
+    Hypermutation { factor: f64, generations: usize },
+    // Replace this fraction of the next generation with random genomes
+    RandomImmigrants { fraction: f64 },
+}
+
+// Diversity restoration: fires the action when the best fitness has stagnated or too much of
+// the population is made of identical copies. Either trigger can be left unset.
+#[derive(Debug, Clone, Copy)]
+pub struct DiversityRestoration {
+    // Generations without improvement before firing, and again every as many after that
+    pub stagnation_generations: Option<usize>,
+    // Fraction of identical individuals at which to fire
+    pub identical_threshold: Option<f64>,
+    pub action: RestorationAction,
+}
+
+// Tracks the triggers across generations and the state of an active hypermutation boost
+pub struct DiversityMonitor {
+    config: Option<DiversityRestoration>,
+    hypermutation_left: usize,
+    immigrants_due: bool,
+    pub activations: usize,
+}
+
+impl DiversityMonitor {
+    pub fn new(config: Option<DiversityRestoration>) -> Self {
+        Self {
+            config,
+            hypermutation_left: 0,
+            immigrants_due: false,
+            activations: 0,
+        }
+    }
+
+    // Checks the triggers against this generation's statistics, returning true if the action
+    // fires. A running hypermutation boost is not restarted.
+    pub fn update(&mut self, stagnant_generations: usize, pct_identical: f64) -> bool {
+        let Some(config) = self.config else {
+            return false;
+        };
+        if self.hypermutation_left > 0 {
+            return false;
+        }
+
+        let stagnated = config
+            .stagnation_generations
+            .is_some_and(|limit| stagnant_generations > 0 && stagnant_generations.is_multiple_of(limit));
+        let cloned = config
+            .identical_threshold
+            .is_some_and(|threshold| pct_identical >= threshold);
+        if !stagnated && !cloned {
+            return false;
+        }
+
+        self.activations += 1;
+        match config.action {
+            RestorationAction::Hypermutation { generations, .. } => {
+                self.hypermutation_left = generations;
+            }
+            RestorationAction::RandomImmigrants { .. } => self.immigrants_due = true,
+        }
+        true
+    }
+
+    // Mutation rate for this generation, boosted while hypermutation is active
+    pub fn mutation_rate(&self, base_rate: f64) -> f64 {
+        match self.config.map(|c| c.action) {
+            Some(RestorationAction::Hypermutation { factor, .. }) if self.hypermutation_left > 0 => {
+                (base_rate * factor).min(1.0)
+            }
+            _ => base_rate,
+        }
+    }
+
+    // Number of offspring to replace with random immigrants this generation
+    pub fn immigrants(&self, pop_size: usize) -> usize {
+        match self.config.map(|c| c.action) {
+            Some(RestorationAction::RandomImmigrants { fraction }) if self.immigrants_due => {
+                ((fraction * pop_size as f64).round() as usize).min(pop_size)
+            }
+            _ => 0,
+        }
+    }
+
+    pub fn end_generation(&mut self) {
+        self.hypermutation_left = self.hypermutation_left.saturating_sub(1);
+        self.immigrants_due = false;
+    }
+
+    pub fn describe(&self) -> String {
+        match self.config.map(|c| c.action) {
+            Some(RestorationAction::Hypermutation { factor, generations }) => format!(
+                "hypermutation x{factor} for {generations} generations ({} left)",
+                self.hypermutation_left
+            ),
+            Some(RestorationAction::RandomImmigrants { fraction }) => {
+                format!("random immigrants replacing {:.0}% of offspring", fraction * 100.0)
+            }
+            None => "none".to_string(),
+        }
+    }
+}
+
+// Handling of offspring identical to a member already placed in the next generation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DuplicatePolicy {
+    Allow,
+    // Discard the duplicate and breed another child in its place
+    Reject,
+    // Mutate the duplicate again, up to max_attempts times, then admit it regardless
+    Remutate { max_attempts: usize },
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DuplicateStats {
+    pub found: usize,
+    pub rejected: usize,
+    pub remutated: usize,
+}
+
+// Applies the duplicate policy to offspring as they enter the next generation
+pub struct DuplicateFilter {
+    policy: DuplicatePolicy,
+    // Rejections allowed per generation before duplicates are let through, so a fully
+    // converged population cannot stall the breeding loop
+    rejection_limit: usize,
+    pub generation: DuplicateStats,
+    pub total: DuplicateStats,
+}
+
+impl DuplicateFilter {
+    pub fn new(policy: DuplicatePolicy, pop_size: usize) -> Self {
+        Self {
+            policy,
+            rejection_limit: 10 * pop_size,
+            generation: DuplicateStats::default(),
+            total: DuplicateStats::default(),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.policy != DuplicatePolicy::Allow
+    }
+
+    // The child to add to the next generation, or None if it was rejected
+    pub fn admit<G: Genome>(
+        &mut self,
+        child: G,
+        next_generation: &[G],
+        mut remutate: impl FnMut(&G) -> G,
+    ) -> Option<G> {
+        if !self.is_active() || !next_generation.contains(&child) {
+            return Some(child);
+        }
+        self.generation.found += 1;
+
+        match self.policy {
+            DuplicatePolicy::Allow => Some(child),
+            DuplicatePolicy::Reject => {
+                if self.generation.rejected >= self.rejection_limit {
+                    return Some(child);
+                }
+                self.generation.rejected += 1;
+                None
+            }
+            DuplicatePolicy::Remutate { max_attempts } => {
+                let mut child = child;
+                for _ in 0..max_attempts {
+                    child = remutate(&child);
+                    if !next_generation.contains(&child) {
+                        break;
+                    }
+                }
+                self.generation.remutated += 1;
+                Some(child)
+            }
+        }
+    }
+
+    pub fn end_generation(&mut self) {
+        self.total.found += self.generation.found;
+        self.total.rejected += self.generation.rejected;
+        self.total.remutated += self.generation.remutated;
+        self.generation = DuplicateStats::default();
+    }
+}
+
+// Largest euclidean distance between two points, over every pair or, given a number of pairs
+// fewer than all n(n-1)/2, over that many pairs drawn at random. The sample can only miss the
+// farthest pair, so it underestimates, but it costs O(pairs) instead of O(n²).
+pub fn max_pairwise_distance<R: Rng + ?Sized>(
+    points: &[Vec<f64>],
+    pairs: Option<usize>,
+    rng: &mut R,
+) -> f64 {
+    let n = points.len();
+    let all_pairs = n * n.saturating_sub(1) / 2;
+    let mut diversity = 0.0;
+    match pairs {
+        Some(pairs) if pairs < all_pairs => {
+            for _ in 0..pairs {
+                let i = rng.random_range(0..n);
+                let mut j = rng.random_range(0..n - 1);
+                if j >= i {
+                    j += 1;
+                }
+                diversity = f64::max(diversity, euclidean_distance(&points[i], &points[j]));
+            }
+        }
+        _ => {
+            for i in 0..n {
+                for j in (i + 1)..n {
+                    diversity = f64::max(diversity, euclidean_distance(&points[i], &points[j]));
+                }
+            }
+        }
+    }
+    diversity
+}
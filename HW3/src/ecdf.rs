@@ -0,0 +1,90 @@
+use crate::curves::{TraceRow, best_so_far};
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+// Fraction of (run, target) pairs of a setting whose target was reached within a number of
+// evaluations
+#[derive(Debug, Clone)]
+pub struct EcdfPoint {
+    pub evaluations: usize,
+    pub fraction: f64,
+}
+
+// Evaluations each run used until its best-so-far fitness first reached `target`, None for the
+// runs that never did
+pub fn runtimes(runs: &[Vec<TraceRow>], target: f64) -> Vec<Option<usize>> {
+    runs.iter()
+        .map(|run| {
+            best_so_far(run)
+                .into_iter()
+                .find(|&(_, best)| best >= target)
+                .map(|(evaluations, _)| evaluations)
+        })
+        .collect()
+}
+
+// Runtime ECDF of one setting over all its (run, target) pairs, at `points` evenly spaced
+// evaluation counts up to `max_evaluations`. Sharing the evaluation axis keeps the ECDFs of
+// different settings comparable; pairs never solved keep the curve below 1.
+pub fn ecdf(
+    runs: &[Vec<TraceRow>],
+    targets: &[f64],
+    max_evaluations: usize,
+    points: usize,
+) -> Vec<EcdfPoint> {
+    let hits: Vec<Option<usize>> = targets.iter().flat_map(|&t| runtimes(runs, t)).collect();
+    if hits.is_empty() {
+        return Vec::new();
+    }
+    let steps = points.max(2) - 1;
+    (0..=steps)
+        .map(|step| {
+            let evaluations = max_evaluations * step / steps;
+            let solved = hits.iter().filter(|hit| hit.is_some_and(|e| e <= evaluations)).count();
+            EcdfPoint { evaluations, fraction: solved as f64 / hits.len() as f64 }
+        })
+        .collect()
+}
+
+// `count` targets evenly spaced from the worst first-generation best to the best fitness any
+// run reached, so the easiest target is solved by every run and the hardest by at least one
+pub fn default_targets<'a>(
+    runs: impl Iterator<Item = &'a Vec<TraceRow>>,
+    count: usize,
+) -> Vec<f64> {
+    let (easiest, hardest) = runs
+        .filter_map(|run| {
+            let best = run.iter().map(|row| row.best).reduce(f64::max)?;
+            Some((run.first()?.best, best))
+        })
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), (first, best)| {
+            (lo.min(first), hi.max(best))
+        });
+    if !easiest.is_finite() || !hardest.is_finite() {
+        return Vec::new();
+    }
+    let steps = count.max(2) - 1;
+    (0..=steps).map(|i| easiest + (hardest - easiest) * i as f64 / steps as f64).collect()
+}
+
+// Area under the ECDF as a fraction of the plot, higher is better: the solved fraction
+// averaged over the evaluation budgets
+pub fn area(ecdf: &[EcdfPoint]) -> f64 {
+    ecdf.iter().map(|point| point.fraction).sum::<f64>() / ecdf.len().max(1) as f64
+}
+
+pub fn save_ecdf_to_csv(
+    ecdfs: &[(String, Vec<EcdfPoint>)],
+    filename: &str,
+) -> Result<(), Box<dyn Error>> {
+    let mut file = BufWriter::new(File::create(filename)?);
+    writeln!(file, "setting,evaluations,fraction")?;
+    for (setting, ecdf) in ecdfs {
+        for point in ecdf {
+            writeln!(file, "{setting},{},{}", point.evaluations, point.fraction)?;
+        }
+    }
+    file.flush()?;
+    Ok(())
+}
@@ -1,6 +1,46 @@
-use crate::fitness::Fitness;
+use crate::algorithm::Algorithm;
+use crate::cache::CacheStats;
+use crate::cancellation::CancellationToken;
+use crate::constraint::{Constrained, ViolationStats, feasibility_order};
+use crate::diversity::max_pairwise_distance;
+use crate::fitness::Objective;
+use crate::genome::{RealCrossover, euclidean_distance, real_crossover};
+use crate::logging::{status_observer, trace_observer};
+use crate::mixed_integer::{self, GeneType};
+use crate::niching::Clearing;
+use crate::observer::{
+    ConsoleObserver, GenerationStats, Observer, RunSummary, StatsConfig, mean_and_std,
+};
+use crate::parameter_tuning::ParameterGrid;
+use crate::rate_control::{EvolutionPath, RateControl, RateController};
+use crate::repair::{
+    BoundHandling, BoundaryStats, MAX_RESAMPLES, NoRepair, Repair, RepairStats, in_range,
+};
+use crate::seeds::SeedSequence;
+use crate::stopping::{RestartPolicy, StopMonitor, StopReason, StoppingCriteria};
+use crate::surrogate::{Surrogate, SurrogateParameters};
+use crate::timing::{OperatorTimer, Phase};
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
+use std::f64::consts::PI;
+
+// Standard deviation of the rotation angle mutation, about 5 degrees as Schwefel recommends
+const ANGLE_STEP: f64 = 0.0873;
+
+// How offspring are mutated and their step sizes adapted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ESMutation {
+    // One self-adapted sigma per member, the same in every direction
+    Isotropic,
+    // Schwefel's correlated mutation: a sigma per gene and a rotation angle per pair of genes,
+    // all self-adapted, so the mutation ellipsoid can turn to follow a curved valley such as
+    // Rosenbrock's
+    Correlated,
+    // One sigma per member, adapted by cumulative step-size adaptation from the steps its
+    // lineage took rather than mutated at random, which is less noisy than self-adaptation
+    // at small populations
+    Cumulative,
+}
 
 #[derive(Debug, Clone)]
 pub struct ESParameters {
@@ -10,120 +50,782 @@ pub struct ESParameters {
     pub mem_range: (f64, f64),
     pub sigma: f64,
     pub tau: f64,
+    // None keeps log-normal self-adaptation of each individual's sigma; Some replaces it
+    // with one externally controlled sigma shared by all offspring
+    pub sigma_control: Option<RateControl>,
+    // Strategy parameters each member carries after its genes: one sigma, mem_size sigmas
+    // followed by mem_size * (mem_size - 1) / 2 rotation angles, or one sigma followed by an
+    // evolution path of mem_size values. sigma_control overrides these with an isotropic
+    // mutation, carrying the other strategy parameters unchanged.
+    pub mutation: ESMutation,
+    // Empty for all-continuous genes. Otherwise the type of each of the mem_size genes, and
+    // members are initialized in their ranges and mutated by the mixed-integer mutation, in
+    // place of `mutation` and sigma_control; sigma is then a fraction of each gene's range.
+    pub gene_types: Vec<GeneType>,
+    // What happens to genes mutated outside mem_range; mixed-integer genes keep to their own
+    // ranges instead
+    pub bound_handling: BoundHandling,
+    // None mutates a single tournament winner; Some first recombines it with a second one
+    pub recombination: Option<RealCrossover>,
+    // Clears offspring fitnesses by niche before the best mu survive, so survivors spread
+    // over the niches' winners
+    pub clearing: Option<Clearing>,
     pub max_gens: usize,
+    pub convergence_threshold: f64,
+    pub resamples: usize,
+    pub stopping: StoppingCriteria,
+    // Pre-screens offspring with a model of the fitness so only the most promising are
+    // evaluated, for expensive objectives
+    pub surrogate: Option<SurrogateParameters>,
+    // Restarts with a bigger population on stagnation or convergence, instead of stopping
+    pub restarts: Option<RestartPolicy>,
+    // Only `diversity` applies to the ES
+    pub stats: StatsConfig,
+    // Writes the run as JSON lines to this file in place of the console status lines
+    pub log_path: Option<String>,
+    // Seed the caller's RNG was created from, recorded in the run log for replay
+    pub seed: Option<u64>,
+    // Writes a CSV of per-generation metrics to this file, alongside the other output
+    pub trace_path: Option<String>,
+}
+
+impl Default for ESParameters {
+    fn default() -> Self {
+        Self {
+            mu: 100,
+            lambda: 100,
+            mem_size: 10,
+            mem_range: (-5.12, 5.11),
+            sigma: 1.0,
+            tau: 1.0 / (2.0 * 10.0_f64).sqrt(),
+            sigma_control: None,
+            mutation: ESMutation::Isotropic,
+            gene_types: Vec::new(),
+            bound_handling: BoundHandling::Ignore,
+            recombination: None,
+            clearing: None,
+            max_gens: 1000,
+            convergence_threshold: 0.99,
+            resamples: 1,
+            stopping: StoppingCriteria::default(),
+            surrogate: None,
+            restarts: None,
+            stats: StatsConfig::default(),
+            log_path: None,
+            seed: None,
+            trace_path: None,
+        }
+    }
 }
 
-fn init_population(
+impl ESParameters {
+    // Strategy parameters of a new member: every sigma at the initial step size and the
+    // rotation angles at zero, which starts correlated mutation off isotropic
+    fn initial_strategy(&self) -> Vec<f64> {
+        if !self.gene_types.is_empty() {
+            return mixed_integer::initial_strategy(&self.gene_types, self.sigma);
+        }
+        match self.mutation {
+            ESMutation::Isotropic => vec![self.sigma],
+            ESMutation::Cumulative => [vec![self.sigma], vec![0.0; self.mem_size]].concat(),
+            ESMutation::Correlated => {
+                let angles = self.mem_size * self.mem_size.saturating_sub(1) / 2;
+                [vec![self.sigma; self.mem_size], vec![0.0; angles]].concat()
+            }
+        }
+    }
+}
+
+fn init_population<R: Rng + ?Sized>(
     params: &ESParameters,
-    rng: &mut ChaCha8Rng,
+    mu: usize,
+    rng: &mut R,
 ) -> Vec<Vec<f64>> {
     let mut population = Vec::new();
-    for _ in 0..params.mu {
+    for _ in 0..mu {
+        if !params.gene_types.is_empty() {
+            let genes = mixed_integer::random_genes(&params.gene_types, rng);
+            population.push([genes, params.initial_strategy()].concat());
+            continue;
+        }
         let mut member: Vec<f64> = Vec::new();
         for _ in 0..params.mem_size {
             let gene: f64 = rng.random_range(params.mem_range.0..params.mem_range.1);
             member.push(gene);
         }
-        member.push(params.sigma);
+        member.extend(params.initial_strategy());
         population.push(member);
     }
     population
 }
 
-pub fn evolution_strategy<F: Fitness>(
+pub fn evolution_strategy<F: Objective, R: Rng + ?Sized>(
+    fitness_fn: &F,
+    params: &ESParameters,
+    rng: &mut R,
+) -> Vec<Vec<f64>> {
+    evolution_strategy_with_repair(fitness_fn, params, &NoRepair, rng)
+}
+
+// ES where the genes (not sigma) of every offspring are passed through a repair operator
+pub fn evolution_strategy_with_repair<F: Objective, R: Rng + ?Sized>(
     fitness_fn: &F,
     params: &ESParameters,
-    rng: &mut ChaCha8Rng,
+    repair: &impl Repair,
+    rng: &mut R,
 ) -> Vec<Vec<f64>> {
-    // Initialize population
-    let mut population = init_population(params, rng);
-    let mut cumulative_evals = 0;
+    EvolutionStrategy::new(fitness_fn, params, repair, rng).run(rng)
+}
 
-    for generation_number in 1..=params.max_gens {
-        // Evaluate fitness of current population
-        let fitnesses: Vec<f64> = population
+// Self-adapts the sigmas log-normally, with the global learning rate tau and a per-gene one,
+// and the rotation angles additively, then adds a normal step with those sigmas rotated by
+// every angle in turn. Returns the child's genes followed by its new strategy parameters.
+fn correlated_mutation<R: Rng + ?Sized>(
+    genes: &[f64],
+    strategy: &[f64],
+    tau: f64,
+    rng: &mut R,
+) -> Vec<f64> {
+    let n = genes.len();
+    let (sigmas, angles) = strategy.split_at(n);
+    let local_tau = 1.0 / (2.0 * (n as f64).sqrt()).sqrt();
+    let global: f64 = tau * rng.sample::<f64, _>(rand_distr::StandardNormal);
+    let sigmas: Vec<f64> = sigmas
+        .iter()
+        .map(|sigma| {
+            let local: f64 = rng.sample(rand_distr::StandardNormal);
+            sigma * (global + local_tau * local).exp()
+        })
+        .collect();
+    let angles: Vec<f64> = angles
+        .iter()
+        .map(|angle| {
+            let step: f64 = rng.sample(rand_distr::StandardNormal);
+            (angle + ANGLE_STEP * step + PI).rem_euclid(2.0 * PI) - PI
+        })
+        .collect();
+
+    let mut step: Vec<f64> = sigmas
+        .iter()
+        .map(|sigma| sigma * rng.sample::<f64, _>(rand_distr::StandardNormal))
+        .collect();
+    let pairs = (0..n).flat_map(|p| (p + 1..n).map(move |q| (p, q)));
+    for ((p, q), &angle) in pairs.zip(&angles) {
+        let (sin, cos) = angle.sin_cos();
+        let (a, b) = (step[p], step[q]);
+        step[p] = a * cos - b * sin;
+        step[q] = a * sin + b * cos;
+    }
+
+    let mut child: Vec<f64> = genes.iter().zip(&step).map(|(gene, step)| gene + step).collect();
+    child.extend(sigmas);
+    child.extend(angles);
+    child
+}
+
+// State of an ES run between generations, advanced through the Algorithm trait
+pub struct EvolutionStrategy<'a, F: Objective, P: Repair> {
+    fitness_fn: &'a F,
+    params: &'a ESParameters,
+    repair: &'a P,
+    population: Vec<Vec<f64>>,
+    // Population sizes of the current restart, params.mu and params.lambda until the first
+    mu: usize,
+    lambda: usize,
+    restarts: usize,
+    generation: usize,
+    cumulative_evals: usize,
+    repair_stats: RepairStats,
+    boundary: BoundaryStats,
+    monitor: StopMonitor,
+    sigma_controller: Option<RateController>,
+    evolution_path: Option<EvolutionPath>,
+    surrogate: Option<Surrogate>,
+    cancellation: CancellationToken,
+    // Cache counts when the run started, as the cache may outlive it
+    cache_start: Option<CacheStats>,
+    observers: Vec<Box<dyn Observer<Vec<f64>> + 'a>>,
+    // Best fitness and member of the whole run; the monitor only sees the current restart
+    best_fitness: f64,
+    best_individual: Option<Vec<f64>>,
+    // Constraints members are compared under, if any
+    constraints: Option<&'a dyn Constrained>,
+    timer: OperatorTimer,
+    // Draws the pairs sampled for diversity, apart from the run's own stream
+    diversity_rng: ChaCha8Rng,
+    // Streams of the evaluations, one per generation, batch and member
+    evaluation_seeds: SeedSequence,
+}
+
+impl<'a, F: Objective, P: Repair> EvolutionStrategy<'a, F, P> {
+    pub fn new<R: Rng + ?Sized>(
+        fitness_fn: &'a F,
+        params: &'a ESParameters,
+        repair: &'a P,
+        rng: &mut R,
+    ) -> Self {
+        let mut observers = vec![status_observer(
+            params.log_path.as_deref(),
+            "ES",
+            ParameterGrid::params_to_map_es(params),
+            params.seed,
+            ConsoleObserver::es(params),
+        )];
+        if let Some(trace) = trace_observer(params.trace_path.as_deref()) {
+            observers.push(Box::new(trace));
+        }
+
+        Self {
+            fitness_fn,
+            params,
+            repair,
+            population: init_population(params, params.mu, rng),
+            mu: params.mu,
+            lambda: params.lambda,
+            restarts: 0,
+            generation: 0,
+            cumulative_evals: 0,
+            repair_stats: RepairStats::default(),
+            boundary: BoundaryStats::default(),
+            monitor: StopMonitor::new(&params.stopping, fitness_fn.optimum_value()),
+            sigma_controller: params
+                .sigma_control
+                .filter(|_| params.gene_types.is_empty())
+                .map(|control| RateController::new(control, params.sigma, params.max_gens)),
+            evolution_path: (params.sigma_control.is_none()
+                && params.mutation == ESMutation::Cumulative)
+                .then(|| EvolutionPath::new(params.mem_size)),
+            surrogate: params.surrogate.map(|model| Surrogate::new(model, params.mem_range)),
+            cancellation: CancellationToken::never(),
+            cache_start: fitness_fn.cache_stats(),
+            observers,
+            best_fitness: f64::NEG_INFINITY,
+            best_individual: None,
+            constraints: None,
+            timer: OperatorTimer::new(params.stats.timing),
+            diversity_rng: SeedSequence::new(params.seed.unwrap_or_default())
+                .operator("diversity")
+                .rng(),
+            evaluation_seeds: SeedSequence::new(params.seed.unwrap_or_default())
+                .operator("evaluation"),
+        }
+    }
+
+    // Stops the run at the next check once the token is cancelled
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    // Replaces part of the random initial population with known members, as the GA's
+    // with_seeds does. A seed is either the genes alone, which start from the initial strategy
+    // parameters, or a whole member with its own; seeds of any other length are skipped.
+    pub fn with_seeds(mut self, seeds: &[Vec<f64>], fraction: f64) -> Self {
+        let count = (fraction.clamp(0.0, 1.0) * self.population.len() as f64).round() as usize;
+        let (mem_size, strategy) = (self.params.mem_size, self.params.initial_strategy());
+        let valid: Vec<Vec<f64>> = seeds
             .iter()
-            .map(|member| fitness_fn.fitness(&member[0..params.mem_size]))
+            .filter_map(|seed| match seed.len() {
+                len if len == mem_size => Some([seed.as_slice(), &strategy].concat()),
+                len if len == mem_size + strategy.len() => Some(seed.clone()),
+                _ => None,
+            })
             .collect();
-        cumulative_evals += params.mu;
+        let seeded = count.min(valid.len());
+        let skipped = seeds.len() - valid.len();
+        for (member, seed) in self.population.iter_mut().zip(valid.into_iter().take(seeded)) {
+            *member = seed;
+        }
+        if !seeds.is_empty() {
+            let message = format!(
+                "Seeded {seeded} of {} initial members; {skipped} seeds had the wrong length",
+                self.population.len()
+            );
+            self.report(&message);
+        }
+        self
+    }
+
+    // Compares members under Deb's feasibility rules in parent tournaments and survivor
+    // selection instead of by fitness alone, and reports violation statistics each generation
+    pub fn with_constraints(mut self, constraints: &'a dyn Constrained) -> Self {
+        self.constraints = Some(constraints);
+        self
+    }
+
+    // Registers an observer alongside the console output
+    pub fn with_observer(mut self, observer: impl Observer<Vec<f64>> + 'a) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    // Removes all observers, including the console output, for runs driven in bulk
+    pub fn quiet(mut self) -> Self {
+        self.observers.clear();
+        self
+    }
+
+    // The parents of the next generation, each with its strategy parameters after the genes
+    pub fn population(&self) -> &[Vec<f64>] {
+        &self.population
+    }
+
+    // Migration with other islands: the genes of the best `count` members are handed to
+    // `swap`, and the genes it returns replace the worst members, starting from the initial
+    // strategy parameters. Immigrants of the wrong length are skipped. Ranking the members
+    // costs an evaluation of the population.
+    pub fn exchange(
+        &mut self,
+        count: usize,
+        swap: impl FnOnce(Vec<Vec<f64>>) -> Vec<Vec<f64>>,
+    ) {
+        let params = self.params;
+        let genes: Vec<Vec<f64>> =
+            self.population.iter().map(|member| member[0..params.mem_size].to_vec()).collect();
+        let seeds = self.evaluation_seeds.generation(self.generation).operator("exchange");
+        let streams = seeds.individuals(genes.len());
+        let resamples = params.resamples;
+        let fitnesses =
+            self.fitness_fn.evaluate_population_seeded_resampled(&genes, &streams, resamples);
+        self.cumulative_evals += genes.len() * params.resamples;
+        let mut order: Vec<usize> = (0..genes.len()).collect();
+        order.sort_by(|&i, &j| fitnesses[j].total_cmp(&fitnesses[i]));
+
+        let emigrants = order.iter().take(count).map(|&i| genes[i].clone()).collect();
+        let strategy = params.initial_strategy();
+        let immigrants = swap(emigrants).into_iter().filter(|genes| genes.len() == params.mem_size);
+        for (&worst, immigrant) in order.iter().rev().zip(immigrants) {
+            self.population[worst] = [immigrant.as_slice(), &strategy].concat();
+        }
+    }
+
+    // This run's lookups in the problem's fitness cache, if it has one
+    fn cache_stats(&self) -> Option<CacheStats> {
+        self.fitness_fn.cache_stats().map(|stats| stats.since(self.cache_start))
+    }
+
+    // An offspring of the given genes and strategy parameters under the configured mutation:
+    // the child's genes followed by its strategy parameters
+    fn mutate<R: Rng + ?Sized>(&self, genes: &[f64], strategy: &[f64], rng: &mut R) -> Vec<f64> {
+        let params = self.params;
+        if !params.gene_types.is_empty() {
+            return mixed_integer::mixed_mutation(
+                genes,
+                strategy,
+                &params.gene_types,
+                params.tau,
+                rng,
+            );
+        }
+        if self.sigma_controller.is_none() && params.mutation == ESMutation::Correlated {
+            return correlated_mutation(genes, strategy, params.tau, rng);
+        }
+        if let Some(evolution_path) = &self.evolution_path {
+            let sigma_val = strategy[0];
+            let steps: Vec<f64> = (0..params.mem_size)
+                .map(|_| rng.sample::<f64, _>(rand_distr::StandardNormal))
+                .collect();
+            let mut child: Vec<f64> =
+                genes.iter().zip(&steps).map(|(gene, step)| gene + sigma_val * step).collect();
+            let mut path = strategy[1..].to_vec();
+            child.push(evolution_path.update(&mut path, sigma_val, &steps));
+            child.extend(path);
+            return child;
+        }
+        let mut child = Vec::new();
+        let sigma_val = match &self.sigma_controller {
+            Some(controller) => controller.value(),
+            None => strategy[0],
+        };
+        for &gene in genes {
+            let mutation: f64 =
+                rng.sample::<f64, _>(rand_distr::Normal::new(0.0, sigma_val).unwrap());
+            let mutated_gene = gene + mutation;
+            child.push(mutated_gene);
+        }
+        // Mutate sigma (self-adaptation only)
+        let new_sigma = if self.sigma_controller.is_some() {
+            sigma_val
+        } else {
+            let sigma_mutation: f64 =
+                rng.sample::<f64, _>(rand_distr::Normal::new(0.0, 1.0).unwrap());
+            sigma_val * (params.tau * sigma_mutation).exp()
+        };
+        child.push(new_sigma);
+        child.extend(&strategy[1..]);
+        child
+    }
+
+    // Starts over from a new random population, grown by the policy's factor, with fresh
+    // step-size control and stopping rules. The best-ever member and the counts carry over.
+    fn restart<R: Rng + ?Sized>(&mut self, policy: RestartPolicy, reason: StopReason, rng: &mut R) {
+        let params = self.params;
+        let grow = |size: usize| ((size as f64 * policy.population_factor).round() as usize).max(1);
+        self.restarts += 1;
+        self.mu = grow(self.mu);
+        self.lambda = grow(self.lambda);
+        self.population = init_population(params, self.mu, rng);
+        self.monitor = StopMonitor::new(&params.stopping, self.fitness_fn.optimum_value());
+        if let Some(control) = params.sigma_control.filter(|_| params.gene_types.is_empty()) {
+            self.sigma_controller =
+                Some(RateController::new(control, params.sigma, params.max_gens));
+        }
+        if self.evolution_path.is_some() {
+            self.evolution_path = Some(EvolutionPath::new(params.mem_size));
+        }
+        let message = format!(
+            "Restart {} of {} at generation {} ({reason}): mu {}, lambda {}",
+            self.restarts, policy.max_restarts, self.generation, self.mu, self.lambda
+        );
+        self.report(&message);
+    }
+
+    // Passes an operator report to every observer
+    fn report(&mut self, message: &str) {
+        for observer in &mut self.observers {
+            observer.on_message(message);
+        }
+    }
+}
+
+impl<F: Objective, P: Repair> Algorithm for EvolutionStrategy<'_, F, P> {
+    type Individual = Vec<f64>;
+
+    fn step<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<StopReason> {
+        let (fitness_fn, params) = (self.fitness_fn, self.params);
+        if self.generation >= params.max_gens {
+            return Some(StopReason::MaxGenerations);
+        }
+        self.timer.start_generation();
+        let generation_number = self.generation + 1;
+        let population = &self.population;
+        // Members carry their step size after the genes, the fitness only sees the genes.
+        // Member i of a batch draws any noise from seeds.individual(i).
+        let seeds = self.evaluation_seeds.generation(self.generation);
+        let evaluate_members = |members: &[Vec<f64>], streams: &[SeedSequence]| {
+            let genes: Vec<Vec<f64>> =
+                members.iter().map(|member| member[0..params.mem_size].to_vec()).collect();
+            fitness_fn.evaluate_population_seeded_resampled(&genes, streams, params.resamples)
+        };
+
+        // Evaluate fitness of current population
+        let started = self.timer.start();
+        let streams = seeds.individuals(population.len());
+        let batch = self.cancellation.evaluate_batch(population, &streams, evaluate_members);
+        let fitnesses = match batch {
+            Ok(values) => values,
+            Err(partial) => {
+                self.cumulative_evals += partial.len() * params.resamples;
+                return Some(StopReason::Cancelled);
+            }
+        };
+        self.cumulative_evals += self.mu * params.resamples;
+        self.timer.stop(Phase::Evaluation, started);
+        let genes_of = |members: &[Vec<f64>]| -> Vec<Vec<f64>> {
+            members.iter().map(|member| member[0..params.mem_size].to_vec()).collect()
+        };
+        if let Some(surrogate) = self.surrogate.as_mut() {
+            surrogate.record(&genes_of(population), &fitnesses);
+        }
+        // Total violation of each member, all 0.0 without constraints so that the feasibility
+        // rules come down to comparing fitness
+        let constraints = self.constraints;
+        let violations_of = |members: &[Vec<f64>]| -> Vec<f64> {
+            match constraints {
+                Some(constraints) => members
+                    .iter()
+                    .map(|member| constraints.total_violation(&member[0..params.mem_size]))
+                    .collect(),
+                None => vec![0.0; members.len()],
+            }
+        };
+        let violations = violations_of(population);
+        let parent_order = |&i: &usize, &j: &usize| {
+            feasibility_order((fitnesses[i], violations[i]), (fitnesses[j], violations[j]))
+        };
 
         // Create lambda offspring
         let mut offspring = Vec::new();
-        for _ in 0..params.lambda {
+        let mut parent_indices = Vec::new();
+        for _ in 0..self.lambda {
             // Select a parent using tournament selection
-            let parent_idx = (0..params.mu)
+            let started = self.timer.start();
+            let parent_idx = (0..self.mu)
                 .choose_multiple(rng, 2)
                 .into_iter()
-                .max_by(|&i, &j| fitnesses[i].partial_cmp(&fitnesses[j]).unwrap())
+                .max_by(parent_order)
                 .unwrap();
             let parent = &population[parent_idx];
+            parent_indices.push(parent_idx);
+            self.timer.stop(Phase::Selection, started);
+
+            // Recombine with a second tournament winner if configured; the child takes the
+            // first offspring's genes and the mean of the parents' strategy parameters
+            let (genes, strategy) = match params.recombination {
+                None => (parent[0..params.mem_size].to_vec(), parent[params.mem_size..].to_vec()),
+                Some(operator) => {
+                    let started = self.timer.start();
+                    let mate = &population[(0..self.mu)
+                        .choose_multiple(rng, 2)
+                        .into_iter()
+                        .max_by(parent_order)
+                        .unwrap()];
+                    self.timer.stop(Phase::Selection, started);
+                    let started = self.timer.start();
+                    let (mut genes, _) = real_crossover(
+                        &parent[0..params.mem_size],
+                        &mate[0..params.mem_size],
+                        operator,
+                        rng,
+                    );
+                    let types = &params.gene_types;
+                    if !types.is_empty() {
+                        mixed_integer::snap_recombined(&mut genes, parent, types);
+                    }
+                    let strategy = parent[params.mem_size..]
+                        .iter()
+                        .zip(&mate[params.mem_size..])
+                        .map(|(a, b)| 0.5 * (a + b))
+                        .collect();
+                    self.timer.stop(Phase::Crossover, started);
+                    (genes, strategy)
+                }
+            };
 
-            // Mutate the parent to create an offspring
-            let mut child = Vec::new();
-            let genes = &parent[0..params.mem_size];
-            let sigma_val = parent[params.mem_size];
-            for &gene in genes {
-                let mutation: f64 =
-                    rng.sample::<f64, _>(rand_distr::Normal::new(0.0, sigma_val).unwrap());
-                let mutated_gene = gene + mutation;
-                child.push(mutated_gene);
+            // Mutate the parent to create an offspring, bringing genes that left mem_range
+            // back as configured
+            let started = self.timer.start();
+            let mut child = self.mutate(&genes, &strategy, rng);
+            if params.gene_types.is_empty() && params.bound_handling != BoundHandling::Ignore {
+                let outside = |child: &[f64]| {
+                    child[0..params.mem_size].iter().any(|gene| !in_range(*gene, params.mem_range))
+                };
+                if params.bound_handling == BoundHandling::Resample {
+                    for _ in 0..MAX_RESAMPLES {
+                        if !outside(&child) {
+                            break;
+                        }
+                        self.boundary.resamples += 1;
+                        child = self.mutate(&genes, &strategy, rng);
+                    }
+                }
+                let genes = &mut child[0..params.mem_size];
+                let hits = params.bound_handling.apply(genes, params.mem_range);
+                self.boundary.genes += hits;
             }
-            // Mutate sigma
-            let sigma_mutation: f64 =
-                rng.sample::<f64, _>(rand_distr::Normal::new(0.0, 1.0).unwrap());
-            let new_sigma = sigma_val * (params.tau * sigma_mutation).exp();
-            child.push(new_sigma);
+            self.timer.stop(Phase::Mutation, started);
+
+            // Repair
+            self.repair_stats.record(self.repair.repair_real(&mut child[0..params.mem_size]));
             offspring.push(child);
         }
 
-        // Evaluate fitness of offspring
-        let offspring_fitnesses: Vec<f64> = offspring
-            .iter()
-            .map(|member| fitness_fn.fitness(&member[0..params.mem_size]))
-            .collect();
-        cumulative_evals += params.lambda;
-
-        let max_fitness = fitnesses.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-        let sum: f64 = fitnesses.iter().sum();
-        let average = sum / params.mu as f64;
-        let mut diversity = 0.0;
-        for i in 0..params.mu {
-            for j in (i + 1)..params.mu {
-                let dist = (0..params.mem_size)
-                    .map(|k| (population[i][k] - population[j][k]).powi(2))
-                    .sum::<f64>()
-                    .sqrt();
-                if dist > diversity {
-                    diversity = dist;
+        // Evaluate fitness of offspring, or only the ones the surrogate rates most promising,
+        // the others keeping their predicted fitness
+        let screened = self.surrogate.as_mut().and_then(|s| s.screen(&genes_of(&offspring)));
+        let mut distrust = None;
+        let started = self.timer.start();
+        let offspring_seeds = seeds.operator("offspring");
+        let offspring_fitnesses = match screened {
+            None => {
+                let streams = offspring_seeds.individuals(offspring.len());
+                let values =
+                    match self.cancellation.evaluate_batch(&offspring, &streams, evaluate_members) {
+                        Ok(values) => values,
+                        Err(partial) => {
+                            self.cumulative_evals += partial.len() * params.resamples;
+                            return Some(StopReason::Cancelled);
+                        }
+                    };
+                self.cumulative_evals += self.lambda * params.resamples;
+                if let Some(surrogate) = self.surrogate.as_mut() {
+                    surrogate.record(&genes_of(&offspring), &values);
                 }
+                values
+            }
+            Some((chosen, mut predicted)) => {
+                let members: Vec<Vec<f64>> = chosen.iter().map(|&i| offspring[i].clone()).collect();
+                let streams = offspring_seeds.individuals(members.len());
+                let values =
+                    match self.cancellation.evaluate_batch(&members, &streams, evaluate_members) {
+                        Ok(values) => values,
+                        Err(partial) => {
+                            self.cumulative_evals += partial.len() * params.resamples;
+                            return Some(StopReason::Cancelled);
+                        }
+                    };
+                self.cumulative_evals += members.len() * params.resamples;
+                let chosen_predictions: Vec<f64> = chosen.iter().map(|&i| predicted[i]).collect();
+                let surrogate = self.surrogate.as_mut().unwrap();
+                surrogate.record(&genes_of(&members), &values);
+                if surrogate.check(&chosen_predictions, &values) {
+                    let correlation = surrogate.stats.last_correlation.unwrap_or(f64::NAN);
+                    distrust = Some(format!(
+                        "Surrogate distrusted at generation {generation_number} (rank \
+                         correlation {correlation:.3}), evaluating the next generation in full"
+                    ));
+                }
+                for (&i, &value) in chosen.iter().zip(&values) {
+                    predicted[i] = value;
+                }
+                predicted
+            }
+        };
+        self.timer.stop(Phase::Evaluation, started);
+
+        // Success feedback: did each offspring beat its parent?
+        if let Some(controller) = self.sigma_controller.as_mut()
+            && controller.needs_success_feedback()
+        {
+            for (child_fitness, &parent_idx) in offspring_fitnesses.iter().zip(&parent_indices) {
+                controller.record_offspring(*child_fitness > fitnesses[parent_idx]);
             }
         }
-        println!(
-            "Dejong Rosenbrock ES {} {} {} 0.0 {} {} {} {} {}",
-            params.mu, params.lambda, params.tau, generation_number, cumulative_evals, max_fitness, average, diversity
-        );
 
-        // Early stopping if average fitness exceeds threshold
-        if average > 0.99 {
-            break;
+        let started = self.timer.start();
+        let (best_index, max_fitness) = fitnesses
+            .iter()
+            .copied()
+            .enumerate()
+            .fold((0, f64::NEG_INFINITY), |best, (i, f)| if f > best.1 { (i, f) } else { best });
+        let (average, std_fitness) = mean_and_std(&fitnesses);
+        let diversity = params.stats.diversity.then(|| {
+            let genes = genes_of(&population[..self.mu]);
+            max_pairwise_distance(&genes, params.stats.diversity_pairs, &mut self.diversity_rng)
+        });
+        let stats = GenerationStats {
+            generation: generation_number,
+            evaluations: self.cumulative_evals,
+            max_fitness,
+            avg_fitness: average,
+            std_fitness,
+            diversity,
+            cache: self.cache_stats(),
+            boundary_hits: (params.bound_handling != BoundHandling::Ignore)
+                .then_some(self.boundary.genes),
+            violations: constraints.map(|_| ViolationStats::of(&violations)),
+            qd: None,
+        };
+        let best = &population[best_index];
+        let improved = max_fitness > self.best_fitness;
+        for observer in &mut self.observers {
+            observer.on_generation(&stats, best);
+        }
+        if improved {
+            self.best_fitness = max_fitness;
+            self.best_individual = Some(best.clone());
+            for observer in &mut self.observers {
+                observer.on_improvement(&stats, best);
+            }
+        }
+        self.timer.stop(Phase::Statistics, started);
+        if let Some(description) = self.sigma_controller.as_ref().map(|c| c.describe()) {
+            self.report(&format!("Sigma control: {description}"));
+        }
+        if let Some(message) = distrust {
+            self.report(&message);
         }
 
-        // Select the best mu from lambda offspring
-        let mut indexed: Vec<(f64, usize)> = offspring_fitnesses
+        // Early stopping if average fitness exceeds threshold, or on stagnation/target rules,
+        // unless the run restarts instead
+        let monitor_stop = self.monitor.update(max_fitness);
+        let stop = if average > params.convergence_threshold {
+            Some(StopReason::Converged)
+        } else {
+            monitor_stop
+        };
+        if let Some(reason) = stop {
+            self.generation = generation_number;
+            let stuck = matches!(reason, StopReason::Converged | StopReason::Stagnation);
+            match params.restarts {
+                Some(policy) if stuck && self.restarts < policy.max_restarts => {
+                    self.restart(policy, reason, rng);
+                    return None;
+                }
+                _ => return Some(reason),
+            }
+        }
+
+        // Select the best mu from lambda offspring, feasible ones first under constraints
+        let started = self.timer.start();
+        let offspring_violations = violations_of(&offspring);
+        let genes = |i: usize| &offspring[i][0..params.mem_size];
+        let survival_fitnesses = match &params.clearing {
+            Some(clearing) => {
+                clearing.clear(&offspring_fitnesses, |i, j| euclidean_distance(genes(i), genes(j)))
+            }
+            None => offspring_fitnesses,
+        };
+        let mut indexed: Vec<(f64, usize)> = survival_fitnesses
             .iter()
             .enumerate()
             .map(|(i, &f)| (f, i))
             .collect();
-        indexed.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap()); // descending
-        let selected_indices: Vec<usize> = indexed.into_iter().take(params.mu).map(|(_, i)| i).collect();
+        indexed.sort_by(|a, b| {
+            feasibility_order((b.0, offspring_violations[b.1]), (a.0, offspring_violations[a.1]))
+        }); // descending
+        let selected_indices: Vec<usize> =
+            indexed.into_iter().take(self.mu).map(|(_, i)| i).collect();
 
         let mut new_population = Vec::new();
         for &idx in &selected_indices {
             new_population.push(offspring[idx].clone());
         }
+        self.timer.stop(Phase::Selection, started);
 
-        population = new_population;
+        self.population = new_population;
+        self.generation = generation_number;
+        if let Some(controller) = self.sigma_controller.as_mut() {
+            controller.end_generation(generation_number, self.cumulative_evals);
+        }
+        None
     }
 
-    population
+    fn generation(&self) -> usize {
+        self.generation
+    }
+
+    fn best_fitness(&self) -> f64 {
+        self.best_fitness
+    }
+
+    fn evaluations(&self) -> usize {
+        self.cumulative_evals
+    }
+
+    // After restarts the final population is the last restart's, led by the best member of
+    // the whole run
+    fn finish(mut self, reason: StopReason) -> Vec<Vec<f64>> {
+        let summary = RunSummary {
+            reason,
+            generations: self.generation,
+            evaluations: self.cumulative_evals,
+            best_fitness: self.best_fitness,
+            stagnant_generations: self.monitor.stagnant_generations,
+        };
+        for observer in &mut self.observers {
+            observer.on_termination(&summary, self.best_individual.as_ref());
+        }
+        if let Some(report) = self.boundary.report(self.params.bound_handling) {
+            self.report(&report);
+        }
+        if let Some(report) = self.repair_stats.report() {
+            self.report(&report);
+        }
+        if let Some(cache) = self.cache_stats() {
+            self.report(&format!("Fitness cache: {cache}"));
+        }
+        if let Some(stats) = self.surrogate.as_ref().map(|surrogate| surrogate.stats) {
+            self.report(&format!("Surrogate: {stats}"));
+        }
+        if let Some(report) = self.timer.finish() {
+            self.report(&report);
+        }
+        if self.restarts > 0
+            && let Some(best) = self.best_individual
+        {
+            self.population.insert(0, best);
+        }
+        self.population
+    }
 }
@@ -0,0 +1,230 @@
+use crate::algorithm::Algorithm;
+use crate::bitgenome::BitGenome;
+use crate::bytegenome::ByteGenome;
+use crate::bitstring::{GAParameters, GeneticAlgorithm};
+use crate::evol_strat::{ESParameters, EvolutionStrategy};
+use crate::decoder::{BinaryDecoder, DEFAULT_BITS_PER_DIM, Decoder, Encoding, PerBit};
+use crate::fitness::{Fitness, Objective};
+use crate::genome::Genome;
+use crate::repair::NoRepair;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use std::ffi::c_char;
+
+// C interface for plugging in fitness kernels written in C, C++ or Fortran. The declarations
+// on the C side are:
+//
+//     typedef double (*ec_fitness_fn)(const double *x, size_t len);
+//     typedef double (*ec_bit_fitness_fn)(const char *bits, size_t len);
+//
+//     typedef struct {
+//         uint32_t algorithm;  /* EC_GA = 0, EC_ES = 1 */
+//         size_t num_dims;
+//         size_t bits_per_dim; /* GA only, ignored by a bit fitness */
+//         size_t pop_size;
+//         size_t generations;
+//         double lower, upper;
+//         uint64_t seed;
+//     } ec_run_config;
+//
+//     double ec_run(const ec_run_config *config, ec_fitness_fn fitness,
+//                   ec_bit_fitness_fn bit_fitness, double *best, size_t best_len);
+//
+// Higher fitness is better, as everywhere else in this crate.
+
+// Fitness over a real vector, as in `double fitness(const double *x, size_t len)`
+pub type RealFitnessFn = unsafe extern "C" fn(*const f64, usize) -> f64;
+
+// Fitness over a bitstring of '0' and '1' characters, not NUL-terminated
+pub type BitFitnessFn = unsafe extern "C" fn(*const c_char, usize) -> f64;
+
+pub const EC_GA: u32 = 0;
+pub const EC_ES: u32 = 1;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RunConfig {
+    pub algorithm: u32,
+    pub num_dims: usize,
+    pub bits_per_dim: usize,
+    pub pop_size: usize,
+    pub generations: usize,
+    pub lower: f64,
+    pub upper: f64,
+    pub seed: u64,
+}
+
+// A C kernel as a problem. A real kernel sees the decoded vector, decoded into the configured
+// bounds and bits per dimension; a bit kernel works on the raw bits, so the phenotype is one
+// 0.0/1.0 gene per bit as for MaxOnes.
+pub enum CFitness {
+    Real { kernel: RealFitnessFn, decoder: BinaryDecoder },
+    Bits { kernel: BitFitnessFn },
+}
+
+impl Objective for CFitness {
+    fn fitness(&self, member: &[f64]) -> f64 {
+        match self {
+            // Safety: the caller of ec_run promised a kernel that reads len doubles from x
+            CFitness::Real { kernel, .. } => unsafe { kernel(member.as_ptr(), member.len()) },
+            CFitness::Bits { kernel } => {
+                let bits: Vec<c_char> = member
+                    .iter()
+                    .map(|&gene| if gene >= 0.5 { b'1' } else { b'0' } as c_char)
+                    .collect();
+                // Safety: as above, for len chars from bits
+                unsafe { kernel(bits.as_ptr(), bits.len()) }
+            }
+        }
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        match self {
+            CFitness::Real { decoder, .. } => decoder.bounds,
+            CFitness::Bits { .. } => (0.0, 1.0),
+        }
+    }
+}
+
+impl Fitness for CFitness {
+    fn decoder(&self) -> impl Decoder {
+        self
+    }
+}
+
+// Real kernels get their bits decoded into bounds, bit kernels one gene per bit
+impl Decoder for CFitness {
+    fn decode_bitstring(&self, bitstring: &str, num_dims: usize) -> Vec<f64> {
+        match self {
+            CFitness::Real { decoder, .. } => decoder.decode_bitstring(bitstring, num_dims),
+            CFitness::Bits { .. } => PerBit.decode_bitstring(bitstring, num_dims),
+        }
+    }
+
+    fn decode_bitgenome(&self, genome: &BitGenome, num_dims: usize) -> Vec<f64> {
+        match self {
+            CFitness::Real { decoder, .. } => decoder.decode_bitgenome(genome, num_dims),
+            CFitness::Bits { .. } => PerBit.decode_bitgenome(genome, num_dims),
+        }
+    }
+
+    fn decode_bytes(&self, genome: &ByteGenome, num_dims: usize) -> Vec<f64> {
+        match self {
+            CFitness::Real { decoder, .. } => decoder.decode_bytes(genome, num_dims),
+            CFitness::Bits { .. } => PerBit.decode_bytes(genome, num_dims),
+        }
+    }
+}
+
+/// Runs the GA or ES on a C kernel, writes the best phenotype found into best (its first
+/// best_len values, best may be null) and returns its fitness. Exactly one of fitness and
+/// bit_fitness must be given; a bit fitness always runs the GA on num_dims bits. Returns NaN
+/// if the arguments are invalid.
+///
+/// # Safety
+///
+/// config must point to a valid ec_run_config, the kernels must be safe to call with any
+/// pointer and length they are given, and best must be valid for best_len writes unless null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ec_run(
+    config: *const RunConfig,
+    fitness: Option<RealFitnessFn>,
+    bit_fitness: Option<BitFitnessFn>,
+    best: *mut f64,
+    best_len: usize,
+) -> f64 {
+    // Safety: the caller passes a valid config or null
+    let Some(config) = (unsafe { config.as_ref() }) else {
+        return f64::NAN;
+    };
+    let problem = match (fitness, bit_fitness) {
+        // Real-valued GA runs decode bits_per_dim bits per dimension into [lower, upper], the
+        // ES evolves the phenotype itself and ignores the precision
+        (Some(kernel), None) => {
+            let bits_per_dim = match config.algorithm {
+                EC_GA => config.bits_per_dim,
+                _ => DEFAULT_BITS_PER_DIM,
+            };
+            let bounds = (config.lower, config.upper);
+            let Ok(decoder) = BinaryDecoder::new(bounds, bits_per_dim, Encoding::Binary) else {
+                return f64::NAN;
+            };
+            CFitness::Real { kernel, decoder }
+        }
+        (None, Some(kernel)) => CFitness::Bits { kernel },
+        _ => return f64::NAN,
+    };
+    let Some((phenotype, value)) = run_config(&problem, config) else {
+        return f64::NAN;
+    };
+    if !best.is_null() {
+        for (i, &x) in phenotype.iter().take(best_len).enumerate() {
+            // Safety: the caller made best valid for best_len writes
+            unsafe { *best.add(i) = x };
+        }
+    }
+    value
+}
+
+// Best phenotype and fitness of one run, None for a configuration that can't run
+fn run_config(problem: &CFitness, config: &RunConfig) -> Option<(Vec<f64>, f64)> {
+    if config.num_dims == 0 || config.pop_size < 2 || config.lower >= config.upper {
+        return None;
+    }
+    let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+    // Fitness scales are unknown, so only the generation budget ends a run
+    match (problem, config.algorithm) {
+        (CFitness::Real { .. }, EC_ES) => {
+            let params = ESParameters {
+                mu: config.pop_size,
+                lambda: config.pop_size,
+                mem_size: config.num_dims,
+                mem_range: (config.lower, config.upper),
+                tau: 1.0 / (2.0 * config.num_dims as f64).sqrt(),
+                max_gens: config.generations,
+                convergence_threshold: f64::INFINITY,
+                ..ESParameters::default()
+            };
+            let population = EvolutionStrategy::new(problem, &params, &NoRepair, &mut rng)
+                .quiet()
+                .run(&mut rng);
+            best_of(problem, population.iter().map(|member| member[..config.num_dims].to_vec()))
+        }
+        (_, EC_GA | EC_ES) => {
+            let mem_size = match problem {
+                CFitness::Real { .. } => config.num_dims * config.bits_per_dim,
+                CFitness::Bits { .. } => config.num_dims,
+            };
+            if mem_size == 0 {
+                return None;
+            }
+            let params = GAParameters {
+                pop_size: config.pop_size,
+                mem_size,
+                num_dims: config.num_dims,
+                max_iters: config.generations,
+                convergence_threshold: f64::INFINITY,
+                ..GAParameters::default()
+            };
+            let population: Vec<BitGenome> =
+                GeneticAlgorithm::new(problem, &params, &NoRepair, &mut rng).quiet().run(&mut rng);
+            let decoder = problem.decoder();
+            let phenotypes =
+                population.iter().map(|member| member.phenotype(&decoder, config.num_dims));
+            best_of(problem, phenotypes)
+        }
+        _ => None,
+    }
+}
+
+fn best_of(
+    problem: &CFitness,
+    phenotypes: impl Iterator<Item = Vec<f64>>,
+) -> Option<(Vec<f64>, f64)> {
+    phenotypes
+        .map(|phenotype| {
+            let value = problem.fitness(&phenotype);
+            (phenotype, value)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
@@ -1,6 +1,151 @@
-// Define a trait for fitness functions for reusability
-pub trait Fitness {
+use crate::bitgenome::BitGenome;
+use crate::cache::CacheStats;
+use crate::decoder::{BinaryDecoder, DEFAULT_BITS_PER_DIM, Decoder, Encoding};
+use crate::seeds::SeedSequence;
+
+// Define a trait for objective functions for reusability. Every problem is an objective over a
+// real-valued phenotype (higher is better), whatever representation the phenotype comes from.
+pub trait Objective {
     fn fitness(&self, member: &[f64]) -> f64;
-    fn fitness_bitstring(&self, bitstring: &str, num_dims: usize) -> f64;
-    fn decode_bitstring(&self, bitstring: &str, num_dims: usize) -> Vec<f64>;
+
+    // Range the phenotype's values lie in
+    fn bounds(&self) -> (f64, f64);
+
+    // Fitness value of the global optimum, if the problem knows it
+    fn optimum_value(&self) -> Option<f64> {
+        None
+    }
+
+    // Phenotypes of the known global optima at the given dimension, empty if the problem
+    // doesn't know them; final populations are classified into their basins
+    fn known_optima(&self, _num_dims: usize) -> Vec<Vec<f64>> {
+        Vec::new()
+    }
+
+    // Fitness of a whole population of phenotypes in one call. Objectives with a high cost per
+    // call (an external simulator, a vectorized or GPU kernel) override this to evaluate the
+    // batch together; the default evaluates the members one by one.
+    fn evaluate_population(&self, members: &[Vec<f64>]) -> Vec<f64> {
+        members.iter().map(|member| self.fitness(member)).collect()
+    }
+
+    // Batch evaluation in which member i draws any randomness (noise, a stochastic simulation)
+    // only from streams[i], so the values are the same whichever threads or order the batch is
+    // evaluated in and a batch split into chunks, each with its share of the streams, gives
+    // the same values as a whole. Deterministic objectives have no use for the streams.
+    fn evaluate_population_seeded(
+        &self,
+        members: &[Vec<f64>],
+        _streams: &[SeedSequence],
+    ) -> Vec<f64> {
+        self.evaluate_population(members)
+    }
+
+    // Average of several passes over a seeded batch, used to smooth comparisons under noisy
+    // fitness: pass k draws from each stream's kth sample stream, a single pass from the
+    // streams themselves
+    fn evaluate_population_seeded_resampled(
+        &self,
+        members: &[Vec<f64>],
+        streams: &[SeedSequence],
+        samples: usize,
+    ) -> Vec<f64> {
+        if samples <= 1 {
+            return self.evaluate_population_seeded(members, streams);
+        }
+        let mut totals = vec![0.0; members.len()];
+        for sample in 0..samples {
+            let sample_streams: Vec<SeedSequence> =
+                streams.iter().map(|stream| stream.sample(sample)).collect();
+            let values = self.evaluate_population_seeded(members, &sample_streams);
+            for (total, value) in totals.iter_mut().zip(values) {
+                *total += value;
+            }
+        }
+        totals.iter().map(|total| total / samples as f64).collect()
+    }
+
+    // Hit and miss counts so far, when evaluations go through a CachedFitness
+    fn cache_stats(&self) -> Option<CacheStats> {
+        None
+    }
+}
+
+// An objective as the GA sees it: composed with the decoder bitstring genomes go through on
+// their way to it. The ES evolves phenotypes directly and only needs the Objective. Problems
+// decode DEFAULT_BITS_PER_DIM bits per dimension into their bounds unless they pick another
+// decoder, and Decoded pairs any objective with any decoder, e.g. one of another precision.
+pub trait Fitness: Objective {
+    fn decoder(&self) -> impl Decoder {
+        BinaryDecoder::new(self.bounds(), DEFAULT_BITS_PER_DIM, Encoding::Binary)
+            .unwrap_or_else(|e| panic!("Invalid problem bounds: {e}"))
+    }
+
+    fn decode_bitstring(&self, bitstring: &str, num_dims: usize) -> Vec<f64> {
+        self.decoder().decode_bitstring(bitstring, num_dims)
+    }
+
+    fn decode_bitgenome(&self, genome: &BitGenome, num_dims: usize) -> Vec<f64> {
+        self.decoder().decode_bitgenome(genome, num_dims)
+    }
+
+    fn fitness_bitstring(&self, bitstring: &str, num_dims: usize) -> f64 {
+        self.fitness(&self.decode_bitstring(bitstring, num_dims))
+    }
+}
+
+// An objective with a decoder other than its own, e.g. Rosenbrock over Gray-coded bitstrings
+// or over an explicit BinaryDecoder
+pub struct Decoded<O: Objective, D: Decoder> {
+    pub objective: O,
+    pub decoder: D,
+}
+
+impl<O: Objective> Decoded<O, BinaryDecoder> {
+    // Gray code instead of plain binary over the objective's bounds, at the default precision
+    pub fn gray(objective: O) -> Self {
+        let decoder = BinaryDecoder::new(objective.bounds(), DEFAULT_BITS_PER_DIM, Encoding::Gray)
+            .unwrap_or_else(|e| panic!("Invalid problem bounds: {e}"));
+        Self { decoder, objective }
+    }
+}
+
+impl<O: Objective, D: Decoder> Objective for Decoded<O, D> {
+    fn fitness(&self, member: &[f64]) -> f64 {
+        self.objective.fitness(member)
+    }
+
+    fn evaluate_population(&self, members: &[Vec<f64>]) -> Vec<f64> {
+        self.objective.evaluate_population(members)
+    }
+
+    fn evaluate_population_seeded(
+        &self,
+        members: &[Vec<f64>],
+        streams: &[SeedSequence],
+    ) -> Vec<f64> {
+        self.objective.evaluate_population_seeded(members, streams)
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        self.objective.bounds()
+    }
+
+    fn optimum_value(&self) -> Option<f64> {
+        self.objective.optimum_value()
+    }
+
+    fn known_optima(&self, num_dims: usize) -> Vec<Vec<f64>> {
+        self.objective.known_optima(num_dims)
+    }
+
+    fn cache_stats(&self) -> Option<CacheStats> {
+        self.objective.cache_stats()
+    }
+}
+
+impl<O: Objective, D: Decoder> Fitness for Decoded<O, D> {
+    fn decoder(&self) -> impl Decoder {
+        &self.decoder
+    }
 }
@@ -0,0 +1,275 @@
+use crate::bitstring::GAParameters;
+use crate::decoder::Decoder;
+use crate::repair::Repair;
+use rand::prelude::*;
+use rand_distr::Normal;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+// Representation-specific operators used by the GA, so the same generational loop can evolve
+// bitstrings, real vectors, permutations, or anything else that implements this trait
+pub trait Genome: Clone + PartialEq {
+    // Whether operators can change the genome length, enabling length statistics
+    const VARIABLE_LENGTH: bool = false;
+
+    // Random genome with len genes; real-valued genes are drawn from bounds
+    fn random<R: Rng + ?Sized>(len: usize, bounds: (f64, f64), rng: &mut R) -> Self;
+
+    // Mutates each gene with probability params.mutation_rate
+    fn mutate<R: Rng + ?Sized>(
+        &self,
+        params: &GAParameters,
+        bounds: (f64, f64),
+        rng: &mut R,
+    ) -> Self;
+
+    // Mutates exactly like mutate and also reports the genes that changed, so problems that
+    // implement IncrementalFitness can update the parent's evaluation instead of starting over.
+    // None when the representation doesn't track them.
+    fn mutate_with_loci<R: Rng + ?Sized>(
+        &self,
+        params: &GAParameters,
+        bounds: (f64, f64),
+        rng: &mut R,
+    ) -> (Self, Option<Vec<usize>>) {
+        (self.mutate(params, bounds, rng), None)
+    }
+
+    // Recombines two parents with probability crossover_rate, otherwise returns copies
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        other: &Self,
+        crossover_rate: f64,
+        rng: &mut R,
+    ) -> (Self, Self);
+
+    // Recombines exactly like crossover at params.crossover_rate, for representations that
+    // offer a choice of operator in the parameters. The GA recombines through this.
+    fn crossover_with_params<R: Rng + ?Sized>(
+        &self,
+        other: &Self,
+        params: &GAParameters,
+        rng: &mut R,
+    ) -> (Self, Self) {
+        self.crossover(other, params.crossover_rate, rng)
+    }
+
+    // Mutates exactly like mutate, in place, so representations that can flip genes where
+    // they are don't allocate a new genome for every child
+    fn mutate_in_place<R: Rng + ?Sized>(
+        &mut self,
+        params: &GAParameters,
+        bounds: (f64, f64),
+        rng: &mut R,
+    ) {
+        *self = self.mutate(params, bounds, rng);
+    }
+
+    // Recombines exactly like crossover_with_params, writing the offspring over two existing
+    // genomes so representations that can reuse their buffers do
+    fn crossover_into<R: Rng + ?Sized>(
+        &self,
+        other: &Self,
+        params: &GAParameters,
+        rng: &mut R,
+        offspring: (&mut Self, &mut Self),
+    ) {
+        (*offspring.0, *offspring.1) = self.crossover_with_params(other, params, rng);
+    }
+
+    // Number of genes
+    fn length(&self) -> usize;
+
+    // Genotypic distance to another genome of the same representation
+    fn distance(&self, other: &Self) -> f64;
+
+    // Hash of the genes, equal for equal genomes, so copies can be counted in one pass
+    fn fingerprint(&self) -> u64;
+
+    // Real-valued phenotype handed to the objective, through the decoder for bitstrings
+    fn phenotype(&self, decoder: &impl Decoder, num_dims: usize) -> Vec<f64>;
+
+    // Genome of len genes with the given phenotype, the inverse of phenotype, for migrants
+    // arriving from islands of another representation. None when the representation can't be
+    // built from a phenotype.
+    fn from_phenotype(_phenotype: &[f64], _decoder: &impl Decoder, _len: usize) -> Option<Self> {
+        None
+    }
+
+    // Applies a repair operator, returning true if the genome was changed
+    fn repair(&mut self, _repair: &impl Repair) -> bool {
+        false
+    }
+}
+
+// Recombination operators for real-valued genomes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RealCrossover {
+    // Children swap the genes after a random cut point
+    SinglePoint,
+    // BLX-alpha: each child gene is drawn uniformly from the interval between the parents'
+    // genes, widened on both sides by alpha times its width, so children can explore a little
+    // beyond their parents. Past alpha of about 0.37 the spread grows from one generation to
+    // the next, which weak selection such as the ES tournament does not hold back.
+    Blend { alpha: f64 },
+    // Whole arithmetic: every gene of the children is the weighted mean
+    // alpha * x + (1 - alpha) * y of the parents' genes, and the other way round
+    WholeArithmetic { alpha: f64 },
+    // Partial arithmetic: the genes after a random cut point are mixed as in whole arithmetic
+    // and the genes before it copied from the child's own parent
+    PartialArithmetic { alpha: f64 },
+}
+
+// Two children of real-valued parents of the same length under the given operator
+pub fn real_crossover<R: Rng + ?Sized>(
+    a: &[f64],
+    b: &[f64],
+    operator: RealCrossover,
+    rng: &mut R,
+) -> (Vec<f64>, Vec<f64>) {
+    let mix = |alpha: f64, x: f64, y: f64| {
+        (alpha * x + (1.0 - alpha) * y, alpha * y + (1.0 - alpha) * x)
+    };
+    match operator {
+        RealCrossover::SinglePoint => {
+            if a.len() < 2 {
+                return (a.to_vec(), b.to_vec());
+            }
+            let point = rng.random_range(1..a.len());
+            ([&a[..point], &b[point..]].concat(), [&b[..point], &a[point..]].concat())
+        }
+        RealCrossover::Blend { alpha } => a
+            .iter()
+            .zip(b)
+            .map(|(&x, &y)| {
+                let (low, high) = (x.min(y), x.max(y));
+                let extent = alpha * (high - low);
+                let (low, high) = (low - extent, high + extent);
+                if high > low {
+                    (rng.random_range(low..high), rng.random_range(low..high))
+                } else {
+                    (x, y)
+                }
+            })
+            .unzip(),
+        RealCrossover::WholeArithmetic { alpha } => {
+            a.iter().zip(b).map(|(&x, &y)| mix(alpha, x, y)).unzip()
+        }
+        RealCrossover::PartialArithmetic { alpha } => {
+            let point = rng.random_range(0..a.len().max(1));
+            a.iter()
+                .zip(b)
+                .enumerate()
+                .map(|(i, (&x, &y))| if i < point { (x, y) } else { mix(alpha, x, y) })
+                .unzip()
+        }
+    }
+}
+
+// Real-valued vector genome: Gaussian creep mutation scaled to a tenth of the search range
+// and single point crossover, or the operator in params.real_crossover
+impl Genome for Vec<f64> {
+    fn random<R: Rng + ?Sized>(len: usize, bounds: (f64, f64), rng: &mut R) -> Self {
+        (0..len).map(|_| rng.random_range(bounds.0..bounds.1)).collect()
+    }
+
+    fn mutate<R: Rng + ?Sized>(
+        &self,
+        params: &GAParameters,
+        bounds: (f64, f64),
+        rng: &mut R,
+    ) -> Self {
+        let mutation_rate = params.mutation_rate;
+        let step = Normal::new(0.0, 0.1 * (bounds.1 - bounds.0)).unwrap();
+        self.iter()
+            .map(|&gene| {
+                let random: f64 = rng.random();
+                if random < mutation_rate {
+                    gene + rng.sample::<f64, _>(step)
+                } else {
+                    gene
+                }
+            })
+            .collect()
+    }
+
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        other: &Self,
+        crossover_rate: f64,
+        rng: &mut R,
+    ) -> (Self, Self) {
+        if self.len() != other.len() {
+            panic!("Parents must be of the same length");
+        }
+        let random: f64 = rng.random();
+        if random >= crossover_rate {
+            return (self.clone(), other.clone());
+        }
+        real_crossover(self, other, RealCrossover::SinglePoint, rng)
+    }
+
+    fn crossover_with_params<R: Rng + ?Sized>(
+        &self,
+        other: &Self,
+        params: &GAParameters,
+        rng: &mut R,
+    ) -> (Self, Self) {
+        if self.len() != other.len() {
+            panic!("Parents must be of the same length");
+        }
+        let random: f64 = rng.random();
+        if random >= params.crossover_rate {
+            return (self.clone(), other.clone());
+        }
+        real_crossover(self, other, params.real_crossover, rng)
+    }
+
+    fn length(&self) -> usize {
+        self.len()
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        euclidean_distance(self, other)
+    }
+
+    fn fingerprint(&self) -> u64 {
+        hash_reals(self)
+    }
+
+    fn phenotype(&self, _decoder: &impl Decoder, _num_dims: usize) -> Vec<f64> {
+        self.clone()
+    }
+
+    fn from_phenotype(phenotype: &[f64], _decoder: &impl Decoder, len: usize) -> Option<Self> {
+        (phenotype.len() == len).then(|| phenotype.to_vec())
+    }
+
+    fn repair(&mut self, repair: &impl Repair) -> bool {
+        repair.repair_real(self)
+    }
+}
+
+// Hash of a value, for Genome::fingerprint
+pub fn hash_of(value: &(impl Hash + ?Sized)) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+// Hash of real genes by their bits, with -0.0 hashed as 0.0 since the two compare equal
+pub fn hash_reals(genes: &[f64]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for gene in genes {
+        (gene + 0.0).to_bits().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+// Euclidean distance over the shared prefix of two real vectors
+pub fn euclidean_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - y).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
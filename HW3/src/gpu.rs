@@ -0,0 +1,304 @@
+use crate::decoder::Decoder;
+use crate::fitness::{Fitness, Objective};
+use crate::seeds::SeedSequence;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use wgpu::util::DeviceExt;
+
+// Analytic benchmarks with a compute kernel. Each kernel evaluates the same fitness as the
+// problem's Objective, in f32, so values agree with the CPU to about 7 significant digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kernel {
+    Rosenbrock,
+    Himmelblau,
+    MaxOnes,
+}
+
+const WORKGROUP_SIZE: usize = 64;
+
+// Shared by every kernel: one invocation per member, reading its genes from the flattened
+// batch and writing its fitness
+const ENTRY_POINT: &str = "
+struct Batch {
+    count: u32,
+    dims: u32,
+}
+
+@group(0) @binding(0) var<storage, read> batch: Batch;
+@group(0) @binding(1) var<storage, read> members: array<f32>;
+@group(0) @binding(2) var<storage, read_write> values: array<f32>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= batch.count) {
+        return;
+    }
+    values[id.x] = objective(id.x * batch.dims, batch.dims);
+}
+";
+
+impl Kernel {
+    // WGSL for `objective(start, dims)`, the fitness of the member whose genes begin at start
+    fn objective(self) -> &'static str {
+        match self {
+            Kernel::Rosenbrock => {
+                "
+fn objective(start: u32, dims: u32) -> f32 {
+    var total = 0.0;
+    for (var k = 0u; k + 1u < dims; k++) {
+        let x = members[start + k];
+        let y = members[start + k + 1u];
+        total += (1.0 - x) * (1.0 - x) + 100.0 * (y - x * x) * (y - x * x);
+    }
+    return 1.0 / (1.0 + total);
+}
+"
+            }
+            Kernel::Himmelblau => {
+                "
+fn objective(start: u32, dims: u32) -> f32 {
+    let x = members[start];
+    let y = members[start + 1u];
+    let a = x * x + y - 11.0;
+    let b = x + y * y - 7.0;
+    return 1.0 / (1.0 + a * a + b * b);
+}
+"
+            }
+            Kernel::MaxOnes => {
+                "
+fn objective(start: u32, dims: u32) -> f32 {
+    var ones = 0.0;
+    for (var k = 0u; k < dims; k++) {
+        ones += select(0.0, 1.0, members[start + k] >= 0.5);
+    }
+    return ones / f32(dims);
+}
+"
+            }
+        }
+    }
+}
+
+// A compiled kernel on the first high-performance adapter wgpu finds
+pub struct GpuKernel {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    adapter: String,
+}
+
+impl GpuKernel {
+    // Fails when no adapter or device is available, as on a machine without a GPU driver
+    pub fn new(kernel: Kernel) -> Result<Self, String> {
+        let instance = wgpu::Instance::default();
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            ..Default::default()
+        }))
+        .map_err(|e| format!("no GPU adapter: {e}"))?;
+        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+            label: Some("fitness"),
+            ..Default::default()
+        }))
+        .map_err(|e| format!("no GPU device: {e}"))?;
+        let source = format!("{ENTRY_POINT}{}", kernel.objective());
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("fitness"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("fitness"),
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        let info = adapter.get_info();
+        Ok(Self { device, queue, pipeline, adapter: format!("{} ({:?})", info.name, info.backend) })
+    }
+
+    pub fn adapter(&self) -> &str {
+        &self.adapter
+    }
+
+    // Members per dispatch: as many as one workgroup grid and one storage binding hold
+    fn max_batch(&self, dims: usize) -> usize {
+        let limits = self.device.limits();
+        let by_grid = limits.max_compute_workgroups_per_dimension as usize * WORKGROUP_SIZE;
+        let by_binding = limits.max_storage_buffer_binding_size as usize / (4 * dims.max(1));
+        by_grid.min(by_binding).max(1)
+    }
+
+    // Fitness of each member of a batch whose members all have dims genes
+    fn evaluate(&self, members: &[Vec<f64>], dims: usize) -> Result<Vec<f64>, String> {
+        let genes: Vec<u8> = members
+            .iter()
+            .flat_map(|member| member.iter().flat_map(|&gene| (gene as f32).to_le_bytes()))
+            .collect();
+        let batch: Vec<u8> = [members.len() as u32, dims as u32]
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+        let size = (4 * members.len()) as wgpu::BufferAddress;
+
+        let storage = |label, contents: &[u8]| {
+            self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents,
+                usage: wgpu::BufferUsages::STORAGE,
+            })
+        };
+        let batch = storage("batch", &batch);
+        let genes = storage("members", &genes);
+        let values = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("values"),
+            size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("fitness"),
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: batch.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: genes.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 2, resource: values.as_entire_binding() },
+            ],
+        });
+
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&Default::default());
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(members.len().div_ceil(WORKGROUP_SIZE) as u32, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&values, 0, &readback, 0, size);
+        self.queue.submit([encoder.finish()]);
+
+        let (sender, receiver) = mpsc::channel();
+        readback.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .map_err(|e| format!("GPU evaluation didn't finish: {e}"))?;
+        receiver
+            .recv()
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("couldn't read the GPU results: {e}"))?;
+        let mapped = readback
+            .slice(..)
+            .get_mapped_range()
+            .map_err(|e| format!("couldn't read the GPU results: {e}"))?;
+        let values = mapped
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64)
+            .collect();
+        drop(mapped);
+        readback.unmap();
+        Ok(values)
+    }
+}
+
+// Evaluates whole batches (a GA generation, an ES offspring population) on the GPU, one
+// dispatch per batch, for experiments with populations too large to evaluate quickly on the
+// CPU. Single evaluations, batches of mixed lengths and batches the GPU fails on go to the
+// inner objective, so they are exact in f64.
+pub struct GpuFitness<F> {
+    inner: F,
+    kernel: GpuKernel,
+    // Set while batches are falling back to the CPU, so the switch is reported once
+    fallback: AtomicBool,
+}
+
+impl<F: Objective> GpuFitness<F> {
+    // The kernel must compute the same fitness as inner
+    pub fn new(inner: F, kernel: GpuKernel) -> Self {
+        Self { inner, kernel, fallback: AtomicBool::new(false) }
+    }
+
+    // The inner objective's values for a batch, seeded if the batch is
+    fn evaluate_on_cpu(&self, members: &[Vec<f64>], streams: Option<&[SeedSequence]>) -> Vec<f64> {
+        match streams {
+            Some(streams) => self.inner.evaluate_population_seeded(members, streams),
+            None => self.inner.evaluate_population(members),
+        }
+    }
+
+    // The kernels are deterministic, so the streams only reach the inner objective, for the
+    // batches that fall back to it
+    fn evaluate_batch(&self, members: &[Vec<f64>], streams: Option<&[SeedSequence]>) -> Vec<f64> {
+        let Some(dims) = members.first().map(Vec::len) else {
+            return Vec::new();
+        };
+        if members.iter().any(|member| member.len() != dims) {
+            return self.evaluate_on_cpu(members, streams);
+        }
+        let mut values = Vec::with_capacity(members.len());
+        let batch = self.kernel.max_batch(dims);
+        for (index, chunk) in members.chunks(batch).enumerate() {
+            match self.kernel.evaluate(chunk, dims) {
+                Ok(chunk_values) => {
+                    if self.fallback.swap(false, Ordering::Relaxed) {
+                        eprintln!("GPU evaluation is back");
+                    }
+                    values.extend(chunk_values);
+                }
+                Err(e) => {
+                    if !self.fallback.swap(true, Ordering::Relaxed) {
+                        eprintln!("GPU evaluation failed: {e}, evaluating on the CPU");
+                    }
+                    let start = index * batch;
+                    let chunk_streams = streams.map(|s| &s[start..start + chunk.len()]);
+                    values.extend(self.evaluate_on_cpu(chunk, chunk_streams));
+                }
+            }
+        }
+        values
+    }
+}
+
+impl<F: Objective> Objective for GpuFitness<F> {
+    fn fitness(&self, member: &[f64]) -> f64 {
+        self.inner.fitness(member)
+    }
+
+    fn evaluate_population(&self, members: &[Vec<f64>]) -> Vec<f64> {
+        self.evaluate_batch(members, None)
+    }
+
+    fn evaluate_population_seeded(
+        &self,
+        members: &[Vec<f64>],
+        streams: &[SeedSequence],
+    ) -> Vec<f64> {
+        self.evaluate_batch(members, Some(streams))
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        self.inner.bounds()
+    }
+
+    fn optimum_value(&self) -> Option<f64> {
+        self.inner.optimum_value()
+    }
+
+    fn known_optima(&self, num_dims: usize) -> Vec<Vec<f64>> {
+        self.inner.known_optima(num_dims)
+    }
+}
+
+impl<F: Fitness> Fitness for GpuFitness<F> {
+    fn decoder(&self) -> impl Decoder {
+        self.inner.decoder()
+    }
+}
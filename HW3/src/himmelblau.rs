@@ -0,0 +1,36 @@
+use crate::fitness::{Fitness, Objective};
+
+pub struct Himmelblau;
+
+// The four global minima, all with value 0
+pub const HIMMELBLAU_OPTIMA: [(f64, f64); 4] = [
+    (3.0, 2.0),
+    (-2.805118, 3.131312),
+    (-3.779310, -3.283186),
+    (3.584428, -1.848126),
+];
+
+impl Objective for Himmelblau {
+    fn fitness(&self, member: &[f64]) -> f64 {
+        let x = member[0];
+        let y = member[1];
+        // Use the standard Himmelblau function formula
+        let himmelblau_value = (x.powi(2) + y - 11.0).powi(2) + (x + y.powi(2) - 7.0).powi(2);
+        // Convert to fitness in the range (0, 1], higher is better, maximum at global optima
+        1.0 / (1.0 + himmelblau_value)
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        (-10.0, 10.0)
+    }
+
+    fn optimum_value(&self) -> Option<f64> {
+        Some(1.0)
+    }
+
+    fn known_optima(&self, _num_dims: usize) -> Vec<Vec<f64>> {
+        HIMMELBLAU_OPTIMA.iter().map(|&(x, y)| vec![x, y]).collect()
+    }
+}
+
+impl Fitness for Himmelblau {}
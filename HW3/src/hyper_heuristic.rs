@@ -0,0 +1,247 @@
+use crate::algorithm::Algorithm;
+use crate::bitstring::{GAParameters, evaluate};
+use crate::fitness::Fitness;
+use crate::genome::Genome;
+use crate::seeds::SeedSequence;
+use crate::stopping::{StopMonitor, StopReason, StoppingCriteria};
+use rand::prelude::*;
+
+// Low-level heuristics the hyper-heuristic chooses between, built from the genome's own
+// mutation operator so they work on any representation
+#[derive(Debug, Clone, Copy)]
+pub enum LowLevelHeuristic {
+    // One mutation of the current solution at this rate
+    Mutation { rate: f64 },
+    // Steepest-ascent local search move: the best of `neighbours` mutations at this rate
+    LocalSearch { rate: f64, neighbours: usize },
+}
+
+impl LowLevelHeuristic {
+    // Evaluations one application costs
+    fn evaluations(&self) -> usize {
+        match *self {
+            LowLevelHeuristic::Mutation { .. } => 1,
+            LowLevelHeuristic::LocalSearch { neighbours, .. } => neighbours.max(1),
+        }
+    }
+
+    fn rate(&self) -> f64 {
+        match *self {
+            LowLevelHeuristic::Mutation { rate } | LowLevelHeuristic::LocalSearch { rate, .. } => {
+                rate
+            }
+        }
+    }
+}
+
+// How the next heuristic is chosen from the credit the heuristics have earned
+#[derive(Debug, Clone, Copy)]
+pub enum HeuristicSelection {
+    // Uniformly at random, the baseline a learning scheme has to beat
+    Random,
+    // The heuristic with the most credit, or one at random with probability epsilon
+    EpsilonGreedy { epsilon: f64 },
+    // Upper confidence bound: credit plus exploration * sqrt(ln(total calls) / calls), with
+    // every heuristic tried once first
+    Ucb { exploration: f64 },
+}
+
+#[derive(Debug, Clone)]
+pub struct HyperHeuristicParameters {
+    pub heuristics: Vec<LowLevelHeuristic>,
+    pub selection: HeuristicSelection,
+    // Weight of the newest reward in a heuristic's credit, so credit follows the search as
+    // the heuristics that pay off change
+    pub learning_rate: f64,
+    pub max_steps: usize,
+    pub stopping: StoppingCriteria,
+    // Genome length, decoding and resampling, and the base of each heuristic's mutation
+    pub ga: GAParameters,
+}
+
+impl Default for HyperHeuristicParameters {
+    fn default() -> Self {
+        Self {
+            heuristics: vec![
+                LowLevelHeuristic::Mutation { rate: 0.001 },
+                LowLevelHeuristic::Mutation { rate: 0.01 },
+                LowLevelHeuristic::Mutation { rate: 0.1 },
+                LowLevelHeuristic::LocalSearch { rate: 0.01, neighbours: 10 },
+            ],
+            selection: HeuristicSelection::EpsilonGreedy { epsilon: 0.1 },
+            learning_rate: 0.1,
+            max_steps: 5000,
+            stopping: StoppingCriteria::default(),
+            ga: GAParameters::default(),
+        }
+    }
+}
+
+// Online performance of one low-level heuristic
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicStats {
+    pub calls: usize,
+    pub improvements: usize,
+    pub evaluations: usize,
+    // Recency-weighted reward, where the reward of a call is 1 per evaluation it used if it
+    // improved the solution and 0 otherwise: a success rate per evaluation that doesn't
+    // depend on the problem's fitness scale
+    pub credit: f64,
+}
+
+// Selection hyper-heuristic over a single solution: each step picks a low-level heuristic
+// by its credit, applies it, keeps the result unless it is worse, and credits the heuristic
+// with what it gained. A step is a generation as far as the Algorithm trait is concerned.
+pub struct HyperHeuristic<'a, G, F> {
+    fitness_fn: &'a F,
+    params: &'a HyperHeuristicParameters,
+    bounds: (f64, f64),
+    // Mutation parameters of each heuristic, built once rather than every step
+    mutation_params: Vec<GAParameters>,
+    current: G,
+    current_fitness: f64,
+    step: usize,
+    evaluations: usize,
+    stats: Vec<HeuristicStats>,
+    // Heuristic applied at each step, for studying the schedule
+    history: Vec<usize>,
+    monitor: StopMonitor,
+    // Streams of the evaluations, one per step and candidate
+    evaluation_seeds: SeedSequence,
+}
+
+impl<'a, G: Genome, F: Fitness> HyperHeuristic<'a, G, F> {
+    pub fn new<R: Rng + ?Sized>(
+        fitness_fn: &'a F,
+        params: &'a HyperHeuristicParameters,
+        rng: &mut R,
+    ) -> Self {
+        let bounds = fitness_fn.bounds();
+        let ga = &params.ga;
+        let current = G::random(ga.mem_size, bounds, rng);
+        let evaluation_seeds =
+            SeedSequence::new(ga.seed.unwrap_or_default()).operator("evaluation");
+        let initial = evaluation_seeds.operator("initial");
+        let current_fitness = evaluate(&current, fitness_fn, ga.num_dims, ga.resamples, initial);
+        Self {
+            fitness_fn,
+            params,
+            bounds,
+            mutation_params: params
+                .heuristics
+                .iter()
+                .map(|heuristic| GAParameters { mutation_rate: heuristic.rate(), ..ga.clone() })
+                .collect(),
+            current,
+            current_fitness,
+            step: 0,
+            evaluations: ga.resamples,
+            stats: vec![HeuristicStats::default(); params.heuristics.len()],
+            history: Vec::new(),
+            monitor: StopMonitor::new(&params.stopping, fitness_fn.optimum_value()),
+            evaluation_seeds,
+        }
+    }
+
+    pub fn stats(&self) -> &[HeuristicStats] {
+        &self.stats
+    }
+
+    pub fn history(&self) -> &[usize] {
+        &self.history
+    }
+
+    fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let count = self.stats.len();
+        let best_credit = || {
+            (0..count).max_by(|&a, &b| self.stats[a].credit.total_cmp(&self.stats[b].credit))
+        };
+        let chosen = match self.params.selection {
+            HeuristicSelection::Random => None,
+            HeuristicSelection::EpsilonGreedy { epsilon } => {
+                if rng.random::<f64>() < epsilon { None } else { best_credit() }
+            }
+            HeuristicSelection::Ucb { exploration } => {
+                let total = self.step.max(1) as f64;
+                let bound = |stats: &HeuristicStats| {
+                    if stats.calls == 0 {
+                        return f64::INFINITY;
+                    }
+                    stats.credit + exploration * (total.ln() / stats.calls as f64).sqrt()
+                };
+                (0..count).max_by(|&a, &b| bound(&self.stats[a]).total_cmp(&bound(&self.stats[b])))
+            }
+        };
+        chosen.unwrap_or_else(|| rng.random_range(0..count))
+    }
+
+    // The heuristic's candidate and its fitness
+    fn apply<R: Rng + ?Sized>(&self, index: usize, rng: &mut R) -> (G, f64) {
+        let ga = &self.params.ga;
+        let mutation_params = &self.mutation_params[index];
+        let heuristic = self.params.heuristics[index];
+        let seeds = self.evaluation_seeds.generation(self.step);
+        (0..heuristic.evaluations())
+            .map(|k| {
+                let candidate = self.current.mutate(mutation_params, self.bounds, rng);
+                let (num_dims, resamples) = (ga.num_dims, ga.resamples);
+                let fitness =
+                    evaluate(&candidate, self.fitness_fn, num_dims, resamples, seeds.individual(k));
+                (candidate, fitness)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap()
+    }
+}
+
+impl<G: Genome, F: Fitness> Algorithm for HyperHeuristic<'_, G, F> {
+    type Individual = G;
+
+    fn step<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<StopReason> {
+        if self.step >= self.params.max_steps {
+            return Some(StopReason::MaxGenerations);
+        }
+        if self.stats.is_empty() {
+            return Some(StopReason::Converged);
+        }
+
+        let index = self.choose(rng);
+        let (candidate, fitness) = self.apply(index, rng);
+        let used = self.params.heuristics[index].evaluations();
+        self.evaluations += used * self.params.ga.resamples;
+
+        let improved = fitness > self.current_fitness;
+        let reward = if improved { 1.0 / used as f64 } else { 0.0 };
+        let stats = &mut self.stats[index];
+        stats.calls += 1;
+        stats.evaluations += used;
+        stats.credit += self.params.learning_rate * (reward - stats.credit);
+        if improved {
+            stats.improvements += 1;
+        }
+        // Equal moves are accepted, so the search can drift across plateaus
+        if fitness >= self.current_fitness {
+            self.current = candidate;
+            self.current_fitness = fitness;
+        }
+        self.history.push(index);
+        self.step += 1;
+        self.monitor.update(self.current_fitness)
+    }
+
+    fn generation(&self) -> usize {
+        self.step
+    }
+
+    fn best_fitness(&self) -> f64 {
+        self.current_fitness
+    }
+
+    fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+
+    fn finish(self, _reason: StopReason) -> Vec<G> {
+        vec![self.current]
+    }
+}
@@ -0,0 +1,96 @@
+use crate::bitgenome::BitGenome;
+use crate::fitness::Fitness;
+use crate::knapsack::Knapsack;
+use crate::maxones::MaxOnes;
+
+// Problems whose fitness can be updated from a parent's evaluation and the bits mutation
+// flipped, in time proportional to the number of flips rather than the genome length. Only
+// for problems with one gene per bit, so locus i of the genome is gene i of the phenotype.
+// The state carries whatever the update needs beyond the fitness value itself.
+pub trait IncrementalFitness: Fitness {
+    type State: Clone;
+
+    // Full evaluation of a phenotype, done once for each genome without a parent to update from
+    fn state(&self, member: &[f64]) -> Self::State;
+
+    // State of the offspring, given its parent's state and the (locus, new bit) pairs that
+    // differ between them
+    fn update(&self, state: &Self::State, changes: &[(usize, bool)]) -> Self::State;
+
+    // Fitness of the genome the state describes, equal to fitness() on its phenotype
+    fn value(&self, state: &Self::State) -> f64;
+}
+
+// The new values of the loci mutate_with_loci reported as changed
+pub fn changes(genome: &BitGenome, loci: &[usize]) -> Vec<(usize, bool)> {
+    loci.iter().map(|&i| (i, genome.get(i))).collect()
+}
+
+// Number of ones and genome length
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OnesCount {
+    pub ones: usize,
+    pub len: usize,
+}
+
+impl IncrementalFitness for MaxOnes {
+    type State = OnesCount;
+
+    fn state(&self, member: &[f64]) -> OnesCount {
+        let ones = member.iter().filter(|&&gene| gene >= 0.5).count();
+        OnesCount { ones, len: member.len() }
+    }
+
+    fn update(&self, state: &OnesCount, changes: &[(usize, bool)]) -> OnesCount {
+        // Every change is a flip, so each one adds or removes exactly one "on" gene
+        let set = changes.iter().filter(|&&(_, bit)| bit).count();
+        OnesCount { ones: state.ones + set - (changes.len() - set), len: state.len }
+    }
+
+    fn value(&self, state: &OnesCount) -> f64 {
+        state.ones as f64 / state.len as f64
+    }
+}
+
+// Total weight and value of the packed items, with the value of all items so value() doesn't
+// have to sum it again
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Packing {
+    pub weight: f64,
+    pub value: f64,
+    pub total: f64,
+}
+
+impl IncrementalFitness for Knapsack {
+    type State = Packing;
+
+    fn state(&self, member: &[f64]) -> Packing {
+        let total = self.values.iter().sum();
+        let mut packing = Packing { weight: 0.0, value: 0.0, total };
+        for (i, &gene) in member.iter().enumerate().take(self.weights.len()) {
+            if gene >= 0.5 {
+                packing.weight += self.weights[i];
+                packing.value += self.values[i];
+            }
+        }
+        packing
+    }
+
+    fn update(&self, state: &Packing, changes: &[(usize, bool)]) -> Packing {
+        let mut packing = *state;
+        // Bits past the last item don't select anything
+        for &(i, bit) in changes.iter().filter(|&&(i, _)| i < self.weights.len()) {
+            let sign = if bit { 1.0 } else { -1.0 };
+            packing.weight += sign * self.weights[i];
+            packing.value += sign * self.values[i];
+        }
+        packing
+    }
+
+    fn value(&self, state: &Packing) -> f64 {
+        if state.weight > self.capacity {
+            return 0.0;
+        }
+        state.value / state.total
+    }
+}
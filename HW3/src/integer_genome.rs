@@ -0,0 +1,102 @@
+use crate::bitstring::GAParameters;
+use crate::decoder::Decoder;
+use crate::genome::{Genome, hash_of};
+use rand::prelude::*;
+
+// How a selected integer gene is changed
+#[derive(Debug, Clone, Copy)]
+pub enum IntegerMutation {
+    // Reset the gene to a uniformly random value within the bounds
+    Uniform,
+    // Add a nonzero step in [-max_step, max_step], clamped to the bounds
+    Creep { max_step: i64 },
+}
+
+// Vector of bounded integers, e.g. bin assignments or categorical choices. The bounds are the
+// integers inside the fitness function's (f64, f64) range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntegerGenome(pub Vec<i64>);
+
+fn integer_bounds(bounds: (f64, f64)) -> (i64, i64) {
+    (bounds.0.ceil() as i64, bounds.1.floor() as i64)
+}
+
+impl Genome for IntegerGenome {
+    fn random<R: Rng + ?Sized>(len: usize, bounds: (f64, f64), rng: &mut R) -> Self {
+        let (low, high) = integer_bounds(bounds);
+        IntegerGenome((0..len).map(|_| rng.random_range(low..=high)).collect())
+    }
+
+    fn mutate<R: Rng + ?Sized>(
+        &self,
+        params: &GAParameters,
+        bounds: (f64, f64),
+        rng: &mut R,
+    ) -> Self {
+        let (low, high) = integer_bounds(bounds);
+        let genes = self
+            .0
+            .iter()
+            .map(|&gene| {
+                let random: f64 = rng.random();
+                if random >= params.mutation_rate {
+                    return gene;
+                }
+                match params.integer_mutation {
+                    IntegerMutation::Uniform => rng.random_range(low..=high),
+                    IntegerMutation::Creep { max_step } => {
+                        let max_step = max_step.max(1);
+                        let step = rng.random_range(1..=max_step);
+                        let step = if rng.random() { step } else { -step };
+                        (gene + step).clamp(low, high)
+                    }
+                }
+            })
+            .collect();
+        IntegerGenome(genes)
+    }
+
+    // Single point crossover
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        other: &Self,
+        crossover_rate: f64,
+        rng: &mut R,
+    ) -> (Self, Self) {
+        if self.0.len() != other.0.len() {
+            panic!("Parents must be of the same length");
+        }
+        let random: f64 = rng.random();
+        if random >= crossover_rate || self.0.len() < 2 {
+            return (self.clone(), other.clone());
+        }
+
+        let crossover_point = rng.random_range(1..self.0.len());
+        let offspring1 = [&self.0[..crossover_point], &other.0[crossover_point..]].concat();
+        let offspring2 = [&other.0[..crossover_point], &self.0[crossover_point..]].concat();
+        (IntegerGenome(offspring1), IntegerGenome(offspring2))
+    }
+
+    fn length(&self) -> usize {
+        self.0.len()
+    }
+
+    // Manhattan distance
+    fn distance(&self, other: &Self) -> f64 {
+        self.0.iter().zip(&other.0).map(|(a, b)| (a - b).abs()).sum::<i64>() as f64
+    }
+
+    fn fingerprint(&self) -> u64 {
+        hash_of(&self.0)
+    }
+
+    fn phenotype(&self, _decoder: &impl Decoder, _num_dims: usize) -> Vec<f64> {
+        self.0.iter().map(|&gene| gene as f64).collect()
+    }
+
+    // Rounded to the nearest integers
+    fn from_phenotype(phenotype: &[f64], _decoder: &impl Decoder, len: usize) -> Option<Self> {
+        (phenotype.len() == len)
+            .then(|| IntegerGenome(phenotype.iter().map(|&x| x.round() as i64).collect()))
+    }
+}
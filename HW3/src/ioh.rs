@@ -0,0 +1,224 @@
+use crate::cache::CacheStats;
+use crate::curves::{TraceRow, best_so_far};
+use crate::decoder::Decoder;
+use crate::fitness::{Fitness, Objective};
+use crate::seeds::SeedSequence;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// Evaluation count of a run and every evaluation that beat the best so far, which is all an
+// IOHprofiler improvement log holds
+#[derive(Debug, Clone)]
+pub struct IohRun {
+    pub evaluations: usize,
+    // (evaluation number counting from 1, fitness)
+    pub improvements: Vec<(usize, f64)>,
+}
+
+impl IohRun {
+    fn new() -> Self {
+        Self { evaluations: 0, improvements: Vec::new() }
+    }
+
+    fn record(&mut self, value: f64) {
+        self.evaluations += 1;
+        let best = self.improvements.last().map_or(f64::NEG_INFINITY, |&(_, best)| best);
+        if value > best {
+            self.improvements.push((self.evaluations, value));
+        }
+    }
+
+    // A run rebuilt from its CSV trace, at the granularity of generations: each improvement
+    // is logged at the evaluation count its generation ended on
+    pub fn from_trace(trace: &[TraceRow]) -> Self {
+        let mut run = Self::new();
+        for (evaluations, best) in best_so_far(trace) {
+            if best > run.best().unwrap_or(f64::NEG_INFINITY) {
+                run.improvements.push((evaluations, best));
+            }
+            run.evaluations = evaluations;
+        }
+        run
+    }
+
+    pub fn best(&self) -> Option<f64> {
+        self.improvements.last().map(|&(_, best)| best)
+    }
+
+    // Best fitness found within the first `evaluations` evaluations
+    pub fn best_within(&self, evaluations: usize) -> Option<f64> {
+        let reached = self.improvements.partition_point(|&(evals, _)| evals <= evaluations);
+        reached.checked_sub(1).map(|last| self.improvements[last].1)
+    }
+}
+
+// Counts every evaluation of the wrapped problem and logs the improvements, at the
+// granularity of single evaluations rather than generations. Batches are still passed on
+// whole and logged in order.
+pub struct IohTracked<'a, F: Objective> {
+    inner: &'a F,
+    run: Mutex<IohRun>,
+}
+
+impl<'a, F: Objective> IohTracked<'a, F> {
+    pub fn new(inner: &'a F) -> Self {
+        Self { inner, run: Mutex::new(IohRun::new()) }
+    }
+
+    // The run as logged so far
+    pub fn run(&self) -> IohRun {
+        self.run.lock().unwrap().clone()
+    }
+}
+
+impl<F: Objective> Objective for IohTracked<'_, F> {
+    fn fitness(&self, member: &[f64]) -> f64 {
+        let value = self.inner.fitness(member);
+        self.run.lock().unwrap().record(value);
+        value
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        self.inner.bounds()
+    }
+
+    fn optimum_value(&self) -> Option<f64> {
+        self.inner.optimum_value()
+    }
+
+    fn known_optima(&self, num_dims: usize) -> Vec<Vec<f64>> {
+        self.inner.known_optima(num_dims)
+    }
+
+    fn evaluate_population(&self, members: &[Vec<f64>]) -> Vec<f64> {
+        let values = self.inner.evaluate_population(members);
+        let mut run = self.run.lock().unwrap();
+        for &value in &values {
+            run.record(value);
+        }
+        values
+    }
+
+    fn evaluate_population_seeded(
+        &self,
+        members: &[Vec<f64>],
+        streams: &[SeedSequence],
+    ) -> Vec<f64> {
+        let values = self.inner.evaluate_population_seeded(members, streams);
+        let mut run = self.run.lock().unwrap();
+        for &value in &values {
+            run.record(value);
+        }
+        values
+    }
+
+    fn cache_stats(&self) -> Option<CacheStats> {
+        self.inner.cache_stats()
+    }
+}
+
+impl<F: Fitness> Fitness for IohTracked<'_, F> {
+    fn decoder(&self) -> impl Decoder {
+        self.inner.decoder()
+    }
+}
+
+// A benchmark function as IOHprofiler identifies it
+#[derive(Debug, Clone)]
+pub struct IohProblem {
+    pub id: usize,
+    pub name: String,
+    pub dimension: usize,
+}
+
+// Data folder of one algorithm in the IOHprofiler format IOHanalyzer reads: per function an
+// .info meta file, and per function and dimension a .dat file with one improvement log per
+// run. Runs are added to whatever the folder already holds, so repeated invocations with
+// different seeds build up one data set.
+pub struct IohArchive {
+    root: PathBuf,
+    algorithm: String,
+    info: String,
+}
+
+impl IohArchive {
+    pub fn new(root: &Path, algorithm: &str, info: &str) -> io::Result<Self> {
+        fs::create_dir_all(root)?;
+        Ok(Self {
+            root: root.to_path_buf(),
+            algorithm: algorithm.to_string(),
+            info: info.to_string(),
+        })
+    }
+
+    pub fn add_run(&self, problem: &IohProblem, run: &IohRun) -> io::Result<()> {
+        let data_dir = format!("data_f{}_{}", problem.id, problem.name);
+        let dat_name = format!("IOHprofiler_f{}_DIM{}.dat", problem.id, problem.dimension);
+        fs::create_dir_all(self.root.join(&data_dir))?;
+        let mut dat = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.root.join(&data_dir).join(&dat_name))?;
+        let mut log = String::from("evaluations raw_y\n");
+        for &(evaluation, value) in &run.improvements {
+            log.push_str(&format!("{evaluation} {value:e}\n"));
+        }
+        dat.write_all(log.as_bytes())?;
+
+        // Each run is instance 1: the problems here have no transformed instances
+        let entry = format!("1:{}|{:e}", run.evaluations, run.best().unwrap_or(f64::NAN));
+        let meta = format!(
+            "suite = 'HW3', funcId = {}, funcName = '{}', DIM = {}, maximization = 'T', \
+             algId = '{}', algInfo = '{}'",
+            problem.id,
+            problem.name,
+            problem.dimension,
+            self.algorithm,
+            self.info.replace('\'', "")
+        );
+        let info = self.root.join(format!("IOHprofiler_f{}_{}.info", problem.id, problem.name));
+        let dat_path = format!("{data_dir}/{dat_name}");
+        let block = InfoBlock { meta, comment: "%".to_string(), dat_path };
+        add_info_entry(&info, problem.dimension, &block, &entry)
+    }
+}
+
+// The lines that start the block of one dimension in an IOHprofiler or COCO .info file
+pub struct InfoBlock {
+    pub meta: String,
+    pub comment: String,
+    // Data file relative to the .info file
+    pub dat_path: String,
+}
+
+// Appends a run's entry to the block of `dimension` in the .info file at `path`, starting the
+// block if the file doesn't have one yet. Both formats use three-line blocks: the meta line,
+// which names the dimension, a comment line and the data file followed by its runs.
+pub fn add_info_entry(
+    path: &Path,
+    dimension: usize,
+    block: &InfoBlock,
+    entry: &str,
+) -> io::Result<()> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+    let dimension = format!("DIM = {dimension},");
+    let existing = (0..lines.len())
+        .step_by(3)
+        .find(|&start| lines[start].contains(&dimension) && start + 2 < lines.len());
+    match existing {
+        Some(start) => lines[start + 2].push_str(&format!(", {entry}")),
+        None => {
+            lines.push(block.meta.clone());
+            lines.push(block.comment.clone());
+            lines.push(format!("{}, {entry}", block.dat_path));
+        }
+    }
+    fs::write(path, lines.join("\n") + "\n")
+}
@@ -0,0 +1,398 @@
+use crate::algorithm::Algorithm;
+use crate::bitstring::{
+    GAParameters, evaluate, evaluate_population, init_population, next_generation,
+};
+use crate::evol_strat::{ESParameters, EvolutionStrategy};
+use crate::fitness::Fitness;
+use crate::genome::Genome;
+use crate::observer::{GenerationStats, Observer};
+use crate::repair::NoRepair;
+use crate::seeds::SeedSequence;
+use std::collections::BTreeMap;
+use std::sync::mpsc;
+use std::thread;
+
+#[derive(Debug, Clone)]
+pub struct IslandParameters {
+    pub num_islands: usize,
+    // Generations between migrations
+    pub migration_interval: usize,
+    // Best members copied to the next island in the ring at each migration
+    pub migrants: usize,
+    // Master seed the islands' seeds are derived from
+    pub seed: u64,
+}
+
+impl Default for IslandParameters {
+    fn default() -> Self {
+        Self {
+            num_islands: 4,
+            migration_interval: 10,
+            migrants: 2,
+            seed: 5000,
+        }
+    }
+}
+
+// The algorithm and settings of one island of a heterogeneous run
+#[derive(Debug, Clone)]
+pub enum IslandAlgorithm {
+    Ga(GAParameters),
+    Es(ESParameters),
+}
+
+impl IslandAlgorithm {
+    pub fn describe(&self) -> String {
+        match self {
+            IslandAlgorithm::Ga(params) => format!(
+                "GA Pop={} Mutation={} Crossover={}",
+                params.pop_size, params.mutation_rate, params.crossover_rate
+            ),
+            IslandAlgorithm::Es(params) => format!(
+                "ES Mu={} Lambda={} Sigma={} Mutation={:?}",
+                params.mu, params.lambda, params.sigma, params.mutation
+            ),
+        }
+    }
+}
+
+// Migrants sent at one migration epoch
+struct Migration<G> {
+    epoch: usize,
+    members: Vec<G>,
+}
+
+// Per-generation statistics reported by an island
+struct IslandStats {
+    island: usize,
+    generation: usize,
+    max_fitness: f64,
+    avg_fitness: f64,
+}
+
+// Island model with one thread per island, each running params.pop_size members for
+// params.max_iters generations. Islands form a ring and send their best members to the next
+// island every migration_interval generations. Migration is asynchronous with one epoch of
+// slack: at epoch k an island sends its emigrants and takes in the predecessor's emigrants
+// from epoch k - 1, which were sent an interval earlier, so islands rarely wait on each
+// other and the run is still deterministic for a fixed seed. Returns the final population
+// of every island.
+pub fn island_ga<G, F>(
+    fitness_fn: &F,
+    params: &GAParameters,
+    island_params: &IslandParameters,
+) -> Vec<Vec<G>>
+where
+    G: Genome + Send,
+    F: Fitness + Sync,
+{
+    let n = island_params.num_islands.max(1);
+    let (stats_tx, stats_rx) = mpsc::channel::<IslandStats>();
+    let (migration_txs, migration_rxs): (Vec<_>, Vec<_>) =
+        (0..n).map(|_| mpsc::channel::<Migration<G>>()).unzip();
+
+    println!(
+        "Running island GA with {} islands of Pop={} MigrationInterval={} Migrants={} Seed={}",
+        n,
+        params.pop_size,
+        island_params.migration_interval,
+        island_params.migrants,
+        island_params.seed
+    );
+
+    thread::scope(|scope| {
+        let mut handles = Vec::new();
+        for (island, inbox) in migration_rxs.into_iter().enumerate() {
+            // Island i sends to island i + 1 around the ring
+            let outbox = migration_txs[(island + 1) % n].clone();
+            let stats_tx = stats_tx.clone();
+            handles.push(scope.spawn(move || {
+                run_island(island, fitness_fn, params, island_params, inbox, outbox, stats_tx)
+            }));
+        }
+        drop(stats_tx);
+        drop(migration_txs);
+
+        print_merged_stats(stats_rx, n, "Island GA");
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("island thread panicked"))
+            .collect()
+    })
+}
+
+// Island model whose islands each run their own algorithm and settings, e.g. a GA island
+// next to an ES island, so an operator portfolio is compared within one run. It works like
+// island_ga, with one island per entry of `islands` in place of num_islands, except that
+// migrants travel as phenotypes: GA islands decode their emigrants and build immigrants of
+// genome type G back through the problem's decoder, dropping any G can't represent. Every
+// island should cover the same number of generations, since a merged line is printed only
+// for the generations all islands reached. Returns the final population of every island as
+// phenotypes.
+pub fn heterogeneous_islands<G, F>(
+    fitness_fn: &F,
+    islands: &[IslandAlgorithm],
+    island_params: &IslandParameters,
+) -> Vec<Vec<Vec<f64>>>
+where
+    G: Genome + Send,
+    F: Fitness + Sync,
+{
+    let n = islands.len();
+    let (stats_tx, stats_rx) = mpsc::channel::<IslandStats>();
+    let (migration_txs, migration_rxs): (Vec<_>, Vec<_>) =
+        (0..n).map(|_| mpsc::channel::<Migration<Vec<f64>>>()).unzip();
+
+    let descriptions: Vec<String> = islands.iter().map(IslandAlgorithm::describe).collect();
+    println!(
+        "Running heterogeneous islands [{}] MigrationInterval={} Migrants={} Seed={}",
+        descriptions.join(", "),
+        island_params.migration_interval,
+        island_params.migrants,
+        island_params.seed
+    );
+
+    thread::scope(|scope| {
+        let mut handles = Vec::new();
+        for ((island, inbox), algorithm) in migration_rxs.into_iter().enumerate().zip(islands) {
+            let link = IslandLink {
+                island,
+                inbox,
+                outbox: migration_txs[(island + 1) % n].clone(),
+                stats_tx: stats_tx.clone(),
+            };
+            handles.push(scope.spawn(move || match algorithm {
+                IslandAlgorithm::Ga(params) => {
+                    run_ga_island::<G, F>(fitness_fn, params, island_params, link)
+                }
+                IslandAlgorithm::Es(params) => {
+                    run_es_island(fitness_fn, params, island_params, link)
+                }
+            }));
+        }
+        drop(stats_tx);
+        drop(migration_txs);
+
+        print_merged_stats(stats_rx, n, "Islands");
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("island thread panicked"))
+            .collect()
+    })
+}
+
+// An island's channels to the rest of a heterogeneous run
+struct IslandLink {
+    island: usize,
+    inbox: mpsc::Receiver<Migration<Vec<f64>>>,
+    outbox: mpsc::Sender<Migration<Vec<f64>>>,
+    stats_tx: mpsc::Sender<IslandStats>,
+}
+
+impl IslandLink {
+    // Sends this epoch's emigrants and, from the second epoch on, receives the previous
+    // epoch's from the predecessor, as island_ga does. No immigrants once it has finished.
+    fn swap(&self, epoch: usize, emigrants: Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+        let _ = self.outbox.send(Migration { epoch, members: emigrants });
+        if epoch <= 1 {
+            return Vec::new();
+        }
+        match self.inbox.recv() {
+            Ok(migration) => {
+                debug_assert_eq!(migration.epoch, epoch - 1);
+                migration.members
+            }
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+fn run_ga_island<G: Genome, F: Fitness>(
+    fitness_fn: &F,
+    params: &GAParameters,
+    island_params: &IslandParameters,
+    link: IslandLink,
+) -> Vec<Vec<f64>> {
+    let mut rng = SeedSequence::new(island_params.seed).island(link.island).rng();
+    let evaluation_seeds =
+        SeedSequence::new(island_params.seed).island(link.island).operator("evaluation");
+    let mut population: Vec<G> = init_population(params, fitness_fn.bounds(), &mut rng);
+    let interval = island_params.migration_interval.max(1);
+    let decoder = fitness_fn.decoder();
+    let (num_dims, resamples) = (params.num_dims, params.resamples);
+
+    for generation in 0..params.max_iters {
+        let seeds = evaluation_seeds.generation(generation);
+        let mut fitnesses =
+            evaluate_population(&population, fitness_fn, num_dims, resamples, seeds);
+        let _ = link.stats_tx.send(IslandStats {
+            island: link.island,
+            generation,
+            max_fitness: fitnesses.iter().cloned().fold(0.0, f64::max),
+            avg_fitness: fitnesses.iter().sum::<f64>() / fitnesses.len() as f64,
+        });
+
+        if generation > 0 && generation.is_multiple_of(interval) {
+            let mut order: Vec<usize> = (0..population.len()).collect();
+            order.sort_by(|&i, &j| fitnesses[j].partial_cmp(&fitnesses[i]).unwrap());
+            let migrants = island_params.migrants.min(population.len());
+            let emigrants = order[..migrants]
+                .iter()
+                .map(|&i| population[i].phenotype(&decoder, params.num_dims))
+                .collect();
+
+            let immigrants = link
+                .swap(generation / interval, emigrants)
+                .into_iter()
+                .filter_map(|phenotype| G::from_phenotype(&phenotype, &decoder, params.mem_size));
+            for (k, (&worst, immigrant)) in order.iter().rev().zip(immigrants).enumerate() {
+                let stream = seeds.operator("immigrants").individual(k);
+                fitnesses[worst] = evaluate(&immigrant, fitness_fn, num_dims, resamples, stream);
+                population[worst] = immigrant;
+            }
+        }
+
+        population = next_generation(&population, &fitnesses, params, fitness_fn, &mut rng);
+    }
+    population.iter().map(|member| member.phenotype(&decoder, params.num_dims)).collect()
+}
+
+// Passes an ES island's generation statistics on to the merged output
+struct IslandStatsForwarder<'a> {
+    island: usize,
+    generation: usize,
+    stats_tx: &'a mpsc::Sender<IslandStats>,
+}
+
+impl Observer<Vec<f64>> for IslandStatsForwarder<'_> {
+    fn on_generation(&mut self, stats: &GenerationStats, _best: &Vec<f64>) {
+        let _ = self.stats_tx.send(IslandStats {
+            island: self.island,
+            generation: self.generation,
+            max_fitness: stats.max_fitness,
+            avg_fitness: stats.avg_fitness,
+        });
+        self.generation += 1;
+    }
+}
+
+// An ES island, quiet apart from the merged lines. It stops early where the ES would, at
+// convergence or on its stopping criteria.
+fn run_es_island<F: Fitness>(
+    fitness_fn: &F,
+    params: &ESParameters,
+    island_params: &IslandParameters,
+    link: IslandLink,
+) -> Vec<Vec<f64>> {
+    let mut rng = SeedSequence::new(island_params.seed).island(link.island).rng();
+    let interval = island_params.migration_interval.max(1);
+    let forwarder =
+        IslandStatsForwarder { island: link.island, generation: 0, stats_tx: &link.stats_tx };
+    let mut es = EvolutionStrategy::new(fitness_fn, params, &NoRepair, &mut rng)
+        .quiet()
+        .with_observer(forwarder);
+
+    let reason = loop {
+        if let Some(reason) = es.step(&mut rng) {
+            break reason;
+        }
+        let generation = es.generation();
+        if generation.is_multiple_of(interval) {
+            es.exchange(island_params.migrants, |emigrants| {
+                link.swap(generation / interval, emigrants)
+            });
+        }
+    };
+    es.finish(reason).into_iter().map(|member| member[0..params.mem_size].to_vec()).collect()
+}
+
+fn run_island<G: Genome, F: Fitness>(
+    island: usize,
+    fitness_fn: &F,
+    params: &GAParameters,
+    island_params: &IslandParameters,
+    inbox: mpsc::Receiver<Migration<G>>,
+    outbox: mpsc::Sender<Migration<G>>,
+    stats_tx: mpsc::Sender<IslandStats>,
+) -> Vec<G> {
+    let mut rng = SeedSequence::new(island_params.seed).island(island).rng();
+    let evaluation_seeds =
+        SeedSequence::new(island_params.seed).island(island).operator("evaluation");
+    let mut population: Vec<G> = init_population(params, fitness_fn.bounds(), &mut rng);
+    let interval = island_params.migration_interval.max(1);
+    let (num_dims, resamples) = (params.num_dims, params.resamples);
+
+    for generation in 0..params.max_iters {
+        let seeds = evaluation_seeds.generation(generation);
+        let mut fitnesses =
+            evaluate_population(&population, fitness_fn, num_dims, resamples, seeds);
+        let _ = stats_tx.send(IslandStats {
+            island,
+            generation,
+            max_fitness: fitnesses.iter().cloned().fold(0.0, f64::max),
+            avg_fitness: fitnesses.iter().sum::<f64>() / fitnesses.len() as f64,
+        });
+
+        if generation > 0 && generation.is_multiple_of(interval) {
+            let epoch = generation / interval;
+            let mut order: Vec<usize> = (0..population.len()).collect();
+            order.sort_by(|&i, &j| fitnesses[j].partial_cmp(&fitnesses[i]).unwrap());
+            let migrants = island_params.migrants.min(population.len());
+
+            let members = order[..migrants].iter().map(|&i| population[i].clone()).collect();
+            let _ = outbox.send(Migration { epoch, members });
+
+            // Immigrants from the previous epoch replace the worst members
+            if epoch > 1
+                && let Ok(migration) = inbox.recv()
+            {
+                debug_assert_eq!(migration.epoch, epoch - 1);
+                let immigrants = order.iter().rev().zip(migration.members).enumerate();
+                for (k, (&worst, immigrant)) in immigrants {
+                    let stream = seeds.operator("immigrants").individual(k);
+                    fitnesses[worst] =
+                        evaluate(&immigrant, fitness_fn, num_dims, resamples, stream);
+                    population[worst] = immigrant;
+                }
+            }
+        }
+
+        population = next_generation(&population, &fitnesses, params, fitness_fn, &mut rng);
+    }
+    population
+}
+
+// Prints one merged line per generation once every island has reported it
+fn print_merged_stats(stats_rx: mpsc::Receiver<IslandStats>, num_islands: usize, label: &str) {
+    let mut pending: BTreeMap<usize, Vec<Option<(f64, f64)>>> = BTreeMap::new();
+    let mut next_generation = 0;
+    for stats in stats_rx {
+        pending.entry(stats.generation).or_insert_with(|| vec![None; num_islands])
+            [stats.island] = Some((stats.max_fitness, stats.avg_fitness));
+
+        while let Some(islands) = pending.get(&next_generation)
+            && islands.iter().all(Option::is_some)
+        {
+            let islands: Vec<(f64, f64)> = pending
+                .remove(&next_generation)
+                .unwrap()
+                .into_iter()
+                .flatten()
+                .collect();
+            let best = islands.iter().map(|s| s.0).fold(0.0, f64::max);
+            let avg = islands.iter().map(|s| s.1).sum::<f64>() / num_islands as f64;
+            let island_bests: Vec<String> =
+                islands.iter().map(|s| format!("{:.6}", s.0)).collect();
+            println!(
+                "{label} {} {} {} [{}]",
+                next_generation,
+                best,
+                avg,
+                island_bests.join(" ")
+            );
+            next_generation += 1;
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use crate::decoder::{Decoder, PerBit};
+use crate::fitness::{Fitness, Objective};
+
+// 0/1 knapsack: bit i selects item i. Fitness is the fraction of the total available value
+// that was packed, or 0.0 if the selection is over capacity.
+pub struct Knapsack {
+    pub weights: Vec<f64>,
+    pub values: Vec<f64>,
+    pub capacity: f64,
+}
+
+impl Objective for Knapsack {
+    fn fitness(&self, member: &[f64]) -> f64 {
+        let mut weight = 0.0;
+        let mut value = 0.0;
+        for (i, &gene) in member.iter().enumerate().take(self.weights.len()) {
+            if gene >= 0.5 {
+                weight += self.weights[i];
+                value += self.values[i];
+            }
+        }
+        if weight > self.capacity {
+            return 0.0;
+        }
+        value / self.values.iter().sum::<f64>()
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+}
+
+impl Fitness for Knapsack {
+    fn decoder(&self) -> impl Decoder {
+        PerBit
+    }
+}
@@ -0,0 +1,81 @@
+// Evolutionary algorithms (GA, ES and their relatives), benchmark problems and the parameter
+// tuning around them. The core runs step by step through Algorithm::step without threads, file
+// IO or a clock, so it builds for wasm32 with default features off; the cli feature adds the
+// plots and progress bars the command line uses.
+
+pub mod algorithm;
+pub mod anova;
+pub mod basins;
+pub mod bayesian_tuning;
+pub mod bitgenome;
+pub mod bitstring;
+pub mod browser;
+pub mod bytegenome;
+pub mod cache;
+pub mod cancellation;
+pub mod cgp;
+pub mod checkpoint;
+pub mod coco;
+pub mod constraint;
+pub mod curves;
+pub mod decoder;
+pub mod diversity;
+pub mod ecdf;
+pub mod evol_strat;
+pub mod ffi;
+pub mod fitness;
+pub mod genome;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+pub mod himmelblau;
+pub mod hyper_heuristic;
+pub mod incremental;
+pub mod integer_genome;
+pub mod ioh;
+pub mod island;
+pub mod knapsack;
+pub mod logging;
+pub mod map_elites;
+pub mod maxones;
+pub mod mixed_integer;
+pub mod mlp;
+pub mod multi_objective;
+pub mod neat;
+pub mod niching;
+pub mod noise;
+pub mod observer;
+pub mod one_plus_one;
+pub mod openai_es;
+pub mod parallel;
+pub mod parameter_tuning;
+pub mod parity;
+pub mod permutation;
+#[cfg(feature = "cli")]
+pub mod plots;
+pub mod population;
+pub mod pressure_vessel;
+pub mod quartic;
+pub mod rate_control;
+pub mod remote;
+pub mod repair;
+#[cfg(feature = "cli")]
+pub mod report;
+pub mod resources;
+pub mod results_analyzer;
+pub mod rosenbrock;
+pub mod screening;
+pub mod seeds;
+pub mod selection;
+pub mod self_adaptive;
+pub mod speciation;
+pub mod statistics;
+pub mod stopping;
+pub mod surrogate;
+pub mod target_sum;
+pub mod timeout_runner;
+pub mod timing;
+pub mod tsp;
+pub mod variable_genome;
+pub mod warm_start;
+pub mod work_queue;
+pub mod xor;
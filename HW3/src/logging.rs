@@ -0,0 +1,274 @@
+use crate::cache::CacheStats;
+use crate::constraint::ViolationStats;
+use crate::map_elites::QdMetrics;
+use crate::observer::{ConsoleObserver, GenerationStats, Observer, RunSummary};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+
+// One line of the run log. Every record carries the algorithm and its parameters, so lines
+// from several runs appended to the same file can be told apart.
+#[derive(Serialize)]
+struct Record<'a> {
+    algorithm: &'a str,
+    params: &'a BTreeMap<String, f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(flatten)]
+    event: Event<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Event<'a> {
+    Generation {
+        generation: usize,
+        evals: usize,
+        max_fitness: f64,
+        avg_fitness: f64,
+        diversity: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cache: Option<CacheStats>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        boundary_hits: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        violations: Option<ViolationStats>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        qd: Option<QdMetrics>,
+    },
+    Message {
+        message: &'a str,
+    },
+    End {
+        reason: String,
+        generations: usize,
+        evals: usize,
+        best_fitness: f64,
+        stagnant_generations: usize,
+    },
+}
+
+// Writes a run as JSON lines instead of the console status lines: one "generation" object
+// per generation, operator reports as "message" objects and a final "end" object
+pub struct JsonLinesLogger {
+    writer: BufWriter<File>,
+    algorithm: String,
+    params: BTreeMap<String, f64>,
+    // Seed of the run's RNG, when the caller recorded it
+    seed: Option<u64>,
+    path: String,
+    // Set after the first write error so a full disk is reported once, not every generation
+    failed: bool,
+}
+
+impl JsonLinesLogger {
+    // Appends to `path`, creating it if needed
+    pub fn open(
+        path: &str,
+        algorithm: &str,
+        params: HashMap<String, f64>,
+        seed: Option<u64>,
+    ) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+            algorithm: algorithm.to_string(),
+            params: params.into_iter().collect(),
+            seed,
+            path: path.to_string(),
+            failed: false,
+        })
+    }
+
+    fn write(&mut self, event: Event) {
+        if self.failed {
+            return;
+        }
+        let record =
+            Record { algorithm: &self.algorithm, params: &self.params, seed: self.seed, event };
+        let result = serde_json::to_writer(&mut self.writer, &record)
+            .map_err(io::Error::from)
+            .and_then(|()| writeln!(self.writer));
+        if let Err(e) = result {
+            eprintln!("Error writing run log {}: {e}", self.path);
+            self.failed = true;
+        }
+    }
+}
+
+impl<I> Observer<I> for JsonLinesLogger {
+    fn on_generation(&mut self, stats: &GenerationStats, _best: &I) {
+        self.write(Event::Generation {
+            generation: stats.generation,
+            evals: stats.evaluations,
+            max_fitness: stats.max_fitness,
+            avg_fitness: stats.avg_fitness,
+            diversity: stats.diversity,
+            cache: stats.cache,
+            boundary_hits: stats.boundary_hits,
+            violations: stats.violations,
+            qd: stats.qd,
+        });
+    }
+
+    fn on_termination(&mut self, summary: &RunSummary, _best: Option<&I>) {
+        self.write(Event::End {
+            reason: summary.reason.to_string(),
+            generations: summary.generations,
+            evals: summary.evaluations,
+            best_fitness: summary.best_fitness,
+            stagnant_generations: summary.stagnant_generations,
+        });
+        if !self.failed
+            && let Err(e) = self.writer.flush()
+        {
+            eprintln!("Error writing run log {}: {e}", self.path);
+            self.failed = true;
+        }
+    }
+
+    fn on_message(&mut self, message: &str) {
+        self.write(Event::Message { message });
+    }
+}
+
+// Writes one CSV row of generation-level metrics per generation, for plotting convergence
+// curves. Unlike the JSON log this runs alongside whatever else is observing the run.
+pub struct CsvTrace {
+    writer: BufWriter<File>,
+    path: String,
+    failed: bool,
+}
+
+impl CsvTrace {
+    // Truncates `path`, so each run gets its own trace
+    pub fn create(path: &str) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "generation,evaluations,best,mean,std,diversity")?;
+        Ok(Self { writer, path: path.to_string(), failed: false })
+    }
+
+    fn check(&mut self, result: io::Result<()>) {
+        if let Err(e) = result {
+            eprintln!("Error writing trace {}: {e}", self.path);
+            self.failed = true;
+        }
+    }
+}
+
+impl<I> Observer<I> for CsvTrace {
+    fn on_generation(&mut self, stats: &GenerationStats, _best: &I) {
+        if self.failed {
+            return;
+        }
+        let result = writeln!(
+            self.writer,
+            "{},{},{},{},{},{}",
+            stats.generation,
+            stats.evaluations,
+            stats.max_fitness,
+            stats.avg_fitness,
+            stats.std_fitness,
+            stats.diversity.map_or(String::new(), |diversity| diversity.to_string())
+        );
+        self.check(result);
+    }
+
+    fn on_termination(&mut self, _summary: &RunSummary, _best: Option<&I>) {
+        if !self.failed {
+            let result = self.writer.flush();
+            self.check(result);
+        }
+    }
+}
+
+// Writes one CSV row of an archive's QD metrics per generation, for runs that keep a
+// quality-diversity archive. Generations without metrics are skipped.
+pub struct QdTrace {
+    writer: BufWriter<File>,
+    path: String,
+    failed: bool,
+}
+
+impl QdTrace {
+    // Truncates `path`, so each run gets its own trace
+    pub fn create(path: &str) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "generation,evaluations,coverage,qd_score,max_fitness")?;
+        Ok(Self { writer, path: path.to_string(), failed: false })
+    }
+
+    fn check(&mut self, result: io::Result<()>) {
+        if let Err(e) = result {
+            eprintln!("Error writing QD trace {}: {e}", self.path);
+            self.failed = true;
+        }
+    }
+}
+
+impl<I> Observer<I> for QdTrace {
+    fn on_generation(&mut self, stats: &GenerationStats, _best: &I) {
+        let Some(qd) = stats.qd.filter(|_| !self.failed) else {
+            return;
+        };
+        let result = writeln!(
+            self.writer,
+            "{},{},{},{},{}",
+            stats.generation, stats.evaluations, qd.coverage, qd.qd_score, qd.max_fitness
+        );
+        self.check(result);
+    }
+
+    fn on_termination(&mut self, _summary: &RunSummary, _best: Option<&I>) {
+        if !self.failed {
+            let result = self.writer.flush();
+            self.check(result);
+        }
+    }
+}
+
+// The observer a run starts with: the JSON logger when `log_path` is set, otherwise (or if the
+// log can't be opened) the console status lines
+pub fn status_observer<'a, I>(
+    log_path: Option<&str>,
+    algorithm: &str,
+    params: HashMap<String, f64>,
+    seed: Option<u64>,
+    console: ConsoleObserver,
+) -> Box<dyn Observer<I> + 'a> {
+    let Some(path) = log_path else {
+        return Box::new(console);
+    };
+    match JsonLinesLogger::open(path, algorithm, params, seed) {
+        Ok(logger) => Box::new(logger),
+        Err(e) => {
+            eprintln!("Error opening run log {path}: {e}");
+            Box::new(console)
+        }
+    }
+}
+
+// The CSV trace for `trace_path`, if one is set and the file can be created
+pub fn trace_observer(trace_path: Option<&str>) -> Option<CsvTrace> {
+    let path = trace_path?;
+    match CsvTrace::create(path) {
+        Ok(trace) => Some(trace),
+        Err(e) => {
+            eprintln!("Error creating trace {path}: {e}");
+            None
+        }
+    }
+}
+
+// The QD trace for `qd_trace_path`, if one is set and the file can be created
+pub fn qd_trace_observer(qd_trace_path: Option<&str>) -> Option<QdTrace> {
+    let path = qd_trace_path?;
+    match QdTrace::create(path) {
+        Ok(trace) => Some(trace),
+        Err(e) => {
+            eprintln!("Error creating QD trace {path}: {e}");
+            None
+        }
+    }
+}
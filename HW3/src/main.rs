@@ -1,158 +1,2891 @@
-mod bitstring;
-mod evol_strat;
-mod fitness;
-mod rosenbrock;
-mod parameter_tuning;
-mod timeout_runner;
-mod results_analyzer;
-
-use crate::bitstring::{GAParameters, sga};
-use crate::evol_strat::{ESParameters, evolution_strategy};
-use crate::fitness::Fitness;
-use crate::rosenbrock::Rosenbrock;
-use crate::parameter_tuning::{ParameterGrid, TuningConfig};
-use crate::timeout_runner::TimeoutRunner;
-use crate::results_analyzer::ResultsAnalyzer;
-use std::env;
-use std::time::Instant;
+mod benchmark;
+mod cli;
 
+use hw3::{anova, checkpoint, curves, ecdf, ffi, plots, remote, screening};
+use hw3::basins::{BasinCounts, distinct_optima};
+use hw3::bayesian_tuning::BayesianTuner;
+use hw3::bitgenome::BitGenome;
+use hw3::algorithm::Algorithm;
+use hw3::bitstring::{GAParameters, GeneticAlgorithm, ga, sga, sga_with_repair};
+use hw3::curves::CurvePoint;
+use hw3::ecdf::EcdfPoint;
+use hw3::constraint::{
+    Constrained, ConstraintSet, DiskConstraint, PenalizedFitness, PenaltyScheme, ViolationStats,
+    feasibility_order,
+};
+use hw3::diversity::{DiversityRestoration, DuplicatePolicy, RestorationAction};
+use hw3::cache::CachedFitness;
+use hw3::cancellation::CancellationToken;
+use hw3::cgp::{Cgp, CgpParameters, ProgramObjective};
+use hw3::checkpoint::{Checkpointer, SessionSnapshot};
+use crate::cli::{
+    AlgorithmKind, AnalysisView, AnalyzeArgs, BoundsKind, Cli, CocoArgs, Command, CurvesArgs,
+    Demo, DemoArgs, EcdfArgs, NoiseKind, OutputFormat, MergeArgs, Problem, RecombinationKind,
+    RunArgs, SearchKind, SurrogateKind, TuneArgs, TuneWorkerArgs, ValidateArgs, WorkerArgs,
+};
+use hw3::coco::CocoArchive;
+use hw3::evol_strat::{
+    ESMutation, ESParameters, EvolutionStrategy, evolution_strategy, evolution_strategy_with_repair,
+};
+use hw3::ffi::{EC_ES, EC_GA, RunConfig};
+use hw3::decoder::{BinaryDecoder, Encoding};
+use hw3::fitness::{Decoded, Fitness, Objective};
+use hw3::genome::{Genome, RealCrossover};
+use hw3::himmelblau::Himmelblau;
+use hw3::hyper_heuristic::{HeuristicSelection, HyperHeuristic, HyperHeuristicParameters};
+use hw3::ioh::{IohArchive, IohProblem, IohRun, IohTracked};
+use hw3::incremental::{IncrementalFitness, changes};
+use hw3::island::{IslandAlgorithm, IslandParameters, heterogeneous_islands, island_ga};
+use hw3::integer_genome::{IntegerGenome, IntegerMutation};
+use hw3::knapsack::Knapsack;
+use hw3::map_elites::{Archive, GenePosition, MapElites, MapElitesParameters};
+use hw3::maxones::MaxOnes;
+use hw3::mixed_integer::MixedSphere;
+use hw3::mlp::{Dataset, Mlp};
+use hw3::multi_objective::{
+    Dtlz2, MultiObjective, Zdt1, Zdt2, Zdt3, generational_distance, non_dominated_sort,
+};
+use hw3::neat::{Neat, NeatParameters, NetworkObjective};
+use hw3::niching::{Clearing, DistanceMetric, FitnessSharing, Replacement};
+use hw3::noise::{NoiseModel, NoisyFitness};
+use hw3::observer::{GenerationStats, ImprovementLog, Observer, StatsConfig, mean_and_std};
+use hw3::one_plus_one::{OnePlusOneES, OnePlusOneParameters};
+use hw3::openai_es::{OpenAiES, OpenAiESParameters};
+use hw3::parallel::ParallelFitness;
+use hw3::rosenbrock::Rosenbrock;
+use hw3::screening::Screening;
+use hw3::parameter_tuning::{
+    GridSpec, ParameterGrid, SampledSearch, Sampling, TuningConfig, TuningProgress, TuningResult,
+};
+use hw3::parity::EvenParity;
+use hw3::quartic::Quartic;
+use hw3::permutation::Permutation;
+use hw3::pressure_vessel::{BEST_KNOWN_COST, PressureVessel, PressureVesselConstraints};
+use hw3::rate_control::{RateControl, ScheduleClock};
+#[cfg(feature = "gpu")]
+use hw3::gpu::{GpuFitness, GpuKernel, Kernel};
+use hw3::remote::RemoteFitness;
+use hw3::report::{Report, TraceView};
+use hw3::repair::{BoundHandling, BoundRepair, CapacityRepair, NoRepair};
+use hw3::seeds::SeedSequence;
+use hw3::self_adaptive::{RateLog, SelfAdaptive};
+use hw3::selection::{FitnessScaling, SelectionMethod};
+use hw3::speciation::Speciation;
+use hw3::stopping::{RestartPolicy, StopReason, StoppingCriteria};
+use hw3::surrogate::{SurrogateModel, SurrogateParameters};
+use hw3::target_sum::TargetSum;
+use hw3::tsp::Tsp;
+use hw3::variable_genome::VariableGenome;
+use hw3::warm_start::SavedPopulation;
+use hw3::work_queue::WorkQueue;
+use hw3::xor::{XOR_CASES, Xor};
+use hw3::timeout_runner::TimeoutRunner;
+use hw3::results_analyzer::{ESAnalysis, ResultsAnalyzer, ResultsWriter, SGAAnalysis};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use clap::Parser;
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    
-    if args.len() > 1 && args[1] == "tune" {
-        run_parameter_tuning();
+// Commands return the error that stopped them, or that lost the output they were run for,
+// and exit with a failure status on it; trouble with extra outputs such as plots is reported
+// along the way without failing the command
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        None => run(&cli.run),
+        Some(Command::Run(args)) => run(&args),
+        Some(Command::Tune(args)) => run_tuning(&args),
+        Some(Command::TuneWorker(args)) => run_tune_worker(&args),
+        Some(Command::Merge(args)) => run_merge(&args),
+        Some(Command::Analyze(args)) => run_analysis(&args),
+        Some(Command::Plot(args)) if args.qd => plot_qd_traces(&args.output, &args.traces),
+        Some(Command::Plot(args)) => plot_traces(&args.output, &args.traces),
+        Some(Command::Curves(args)) => run_curves(&args),
+        Some(Command::Ecdf(args)) => run_ecdf(&args),
+        Some(Command::Coco(args)) => run_coco_export(&args),
+        Some(Command::Validate(args)) => run_validation(&args),
+        Some(Command::Report(args)) => {
+            let views: Vec<TraceView> = args
+                .view
+                .iter()
+                .map(|view| match view {
+                    AnalysisView::FixedBudget => TraceView::FixedBudget(args.budget),
+                    AnalysisView::FixedTarget => TraceView::FixedTarget(args.target),
+                })
+                .collect();
+            Report::write(&args.results, &args.output, args.traces.as_deref(), &views)
+                .map(|()| println!("Report saved to {}", args.output))
+                .map_err(|e| format!("Error writing report: {e}"))
+        }
+        Some(Command::Demo(args)) => run_demo(&args),
+        Some(Command::Worker(args)) => run_worker(&args),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_demo(args: &DemoArgs) -> Result<(), String> {
+    match args.name {
+        Demo::Constrained => run_constrained(),
+        Demo::Bench => benchmark::run_genome_benchmark(),
+        Demo::Genomes => run_genomes(),
+        Demo::Control => run_rate_control(),
+        Demo::Gray => run_gray_comparison(),
+        Demo::Selection => run_selection_comparison(),
+        Demo::Niching => run_niching(),
+        Demo::Diversity => run_diversity_restoration(),
+        Demo::Islands => run_islands(),
+        Demo::Compare => run_problem_comparison(),
+        Demo::Repair => run_repair(),
+        Demo::Noisy => return run_noisy(args.noise, args.noise_std),
+        Demo::Observers => run_observers(),
+        Demo::Incremental => run_incremental(),
+        Demo::Ffi => run_ffi(),
+        Demo::SelfAdaptive => run_self_adaptive(),
+        Demo::HyperHeuristic => run_hyper_heuristic(),
+        Demo::Crossover => run_crossover_comparison(),
+        Demo::MixedInteger => run_mixed_integer(),
+        Demo::MultiObjective => run_multi_objective(),
+        Demo::Neat => run_neat(),
+        Demo::Mlp => return run_mlp(&args.dataset),
+        Demo::Cgp => run_cgp(),
+    }
+    Ok(())
+}
+
+// Serves the chosen problem's fitness to remote evaluators until the listener fails
+fn run_worker(args: &WorkerArgs) -> Result<(), String> {
+    let delay = Duration::from_millis(args.delay_ms);
+    let served = match args.problem {
+        Problem::Rosenbrock => remote::serve(&args.listen, &Rosenbrock, delay),
+        Problem::Himmelblau => remote::serve(&args.listen, &Himmelblau, delay),
+        Problem::MaxOnes => remote::serve(&args.listen, &MaxOnes, delay),
+    };
+    served.map_err(|e| format!("Error running worker on {}: {e}", args.listen))
+}
+
+// Runs the selected algorithms on the selected problem, then plots their traces if asked
+fn run(args: &RunArgs) -> Result<(), String> {
+    args.check_ranges().map_err(|e| format!("Error in {e}"))?;
+    // Traces go next to the plot unless --trace says otherwise
+    let trace_stem = args.trace.clone().or_else(|| {
+        let plot = args.plot.as_ref()?;
+        Some(Path::new(plot).with_extension("").display().to_string())
+    });
+    match args.problem {
+        Problem::Rosenbrock => run_decoded(Rosenbrock, args, trace_stem.as_deref())?,
+        Problem::Himmelblau => run_decoded(Himmelblau, args, trace_stem.as_deref())?,
+        // Every bit is a dimension of its own, so there is nothing to scale
+        Problem::MaxOnes if args.decode_bounds.is_some() => {
+            return Err("Error in --decode-bounds: maxones decodes each bit to 0 or 1".to_string());
+        }
+        Problem::MaxOnes => run_maybe_remote(MaxOnes, args, trace_stem.as_deref())?,
+    }
+    if let (Some(plot), Some(stem)) = (&args.plot, trace_stem) {
+        let algorithms = [
+            ("ES", AlgorithmKind::Es, "es"),
+            ("GA", AlgorithmKind::Ga, "ga"),
+            ("(1+1)-ES", AlgorithmKind::OnePlusOne, "es11"),
+            ("OpenAI-ES", AlgorithmKind::Nes, "nes"),
+            ("MAP-Elites", AlgorithmKind::MapElites, "me"),
+        ];
+        // Both means the GA and the ES, without the baseline or NES
+        let both = |kind| matches!(kind, AlgorithmKind::Es | AlgorithmKind::Ga)
+            && args.algorithm == AlgorithmKind::Both;
+        let traces: Vec<(&str, String)> = algorithms
+            .into_iter()
+            .filter(|&(_, kind, _)| args.algorithm == kind || both(kind))
+            .map(|(label, _, suffix)| (label, format!("{stem}_{suffix}.csv")))
+            .collect();
+        plot_traces_by_algorithm(plot, &traces);
+    }
+    Ok(())
+}
+
+// Plots the given traces as runs of one configuration
+fn plot_traces(output: &str, paths: &[String]) -> Result<(), String> {
+    let runs: Result<Vec<_>, _> = paths.iter().map(|path| curves::read_trace(path)).collect();
+    let result = runs.and_then(|runs| {
+        let title = format!("Convergence over {} runs", runs.len());
+        plots::plot_convergence(output, &title, &[("Runs".to_string(), runs)])
+    });
+    result.map_err(|e| format!("Error plotting traces: {e}"))?;
+    println!("Plot saved to {output}");
+    Ok(())
+}
+
+// Plots the QD metrics of the given QD traces as runs of one configuration
+fn plot_qd_traces(output: &str, paths: &[String]) -> Result<(), String> {
+    let runs: Result<Vec<_>, _> = paths.iter().map(|path| curves::read_qd_trace(path)).collect();
+    let result = runs.and_then(|runs| {
+        let title = format!("MAP-Elites archives over {} runs", runs.len());
+        plots::plot_qd_metrics(output, &title, &[("Runs".to_string(), runs)])
+    });
+    result.map_err(|e| format!("Error plotting QD traces: {e}"))?;
+    println!("Plot saved to {output}");
+    Ok(())
+}
+
+// Aggregates the traces in a directory into best-so-far curves per setting
+fn run_curves(args: &CurvesArgs) -> Result<(), String> {
+    let settings = curves::read_trace_dir(&args.dir)
+        .map_err(|e| format!("Error reading traces in {}: {e}", args.dir))?;
+    let mut aggregated: Vec<(String, Vec<CurvePoint>)> = settings
+        .iter()
+        .map(|(setting, runs)| (setting.clone(), curves::aggregate(runs, args.points)))
+        .collect();
+    let runs: usize = settings.iter().map(|(_, runs)| runs.len()).sum();
+    println!("Aggregated {runs} runs of {} settings", settings.len());
+    curves::save_curves_to_csv(&aggregated, &args.output)
+        .map_err(|e| format!("Error saving curves: {e}"))?;
+    println!("Curves saved to {}", args.output);
+
+    if let Some(plot) = &args.plot {
+        curves::top_by_final_median(&mut aggregated, args.top);
+        let title = format!("Best-so-far fitness, top {} settings", aggregated.len());
+        match plots::plot_quantile_curves(plot, &title, &aggregated) {
+            Ok(()) => println!("Plot saved to {plot}"),
+            Err(e) => eprintln!("Error plotting curves: {e}"),
+        }
+    }
+    Ok(())
+}
+
+fn run_ecdf(args: &EcdfArgs) -> Result<(), String> {
+    let mut settings = curves::read_trace_dir(&args.dir)
+        .map_err(|e| format!("Error reading traces in {}: {e}", args.dir))?;
+    if args.by_algorithm {
+        // "sga_3" and "sga_7" both pool into "SGA"
+        let mut pooled: Vec<(String, curves::Runs)> = Vec::new();
+        for (setting, runs) in settings {
+            let algorithm = setting.split('_').next().unwrap_or(&setting).to_uppercase();
+            match pooled.iter_mut().find(|(name, _)| *name == algorithm) {
+                Some((_, pooled_runs)) => pooled_runs.extend(runs),
+                None => pooled.push((algorithm, runs)),
+            }
+        }
+        settings = pooled;
+    }
+
+    let all_runs = || settings.iter().flat_map(|(_, runs)| runs);
+    let targets = if args.targets.is_empty() {
+        ecdf::default_targets(all_runs(), args.num_targets)
     } else {
-        run_default();
+        args.targets.clone()
+    };
+    let max_evaluations =
+        all_runs().filter_map(|run| run.last()).map(|row| row.evaluations).max().unwrap_or(0);
+    let formatted: Vec<String> = targets.iter().map(|t| format!("{t:.4}")).collect();
+    println!(
+        "{} runs of {} settings, targets {}",
+        all_runs().count(),
+        settings.len(),
+        formatted.join(", ")
+    );
+
+    let mut ecdfs: Vec<(String, Vec<EcdfPoint>)> = settings
+        .iter()
+        .map(|(setting, runs)| {
+            let ecdf = ecdf::ecdf(runs, &targets, max_evaluations, args.points);
+            (setting.clone(), ecdf)
+        })
+        .collect();
+    ecdf::save_ecdf_to_csv(&ecdfs, &args.output).map_err(|e| format!("Error saving ECDFs: {e}"))?;
+    println!("ECDFs saved to {}", args.output);
+
+    ecdfs.sort_by(|a, b| ecdf::area(&b.1).total_cmp(&ecdf::area(&a.1)));
+    for (setting, ecdf) in ecdfs.iter().take(args.top) {
+        let solved = ecdf.last().map_or(0.0, |point| point.fraction);
+        println!(
+            "  {setting}: {:.1}% of (run, target) pairs solved, area under the ECDF {:.3}",
+            solved * 100.0,
+            ecdf::area(ecdf)
+        );
+    }
+    if let Some(plot) = &args.plot {
+        ecdfs.truncate(args.top);
+        let title = format!("Runtime ECDF over {} targets", targets.len());
+        match plots::plot_ecdf(plot, &title, &ecdfs) {
+            Ok(()) => println!("Plot saved to {plot}"),
+            Err(e) => eprintln!("Error plotting ECDFs: {e}"),
+        }
     }
+    Ok(())
 }
 
-fn run_default() {
-    const NUM_DIMS: usize = 10;
-    // Seed the random number generator for reproducibility
-    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+// Plots one trace per algorithm side by side
+fn plot_traces_by_algorithm(output: &str, traces: &[(&str, String)]) {
+    let result = traces
+        .iter()
+        .map(|(label, path)| curves::read_trace(path).map(|run| (label.to_string(), vec![run])))
+        .collect::<Result<Vec<_>, _>>()
+        .and_then(|groups| plots::plot_convergence(output, "Convergence", &groups));
+    match result {
+        Ok(()) => println!("Plot saved to {output}"),
+        Err(e) => eprintln!("Error plotting traces: {e}"),
+    }
+}
 
-    // Run ES for comparison
-    let es_params = ESParameters {
-        mu: 100,                                   // population size (mu)
-        lambda: 100,                               // offspring size (lambda)
-        mem_size: NUM_DIMS,                        // number of dimensions
-        mem_range: (-5.12, 5.11),                  // range for initial population
-        sigma: 1.0,                                // initial step size (sigma)
-        tau: 1.0 / (2.0 * NUM_DIMS as f64).sqrt(), // learning rate (tau)
-        max_gens: 1000,                            // max generations
-    };
-    let final_es_pop = evolution_strategy(&Rosenbrock, &es_params, &mut rng);
-
-    // Print ES results
-    println!("\n=== ES Results ===");
-    for member in final_es_pop.iter().take(3) {
-        let fitness = Rosenbrock.fitness(&member[0..NUM_DIMS]);
-        let x = &member[0..NUM_DIMS];
-        print!("ES Member: [");
-        for val in x.iter().take(3) {
-            print!("{val:.4}, ");
+// Offspring screening for the ES, with the model's other settings at their defaults
+fn surrogate_parameters(args: &RunArgs) -> Option<SurrogateParameters> {
+    let model = match args.surrogate? {
+        SurrogateKind::Kriging => SurrogateParameters::default().model,
+        SurrogateKind::Rbf => SurrogateModel::Rbf { width: 0.2 },
+    };
+    Some(SurrogateParameters {
+        model,
+        evaluated_fraction: args.surrogate_fraction,
+        ..SurrogateParameters::default()
+    })
+}
+
+fn bound_handling(args: &RunArgs) -> BoundHandling {
+    match args.bounds {
+        None => BoundHandling::Ignore,
+        Some(BoundsKind::Clamp) => BoundHandling::Clamp,
+        Some(BoundsKind::Reflect) => BoundHandling::Reflect,
+        Some(BoundsKind::Wrap) => BoundHandling::Wrap,
+        Some(BoundsKind::Resample) => BoundHandling::Resample,
+    }
+}
+
+fn recombination(args: &RunArgs) -> Option<RealCrossover> {
+    let alpha = args.alpha;
+    Some(match args.recombination? {
+        RecombinationKind::SinglePoint => RealCrossover::SinglePoint,
+        RecombinationKind::Blend => RealCrossover::Blend { alpha },
+        RecombinationKind::WholeArithmetic => RealCrossover::WholeArithmetic { alpha },
+        RecombinationKind::PartialArithmetic => RealCrossover::PartialArithmetic { alpha },
+    })
+}
+
+// Clearing in phenotype space, which for the ES is its genes
+fn clearing(args: &RunArgs) -> Option<Clearing> {
+    Some(Clearing {
+        radius: args.clearing?,
+        capacity: args.niche_capacity,
+        distance: DistanceMetric::Phenotypic,
+    })
+}
+
+// Every metric for the status lines, diversity sampled and operators timed if asked for
+fn stats_config(args: &RunArgs) -> StatsConfig {
+    StatsConfig {
+        diversity_pairs: args.diversity_pairs,
+        timing: args.profile,
+        ..StatsConfig::full()
+    }
+}
+
+fn speciation(args: &RunArgs) -> Option<Speciation> {
+    Some(Speciation { threshold: args.speciation?, stagnation_limit: args.species_stagnation })
+}
+
+// Decodes GA bitstrings with --bits-per-dim bits per dimension into the problem's bounds, or
+// into --decode-bounds when it is given
+fn run_decoded(
+    fitness_fn: impl Fitness + Sync,
+    args: &RunArgs,
+    trace_stem: Option<&str>,
+) -> Result<(), String> {
+    let bounds = match args.decode_bounds.as_deref() {
+        None => fitness_fn.bounds(),
+        Some(&[low, high]) => (low, high),
+        Some(_) => return Err("Error in --decode-bounds: expected LOW HIGH".to_string()),
+    };
+    let decoder = BinaryDecoder::new(bounds, args.bits_per_dim, Encoding::Binary)
+        .map_err(|e| format!("Error in GA decoding: {e}"))?;
+    if matches!(args.algorithm, AlgorithmKind::Ga | AlgorithmKind::Both) {
+        let (low, high) = decoder.bounds;
+        println!(
+            "Decoding {} bits per dimension into [{low}, {high}], precision {:.3e}",
+            decoder.bits_per_dim,
+            decoder.precision()
+        );
+    }
+    run_maybe_remote(Decoded { objective: fitness_fn, decoder }, args, trace_stem)
+}
+
+// Workers only see phenotypes the cache missed, since the cache wraps the remote evaluation
+fn run_maybe_remote(
+    fitness_fn: impl Fitness + Sync,
+    args: &RunArgs,
+    trace_stem: Option<&str>,
+) -> Result<(), String> {
+    #[cfg(feature = "gpu")]
+    if args.gpu {
+        return run_on_gpu(fitness_fn, args, trace_stem);
+    }
+    if args.remote.is_empty() {
+        run_maybe_cached(fitness_fn, args, trace_stem)
+    } else {
+        let remote = RemoteFitness::new(fitness_fn, &args.remote)
+            .with_chunk_size(args.chunk_size)
+            .with_timeout(Duration::from_secs(args.remote_timeout));
+        run_maybe_cached(remote, args, trace_stem)
+    }
+}
+
+// The GPU takes the place of the workers, and of the CPU unless no GPU is available
+#[cfg(feature = "gpu")]
+fn run_on_gpu(
+    fitness_fn: impl Fitness + Sync,
+    args: &RunArgs,
+    trace_stem: Option<&str>,
+) -> Result<(), String> {
+    let kernel = match args.problem {
+        Problem::Rosenbrock => Kernel::Rosenbrock,
+        Problem::Himmelblau => Kernel::Himmelblau,
+        Problem::MaxOnes => Kernel::MaxOnes,
+    };
+    match GpuKernel::new(kernel) {
+        Ok(kernel) => {
+            println!("Evaluating batches on {}", kernel.adapter());
+            run_maybe_cached(GpuFitness::new(fitness_fn, kernel), args, trace_stem)
+        }
+        Err(e) => {
+            eprintln!("Error starting GPU evaluation: {e}, evaluating on the CPU");
+            run_maybe_cached(fitness_fn, args, trace_stem)
         }
-        println!("...] Fitness: {fitness}");
     }
+}
 
-    // Reset RNG for GA
-    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+fn run_maybe_cached(
+    fitness_fn: impl Fitness + Sync,
+    args: &RunArgs,
+    trace_stem: Option<&str>,
+) -> Result<(), String> {
+    if args.cache {
+        run_maybe_parallel(CachedFitness::new(fitness_fn), args, trace_stem)
+    } else {
+        run_maybe_parallel(fitness_fn, args, trace_stem)
+    }
+}
+
+// The GA and ES split their batches across --workers threads; NES splits its own
+fn run_maybe_parallel(
+    fitness_fn: impl Fitness + Sync,
+    args: &RunArgs,
+    trace_stem: Option<&str>,
+) -> Result<(), String> {
+    match args.workers.filter(|&workers| workers > 1 && args.algorithm != AlgorithmKind::Nes) {
+        Some(workers) => run_problem(&ParallelFitness::new(fitness_fn, workers), args, trace_stem),
+        None => run_problem(&fitness_fn, args, trace_stem),
+    }
+}
+
+fn run_problem(
+    fitness_fn: &(impl Fitness + Sync),
+    args: &RunArgs,
+    trace_stem: Option<&str>,
+) -> Result<(), String> {
+    let num_dims = args.dims();
+    if args.algorithm == AlgorithmKind::OnePlusOne {
+        run_one_plus_one(fitness_fn, args, trace_stem);
+        return Ok(());
+    }
+    if args.algorithm == AlgorithmKind::Nes {
+        run_openai_es(fitness_fn, args, trace_stem);
+        return Ok(());
+    }
+    if args.algorithm == AlgorithmKind::MapElites {
+        return run_map_elites(fitness_fn, args, trace_stem);
+    }
+    let run_es = matches!(args.algorithm, AlgorithmKind::Es | AlgorithmKind::Both);
+    let run_ga = matches!(args.algorithm, AlgorithmKind::Ga | AlgorithmKind::Both);
+    let seeds = match &args.seed_population {
+        Some(path) => SavedPopulation::load(path)
+            .map_err(|e| format!("Error loading seed population {path}: {e}"))?,
+        None => SavedPopulation::default(),
+    };
+    let mut saved = SavedPopulation::default();
+
+    if run_es {
+        // Seed the random number generator for reproducibility
+        let mut rng = ChaCha8Rng::seed_from_u64(args.seed);
+        // Rosenbrock starts from the classic De Jong range, other problems anywhere in bounds
+        let mem_range = match args.problem {
+            Problem::Rosenbrock => (-5.12, 5.11),
+            _ => fitness_fn.bounds(),
+        };
+        let es_params = ESParameters {
+            mu: args.pop_size,                         // population size (mu)
+            lambda: args.pop_size,                     // offspring size (lambda)
+            mem_size: num_dims,                        // number of dimensions
+            mem_range,                                 // range for initial population
+            sigma: args.sigma,                         // initial step size (sigma)
+            tau: 1.0 / (2.0 * num_dims as f64).sqrt(), // learning rate (tau)
+            sigma_control: None,                       // self-adaptive sigma
+            mutation: if args.correlated {
+                ESMutation::Correlated // sigmas per gene and rotation angles
+            } else if args.csa {
+                ESMutation::Cumulative // one sigma adapted by an evolution path
+            } else {
+                ESMutation::Isotropic // one sigma per member
+            },
+            gene_types: Vec::new(),                    // all genes continuous
+            bound_handling: bound_handling(args),      // genes may leave mem_range unless asked
+            recombination: recombination(args),        // mutation only unless asked for
+            clearing: clearing(args),                  // no niching unless asked for
+            max_gens: args.budget,                     // max generations
+            convergence_threshold: 0.99,               // average fitness to stop at
+            resamples: 1,                              // evaluations averaged per fitness
+            stopping: StoppingCriteria {
+                stagnation_generations: Some(200), // stop after 200 gens without improvement
+                target_epsilon: Some(1e-6),        // stop when within 1e-6 of the optimum
+            },
+            surrogate: surrogate_parameters(args), // offspring pre-screening, if asked for
+            restarts: args.restarts.map(|max_restarts| RestartPolicy {
+                max_restarts,                           // IPOP restarts, if asked for
+                population_factor: args.restart_factor, // growth of mu and lambda per restart
+            }),
+            stats: stats_config(args),             // status lines
+            log_path: args.log.clone(),            // JSON-lines run log
+            seed: Some(args.seed),                 // recorded in the run log
+            trace_path: trace_stem.map(|stem| format!("{stem}_es.csv")), // CSV trace
+        };
+        let final_es_pop = if args.ioh.is_some() || args.coco.is_some() {
+            let tracked = IohTracked::new(fitness_fn);
+            let population =
+                seeded_es(&tracked, &es_params, &seeds.es, args.seed_fraction, &mut rng);
+            let info = format!("mu {}, lambda {}, sigma {}", es_params.mu, es_params.lambda,
+                es_params.sigma);
+            save_tracked_run("ES", &info, &tracked, args);
+            population
+        } else {
+            seeded_es(fitness_fn, &es_params, &seeds.es, args.seed_fraction, &mut rng)
+        };
+        if args.save_population.is_some() {
+            saved.es = final_es_pop.clone();
+            let fitness = |member: &Vec<f64>| fitness_fn.fitness(&member[..num_dims]);
+            saved.es.sort_by(|a, b| fitness(b).total_cmp(&fitness(a)));
+        }
+
+        // Print ES results
+        println!("\n=== ES Results ===");
+        for member in final_es_pop.iter().take(3) {
+            let fitness = fitness_fn.fitness(&member[0..num_dims]);
+            let x = &member[0..num_dims];
+            print!("ES Member: [");
+            for val in x.iter().take(3) {
+                print!("{val:.4}, ");
+            }
+            println!("...] Fitness: {fitness}");
+        }
+        let genes: Vec<Vec<f64>> =
+            final_es_pop.iter().map(|member| member[0..num_dims].to_vec()).collect();
+        report_optima("ES", fitness_fn, &genes, args);
+    }
+
+    if !run_ga {
+        return save_final_population(&saved, args);
+    }
+    let mut rng = ChaCha8Rng::seed_from_u64(args.seed);
     let ga_params = GAParameters {
-        pop_size: 100,               //population size (mu = lambda)
-        mem_size: 16 * NUM_DIMS,     // member size (in bits)
-        mutation_rate: 0.01,         // mutation rate
-        crossover_rate: 0.75,        // crossover rate
-        max_iters: 1000,             // max evaluations
-        convergence_threshold: 0.95, // convergence threshold
+        pop_size: args.pop_size,                  //population size (mu = lambda)
+        mem_size: args.bits_per_dim * num_dims,   // member size (in bits)
+        num_dims,                                 // number of decoded dimensions
+        mutation_rate: args.mutation_rate,        // mutation rate
+        crossover_rate: args.crossover_rate,      // crossover rate
+        max_iters: args.budget,                   // max generations
+        convergence_threshold: 0.95,              // convergence threshold
+        resamples: 1,                             // evaluations averaged per fitness
+        stopping: StoppingCriteria {
+            stagnation_generations: Some(200),
+            target_epsilon: Some(1e-6),
+        },
+        stats: stats_config(args),
+        clearing: clearing(args),
+        speciation: speciation(args),
+        log_path: args.log.clone(),
+        seed: Some(args.seed),
+        trace_path: trace_stem.map(|stem| format!("{stem}_ga.csv")),
+        ..GAParameters::default()
+    };
+    let final_ea_pop = if args.ioh.is_some() || args.coco.is_some() {
+        let tracked = IohTracked::new(fitness_fn);
+        let population = seeded_ga(&tracked, &ga_params, &seeds.ga, args.seed_fraction, &mut rng);
+        let info = format!("pop {}, mutation {}, crossover {}", ga_params.pop_size,
+            ga_params.mutation_rate, ga_params.crossover_rate);
+        save_tracked_run("GA", &info, &tracked, args);
+        population
+    } else {
+        seeded_ga(fitness_fn, &ga_params, &seeds.ga, args.seed_fraction, &mut rng)
     };
-    let final_ea_pop = sga(&Rosenbrock, &ga_params, &mut rng);
+    if args.save_population.is_some() {
+        saved.ga = final_ea_pop.clone();
+        let fitness = |member: &String| fitness_fn.fitness_bitstring(member, num_dims);
+        saved.ga.sort_by(|a, b| fitness(b).total_cmp(&fitness(a)));
+        save_final_population(&saved, args)?;
+    }
 
     // Print final populations and their fitnesses
+    let decoded: Vec<Vec<f64>> =
+        final_ea_pop.iter().map(|member| fitness_fn.decode_bitstring(member, num_dims)).collect();
     for member in final_ea_pop {
-        let fitness = Rosenbrock.fitness_bitstring(&member, NUM_DIMS);
-        let x = Rosenbrock.decode_bitstring(&member, NUM_DIMS);
+        let fitness = fitness_fn.fitness_bitstring(&member, num_dims);
+        let x = fitness_fn.decode_bitstring(&member, num_dims);
         print!("GA Member: [");
         for val in x {
             print!("{val:.4}, ");
         }
         println!("] Fitness: {fitness}");
     }
+    report_optima("GA", fitness_fn, &decoded, args);
 
     // Test a few random individuals to see typical values
     println!("\nTesting random individuals:");
     for _ in 0..5 {
         let mut random_bits = String::new();
-        for _ in 0..(20 * NUM_DIMS) {
+        for _ in 0..(args.bits_per_dim * num_dims) {
             random_bits.push(if rng.random() { '1' } else { '0' });
         }
-        let x = Rosenbrock.decode_bitstring(&random_bits, NUM_DIMS);
-        let fitness = Rosenbrock.fitness_bitstring(&random_bits, NUM_DIMS);
+        let x = fitness_fn.decode_bitstring(&random_bits, num_dims);
+        let fitness = fitness_fn.fitness_bitstring(&random_bits, num_dims);
         print!("Random: [");
         for val in x.iter().take(3) {
             print!("{val:.4}, ");
         }
         println!("...] Fitness: {fitness:e}");
     }
+    Ok(())
+}
+
+// How many distinct optima the final members cluster on and, if the problem declares its
+// known optima, which of them they sit on
+fn report_optima(label: &str, fitness_fn: &impl Objective, members: &[Vec<f64>], args: &RunArgs) {
+    let fitnesses = fitness_fn.evaluate_population(members);
+    let clusters = distinct_optima(members, &fitnesses, args.cluster_radius);
+    println!("{label} distinct optima within {}: {clusters}", args.cluster_radius);
+    let optima = fitness_fn.known_optima(args.dims());
+    if optima.is_empty() {
+        return;
+    }
+    let basins = BasinCounts::classify(members, &optima, args.optimum_tolerance);
+    println!("{label} basins within {}: {basins}", args.optimum_tolerance);
+}
+
+// The (1+1)-ES baseline of `run`, on the same evaluation budget as the ES, reported once
+// per ES generation's worth of evaluations
+fn run_one_plus_one(fitness_fn: &impl Fitness, args: &RunArgs, trace_stem: Option<&str>) {
+    let num_dims = args.dims();
+    let mut rng = ChaCha8Rng::seed_from_u64(args.seed);
+    let params = OnePlusOneParameters {
+        mem_size: num_dims,
+        mem_range: match args.problem {
+            Problem::Rosenbrock => (-5.12, 5.11),
+            _ => fitness_fn.bounds(),
+        },
+        sigma: args.sigma,
+        window: num_dims,
+        bound_handling: bound_handling(args),
+        max_iterations: args.budget * args.pop_size,
+        stopping: StoppingCriteria { stagnation_generations: None, target_epsilon: Some(1e-6) },
+        report_every: args.pop_size,
+        log_path: args.log.clone(),
+        seed: Some(args.seed),
+        trace_path: trace_stem.map(|stem| format!("{stem}_es11.csv")),
+        ..OnePlusOneParameters::default()
+    };
+    let info = format!("sigma {}, factor {}, window {}", params.sigma, params.factor,
+        params.window);
+    let final_member = if args.ioh.is_some() || args.coco.is_some() {
+        let tracked = IohTracked::new(fitness_fn);
+        let final_member = OnePlusOneES::new(&tracked, &params, &mut rng).run(&mut rng);
+        save_tracked_run("ES11", &info, &tracked, args);
+        final_member
+    } else {
+        OnePlusOneES::new(fitness_fn, &params, &mut rng).run(&mut rng)
+    };
+    for member in &final_member {
+        let fitness = fitness_fn.fitness(&member[0..num_dims]);
+        let genes: Vec<String> = member[0..num_dims].iter().map(|x| format!("{x:.4}")).collect();
+        println!("(1+1)-ES Member: [{}] Fitness: {fitness}", genes.join(", "));
+    }
+}
+
+// OpenAI-ES in `run`, with a generation's worth of perturbations in place of the ES's lambda
+fn run_openai_es(fitness_fn: &(impl Fitness + Sync), args: &RunArgs, trace_stem: Option<&str>) {
+    let num_dims = args.dims();
+    let mut rng = ChaCha8Rng::seed_from_u64(args.seed);
+    let defaults = OpenAiESParameters::default();
+    let params = OpenAiESParameters {
+        pop_size: args.pop_size,
+        mem_size: num_dims,
+        mem_range: match args.problem {
+            Problem::Rosenbrock => (-5.12, 5.11),
+            _ => fitness_fn.bounds(),
+        },
+        sigma: args.sigma,
+        learning_rate: args.learning_rate,
+        workers: args.workers.unwrap_or(defaults.workers),
+        max_gens: args.budget,
+        stopping: StoppingCriteria { stagnation_generations: None, target_epsilon: Some(1e-6) },
+        log_path: args.log.clone(),
+        seed: Some(args.seed),
+        trace_path: trace_stem.map(|stem| format!("{stem}_nes.csv")),
+    };
+    let info = format!("pop {}, sigma {}, learning rate {}", params.pop_size, params.sigma,
+        params.learning_rate);
+    let final_members = if args.ioh.is_some() || args.coco.is_some() {
+        let tracked = IohTracked::new(fitness_fn);
+        let final_members = OpenAiES::new(&tracked, &params, &mut rng).run(&mut rng);
+        save_tracked_run("NES", &info, &tracked, args);
+        final_members
+    } else {
+        OpenAiES::new(fitness_fn, &params, &mut rng).run(&mut rng)
+    };
+    for (label, member) in ["Best", "Mean"].iter().zip(&final_members) {
+        let fitness = fitness_fn.fitness(&member[0..num_dims]);
+        let genes: Vec<String> = member[0..num_dims].iter().map(|x| format!("{x:.4}")).collect();
+        println!("OpenAI-ES {label}: [{}] Fitness: {fitness}", genes.join(", "));
+    }
+}
+
+// MAP-Elites in `run` over the first two genes (the only one of a one-dimensional problem),
+// breeding a generation's worth of offspring per batch. It runs the whole budget, as it is
+// after good members all over the map rather than the optimum. The final archive goes next
+// to the traces: every elite to {stem}_me_archive.json, the best fitness per cell to
+// {stem}_me_archive.csv, and with --plot a heatmap of it and the QD metrics per generation.
+fn run_map_elites(
+    fitness_fn: &impl Fitness,
+    args: &RunArgs,
+    trace_stem: Option<&str>,
+) -> Result<(), String> {
+    let num_dims = args.dims();
+    let mut rng = ChaCha8Rng::seed_from_u64(args.seed);
+    let mem_range = match args.problem {
+        Problem::Rosenbrock => (-5.12, 5.11),
+        _ => fitness_fn.bounds(),
+    };
+    let params = MapElitesParameters {
+        mem_size: num_dims,
+        mem_range,
+        cells_per_axis: args.cells,
+        initial_samples: args.pop_size,
+        batch_size: args.pop_size,
+        sigma: args.sigma,
+        max_gens: args.budget,
+        stopping: StoppingCriteria::default(),
+        log_path: args.log.clone(),
+        seed: Some(args.seed),
+        trace_path: trace_stem.map(|stem| format!("{stem}_me.csv")),
+        qd_trace_path: trace_stem.map(|stem| format!("{stem}_me_qd.csv")),
+    };
+    let descriptor = GenePosition { genes: num_dims.min(2), range: mem_range };
+    let archive = if args.ioh.is_some() || args.coco.is_some() {
+        let tracked = IohTracked::new(fitness_fn);
+        let archive = map_elites_archive(&tracked, &descriptor, &params, &mut rng);
+        let info = format!("cells {}, batch {}, sigma {}", params.cells_per_axis,
+            params.batch_size, params.sigma);
+        save_tracked_run("ME", &info, &tracked, args);
+        archive
+    } else {
+        map_elites_archive(fitness_fn, &descriptor, &params, &mut rng)
+    };
+    if let Some(best) = archive.best() {
+        let genes: Vec<String> = best.member.iter().map(|x| format!("{x:.4}")).collect();
+        println!("MAP-Elites Best: [{}] Fitness: {}", genes.join(", "), best.fitness);
+    }
+
+    let Some(stem) = trace_stem else {
+        return Ok(());
+    };
+    let json_path = format!("{stem}_me_archive.json");
+    archive.save_json(&json_path).map_err(|e| format!("Error saving archive: {e}"))?;
+    println!("Archive saved to {json_path}");
+    if archive.axes.len() < 2 {
+        return Ok(());
+    }
+    let heatmap = archive.heatmap(1, 0);
+    let csv_path = format!("{stem}_me_archive.csv");
+    anova::save_heatmap(&heatmap, &csv_path)
+        .map_err(|e| format!("Error saving archive heatmap: {e}"))?;
+    println!("Archive heatmap saved to {csv_path}");
+    if args.plot.is_some() {
+        let plot_path = format!("{stem}_me_archive.png");
+        let title = format!("MAP-Elites best fitness per cell, {} x {}", heatmap.row,
+            heatmap.column);
+        match plots::plot_heatmap(&plot_path, &title, &heatmap) {
+            Ok(()) => println!("Archive heatmap plot saved to {plot_path}"),
+            Err(e) => eprintln!("Error plotting archive heatmap: {e}"),
+        }
+        let qd_path = format!("{stem}_me_qd.png");
+        let result = curves::read_qd_trace(&format!("{stem}_me_qd.csv")).and_then(|run| {
+            let runs = [("Run".to_string(), vec![run])];
+            plots::plot_qd_metrics(&qd_path, "MAP-Elites archive", &runs)
+        });
+        match result {
+            Ok(()) => println!("QD metrics plot saved to {qd_path}"),
+            Err(e) => eprintln!("Error plotting QD metrics: {e}"),
+        }
+    }
+    Ok(())
+}
+
+// Runs MAP-Elites to the end and hands back its final archive
+fn map_elites_archive(
+    fitness_fn: &impl Objective,
+    descriptor: &GenePosition,
+    params: &MapElitesParameters,
+    rng: &mut ChaCha8Rng,
+) -> Archive {
+    let mut map_elites = MapElites::new(fitness_fn, descriptor, params, rng);
+    let reason = loop {
+        if let Some(reason) = map_elites.step(rng) {
+            break reason;
+        }
+    };
+    let archive = map_elites.archive().clone();
+    map_elites.finish(reason);
+    archive
+}
+
+// The GA of `run`, with part of its initial population taken from `seeds`
+fn seeded_ga<R: Rng + ?Sized>(
+    fitness_fn: &impl Fitness,
+    params: &GAParameters,
+    seeds: &[String],
+    fraction: f64,
+    rng: &mut R,
+) -> Vec<String> {
+    GeneticAlgorithm::new(fitness_fn, params, &NoRepair, rng).with_seeds(seeds, fraction).run(rng)
+}
+
+// The ES of `run`, with part of its initial population taken from `seeds`
+fn seeded_es<R: Rng + ?Sized>(
+    fitness_fn: &impl Fitness,
+    params: &ESParameters,
+    seeds: &[Vec<f64>],
+    fraction: f64,
+    rng: &mut R,
+) -> Vec<Vec<f64>> {
+    EvolutionStrategy::new(fitness_fn, params, &NoRepair, rng).with_seeds(seeds, fraction).run(rng)
+}
+
+fn save_final_population(saved: &SavedPopulation, args: &RunArgs) -> Result<(), String> {
+    let Some(path) = &args.save_population else {
+        return Ok(());
+    };
+    saved.save(path).map_err(|e| format!("Error saving final populations to {path}: {e}"))?;
+    println!("Final populations saved to {path}");
+    Ok(())
+}
+
+// Function id and name of a problem in exported benchmark data
+fn benchmark_problem(problem: Problem, dimension: usize) -> IohProblem {
+    let (id, name) = match problem {
+        Problem::Rosenbrock => (1, "Rosenbrock"),
+        Problem::Himmelblau => (2, "Himmelblau"),
+        Problem::MaxOnes => (3, "MaxOnes"),
+    };
+    IohProblem { id, name: name.to_string(), dimension }
+}
+
+// Adds a finished run to the IOHprofiler and COCO data folders of its algorithm asked for
+fn save_tracked_run(
+    algorithm: &str,
+    info: &str,
+    tracked: &IohTracked<impl Fitness>,
+    args: &RunArgs,
+) {
+    let problem = benchmark_problem(args.problem, args.dims());
+    let run = tracked.run();
+    if let Some(dir) = &args.ioh {
+        let root = Path::new(dir).join(algorithm.to_lowercase());
+        let result = IohArchive::new(&root, algorithm, info)
+            .and_then(|archive| archive.add_run(&problem, &run));
+        match result {
+            Ok(()) => println!("IOHprofiler data saved to {}", root.display()),
+            Err(e) => eprintln!("Error writing IOHprofiler data to {}: {e}", root.display()),
+        }
+    }
+    if let Some(dir) = &args.coco {
+        let root = Path::new(dir).join(algorithm.to_lowercase());
+        let optimum = tracked.optimum_value().unwrap_or(1.0);
+        let result = CocoArchive::new(&root, algorithm, info)
+            .and_then(|archive| archive.add_run(&problem, optimum, &run));
+        match result {
+            Ok(()) => println!("COCO data saved to {}", root.display()),
+            Err(e) => eprintln!("Error writing COCO data to {}: {e}", root.display()),
+        }
+    }
+}
+
+// Exports the traces in a directory, e.g. a tuning session's, with a COCO data folder per
+// setting. Traces only hold the best of each generation, so the runs are logged at that
+// granularity.
+fn run_coco_export(args: &CocoArgs) -> Result<(), String> {
+    let settings = curves::read_trace_dir(&args.dir)
+        .map_err(|e| format!("Error reading traces in {}: {e}", args.dir))?;
+    let problem = benchmark_problem(args.problem, args.dims.unwrap_or(args.problem.default_dims()));
+    let optimum = match args.problem {
+        Problem::Rosenbrock => Rosenbrock.optimum_value(),
+        Problem::Himmelblau => Himmelblau.optimum_value(),
+        Problem::MaxOnes => MaxOnes.optimum_value(),
+    };
+    let optimum = optimum.unwrap_or(1.0);
+    let info = format!("traces from {}", args.dir);
+    for (setting, runs) in &settings {
+        let root = Path::new(&args.output).join(setting);
+        let result = CocoArchive::new(&root, setting, &info).and_then(|archive| {
+            runs.iter()
+                .try_for_each(|run| archive.add_run(&problem, optimum, &IohRun::from_trace(run)))
+        });
+        result.map_err(|e| format!("Error writing COCO data to {}: {e}", root.display()))?;
+    }
+    let runs: usize = settings.iter().map(|(_, runs)| runs.len()).sum();
+    println!("Exported {runs} runs of {} settings to {}", settings.len(), args.output);
+    Ok(())
+}
+
+// Runs both algorithms, and the (1+1)-ES baseline, on every problem through the shared
+// Fitness trait
+fn run_problem_comparison() {
+    let results = [
+        ("MaxOnes", run_on_problem(&MaxOnes, 32)),
+        ("Himmelblau", run_on_problem(&Himmelblau, 2)),
+        ("Rosenbrock", run_on_problem(&Rosenbrock, 10)),
+    ];
+
+    println!("\n=== Problem Comparison ===");
+    for (name, (best_ga, best_es, (best_baseline, improvements))) in results {
+        println!(
+            "{name}: GA best {best_ga} ES best {best_es} (1+1)-ES best {best_baseline} \
+             ({improvements} improvements)"
+        );
+    }
+}
+
+// Best final fitness of the bitstring GA with 16 bits per dimension
+fn ga_best_fitness(fitness_fn: &impl Fitness, num_dims: usize) -> f64 {
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let ga_params = GAParameters {
+        mem_size: 16 * num_dims,
+        num_dims,
+        ..GAParameters::default()
+    };
+    sga(fitness_fn, &ga_params, &mut rng)
+        .iter()
+        .map(|m| fitness_fn.fitness_bitstring(m, num_dims))
+        .fold(0.0, f64::max)
+}
+
+// Returns the best final fitness of the GA and of the ES, and of the (1+1)-ES with the number
+// of times it improved
+fn run_on_problem(fitness_fn: &impl Fitness, num_dims: usize) -> (f64, f64, (f64, usize)) {
+    let best_ga = ga_best_fitness(fitness_fn, num_dims);
+
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let es_params = ESParameters {
+        mem_size: num_dims,
+        mem_range: fitness_fn.bounds(),
+        tau: 1.0 / (2.0 * num_dims as f64).sqrt(),
+        ..ESParameters::default()
+    };
+    let best_es = evolution_strategy(fitness_fn, &es_params, &mut rng)
+        .iter()
+        .map(|m| fitness_fn.fitness(&m[0..num_dims]))
+        .fold(0.0, f64::max);
+
+    // The same evaluation budget as the ES, which evaluates 100 offspring a generation
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let baseline_params = OnePlusOneParameters {
+        mem_size: num_dims,
+        mem_range: fitness_fn.bounds(),
+        window: num_dims,
+        max_iterations: 100 * es_params.max_gens,
+        ..OnePlusOneParameters::default()
+    };
+    let mut improvements = ImprovementLog::default();
+    let best_baseline = OnePlusOneES::new(fitness_fn, &baseline_params, &mut rng)
+        .quiet()
+        .with_observer(&mut improvements)
+        .run(&mut rng)
+        .iter()
+        .map(|m| fitness_fn.fitness(&m[0..num_dims]))
+        .fold(0.0, f64::max);
+
+    (best_ga, best_es, (best_baseline, improvements.improvements.len()))
+}
+
+// Mutation rate (GA) and sigma (ES) controllers on 10-dimensional Rosenbrock
+fn run_rate_control() {
+    const NUM_DIMS: usize = 10;
+
+    let controls = [
+        RateControl::Constant,
+        RateControl::LinearDecay { final_value: 0.001 },
+        RateControl::ExponentialDecay { decay: 0.995 },
+        RateControl::Step { factor: 0.5, every: 250 },
+        RateControl::OneFifthRule { factor: 0.85, window: 5 },
+    ];
+
+    let mut results = Vec::new();
+    for control in controls {
+        let mut rng = ChaCha8Rng::seed_from_u64(5000);
+        let ga_params = GAParameters {
+            mem_size: 16 * NUM_DIMS,
+            num_dims: NUM_DIMS,
+            mutation_rate: 0.02,
+            mutation_control: control,
+            ..GAParameters::default()
+        };
+        let best_ga = sga(&Rosenbrock, &ga_params, &mut rng)
+            .iter()
+            .map(|m| Rosenbrock.fitness_bitstring(m, NUM_DIMS))
+            .fold(0.0, f64::max);
+
+        // Constant leaves the ES on self-adaptation for comparison
+        let mut rng = ChaCha8Rng::seed_from_u64(5000);
+        let es_params = ESParameters {
+            mu: 20,
+            lambda: 100,
+            mem_size: NUM_DIMS,
+            sigma_control: match control {
+                RateControl::Constant => None,
+                other => Some(other),
+            },
+            ..ESParameters::default()
+        };
+        let best_es = evolution_strategy(&Rosenbrock, &es_params, &mut rng)
+            .iter()
+            .map(|m| Rosenbrock.fitness(&m[0..NUM_DIMS]))
+            .fold(0.0, f64::max);
+        results.push((control, best_ga, best_es));
+    }
+
+    // Crossover rate schedules, and a mutation rate annealed over the evaluation budget
+    let base = GAParameters {
+        mem_size: 16 * NUM_DIMS,
+        num_dims: NUM_DIMS,
+        mutation_rate: 0.02,
+        ..GAParameters::default()
+    };
+    let schedules = [
+        (
+            "Crossover LinearDecay 0.9 -> 0.5",
+            GAParameters {
+                crossover_rate: 0.9,
+                crossover_control: RateControl::LinearDecay { final_value: 0.5 },
+                ..base.clone()
+            },
+        ),
+        (
+            "Crossover Step x0.8 every 200 generations",
+            GAParameters {
+                crossover_rate: 0.9,
+                crossover_control: RateControl::Step { factor: 0.8, every: 200 },
+                ..base.clone()
+            },
+        ),
+        (
+            "Mutation ExponentialDecay over evaluations",
+            GAParameters {
+                mutation_control: RateControl::ExponentialDecay { decay: 0.99998 },
+                schedule_clock: ScheduleClock::Evaluations,
+                ..base.clone()
+            },
+        ),
+    ];
+    let mut schedule_results = Vec::new();
+    for (name, params) in schedules {
+        let mut rng = ChaCha8Rng::seed_from_u64(5000);
+        let best = sga(&Rosenbrock, &params, &mut rng)
+            .iter()
+            .map(|m| Rosenbrock.fitness_bitstring(m, NUM_DIMS))
+            .fold(0.0, f64::max);
+        schedule_results.push((name, best));
+    }
+
+    println!("\n=== Rate Control ===");
+    for (control, best_ga, best_es) in results {
+        println!("{control:?}: GA best {best_ga} ES best {best_es}");
+    }
+    for (name, best) in schedule_results {
+        println!("{name}: GA best {best}");
+    }
+}
+
+// Plain binary versus Gray-coded decoding for the GA on the continuous benchmarks
+fn run_gray_comparison() {
+    // The Rosenbrock optimum survives a round trip through the Gray encoder
+    let gray = BinaryDecoder::new(Rosenbrock.bounds(), 16, Encoding::Gray).unwrap();
+    let encoded = gray.encode(&[1.0; 10]);
+    let decoded = Decoded::gray(Rosenbrock).decode_bitstring(&encoded, 10);
+    let round_trip_fitness = Rosenbrock.fitness(&decoded);
+
+    let results = [
+        (
+            "Himmelblau",
+            ga_best_fitness(&Himmelblau, 2),
+            ga_best_fitness(&Decoded::gray(Himmelblau), 2),
+        ),
+        (
+            "Rosenbrock",
+            ga_best_fitness(&Rosenbrock, 10),
+            ga_best_fitness(&Decoded::gray(Rosenbrock), 10),
+        ),
+    ];
+
+    println!("\n=== Binary vs Gray Decoding ===");
+    println!("Gray-encoded Rosenbrock optimum decodes to fitness {round_trip_fitness}");
+    for (name, binary, gray) in results {
+        println!("{name}: binary GA best {binary} Gray GA best {gray}");
+    }
 }
 
-fn run_parameter_tuning() {
+// Tournament, roulette, SUS, rank and truncation selection on Rosenbrock, where 1/(1+f)
+// squeezes the late-run fitnesses together and roulette loses its selection pressure unless
+// the fitnesses are scaled first.
+// Truncation keeps the best 20% as parents, matching the survivor ratio of a (20,100)-ES.
+fn run_selection_comparison() {
+    const NUM_DIMS: usize = 10;
+    const TRUNCATION_FRACTION: f64 = 0.2;
+
+    let unscaled = FitnessScaling::None;
+    let methods = [
+        (SelectionMethod::Tournament { size: 3 }, unscaled),
+        (SelectionMethod::Roulette, unscaled),
+        (SelectionMethod::Roulette, FitnessScaling::Linear { multiple: 2.0 }),
+        (SelectionMethod::Roulette, FitnessScaling::SigmaTruncation { c: 2.0 }),
+        (SelectionMethod::StochasticUniversal, unscaled),
+        (SelectionMethod::StochasticUniversal, FitnessScaling::SigmaTruncation { c: 2.0 }),
+        (SelectionMethod::LinearRank { pressure: 1.5 }, unscaled),
+        (SelectionMethod::LinearRank { pressure: 2.0 }, unscaled),
+        (SelectionMethod::ExponentialRank { base: 0.97 }, unscaled),
+        (SelectionMethod::Truncation, unscaled),
+    ];
+
+    let mut results = Vec::new();
+    for (selection, scaling) in methods {
+        let mut rng = ChaCha8Rng::seed_from_u64(5000);
+        let params = GAParameters {
+            mem_size: 16 * NUM_DIMS,
+            num_dims: NUM_DIMS,
+            selection,
+            truncation_fraction: TRUNCATION_FRACTION,
+            scaling,
+            ..GAParameters::default()
+        };
+        let best = sga(&Rosenbrock, &params, &mut rng)
+            .iter()
+            .map(|m| Rosenbrock.fitness_bitstring(m, NUM_DIMS))
+            .fold(0.0, f64::max);
+        results.push((selection, scaling, best));
+    }
+
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let es_params = ESParameters {
+        mu: (TRUNCATION_FRACTION * 100.0) as usize,
+        lambda: 100,
+        mem_size: NUM_DIMS,
+        mem_range: Rosenbrock.bounds(),
+        ..ESParameters::default()
+    };
+    let best_es = evolution_strategy(&Rosenbrock, &es_params, &mut rng)
+        .iter()
+        .map(|m| Rosenbrock.fitness(&m[..NUM_DIMS]))
+        .fold(0.0, f64::max);
+
+    println!("\n=== Selection Method Comparison ===");
+    for (selection, scaling, best) in results {
+        if scaling == unscaled {
+            println!("{selection:?}: GA best {best}");
+        } else {
+            println!("{selection:?} with {scaling:?} scaling: GA best {best}");
+        }
+    }
+    println!(
+        "({},{})-ES truncation survivor selection: ES best {best_es}",
+        es_params.mu, es_params.lambda
+    );
+}
+
+// MaxOnes fills with clones within a few generations; compare the GA with and without
+// hypermutation or random immigrants when stagnation or cloning is detected, and with
+// duplicate offspring rejected or re-mutated
+fn run_diversity_restoration() {
+    const NUM_BITS: usize = 64;
+
+    let triggers = |action| DiversityRestoration {
+        stagnation_generations: Some(20),
+        identical_threshold: Some(0.5),
+        action,
+    };
+    let allow = DuplicatePolicy::Allow;
+    let schemes = [
+        ("none", None, allow),
+        (
+            "hypermutation",
+            Some(triggers(RestorationAction::Hypermutation { factor: 4.0, generations: 2 })),
+            allow,
+        ),
+        (
+            "random immigrants",
+            Some(triggers(RestorationAction::RandomImmigrants { fraction: 0.2 })),
+            allow,
+        ),
+        ("reject duplicates", None, DuplicatePolicy::Reject),
+        ("re-mutate duplicates", None, DuplicatePolicy::Remutate { max_attempts: 5 }),
+    ];
+
+    let mut results = Vec::new();
+    for (name, diversity_restoration, duplicates) in schemes {
+        let mut rng = ChaCha8Rng::seed_from_u64(5000);
+        let params = GAParameters {
+            mem_size: NUM_BITS,
+            num_dims: NUM_BITS,
+            max_iters: 300,
+            convergence_threshold: 1.0,
+            diversity_restoration,
+            duplicates,
+            ..GAParameters::default()
+        };
+        let final_pop = sga(&MaxOnes, &params, &mut rng);
+        let best = final_pop
+            .iter()
+            .map(|m| MaxOnes.fitness_bitstring(m, NUM_BITS))
+            .fold(0.0, f64::max);
+        let distinct = final_pop.iter().collect::<HashSet<_>>().len();
+        results.push((name, best, distinct));
+    }
+
+    println!("\n=== Diversity Restoration on MaxOnes ===");
+    for (name, best, distinct) in results {
+        println!("{name}: GA best {best}, {distinct} distinct genomes in the final population");
+    }
+}
+
+// Threaded island GA on Rosenbrock, run twice with the same seeds to check that asynchronous
+// migration still gives identical results, then islands running different algorithms
+fn run_islands() {
+    const NUM_DIMS: usize = 10;
+
+    let params = GAParameters {
+        pop_size: 50,
+        mem_size: 16 * NUM_DIMS,
+        num_dims: NUM_DIMS,
+        max_iters: 300,
+        ..GAParameters::default()
+    };
+    let island_params = IslandParameters::default();
+
+    let first: Vec<Vec<String>> = island_ga(&Rosenbrock, &params, &island_params);
+    let second: Vec<Vec<String>> = island_ga(&Rosenbrock, &params, &island_params);
+
+    let best = first
+        .iter()
+        .flatten()
+        .map(|m| Rosenbrock.fitness_bitstring(m, NUM_DIMS))
+        .fold(0.0, f64::max);
+    println!("\n=== Island Model ===");
+    println!("{} islands, best fitness {best}", island_params.num_islands);
+    println!("Repeat run with the same seeds identical: {}", first == second);
+
+    // Two GA islands at different mutation rates next to two ES islands, one of them
+    // controlling sigma by CSA, exchanging migrants through the bitstring decoder
+    let es_params = ESParameters {
+        mu: 50,
+        lambda: 50,
+        mem_size: NUM_DIMS,
+        max_gens: 300,
+        convergence_threshold: 1.0, // never stop on the average, so every island runs 300
+        ..ESParameters::default()
+    };
+    let islands = [
+        IslandAlgorithm::Ga(params.clone()),
+        IslandAlgorithm::Ga(GAParameters { mutation_rate: 0.05, ..params.clone() }),
+        IslandAlgorithm::Es(es_params.clone()),
+        IslandAlgorithm::Es(ESParameters { mutation: ESMutation::Cumulative, ..es_params }),
+    ];
+    let finals = heterogeneous_islands::<String, _>(&Rosenbrock, &islands, &island_params);
+    println!("\n=== Heterogeneous Islands ===");
+    for (algorithm, population) in islands.iter().zip(&finals) {
+        let best = population.iter().map(|x| Rosenbrock.fitness(x)).fold(0.0, f64::max);
+        println!("{}: best fitness {best}", algorithm.describe());
+    }
+}
+
+// Himmelblau has four equal optima; without niching the GA settles on one of them. SUS keeps
+// the shared fitness proportional, where a tournament would still favour the biggest peak.
+// Crowding holds the optima early but drifts over long runs towards whichever optimum decodes
+// closest at 16 bits, since a child from another basin can still beat its nearest parent.
+fn run_niching() {
+    const NUM_DIMS: usize = 2;
+    const OPTIMUM_RADIUS: f64 = 1.0;
+
+    let generational = Replacement::Generational;
+    let clearing = Clearing { radius: 2.0, capacity: 5, distance: DistanceMetric::Phenotypic };
+    // A species on an optimum stops improving once it has converged, so culling stagnant
+    // species would leave only the best one; the limit is past the run's 50 generations
+    let speciation = Speciation { threshold: 12.0, stagnation_limit: 50 };
+    let schemes = [
+        ("no niching", None, None, None, generational),
+        (
+            "phenotypic sharing",
+            Some(FitnessSharing {
+                radius: 2.0,
+                alpha: 1.0,
+                distance: DistanceMetric::Phenotypic,
+            }),
+            None,
+            None,
+            generational,
+        ),
+        (
+            "genotypic sharing",
+            Some(FitnessSharing {
+                radius: 12.0,
+                alpha: 1.0,
+                distance: DistanceMetric::Genotypic,
+            }),
+            None,
+            None,
+            generational,
+        ),
+        ("phenotypic clearing", None, Some(clearing), None, generational),
+        ("genotypic speciation", None, None, Some(speciation), generational),
+        (
+            "phenotypic deterministic crowding",
+            None,
+            None,
+            None,
+            Replacement::DeterministicCrowding { distance: DistanceMetric::Phenotypic },
+        ),
+        (
+            "genotypic deterministic crowding",
+            None,
+            None,
+            None,
+            Replacement::DeterministicCrowding { distance: DistanceMetric::Genotypic },
+        ),
+    ];
+
+    let mut results = Vec::new();
+    for (name, sharing, clearing, speciation, replacement) in schemes {
+        // Crowding supplies its own selection pressure, so parents are paired uniformly
+        // (linear ranking at pressure 1.0)
+        let selection = if replacement == generational {
+            SelectionMethod::StochasticUniversal
+        } else {
+            SelectionMethod::LinearRank { pressure: 1.0 }
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(5000);
+        let params = GAParameters {
+            pop_size: 200,
+            mem_size: 16 * NUM_DIMS,
+            num_dims: NUM_DIMS,
+            max_iters: 50,
+            convergence_threshold: 1.0,
+            selection,
+            sharing,
+            clearing,
+            speciation,
+            replacement,
+            ..GAParameters::default()
+        };
+        let final_pop = sga(&Himmelblau, &params, &mut rng);
+
+        // Members sitting on each optimum
+        let decoded: Vec<Vec<f64>> = final_pop
+            .iter()
+            .map(|m| Himmelblau.decode_bitstring(m, NUM_DIMS))
+            .collect();
+        let optima = Himmelblau.known_optima(NUM_DIMS);
+        let fitnesses = Himmelblau.evaluate_population(&decoded);
+        let clusters = distinct_optima(&decoded, &fitnesses, OPTIMUM_RADIUS);
+        results.push((name, BasinCounts::classify(&decoded, &optima, OPTIMUM_RADIUS), clusters));
+    }
+
+    // The ES clears offspring before its survivors are chosen; it converges much faster than
+    // the GA, so it gets fewer generations to lose its optima in
+    for (name, clearing) in [("ES, no niching", None), ("ES, clearing", Some(clearing))] {
+        let mut rng = ChaCha8Rng::seed_from_u64(5000);
+        let params = ESParameters {
+            mu: 20,
+            lambda: 140,
+            mem_size: NUM_DIMS,
+            mem_range: (-5.0, 5.0),
+            sigma: 0.5,
+            tau: 1.0 / (2.0 * NUM_DIMS as f64).sqrt(),
+            max_gens: 10,
+            convergence_threshold: 1.0,
+            clearing,
+            ..ESParameters::default()
+        };
+        let genes: Vec<Vec<f64>> = EvolutionStrategy::new(&Himmelblau, &params, &NoRepair, &mut rng)
+            .quiet()
+            .run(&mut rng)
+            .iter()
+            .map(|member| member[0..NUM_DIMS].to_vec())
+            .collect();
+        let optima = Himmelblau.known_optima(NUM_DIMS);
+        let fitnesses = Himmelblau.evaluate_population(&genes);
+        let clusters = distinct_optima(&genes, &fitnesses, OPTIMUM_RADIUS);
+        results.push((name, BasinCounts::classify(&genes, &optima, OPTIMUM_RADIUS), clusters));
+    }
+
+    println!("\n=== Niching on Himmelblau ===");
+    for (name, basins, clusters) in results {
+        let (covered, counts) = (basins.found(), basins.counts);
+        println!(
+            "{name}: {covered}/4 optima held, members per optimum {counts:?}, {clusters} clusters"
+        );
+    }
+}
+
+// The same GA loop evolving a real-valued vector and a permutation
+fn run_genomes() {
+    const NUM_DIMS: usize = 10;
+    const NUM_CITIES: usize = 20;
+
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let real_params = GAParameters {
+        mem_size: NUM_DIMS,
+        num_dims: NUM_DIMS,
+        mutation_rate: 0.1,
+        ..GAParameters::default()
+    };
+    let final_real_pop: Vec<Vec<f64>> = ga(&Rosenbrock, &real_params, &NoRepair, &mut rng);
+    let best_real = final_real_pop
+        .iter()
+        .map(|m| Rosenbrock.fitness(m))
+        .fold(0.0, f64::max);
+
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let packed_params = GAParameters {
+        mem_size: 16 * NUM_DIMS,
+        num_dims: NUM_DIMS,
+        ..GAParameters::default()
+    };
+    let final_packed_pop: Vec<BitGenome> = ga(&Rosenbrock, &packed_params, &NoRepair, &mut rng);
+    let best_packed = final_packed_pop
+        .iter()
+        .map(|m| Rosenbrock.fitness(&Rosenbrock.decode_bitgenome(m, NUM_DIMS)))
+        .fold(0.0, f64::max);
+
+    // Cities on a circle, so the optimal tour length is known to be close to 2 * pi
+    let tsp = Tsp {
+        cities: (0..NUM_CITIES)
+            .map(|i| {
+                let angle = 2.0 * std::f64::consts::PI * i as f64 / NUM_CITIES as f64;
+                (angle.cos(), angle.sin())
+            })
+            .collect(),
+    };
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let perm_params = GAParameters {
+        mem_size: NUM_CITIES,
+        num_dims: NUM_CITIES,
+        mutation_rate: 0.05,
+        ..GAParameters::default()
+    };
+    let final_perm_pop: Vec<Permutation> = ga(&tsp, &perm_params, &NoRepair, &mut rng);
+    let best_tour = final_perm_pop
+        .iter()
+        .map(|p| tsp.tour_length(&p.0.iter().map(|&i| i as f64).collect::<Vec<f64>>()))
+        .fold(f64::INFINITY, f64::min);
+
+    // Himmelblau's optimum at (3, 2) lies on the integer grid
+    let mut integer_results = Vec::new();
+    for integer_mutation in [IntegerMutation::Uniform, IntegerMutation::Creep { max_step: 2 }] {
+        let mut rng = ChaCha8Rng::seed_from_u64(5000);
+        let integer_params = GAParameters {
+            mem_size: 2,
+            num_dims: 2,
+            mutation_rate: 0.2,
+            integer_mutation,
+            ..GAParameters::default()
+        };
+        let final_integer_pop: Vec<IntegerGenome> =
+            ga(&Himmelblau, &integer_params, &NoRepair, &mut rng);
+        let best = final_integer_pop
+            .iter()
+            .max_by(|a, b| {
+                let fa = Himmelblau.fitness(&a.phenotype(&Himmelblau.decoder(), 2));
+                let fb = Himmelblau.fitness(&b.phenotype(&Himmelblau.decoder(), 2));
+                fa.partial_cmp(&fb).unwrap()
+            })
+            .unwrap()
+            .clone();
+        integer_results.push((integer_mutation, best));
+    }
+
+    // Variable-length genome that has to discover how many values it needs
+    let target_sum = TargetSum {
+        target: 7.5,
+        length_penalty: 0.01,
+    };
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let variable_params = GAParameters {
+        mem_size: 20,
+        num_dims: 20,
+        mutation_rate: 0.1,
+        max_iters: 300,
+        ..GAParameters::default()
+    };
+    let final_variable_pop: Vec<VariableGenome> =
+        ga(&target_sum, &variable_params, &NoRepair, &mut rng);
+    let best_variable = final_variable_pop
+        .iter()
+        .max_by(|a, b| target_sum.fitness(&a.0).partial_cmp(&target_sum.fitness(&b.0)).unwrap())
+        .unwrap();
+
+    println!("\n=== Genome Representations ===");
+    println!("Real-valued Rosenbrock GA best fitness: {best_real}");
+    println!("Bit-packed Rosenbrock GA best fitness: {best_packed}");
+    println!("Permutation TSP GA best tour length: {best_tour}");
+    println!(
+        "Variable-length TargetSum GA best: {} genes summing to {:.4}",
+        best_variable.0.len(),
+        best_variable.0.iter().sum::<f64>()
+    );
+    for (integer_mutation, best) in integer_results {
+        println!("Integer Himmelblau GA ({integer_mutation:?}) best: {:?}", best.0);
+    }
+}
+
+// Noise of the given standard deviation, a uniform one spanning +-sqrt(3) standard deviations
+fn run_noisy(noise_kind: NoiseKind, std_dev: f64) -> Result<(), String> {
+    const NUM_DIMS: usize = 10;
+    const RESAMPLES: usize = 5;
+
+    let noise = match noise_kind {
+        NoiseKind::Uniform => NoiseModel::uniform(std_dev * 3_f64.sqrt()),
+        NoiseKind::Gaussian => NoiseModel::gaussian(std_dev),
+    };
+    let noise = noise.map_err(|e| format!("Error in --noise-std: {e}"))?;
+    println!("Running with noise {noise:?} and {RESAMPLES} resamples per evaluation");
+    // The noise gets its own stream, so both algorithms see noise from the same seed
+    let seeds = SeedSequence::new(5000);
+    let noisy_rosenbrock = NoisyFitness::new(Rosenbrock, noise, seeds.operator("noise").seed());
+
+    let mut rng = seeds.rng();
+    let es_params = ESParameters {
+        mem_size: NUM_DIMS,
+        resamples: RESAMPLES,
+        ..ESParameters::default()
+    };
+    let final_es_pop = evolution_strategy(&noisy_rosenbrock, &es_params, &mut rng);
+
+    let mut rng = seeds.rng();
+    let ga_params = GAParameters {
+        mem_size: 16 * NUM_DIMS,
+        num_dims: NUM_DIMS,
+        resamples: RESAMPLES,
+        ..GAParameters::default()
+    };
+    let final_ga_pop = sga(&noisy_rosenbrock, &ga_params, &mut rng);
+
+    // Report the noise-free fitness of what each algorithm actually found
+    let best_es = final_es_pop
+        .iter()
+        .map(|m| Rosenbrock.fitness(&m[0..NUM_DIMS]))
+        .fold(0.0, f64::max);
+    let best_ga = final_ga_pop
+        .iter()
+        .map(|m| Rosenbrock.fitness_bitstring(m, NUM_DIMS))
+        .fold(0.0, f64::max);
+    println!("\n=== Noisy Results (true fitness) ===");
+    println!("ES best: {best_es}");
+    println!("GA best: {best_ga}");
+    Ok(())
+}
+
+// Cancels a run once it has used up a budget of fitness evaluations
+struct EvaluationBudget {
+    max_evaluations: usize,
+    token: CancellationToken,
+}
+
+impl<I> Observer<I> for EvaluationBudget {
+    fn on_generation(&mut self, stats: &GenerationStats, _best: &I) {
+        if stats.evaluations >= self.max_evaluations {
+            self.token.cancel();
+        }
+    }
+}
+
+// Drives both algorithms silently with custom observers instead of the console output
+fn run_observers() {
+    const NUM_DIMS: usize = 10;
+
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let es_params = ESParameters { mem_size: NUM_DIMS, ..ESParameters::default() };
+    let mut es_log = ImprovementLog::default();
+    EvolutionStrategy::new(&Rosenbrock, &es_params, &NoRepair, &mut rng)
+        .quiet()
+        .with_observer(&mut es_log)
+        .run(&mut rng);
+
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let ga_params = GAParameters {
+        mem_size: 16 * NUM_DIMS,
+        num_dims: NUM_DIMS,
+        ..GAParameters::default()
+    };
+    let token = CancellationToken::new();
+    let mut ga_log = ImprovementLog::default();
+    GeneticAlgorithm::<String, _, _>::new(&Rosenbrock, &ga_params, &NoRepair, &mut rng)
+        .quiet()
+        .with_cancellation(token.clone())
+        .with_observer(EvaluationBudget { max_evaluations: 20_000, token })
+        .with_observer(&mut ga_log)
+        .run(&mut rng);
+
+    for (name, log) in [("ES", &es_log), ("GA", &ga_log)] {
+        println!("\n=== {name} improvements ===");
+        for (generation, evaluations, fitness) in &log.improvements {
+            println!("gen {generation} evals {evaluations} best {fitness}");
+        }
+        if let Some(summary) = &log.summary {
+            println!(
+                "Stopped after {} generations and {} evaluations ({}): best fitness {}",
+                summary.generations, summary.evaluations, summary.reason, summary.best_fitness
+            );
+        }
+    }
+}
+
+// (1+1) EA on long bit genomes, evaluating every offspring from scratch and then updating its
+// parent's evaluation from the flipped bits. Both use the same seed, so they follow the same
+// path and should end on the same fitness.
+fn run_incremental() {
+    const NUM_BITS: usize = 20_000;
+    const GENERATIONS: usize = 20_000;
+
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let weights: Vec<f64> = (0..NUM_BITS).map(|_| rng.random_range(1.0..10.0)).collect();
+    let values: Vec<f64> = (0..NUM_BITS).map(|_| rng.random_range(1.0..10.0)).collect();
+    // Loose enough that a random selection of about half the items starts out feasible
+    let capacity = weights.iter().sum::<f64>() * 0.6;
+    let knapsack = Knapsack { weights, values, capacity };
+
+    println!("\n=== MaxOnes, {NUM_BITS} bits ===");
+    compare_incremental(&MaxOnes, NUM_BITS, GENERATIONS);
+    println!("\n=== Knapsack, {NUM_BITS} items ===");
+    compare_incremental(&knapsack, NUM_BITS, GENERATIONS);
+}
+
+fn compare_incremental<F: IncrementalFitness>(fitness_fn: &F, num_bits: usize, generations: usize) {
+    let mut results = Vec::new();
+    for incremental in [false, true] {
+        let start = Instant::now();
+        let best = one_plus_one(fitness_fn, num_bits, generations, incremental);
+        let elapsed = start.elapsed();
+        let label = if incremental { "incremental" } else { "full" };
+        println!("{label:>11} evaluation: best fitness {best:.6} in {elapsed:.2?}");
+        results.push(best);
+    }
+    if (results[0] - results[1]).abs() > 1e-9 {
+        eprintln!("Full and incremental evaluation disagree: {} vs {}", results[0], results[1]);
+    }
+}
+
+// Keeps the offspring whenever it is at least as fit as its parent, with the standard 1/n
+// mutation rate
+fn one_plus_one<F: IncrementalFitness>(
+    fitness_fn: &F,
+    num_bits: usize,
+    generations: usize,
+    incremental: bool,
+) -> f64 {
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let params = GAParameters {
+        mutation_rate: 1.0 / num_bits as f64,
+        ..GAParameters::default()
+    };
+    let bounds = fitness_fn.bounds();
+    let mut parent = BitGenome::random(num_bits, bounds, &mut rng);
+    let mut state = fitness_fn.state(&parent.phenotype(&fitness_fn.decoder(), num_bits));
+    let mut parent_fitness = fitness_fn.value(&state);
+    for _ in 0..generations {
+        let (child, loci) = parent.mutate_with_loci(&params, bounds, &mut rng);
+        let (child_state, child_fitness) = match loci {
+            Some(loci) if incremental => {
+                let child_state = fitness_fn.update(&state, &changes(&child, &loci));
+                let child_fitness = fitness_fn.value(&child_state);
+                (child_state, child_fitness)
+            }
+            _ => {
+                let child_phenotype = child.phenotype(&fitness_fn.decoder(), num_bits);
+                let child_fitness = fitness_fn.fitness(&child_phenotype);
+                (state.clone(), child_fitness)
+            }
+        };
+        if child_fitness >= parent_fitness {
+            parent = child;
+            parent_fitness = child_fitness;
+            if incremental {
+                state = child_state;
+            }
+        }
+    }
+    parent_fitness
+}
+
+// Kernels with the C fitness signatures, standing in for ones compiled from C
+extern "C" fn negated_sphere(x: *const f64, len: usize) -> f64 {
+    // Safety: ec_run passes a pointer to len doubles
+    let x = unsafe { std::slice::from_raw_parts(x, len) };
+    -x.iter().map(|xi| xi * xi).sum::<f64>()
+}
+
+extern "C" fn leading_ones(bits: *const std::ffi::c_char, len: usize) -> f64 {
+    // Safety: ec_run passes a pointer to len chars
+    let bits = unsafe { std::slice::from_raw_parts(bits, len) };
+    bits.iter().take_while(|&&bit| bit == b'1' as std::ffi::c_char).count() as f64
+}
+
+// Drives the GA and ES through the C entry point, as a C program linking against it would
+fn run_ffi() {
+    let config = RunConfig {
+        algorithm: EC_ES,
+        num_dims: 5,
+        bits_per_dim: 16,
+        pop_size: 50,
+        generations: 200,
+        lower: -5.0,
+        upper: 5.0,
+        seed: 5000,
+    };
+    let mut best = [0.0; 5];
+    for (name, algorithm) in [("ES", EC_ES), ("GA", EC_GA)] {
+        let config = RunConfig { algorithm, ..config };
+        // Safety: the config and buffer outlive the call and the kernel reads within len
+        let value = unsafe {
+            ffi::ec_run(&config, Some(negated_sphere), None, best.as_mut_ptr(), best.len())
+        };
+        println!("{name} on the C sphere kernel: best fitness {value:.6} at {best:.4?}");
+    }
+
+    let config = RunConfig { algorithm: EC_GA, num_dims: 64, ..config };
+    // Safety: as above, without a best buffer
+    let value = unsafe { ffi::ec_run(&config, None, Some(leading_ones), std::ptr::null_mut(), 0) };
+    println!("GA on the C leading ones kernel: {value} of {} bits", config.num_dims);
+}
+
+// Fixed operator rates versus rates that each individual carries and evolves, on a packed
+// bitstring Rosenbrock
+fn run_self_adaptive() {
+    const NUM_DIMS: usize = 10;
+
+    let params = GAParameters {
+        mem_size: 16 * NUM_DIMS,
+        num_dims: NUM_DIMS,
+        ..GAParameters::default()
+    };
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let fixed: Vec<BitGenome> =
+        GeneticAlgorithm::new(&Rosenbrock, &params, &NoRepair, &mut rng).quiet().run(&mut rng);
+    let best_fixed = fixed
+        .iter()
+        .map(|m| Rosenbrock.fitness(&m.phenotype(&Rosenbrock.decoder(), NUM_DIMS)))
+        .fold(0.0, f64::max);
+
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let mut rates = RateLog::new(100);
+    let adaptive: Vec<SelfAdaptive<BitGenome>> =
+        GeneticAlgorithm::new(&Rosenbrock, &params, &NoRepair, &mut rng)
+            .quiet()
+            .with_observer(&mut rates)
+            .run(&mut rng);
+    let best_adaptive = adaptive
+        .iter()
+        .map(|m| Rosenbrock.fitness(&m.phenotype(&Rosenbrock.decoder(), NUM_DIMS)))
+        .fold(0.0, f64::max);
+    let count = adaptive.len() as f64;
+    let mean_mutation = adaptive.iter().map(|m| m.mutation_rate).sum::<f64>() / count;
+    let mean_crossover = adaptive.iter().map(|m| m.crossover_rate).sum::<f64>() / count;
+
+    println!("\n=== Self-Adaptive Operator Rates ===");
+    println!(
+        "Fixed rates (mutation {}, crossover {}): GA best {best_fixed}",
+        params.mutation_rate, params.crossover_rate
+    );
+    println!("Self-adaptive rates: GA best {best_adaptive}");
+    for (generation, mutation_rate, crossover_rate) in &rates.samples {
+        println!(
+            "  generation {generation:4}: best carries mutation {mutation_rate:.5} \
+             crossover {crossover_rate:.3}"
+        );
+    }
+    println!("Final population mean: mutation {mean_mutation:.5} crossover {mean_crossover:.3}");
+}
+
+// Recombination operators for real vectors on Rosenbrock, in a real-coded GA and as ES
+// recombination ahead of mutation
+fn run_crossover_comparison() {
+    const NUM_DIMS: usize = 10;
+
+    let operators = [
+        RealCrossover::SinglePoint,
+        RealCrossover::Blend { alpha: 0.3 },
+        RealCrossover::WholeArithmetic { alpha: 0.3 },
+        RealCrossover::PartialArithmetic { alpha: 0.3 },
+    ];
+    println!("\n=== Real-Valued Crossover ===");
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let best_mutation_only =
+        EvolutionStrategy::new(&Rosenbrock, &ESParameters::default(), &NoRepair, &mut rng)
+            .quiet()
+            .run(&mut rng)
+            .iter()
+            .map(|m| Rosenbrock.fitness(&m[0..NUM_DIMS]))
+            .fold(0.0, f64::max);
+    println!("ES without recombination: best {best_mutation_only:.6}");
+    for operator in operators {
+        let mut rng = ChaCha8Rng::seed_from_u64(5000);
+        let ga_params = GAParameters {
+            mem_size: NUM_DIMS,
+            num_dims: NUM_DIMS,
+            mutation_rate: 0.1,
+            real_crossover: operator,
+            ..GAParameters::default()
+        };
+        let final_ga_pop: Vec<Vec<f64>> =
+            GeneticAlgorithm::new(&Rosenbrock, &ga_params, &NoRepair, &mut rng)
+                .quiet()
+                .run(&mut rng);
+        let best_ga = final_ga_pop.iter().map(|m| Rosenbrock.fitness(m)).fold(0.0, f64::max);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(5000);
+        let es_params = ESParameters { recombination: Some(operator), ..ESParameters::default() };
+        let best_es = EvolutionStrategy::new(&Rosenbrock, &es_params, &NoRepair, &mut rng)
+            .quiet()
+            .run(&mut rng)
+            .iter()
+            .map(|m| Rosenbrock.fitness(&m[0..NUM_DIMS]))
+            .fold(0.0, f64::max);
+        println!("{operator:?}: real-coded GA best {best_ga:.6}, ES best {best_es:.6}");
+    }
+}
+
+// Heuristic selection schemes choosing between mutation strengths and a local search move
+// on a packed bitstring Rosenbrock, with how often each heuristic was picked in each quarter
+// of the run
+fn run_hyper_heuristic() {
+    const NUM_DIMS: usize = 10;
+    const QUARTERS: usize = 4;
+
+    let selections = [
+        HeuristicSelection::Random,
+        HeuristicSelection::EpsilonGreedy { epsilon: 0.1 },
+        HeuristicSelection::Ucb { exploration: 0.05 },
+    ];
+    println!("\n=== Selection Hyper-Heuristic ===");
+    for selection in selections {
+        let params = HyperHeuristicParameters {
+            selection,
+            max_steps: 4000,
+            ga: GAParameters {
+                mem_size: 16 * NUM_DIMS,
+                num_dims: NUM_DIMS,
+                ..GAParameters::default()
+            },
+            ..HyperHeuristicParameters::default()
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(5000);
+        let mut search: HyperHeuristic<BitGenome, _> =
+            HyperHeuristic::new(&Rosenbrock, &params, &mut rng);
+        let reason = loop {
+            if let Some(reason) = search.step(&mut rng) {
+                break reason;
+            }
+        };
+        println!(
+            "{selection:?}: best {} after {} evaluations",
+            search.best_fitness(),
+            search.evaluations()
+        );
+        let quarter = search.history().len().div_ceil(QUARTERS).max(1);
+        let heuristics = params.heuristics.iter().zip(search.stats());
+        for (index, (heuristic, stats)) in heuristics.enumerate() {
+            let shares: Vec<String> = search
+                .history()
+                .chunks(quarter)
+                .map(|chunk| {
+                    let picked = chunk.iter().filter(|&&i| i == index).count();
+                    format!("{:3.0}%", 100.0 * picked as f64 / chunk.len() as f64)
+                })
+                .collect();
+            println!(
+                "  {heuristic:?}: {} calls, {} improvements, credit {:.4}, share by quarter {}",
+                stats.calls,
+                stats.improvements,
+                stats.credit,
+                shares.join(" ")
+            );
+        }
+        search.finish(reason);
+    }
+}
+
+// Minimize Rosenbrock subject to x lying inside a disk of radius 1 around the origin
+fn run_constrained() {
+    const NUM_DIMS: usize = 10;
+
+    let schemes = [
+        PenaltyScheme::Static { weight: 100.0 },
+        PenaltyScheme::Dynamic { coefficient: 0.5, alpha: 2.0, evals_per_step: 200 },
+        PenaltyScheme::Adaptive { initial_weight: 10.0, increase: 2.0, decrease: 1.5, window: 200 },
+    ];
+
+    for scheme in schemes {
+        let constrained = PenalizedFitness::new(Rosenbrock, scheme).with_constraint(DiskConstraint {
+            center: vec![0.0; NUM_DIMS],
+            radius: 1.0,
+        });
+
+        let mut rng = ChaCha8Rng::seed_from_u64(5000);
+        let es_params = ESParameters {
+            mem_size: NUM_DIMS,
+            ..ESParameters::default()
+        };
+        let final_es_pop = evolution_strategy(&constrained, &es_params, &mut rng);
+
+        let best = final_es_pop
+            .iter()
+            .max_by(|a, b| {
+                let fa = constrained.fitness(&a[0..NUM_DIMS]);
+                let fb = constrained.fitness(&b[0..NUM_DIMS]);
+                fa.partial_cmp(&fb).unwrap()
+            })
+            .unwrap();
+        println!("\n=== Constrained ES Results ({scheme:?}) ===");
+        println!(
+            "Best raw fitness: {} Violation: {} Final penalty weight: {}",
+            Rosenbrock.fitness(&best[0..NUM_DIMS]),
+            constrained.total_violation(&best[0..NUM_DIMS]),
+            constrained.current_weight()
+        );
+    }
+
+    // Deb's feasibility rules need no penalty weight: tournaments and survivor selection
+    // compare raw fitness among feasible members and violation among the others
+    let disk =
+        ConstraintSet::new().with(DiskConstraint { center: vec![0.0; NUM_DIMS], radius: 1.0 });
+    let by_feasibility = |a: &[f64], b: &[f64]| {
+        feasibility_order(
+            (Rosenbrock.fitness(a), disk.total_violation(a)),
+            (Rosenbrock.fitness(b), disk.total_violation(b)),
+        )
+    };
+
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let es_params = ESParameters {
+        mem_size: NUM_DIMS,
+        ..ESParameters::default()
+    };
+    let final_es_pop = EvolutionStrategy::new(&Rosenbrock, &es_params, &NoRepair, &mut rng)
+        .with_constraints(&disk)
+        .run(&mut rng);
+    let es_genes: Vec<&[f64]> = final_es_pop.iter().map(|member| &member[0..NUM_DIMS]).collect();
+    let best = es_genes.iter().copied().max_by(|a, b| by_feasibility(a, b)).unwrap();
+    let violations: Vec<f64> = es_genes.iter().map(|genes| disk.total_violation(genes)).collect();
+    println!("\n=== Constrained ES Results (feasibility first) ===");
+    let population = ViolationStats::of(&violations);
+    println!(
+        "Best raw fitness: {} Violation: {} Final population: {:.0}% feasible, mean \
+         violation {:.4}",
+        Rosenbrock.fitness(best),
+        disk.total_violation(best),
+        population.feasible * 100.0,
+        population.mean_violation
+    );
+
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let ga_params = GAParameters {
+        mem_size: 16 * NUM_DIMS,
+        num_dims: NUM_DIMS,
+        ..GAParameters::default()
+    };
+    let final_ga_pop: Vec<BitGenome> =
+        GeneticAlgorithm::new(&Rosenbrock, &ga_params, &NoRepair, &mut rng)
+            .with_constraints(&disk)
+            .quiet()
+            .run(&mut rng);
+    let decoder = Rosenbrock.decoder();
+    let phenotypes: Vec<Vec<f64>> =
+        final_ga_pop.iter().map(|member| member.phenotype(&decoder, NUM_DIMS)).collect();
+    let best = phenotypes.iter().max_by(|a, b| by_feasibility(a, b)).unwrap();
+    let violations: Vec<f64> = phenotypes.iter().map(|x| disk.total_violation(x)).collect();
+    println!("\n=== Constrained GA Results (feasibility first) ===");
+    let population = ViolationStats::of(&violations);
+    println!(
+        "Best raw fitness: {} Violation: {} Final population: {:.0}% feasible, mean \
+         violation {:.4}",
+        Rosenbrock.fitness(best),
+        disk.total_violation(best),
+        population.feasible * 100.0,
+        population.mean_violation
+    );
+}
+
+// The mixed-integer ES on a test problem with every gene type and on the pressure vessel
+// design, whose plate thicknesses come in whole gauges
+fn run_mixed_integer() {
+    let sphere = MixedSphere { continuous: 4, integer: 4, categorical: 4 };
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let sphere_params = ESParameters {
+        mu: 20,
+        lambda: 100,
+        mem_size: 12,
+        sigma: 0.1,
+        tau: 1.0 / (2.0 * 12.0_f64).sqrt(),
+        gene_types: sphere.gene_types(),
+        ..ESParameters::default()
+    };
+    let best_sphere = EvolutionStrategy::new(&sphere, &sphere_params, &NoRepair, &mut rng)
+        .quiet()
+        .run(&mut rng)
+        .into_iter()
+        .max_by(|a, b| sphere.fitness(&a[..12]).total_cmp(&sphere.fitness(&b[..12])))
+        .unwrap();
+
+    let vessel = PenalizedFitness::new(PressureVessel, PenaltyScheme::Static { weight: 1000.0 })
+        .with_constraint(PressureVesselConstraints);
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let vessel_params = ESParameters {
+        mu: 20,
+        lambda: 100,
+        mem_size: 4,
+        mem_range: PressureVessel.bounds(),
+        sigma: 0.1,
+        tau: 1.0 / (2.0 * 4.0_f64).sqrt(),
+        gene_types: PressureVessel.gene_types(),
+        ..ESParameters::default()
+    };
+    let best_vessel = EvolutionStrategy::new(&vessel, &vessel_params, &NoRepair, &mut rng)
+        .quiet()
+        .run(&mut rng)
+        .into_iter()
+        .max_by(|a, b| vessel.fitness(&a[..4]).total_cmp(&vessel.fitness(&b[..4])))
+        .unwrap();
+    let (shell, head, radius, length) = PressureVessel::design(&best_vessel);
+
+    println!("\n=== Mixed-Integer ES ===");
+    println!(
+        "Mixed sphere: best fitness {:.6} at {:?}",
+        sphere.fitness(&best_sphere[..12]),
+        &best_sphere[..12]
+    );
+    println!(
+        "Pressure vessel: cost {:.3} (best known {BEST_KNOWN_COST}), violation {:.2e}",
+        PressureVessel::cost(&best_vessel),
+        vessel.total_violation(&best_vessel[..4])
+    );
+    println!(
+        "  shell {shell} in ({} gauges), head {head} in ({} gauges), radius {radius:.3}, \
+         length {length:.3}",
+        best_vessel[0], best_vessel[1]
+    );
+}
+
+// Random sampling on the multi-objective test problems: how many fronts the samples sort into
+// and how far the non-dominated ones are from the true front, which a member built to lie on
+// it reaches exactly
+fn run_multi_objective() {
+    // f1 = 0.2 is on all three ZDT fronts and a point of the 501-point references
+    let mut zdt_member = vec![0.0; 30];
+    zdt_member[0] = 0.2;
+    // The corner (1, 0, 0) of the DTLZ2 sphere
+    let mut dtlz_member = vec![0.5; 12];
+    dtlz_member[..2].copy_from_slice(&[0.0, 0.0]);
+
+    println!("\n=== Multi-Objective Problems ===");
+    describe_multi_objective("ZDT1", &Zdt1, &zdt_member, 501);
+    describe_multi_objective("ZDT2", &Zdt2, &zdt_member, 501);
+    describe_multi_objective("ZDT3", &Zdt3, &zdt_member, 501);
+    describe_multi_objective("DTLZ2", &Dtlz2 { objectives: 3 }, &dtlz_member, 20);
+}
+
+fn describe_multi_objective(
+    name: &str,
+    problem: &impl MultiObjective,
+    on_front: &[f64],
+    resolution: usize,
+) {
+    const SAMPLES: usize = 1000;
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let (low, high) = problem.bounds();
+    let points: Vec<Vec<f64>> = (0..SAMPLES)
+        .map(|_| {
+            let member: Vec<f64> =
+                (0..on_front.len()).map(|_| rng.random_range(low..high)).collect();
+            problem.objectives(&member)
+        })
+        .collect();
+    let fronts = non_dominated_sort(&points);
+    let first: Vec<Vec<f64>> = fronts[0].iter().map(|&i| points[i].clone()).collect();
+    let reference = problem.reference_front(resolution);
+
+    let distance = generational_distance(&[problem.objectives(on_front)], &reference);
+    // Any point on the true front dominating another would mean a wrong reference
+    let consistent = non_dominated_sort(&reference).len() == 1;
+    println!(
+        "{name} ({} objectives): {SAMPLES} random members in {} fronts, {} non-dominated at \
+         distance {:.3}; member on the front at {distance:.4} from the {}-point reference \
+         (consistent: {consistent})",
+        problem.num_objectives(),
+        fronts.len(),
+        first.len(),
+        generational_distance(&first, &reference),
+        reference.len()
+    );
+}
+
+// NEAT on XOR over several seeds: how often it finds a solution, and the network it grew
+fn run_neat() {
+    const RUNS: u64 = 10;
+    // Solved once the total error is below about 0.13, when every case is within 0.13 of
+    // its target
+    let params = NeatParameters {
+        max_gens: 200,
+        stopping: StoppingCriteria { target_epsilon: Some(1.0), ..StoppingCriteria::default() },
+        ..NeatParameters::default()
+    };
+
+    let mut solved_generations = Vec::new();
+    let mut champion = None;
+    for run in 0..RUNS {
+        let mut rng = ChaCha8Rng::seed_from_u64(5000 + run);
+        let mut neat = Neat::new(&Xor, &params, &mut rng).quiet();
+        let reason = loop {
+            if let Some(reason) = neat.step(&mut rng) {
+                break reason;
+            }
+        };
+        let generations = neat.generation();
+        let population = neat.finish(reason);
+        if reason == StopReason::TargetReached {
+            solved_generations.push(generations as f64);
+            if champion.is_none() {
+                champion = population
+                    .into_iter()
+                    .max_by(|a, b| Xor.fitness(a).total_cmp(&Xor.fitness(b)));
+            }
+        }
+    }
+
+    println!("\n=== NEAT on XOR ===");
+    let (mean, _) = mean_and_std(&solved_generations);
+    println!(
+        "{}/{RUNS} runs solved XOR, after {mean:.1} generations on average",
+        solved_generations.len()
+    );
+    if let Some(network) = champion {
+        println!(
+            "First solution: fitness {:.3}, {} hidden nodes, {} enabled connections",
+            Xor.fitness(&network),
+            network.hidden_nodes(),
+            network.enabled_connections()
+        );
+        for (inputs, target) in XOR_CASES {
+            println!("  {inputs:?} -> {:.3} (target {target})", network.activate(&inputs)[0]);
+        }
+    }
+}
+
+// An MLP's weights trained on a dataset by the self-adaptive ES, the ES with cumulative
+// step-size adaptation and OpenAI-ES, all on the same fixed topology
+fn run_mlp(path: &str) -> Result<(), String> {
+    const HIDDEN: usize = 4;
+    let dataset = Dataset::load(path).map_err(|e| format!("Error loading dataset {path}: {e}"))?;
+    let rows = dataset.targets.len();
+    let mlp = Mlp::new(dataset, HIDDEN);
+    let num_weights = mlp.num_weights();
+
+    println!("\n=== MLP weights on {path} ===");
+    println!(
+        "{rows} rows, {} features, {HIDDEN} hidden units: {num_weights} weights, {}",
+        mlp.dataset.num_features(),
+        if mlp.is_classification() { "classification" } else { "regression" }
+    );
+    let mutations = [("Self-adaptive", ESMutation::Isotropic), ("CSA", ESMutation::Cumulative)];
+    for (label, mutation) in mutations {
+        let mut rng = ChaCha8Rng::seed_from_u64(5000);
+        let es_params = ESParameters {
+            mu: 20,
+            lambda: 100,
+            mem_size: num_weights,
+            mem_range: mlp.bounds(),
+            sigma: 0.5,
+            tau: 1.0 / (2.0 * num_weights as f64).sqrt(),
+            mutation,
+            max_gens: 300,
+            ..ESParameters::default()
+        };
+        let best = EvolutionStrategy::new(&mlp, &es_params, &NoRepair, &mut rng)
+            .quiet()
+            .run(&mut rng)
+            .into_iter()
+            .map(|member| member[..num_weights].to_vec())
+            .max_by(|a, b| mlp.fitness(a).total_cmp(&mlp.fitness(b)))
+            .unwrap();
+        print!("{label} ES: training MSE {:.4}", mlp.mean_squared_error(&best));
+        if mlp.is_classification() {
+            print!(", accuracy {:.1}%", 100.0 * mlp.accuracy(&best));
+        }
+        println!();
+    }
+
+    // OpenAI-ES on the same budget of 100 evaluations a generation
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let nes_params = OpenAiESParameters {
+        pop_size: 100,
+        mem_size: num_weights,
+        mem_range: mlp.bounds(),
+        max_gens: 300,
+        ..OpenAiESParameters::default()
+    };
+    let best = OpenAiES::new(&mlp, &nes_params, &mut rng).quiet().run(&mut rng).swap_remove(0);
+    print!("OpenAI-ES: training MSE {:.4}", mlp.mean_squared_error(&best[..num_weights]));
+    if mlp.is_classification() {
+        print!(", accuracy {:.1}%", 100.0 * mlp.accuracy(&best[..num_weights]));
+    }
+    println!();
+    Ok(())
+}
+
+// CGP over several seeds on even parity, built from AND, OR, NAND and NOR, and on Koza's
+// quartic: how often it solves each, how many evaluations that takes, and how much of the
+// grid the solutions use
+fn run_cgp() {
+    let params = CgpParameters {
+        stopping: StoppingCriteria { target_epsilon: Some(0.5), ..StoppingCriteria::default() },
+        ..CgpParameters::default()
+    };
+    println!("\n=== CGP ===");
+    describe_cgp("3-bit even parity", &EvenParity { bits: 3 }, &params);
+    describe_cgp("Quartic", &Quartic { samples: 20 }, &params);
+}
+
+fn describe_cgp(label: &str, objective: &impl ProgramObjective, params: &CgpParameters) {
+    const RUNS: u64 = 10;
+    let (mut evaluations, mut active_nodes) = (Vec::new(), Vec::new());
+    for run in 0..RUNS {
+        let mut rng = ChaCha8Rng::seed_from_u64(5000 + run);
+        let mut cgp = Cgp::new(objective, params, &mut rng).quiet();
+        let reason = loop {
+            if let Some(reason) = cgp.step(&mut rng) {
+                break reason;
+            }
+        };
+        let used = cgp.evaluations();
+        let program = cgp.finish(reason).swap_remove(0);
+        if reason == StopReason::TargetReached {
+            evaluations.push(used as f64);
+            active_nodes.push(program.active_nodes() as f64);
+        }
+    }
+    let (mean_evaluations, _) = mean_and_std(&evaluations);
+    let (mean_active, _) = mean_and_std(&active_nodes);
+    println!(
+        "{label}: {}/{RUNS} runs solved it, after {mean_evaluations:.0} evaluations on average, \
+         with {mean_active:.1} of {} nodes active",
+        evaluations.len(),
+        params.columns
+    );
+}
+
+fn run_repair() {
+    const NUM_ITEMS: usize = 50;
+    const NUM_DIMS: usize = 10;
+
+    // Random knapsack instance whose capacity fits roughly a third of the items
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let weights: Vec<f64> = (0..NUM_ITEMS).map(|_| rng.random_range(1.0..10.0)).collect();
+    let values: Vec<f64> = (0..NUM_ITEMS).map(|_| rng.random_range(1.0..10.0)).collect();
+    let capacity = weights.iter().sum::<f64>() / 3.0;
+    let knapsack = Knapsack {
+        weights: weights.clone(),
+        values: values.clone(),
+        capacity,
+    };
+    let capacity_repair = CapacityRepair {
+        weights,
+        values,
+        capacity,
+    };
+
+    let ga_params = GAParameters {
+        mem_size: NUM_ITEMS,
+        ..GAParameters::default()
+    };
+    let final_ga_pop = sga_with_repair(&knapsack, &ga_params, &capacity_repair, &mut rng);
+    let best_ga = final_ga_pop
+        .iter()
+        .map(|m| knapsack.fitness_bitstring(m, NUM_ITEMS))
+        .fold(0.0, f64::max);
+    println!("Knapsack GA best fraction of total value: {best_ga}");
+
+    // ES on Rosenbrock with genes clamped to the initialization range
+    let mut rng = ChaCha8Rng::seed_from_u64(5000);
+    let es_params = ESParameters {
+        mem_size: NUM_DIMS,
+        ..ESParameters::default()
+    };
+    let bound_repair = BoundRepair {
+        bounds: es_params.mem_range,
+    };
+    let final_es_pop = evolution_strategy_with_repair(&Rosenbrock, &es_params, &bound_repair, &mut rng);
+    let best_es = final_es_pop
+        .iter()
+        .map(|m| Rosenbrock.fitness(&m[0..NUM_DIMS]))
+        .fold(0.0, f64::max);
+    println!("Bounded Rosenbrock ES best fitness: {best_es}");
+}
+
+// Grid search over GA and ES parameters; the optional worker count defaults to the number
+// of available cores
+// Summarizes a results CSV from an earlier tuning session
+fn run_analysis(args: &AnalyzeArgs) -> Result<(), String> {
+    if !args.qd.is_empty() {
+        return run_qd_analysis(args);
+    }
+    let path = &args.results;
+    let results = ResultsAnalyzer::load_results_from_csv(path)
+        .map_err(|e| format!("Error loading results: {e}"))?;
+    if let Some(combinations_path) = &args.combinations {
+        ResultsAnalyzer::save_combinations_to_csv(&results, combinations_path)
+            .map_err(|e| format!("Error saving combination statistics: {e}"))?;
+    }
+    let sga_analysis = ResultsAnalyzer::analyze_sga_results(&results);
+    let es_analysis = ResultsAnalyzer::analyze_es_results(&results);
+    if args.format == OutputFormat::Json {
+        let stdout = std::io::stdout().lock();
+        return ResultsAnalyzer::write_analysis_json(&sga_analysis, &es_analysis, stdout)
+            .map_err(|e| format!("Error writing analysis: {e}"));
+    }
+    let designs: BTreeSet<&str> = results.iter().map(|r| r.design.as_str()).collect();
+    let designs: Vec<&str> = designs.into_iter().collect();
+    println!("Loaded {} runs from {path} ({} design)", results.len(), designs.join(", "));
+    ResultsAnalyzer::print_summary(&sga_analysis, &es_analysis);
+    if let Some(stem) = &args.main_effects {
+        save_main_effects(stem, &sga_analysis, &es_analysis);
+    }
+    if let Some(stem) = &args.heatmaps {
+        save_heatmaps(stem, &sga_analysis, &es_analysis);
+    }
+    Ok(())
+}
+
+// Final archive metrics of MAP-Elites runs from their QD traces
+fn run_qd_analysis(args: &AnalyzeArgs) -> Result<(), String> {
+    let runs = args
+        .qd
+        .iter()
+        .map(|path| curves::read_qd_trace(path))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Error loading QD traces: {e}"))?;
+    let analysis = ResultsAnalyzer::analyze_qd(&runs);
+    if args.format == OutputFormat::Json {
+        let stdout = std::io::stdout().lock();
+        return serde_json::to_writer_pretty(stdout, &analysis)
+            .map(|()| println!())
+            .map_err(|e| format!("Error writing analysis: {e}"));
+    }
+    println!("Loaded {} QD traces", runs.len());
+    ResultsAnalyzer::print_qd_summary(&analysis);
+    Ok(())
+}
+
+// Main effects of each algorithm's factorial parameters on score: {stem}_main_effects.csv and
+// a plot per algorithm, {stem}_main_effects_<algorithm>.png
+fn save_main_effects(stem: &str, sga: &SGAAnalysis, es: &ESAnalysis) {
+    let effects = [("SGA", sga.main_effects.as_slice()), ("ES", es.main_effects.as_slice())];
+    if effects.iter().all(|(_, effects)| effects.is_empty()) {
+        return;
+    }
+    let table_path = format!("{stem}_main_effects.csv");
+    match anova::save_main_effects(&effects, &table_path) {
+        Ok(()) => println!("Main effects saved to {table_path}"),
+        Err(e) => eprintln!("Error saving main effects: {e}"),
+    }
+    for (algorithm, effects) in effects.iter().filter(|(_, effects)| !effects.is_empty()) {
+        let plot_path = format!("{stem}_main_effects_{}.png", algorithm.to_lowercase());
+        let title = format!("{algorithm} main effects on score (95% CI)");
+        match plots::plot_main_effects(&plot_path, &title, effects) {
+            Ok(()) => println!("Main-effects plot saved to {plot_path}"),
+            Err(e) => eprintln!("Error plotting main effects: {e}"),
+        }
+    }
+}
+
+// Mean score over each pair of an algorithm's factorial parameters, as a CSV matrix and a
+// heatmap each: {stem}_heatmap_<algorithm>_<row>_x_<column>.csv and .png
+fn save_heatmaps(stem: &str, sga: &SGAAnalysis, es: &ESAnalysis) {
+    for (algorithm, heatmaps) in [("SGA", &sga.heatmaps), ("ES", &es.heatmaps)] {
+        for heatmap in heatmaps {
+            let name = format!(
+                "{stem}_heatmap_{}_{}_x_{}",
+                algorithm.to_lowercase(),
+                heatmap.row,
+                heatmap.column
+            );
+            if let Err(e) = anova::save_heatmap(heatmap, &format!("{name}.csv")) {
+                eprintln!("Error saving heatmap: {e}");
+                continue;
+            }
+            let title = format!("{algorithm} mean score, {} x {}", heatmap.row, heatmap.column);
+            match plots::plot_heatmap(&format!("{name}.png"), &title, heatmap) {
+                Ok(()) => println!("Heatmap saved to {name}.csv and {name}.png"),
+                Err(e) => eprintln!("Error plotting heatmap: {e}"),
+            }
+        }
+    }
+}
+
+// The JSON exports of a tuning session, next to its results CSV
+fn save_json(
+    output: &str,
+    results: &[TuningResult],
+    sga: &SGAAnalysis,
+    es: &ESAnalysis,
+) -> Result<(), String> {
+    let stem = Path::new(output).with_extension("").display().to_string();
+    let results_path = format!("{stem}.json");
+    ResultsAnalyzer::save_results_to_json(results, &results_path)
+        .map_err(|e| format!("Error saving results: {e}"))?;
+    println!("Results saved to {results_path}");
+    let analysis_path = format!("{stem}_analysis.json");
+    std::fs::File::create(&analysis_path)
+        .map_err(Into::into)
+        .and_then(|file| ResultsAnalyzer::write_analysis_json(sga, es, file))
+        .map_err(|e| format!("Error saving analysis: {e}"))?;
+    println!("Analysis saved to {analysis_path}");
+    Ok(())
+}
+
+// The full grids, the combinations sampled by `search` or a screening design over the grid's
+// axes (the sampled ranges without one), recording the design in config
+fn tuning_grids(
+    kind: SearchKind,
+    search: &SampledSearch,
+    grid: Option<&GridSpec>,
+    config: &mut TuningConfig,
+) -> Result<(Vec<GAParameters>, Vec<ESParameters>), String> {
+    let screening = match kind {
+        SearchKind::Grid => {
+            return Ok(match grid {
+                Some(grid) => (grid.sga_grid()?, grid.es_grid()?),
+                None => (ParameterGrid::generate_sga_grid(), ParameterGrid::generate_es_grid()),
+            });
+        }
+        SearchKind::Random | SearchKind::Lhs | SearchKind::Sobol | SearchKind::Bayes => {
+            config.design = search.sampling.to_string();
+            return Ok((search.sample_sga(), search.sample_es()));
+        }
+        SearchKind::Fractional => Screening::Fractional,
+        SearchKind::PlackettBurman => Screening::PlackettBurman,
+    };
+    let (sga_fixed, sga_factors) = grid
+        .and_then(GridSpec::sga_factors)
+        .unwrap_or_else(|| (HashMap::new(), search.sga_factors()));
+    let (es_fixed, es_factors) = grid
+        .and_then(GridSpec::es_factors)
+        .unwrap_or_else(|| (HashMap::new(), search.es_factors()));
+    let (sga_design, es_design) = (screening.design(sga_factors)?, screening.design(es_factors)?);
+    let with_fixed = |fixed: &HashMap<String, f64>, mut values: HashMap<String, f64>| {
+        values.extend(fixed.iter().map(|(name, &value)| (name.clone(), value)));
+        values
+    };
+    let sga_grid = sga_design
+        .combinations()
+        .into_iter()
+        .map(|values| ParameterGrid::sga_checked(&with_fixed(&sga_fixed, values)))
+        .collect::<Result<_, _>>()?;
+    let es_grid = es_design
+        .combinations()
+        .into_iter()
+        .map(|values| ParameterGrid::es_checked(&with_fixed(&es_fixed, values)))
+        .collect::<Result<_, _>>()?;
+    config.design = screening.to_string();
+    config.design_matrices = vec![("SGA".to_string(), sga_design), ("ES".to_string(), es_design)];
+    Ok((sga_grid, es_grid))
+}
+
+// Writes the coded design matrix of a screening design, if the session has one
+fn save_design(path: &str, config: &TuningConfig) {
+    if config.design_matrices.is_empty() {
+        return;
+    }
+    match screening::save_design_matrices(&config.design_matrices, path) {
+        Ok(()) => println!("Design matrix saved to {path}"),
+        Err(e) => eprintln!("Error saving design matrix: {e}"),
+    }
+}
+
+// Prints what a session would run, how long it would take and what it would write. The
+// expected time comes from one calibration run of a middle combination of each algorithm,
+// the worst case from every run hitting its timeout.
+fn dry_run_tuning(
+    args: &TuneArgs,
+    search: &SampledSearch,
+    grid: Option<&GridSpec>,
+    config: &mut TuningConfig,
+) -> Result<(), String> {
+    let (sga_grid, es_grid) = if args.search == SearchKind::Bayes {
+        // Bayesian optimization places its combinations as it goes, so only the count is known
+        config.design = "bayes".to_string();
+        let sga = search.sga_at(&[0.5; SampledSearch::SGA_AXES]);
+        let es = search.es_at(&[0.5; SampledSearch::ES_AXES]);
+        (vec![sga; args.samples], vec![es; args.samples])
+    } else {
+        tuning_grids(args.search, search, grid, config).map_err(|e| format!("Error: {e}"))?
+    };
+    let (sga_runs, es_runs) = (sga_grid.len() * config.num_runs, es_grid.len() * config.num_runs);
+    let total_runs = sga_runs + es_runs;
+    let workers = config.num_workers.clamp(1, total_runs.max(1));
+    println!(
+        "{} SGA and {} ES combinations ({} design), {} runs each: {total_runs} runs on {workers} \
+         worker threads",
+        sga_grid.len(),
+        es_grid.len(),
+        config.design,
+        config.num_runs
+    );
+
+    println!("Calibrating with one run of each algorithm...");
+    let sga_time = sga_grid.get(sga_grid.len() / 2).map_or(0.0, |params| {
+        TimeoutRunner::run_sga_with_timeout(Rosenbrock, params.clone(), config, 0, None)
+            .execution_time
+    });
+    let es_time = es_grid.get(es_grid.len() / 2).map_or(0.0, |params| {
+        TimeoutRunner::run_es_with_timeout(Rosenbrock, params.clone(), config, 0, None)
+            .execution_time
+    });
+    println!("  SGA run: {sga_time:.2}s, ES run: {es_time:.2}s");
+
+    let worst = total_runs.div_ceil(workers) as f64 * config.timeout_seconds as f64;
+    let expected = (sga_runs as f64 * sga_time + es_runs as f64 * es_time) / workers as f64;
+    let minutes = |seconds: f64| seconds / 60.0;
+    match config.total_budget_seconds {
+        Some(budget) => println!(
+            "Wall-clock time: about {:.1} minutes expected, {:.1} at worst, cut to the {:.1} \
+             minute budget",
+            minutes(expected),
+            minutes(worst),
+            minutes(budget as f64)
+        ),
+        None => println!(
+            "Wall-clock time: about {:.1} minutes expected, {:.1} at worst ({}s timeout per run)",
+            minutes(expected),
+            minutes(worst),
+            config.timeout_seconds
+        ),
+    }
+
+    let output = args.resume.as_deref().unwrap_or(&args.output);
+    let stem = Path::new(output).with_extension("").display().to_string();
+    println!("Would write:");
+    println!("  {output} (results, one row per run)");
+    println!("  {stem}_combinations.csv (per-combination statistics)");
+    if args.format == OutputFormat::Json {
+        println!("  {stem}.json and {stem}_analysis.json (results and analysis)");
+    }
+    if let Some(dir) = &args.plot {
+        println!("  {dir}/<algorithm>_<combination>_run<run>.csv traces and a PNG per combination");
+    }
+    if !config.design_matrices.is_empty() {
+        println!("  {stem}_design.csv (coded design matrix)");
+    }
+    println!("  {stem}_main_effects.csv and a plot per algorithm (mean score by parameter level)");
+    println!("  {stem}_heatmap_<algorithm>_<row>_x_<column>.csv and .png per parameter pair");
+    println!("  {} (crash recovery, removed when the session ends)", checkpoint::path_for(output));
+    Ok(())
+}
+
+// Writes the session's combinations to a shared queue for `tune-worker`s on other machines
+fn queue_tuning(
+    dir: &str,
+    kind: SearchKind,
+    search: &SampledSearch,
+    grid: Option<&GridSpec>,
+    config: &mut TuningConfig,
+) -> Result<(), String> {
+    let (sga_grid, es_grid) =
+        tuning_grids(kind, search, grid, config).map_err(|e| format!("Error: {e}"))?;
+    let jobs = WorkQueue::new(dir)
+        .create(&sga_grid, &es_grid, config)
+        .map_err(|e| format!("Error queueing jobs in {dir}: {e}"))?;
+    save_design(&format!("{dir}/design.csv"), config);
+    println!(
+        "Queued {jobs} jobs ({} SGA, {} ES combinations, {} design) in {dir}",
+        sga_grid.len(),
+        es_grid.len(),
+        config.design
+    );
+    println!("Start workers with `tune-worker {dir}`, then collect with `merge {dir}`");
+    Ok(())
+}
+
+// Claims and runs jobs from a shared queue until none are left, failing if any job did
+fn run_tune_worker(args: &TuneWorkerArgs) -> Result<(), String> {
+    let queue = WorkQueue::new(&args.dir);
+    let num_workers = args.jobs.unwrap_or_else(|| TuningConfig::default().num_workers);
+    let (mut completed, mut failed) = (0, 0);
+    loop {
+        let job = queue
+            .claim()
+            .map_err(|e| format!("Error claiming a job from {}: {e}", args.dir))?;
+        let Some(job) = job else {
+            break;
+        };
+        let (algorithm, combination) = (&job.algorithm, job.combination + 1);
+        println!(
+            "Running {algorithm} combination {combination}/{} ({} runs, {} second timeout)",
+            job.grid_len, job.num_runs, job.timeout_seconds
+        );
+        match queue.complete(&job, Rosenbrock, num_workers) {
+            Ok(results) => {
+                let best = results.iter().map(|r| r.max_fitness).fold(f64::NEG_INFINITY, f64::max);
+                println!("Finished {algorithm} combination {combination} (best {best:.6})");
+                completed += 1;
+            }
+            Err(e) => {
+                eprintln!("Error running {algorithm} combination {combination}: {e}");
+                failed += 1;
+            }
+        }
+    }
+    println!("No jobs left in {}, {completed} completed by this worker", args.dir);
+    match failed {
+        0 => Ok(()),
+        _ => Err(format!("Error: {failed} jobs failed on this worker")),
+    }
+}
+
+// Collects the results of a queued session into one results CSV
+fn run_merge(args: &MergeArgs) -> Result<(), String> {
+    let queue = WorkQueue::new(&args.dir);
+    if args.requeue {
+        let jobs = queue.requeue_claimed().map_err(|e| format!("Error requeueing jobs: {e}"))?;
+        println!("Requeued {jobs} claimed jobs");
+    }
+    let status = queue.status().map_err(|e| format!("Error reading queue {}: {e}", args.dir))?;
+    println!(
+        "{} jobs done, {} claimed, {} pending",
+        status.done, status.claimed, status.pending
+    );
+    let results = queue.merge().map_err(|e| format!("Error merging results: {e}"))?;
+    ResultsAnalyzer::save_results_to_csv(&results, &args.output)
+        .map_err(|e| format!("Error saving results: {e}"))?;
+    println!("Merged {} runs into {}", results.len(), args.output);
+    Ok(())
+}
+
+// Tunes as asked, unless an earlier session writing the same results crashed, in which case
+// that session is recovered from its checkpoint instead
+fn run_tuning(args: &TuneArgs) -> Result<(), String> {
+    if args.resume.is_none() && args.queue.is_none() && !args.fresh {
+        let path = checkpoint::path_for(&args.output);
+        match SessionSnapshot::load(&path) {
+            Ok(Some(snapshot)) => match recovered_session(&snapshot) {
+                Some(recovered) => {
+                    println!(
+                        "Recovering the interrupted session from {path} ({} of {} runs done, \
+                         checkpointed {}s ago); pass --fresh to start over",
+                        snapshot.completed_runs,
+                        snapshot.total_runs,
+                        snapshot.age()
+                    );
+                    return run_parameter_tuning(&recovered, snapshot.args);
+                }
+                None => eprintln!("Error: {path} doesn't hold a tuning session, ignoring it"),
+            },
+            Ok(None) => {}
+            Err(e) => eprintln!("Error reading checkpoint {path}: {e}"),
+        }
+    }
+    run_parameter_tuning(args, std::env::args().collect())
+}
+
+// The checkpointed command line, resuming from the results it was writing
+fn recovered_session(snapshot: &SessionSnapshot) -> Option<TuneArgs> {
+    let Some(Command::Tune(mut args)) = Cli::try_parse_from(&snapshot.args).ok()?.command else {
+        return None;
+    };
+    if args.resume.is_none() {
+        args.resume = Some(snapshot.results.clone());
+    }
+    Some(args)
+}
+
+// argv is the command line that starts the session, saved in its checkpoints
+fn run_parameter_tuning(args: &TuneArgs, argv: Vec<String>) -> Result<(), String> {
     println!("Starting parameter tuning...");
     let start_time = Instant::now();
     
-    let config = TuningConfig::default();
-    let mut all_results = Vec::new();
-    
-    // Generate parameter grids
-    let sga_grid = ParameterGrid::generate_sga_grid();
-    let es_grid = ParameterGrid::generate_es_grid();
-    
-    println!("Generated {} SGA parameter combinations", sga_grid.len());
-    println!("Generated {} ES parameter combinations", es_grid.len());
-    println!("Each will be tested {} times with {} second timeout", config.num_runs, config.timeout_seconds);
+    let mut config = TuningConfig {
+        seeds: SeedSequence::new(args.seed),
+        ..TuningConfig::default()
+    };
+    if let Some(jobs) = args.jobs {
+        config.num_workers = jobs;
+    }
+    if let Some(num_runs) = args.runs {
+        config.num_runs = num_runs;
+    }
+    if let Some(timeout_seconds) = args.timeout {
+        config.timeout_seconds = timeout_seconds;
+    }
+    config.total_budget_seconds = args.total_budget;
+    if let Some(target_fitness) = args.target {
+        config.target_fitness = target_fitness;
+    }
+    if let Some(success_epsilon) = args.success_epsilon {
+        config.success_epsilon = success_epsilon;
+    }
+    if let Some(cluster_radius) = args.cluster_radius {
+        config.cluster_radius = cluster_radius;
+    }
+    let sampling = match args.search {
+        SearchKind::Grid | SearchKind::Bayes => None,
+        SearchKind::Fractional | SearchKind::PlackettBurman => None,
+        SearchKind::Random => Some(Sampling::Random),
+        SearchKind::Lhs => Some(Sampling::LatinHypercube),
+        SearchKind::Sobol => Some(Sampling::Sobol),
+    };
+    let search = SampledSearch {
+        sampling: sampling.unwrap_or(Sampling::Random),
+        samples: args.samples,
+        seed: args.search_seed,
+        ..SampledSearch::default()
+    };
+    let grid = match &args.grid {
+        Some(_) if sampling.is_some() || args.search == SearchKind::Bayes => {
+            return Err("Error: --grid gives the parameter values itself, so it needs --search \
+                        grid, fractional or plackett-burman"
+                .to_string());
+        }
+        Some(path) => {
+            Some(GridSpec::load(path).map_err(|e| format!("Error loading grid {path}: {e}"))?)
+        }
+        None => None,
+    };
     
-    // Test SGA parameters
-    println!("\n=== Testing SGA Parameters ===");
-    for (i, params) in sga_grid.iter().enumerate() {
-        println!("Testing SGA combination {}/{}: pop_size={}, mutation_rate={:.3}", 
-            i + 1, sga_grid.len(), params.pop_size, params.mutation_rate);
-        
-        for run in 0..config.num_runs {
-            let result = TimeoutRunner::run_sga_with_timeout(
-                Rosenbrock,
-                params.clone(),
-                &config,
-                run,
-            );
-            all_results.push(result);
+    if let Some(dir) = &args.queue {
+        // Bayesian optimization picks each combination from the results of the last ones
+        if args.search == SearchKind::Bayes {
+            return Err("Error: --queue needs a design fixed up front, not --search bayes".to_string());
+        }
+        return queue_tuning(dir, args.search, &search, grid.as_ref(), &mut config);
+    }
+    if args.dry_run {
+        return dry_run_tuning(args, &search, grid.as_ref(), &mut config);
+    }
+
+    // Plotting needs a trace of every run
+    if let Some(dir) = args.plot.clone() {
+        match std::fs::create_dir_all(&dir) {
+            Ok(()) => config.trace_dir = Some(dir),
+            Err(e) => eprintln!("Error creating plot directory {dir}: {e}"),
         }
     }
     
-    // Test ES parameters
-    println!("\n=== Testing ES Parameters ===");
-    for (i, params) in es_grid.iter().enumerate() {
-        println!("Testing ES combination {}/{}: lambda={}, sigma={:.3}", 
-            i + 1, es_grid.len(), params.lambda, params.sigma);
+    // Results go to the file as each run finishes, so an interrupted session can be resumed
+    let output = args.resume.as_deref().unwrap_or(&args.output);
+    let writer = match &args.resume {
+        Some(path) => match ResultsAnalyzer::load_results_from_csv(path) {
+            Ok(completed) => {
+                println!("Resuming from {path} ({} runs completed)", completed.len());
+                config.completed = completed;
+                ResultsWriter::append(path)
+            }
+            Err(e) => return Err(format!("Error loading results: {e}")),
+        },
+        None => ResultsWriter::create(output).map_err(Into::into),
+    };
+    match writer {
+        Ok(writer) => config.results_writer = Some(Mutex::new(writer)),
+        Err(e) => eprintln!("Error opening results {output}: {e}"),
+    }
+    config.checkpoint = Some(Checkpointer::new(
+        output,
+        argv,
+        Duration::from_secs(args.checkpoint_interval),
+        config.num_runs,
+        config.completed.clone(),
+    ));
+    
+    let (sga_combinations, es_combinations, all_results) = if args.search == SearchKind::Bayes {
+        config.design = "bayes".to_string();
+        let tuner = BayesianTuner::new(search);
+        println!(
+            "Tuning {} SGA and {} ES parameter combinations by Bayesian optimization ({} initial)",
+            args.samples, args.samples, tuner.initial.min(args.samples)
+        );
+        println!("Each will be tested {} times with {} second timeout", config.num_runs, config.timeout_seconds);
+        println!("Running on {} worker threads", config.num_workers);
+        config.progress = TuningProgress::new(2 * args.samples * config.num_runs);
+        if let Some(checkpoint) = &config.checkpoint {
+            checkpoint.expect_runs(2 * args.samples * config.num_runs);
+        }
+        let results = tuner.run(Rosenbrock, &config);
+        (args.samples, args.samples, results)
+    } else {
+        let grids = tuning_grids(args.search, &search, grid.as_ref(), &mut config);
+        let (sga_grid, es_grid) = grids.map_err(|e| format!("Error: {e}"))?;
+        let stem = Path::new(output).with_extension("").display().to_string();
+        save_design(&format!("{stem}_design.csv"), &config);
         
-        for run in 0..config.num_runs {
-            let result = TimeoutRunner::run_es_with_timeout(
-                Rosenbrock,
-                params.clone(),
-                &config,
-                run,
-            );
-            all_results.push(result);
+        println!("Generated {} SGA parameter combinations ({} design)", sga_grid.len(), config.design);
+        println!("Generated {} ES parameter combinations ({} design)", es_grid.len(), config.design);
+        println!("Each will be tested {} times with {} second timeout", config.num_runs, config.timeout_seconds);
+        println!("Running on {} worker threads", config.num_workers);
+        
+        // Test SGA and ES parameters
+        let total_runs = (sga_grid.len() + es_grid.len()) * config.num_runs;
+        config.progress = TuningProgress::new(total_runs);
+        if let Some(checkpoint) = &config.checkpoint {
+            checkpoint.plan(&sga_grid, &es_grid);
         }
-    }
+        let results = TimeoutRunner::run_grid(Rosenbrock, &sga_grid, &es_grid, &config);
+        (sga_grid.len(), es_grid.len(), results)
+    };
     
     // Save results to files
+    config.progress.finish();
+    let planned_runs = (sga_combinations + es_combinations) * config.num_runs;
+    if all_results.len() < planned_runs {
+        println!(
+            "Session budget of {}s spent: {} of {planned_runs} runs done, --resume {output} to run \
+             the rest",
+            config.total_budget_seconds.unwrap_or_default(),
+            all_results.len()
+        );
+    }
+    
+    // Rewritten in run order now that every run is in
     println!("\n=== Saving Results ===");
-    if let Err(e) = ResultsAnalyzer::save_results_to_csv(&all_results, "tuning_results.csv") {
-        eprintln!("Error saving results: {e}");
-    } else {
-        println!("Results saved to tuning_results.csv");
+    config.results_writer = None;
+    let saved = ResultsAnalyzer::save_results_to_csv(&all_results, output)
+        .map_err(|e| format!("Error saving results: {e}"));
+    if saved.is_ok() {
+        println!("Results saved to {output}");
+        if let Some(checkpoint) = config.checkpoint.take() {
+            checkpoint.finish();
+        }
+    }
+    let stem = Path::new(output).with_extension("").display().to_string();
+    let combinations_path = format!("{stem}_combinations.csv");
+    match ResultsAnalyzer::save_combinations_to_csv(&all_results, &combinations_path) {
+        Ok(()) => println!("Per-combination statistics saved to {combinations_path}"),
+        Err(e) => eprintln!("Error saving combination statistics: {e}"),
     }
     
     // Analyze results
@@ -161,8 +2894,93 @@ fn run_parameter_tuning() {
     
     // Print summary
     ResultsAnalyzer::print_summary(&sga_analysis, &es_analysis);
+    save_main_effects(&stem, &sga_analysis, &es_analysis);
+    save_heatmaps(&stem, &sga_analysis, &es_analysis);
+    
+    // A failed save fails the session, but only once the analysis has been printed
+    let saved = saved.and_then(|()| match args.format {
+        OutputFormat::Json => save_json(output, &all_results, &sga_analysis, &es_analysis),
+        OutputFormat::Csv => Ok(()),
+    });
+
+    if let Some(dir) = &config.trace_dir {
+        let plotted = plots::plot_tuning_traces(&config, "SGA", sga_combinations)
+            .and_then(|()| plots::plot_tuning_traces(&config, "ES", es_combinations));
+        match plotted {
+            Ok(()) => println!("Convergence plots saved to {dir}"),
+            Err(e) => eprintln!("Error plotting tuning traces: {e}"),
+        }
+    }
     
     let total_time = start_time.elapsed();
     println!("\nTotal tuning time: {:.2} minutes", total_time.as_secs_f64() / 60.0);
     println!("Total runs completed: {}", all_results.len());
+    saved
+}
+
+// Reruns the SGA and ES parameter sets the effects analysis of a tuning session recommends,
+// with more runs and a longer timeout, and writes a report comparing the reruns with the
+// tuning runs
+fn run_validation(args: &ValidateArgs) -> Result<(), String> {
+    let tuning = ResultsAnalyzer::load_results_from_csv(&args.results)
+        .map_err(|e| format!("Error loading results: {e}"))?;
+    let sga_analysis = ResultsAnalyzer::analyze_sga_results(&tuning);
+    let es_analysis = ResultsAnalyzer::analyze_es_results(&tuning);
+    let sga_grid: Vec<GAParameters> = sga_analysis
+        .recommended_params
+        .iter()
+        .map(|params| GAParameters {
+            max_iters: params.max_iters * args.budget_factor,
+            ..params.clone()
+        })
+        .collect();
+    let es_grid: Vec<ESParameters> = es_analysis
+        .recommended_params
+        .iter()
+        .map(|params| ESParameters {
+            max_gens: params.max_gens * args.budget_factor,
+            ..params.clone()
+        })
+        .collect();
+    if sga_grid.is_empty() && es_grid.is_empty() {
+        return Err(format!("No recommended parameters in {}", args.results));
+    }
+
+    let mut config = TuningConfig {
+        num_runs: args.runs,
+        timeout_seconds: args.timeout,
+        design: "validation".to_string(),
+        seeds: SeedSequence::new(args.seed),
+        ..TuningConfig::default()
+    };
+    if let Some(jobs) = args.jobs {
+        config.num_workers = jobs;
+    }
+    match ResultsWriter::create(&args.output) {
+        Ok(writer) => config.results_writer = Some(Mutex::new(writer)),
+        Err(e) => eprintln!("Error opening results {}: {e}", args.output),
+    }
+    println!(
+        "Validating {} SGA and {} ES parameter sets from {}",
+        sga_grid.len(),
+        es_grid.len(),
+        args.results
+    );
+    println!(
+        "Each will be run {} times with {} times the generations and {} second timeout",
+        config.num_runs, args.budget_factor, config.timeout_seconds
+    );
+    config.progress = TuningProgress::new((sga_grid.len() + es_grid.len()) * config.num_runs);
+    let results = TimeoutRunner::run_grid(Rosenbrock, &sga_grid, &es_grid, &config);
+    config.progress.finish();
+
+    config.results_writer = None;
+    ResultsAnalyzer::save_results_to_csv(&results, &args.output)
+        .map_err(|e| format!("Error saving results: {e}"))?;
+    println!("Results saved to {}", args.output);
+    let budget = (args.budget_factor, args.timeout);
+    let report = Report::validation(&sga_analysis, &es_analysis, &results, &args.results, budget);
+    report.save(&args.report).map_err(|e| format!("Error writing report: {e}"))?;
+    println!("Validation report saved to {}", args.report);
+    Ok(())
 }
@@ -0,0 +1,414 @@
+use crate::algorithm::Algorithm;
+use crate::anova::Heatmap;
+use crate::cache::CacheStats;
+use crate::fitness::Objective;
+use crate::logging::{qd_trace_observer, status_observer, trace_observer};
+use crate::observer::{ConsoleObserver, GenerationStats, Observer, RunSummary, mean_and_std};
+use crate::stopping::{StopMonitor, StopReason, StoppingCriteria};
+use rand::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+// Where a member sits in behaviour space, one value per axis of the archive. Values outside an
+// axis's range land in its edge cells.
+pub trait Descriptor {
+    // Name and range of each axis
+    fn axes(&self) -> Vec<(String, (f64, f64))>;
+
+    fn describe(&self, member: &[f64]) -> Vec<f64>;
+}
+
+// The first genes themselves, so the archive keeps the best member found in each region of
+// the first dimensions of the search space
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GenePosition {
+    pub genes: usize,
+    pub range: (f64, f64),
+}
+
+impl Descriptor for GenePosition {
+    fn axes(&self) -> Vec<(String, (f64, f64))> {
+        (0..self.genes).map(|gene| (format!("x{gene}"), self.range)).collect()
+    }
+
+    fn describe(&self, member: &[f64]) -> Vec<f64> {
+        member[..self.genes].to_vec()
+    }
+}
+
+// Quality-diversity measures of an archive at one point of a run
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct QdMetrics {
+    // Fraction of the cells holding an elite
+    pub coverage: f64,
+    // Sum of the elites' fitnesses. The problems here score in (0, 1], so empty cells count
+    // as zero without an offset.
+    pub qd_score: f64,
+    // Best fitness in the archive, NaN while it is empty
+    pub max_fitness: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Axis {
+    pub name: String,
+    pub low: f64,
+    pub high: f64,
+}
+
+// The best member found in one cell
+#[derive(Debug, Clone, Serialize)]
+pub struct Elite {
+    // Index along each axis
+    pub cell: Vec<usize>,
+    pub descriptor: Vec<f64>,
+    pub fitness: f64,
+    pub member: Vec<f64>,
+}
+
+// A grid of cells_per_axis cells along each behaviour axis, each keeping the fittest member
+// whose descriptor fell in it
+#[derive(Debug, Clone)]
+pub struct Archive {
+    pub axes: Vec<Axis>,
+    pub cells_per_axis: usize,
+    elites: Vec<Elite>,
+    // Position in `elites` of each filled cell
+    index: HashMap<Vec<usize>, usize>,
+}
+
+impl Archive {
+    pub fn new(axes: Vec<(String, (f64, f64))>, cells_per_axis: usize) -> Self {
+        let axes = axes.into_iter().map(|(name, (low, high))| Axis { name, low, high }).collect();
+        Self { axes, cells_per_axis, elites: Vec::new(), index: HashMap::new() }
+    }
+
+    // Cell of a descriptor: each value's share of its axis's range, cut into cells_per_axis
+    pub fn cell(&self, descriptor: &[f64]) -> Vec<usize> {
+        let cells = self.cells_per_axis.max(1);
+        self.axes
+            .iter()
+            .zip(descriptor)
+            .map(|(axis, &value)| {
+                let fraction = (value - axis.low) / (axis.high - axis.low);
+                ((fraction * cells as f64).floor().max(0.0) as usize).min(cells - 1)
+            })
+            .collect()
+    }
+
+    // Keeps the member if its cell is empty or holds a less fit elite, returning whether it did
+    pub fn insert(&mut self, member: Vec<f64>, fitness: f64, descriptor: Vec<f64>) -> bool {
+        let cell = self.cell(&descriptor);
+        match self.index.get(&cell) {
+            Some(&i) if self.elites[i].fitness >= fitness => false,
+            Some(&i) => {
+                self.elites[i] = Elite { cell, descriptor, fitness, member };
+                true
+            }
+            None => {
+                self.index.insert(cell.clone(), self.elites.len());
+                self.elites.push(Elite { cell, descriptor, fitness, member });
+                true
+            }
+        }
+    }
+
+    // In the order their cells were first filled
+    pub fn elites(&self) -> &[Elite] {
+        &self.elites
+    }
+
+    pub fn best(&self) -> Option<&Elite> {
+        self.elites.iter().max_by(|a, b| a.fitness.total_cmp(&b.fitness))
+    }
+
+    pub fn total_cells(&self) -> usize {
+        self.cells_per_axis.pow(self.axes.len() as u32)
+    }
+
+    pub fn metrics(&self) -> QdMetrics {
+        QdMetrics {
+            coverage: self.elites.len() as f64 / self.total_cells().max(1) as f64,
+            qd_score: self.elites.iter().map(|elite| elite.fitness).sum(),
+            max_fitness: self.best().map_or(f64::NAN, |elite| elite.fitness),
+        }
+    }
+
+    // Best fitness in each cell of two axes, over the cells of any others. Levels are the
+    // centres of the cells.
+    pub fn heatmap(&self, row: usize, column: usize) -> Heatmap {
+        let cells = self.cells_per_axis;
+        let centres = |axis: &Axis| -> Vec<f64> {
+            let width = (axis.high - axis.low) / cells as f64;
+            (0..cells).map(|i| axis.low + (i as f64 + 0.5) * width).collect()
+        };
+        let mut means = vec![vec![None; cells]; cells];
+        for elite in &self.elites {
+            let best: &mut Option<f64> = &mut means[elite.cell[row]][elite.cell[column]];
+            if best.is_none_or(|best| elite.fitness > best) {
+                *best = Some(elite.fitness);
+            }
+        }
+        Heatmap {
+            response: "fitness".to_string(),
+            row: self.axes[row].name.clone(),
+            column: self.axes[column].name.clone(),
+            row_levels: centres(&self.axes[row]),
+            column_levels: centres(&self.axes[column]),
+            means,
+        }
+    }
+
+    // The axes, the metrics and every elite as one JSON object
+    pub fn save_json(&self, path: &str) -> io::Result<()> {
+        #[derive(Serialize)]
+        struct Export<'a> {
+            axes: &'a [Axis],
+            cells_per_axis: usize,
+            #[serde(flatten)]
+            metrics: QdMetrics,
+            elites: &'a [Elite],
+        }
+        let export = Export {
+            axes: &self.axes,
+            cells_per_axis: self.cells_per_axis,
+            metrics: self.metrics(),
+            elites: &self.elites,
+        };
+        let mut file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut file, &export)?;
+        writeln!(file)?;
+        file.flush()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MapElitesParameters {
+    pub mem_size: usize,
+    pub mem_range: (f64, f64),
+    pub cells_per_axis: usize,
+    // Random members evaluated to seed the archive before any are bred from it
+    pub initial_samples: usize,
+    // Members bred per generation, each a mutated copy of an elite picked uniformly at random
+    pub batch_size: usize,
+    // Standard deviation of the Gaussian mutation of each gene
+    pub sigma: f64,
+    pub max_gens: usize,
+    pub stopping: StoppingCriteria,
+    // Writes the run as JSON lines to this file in place of the console status lines
+    pub log_path: Option<String>,
+    // Seed the caller's RNG was created from, recorded in the run log for replay
+    pub seed: Option<u64>,
+    // Writes a CSV of per-generation metrics to this file, alongside the other output
+    pub trace_path: Option<String>,
+    // Writes a CSV of coverage, QD-score and archive max fitness per generation to this file
+    pub qd_trace_path: Option<String>,
+}
+
+impl Default for MapElitesParameters {
+    fn default() -> Self {
+        Self {
+            mem_size: 2,
+            mem_range: (-5.12, 5.11),
+            cells_per_axis: 10,
+            initial_samples: 100,
+            batch_size: 100,
+            sigma: 0.5,
+            max_gens: 1000,
+            stopping: StoppingCriteria::default(),
+            log_path: None,
+            seed: None,
+            trace_path: None,
+            qd_trace_path: None,
+        }
+    }
+}
+
+impl MapElitesParameters {
+    // Parameters as recorded in the run log
+    fn to_map(&self) -> HashMap<String, f64> {
+        HashMap::from([
+            ("mem_size".to_string(), self.mem_size as f64),
+            ("cells_per_axis".to_string(), self.cells_per_axis as f64),
+            ("initial_samples".to_string(), self.initial_samples as f64),
+            ("batch_size".to_string(), self.batch_size as f64),
+            ("sigma".to_string(), self.sigma),
+            ("max_gens".to_string(), self.max_gens as f64),
+        ])
+    }
+}
+
+// MAP-Elites: an archive of the fittest member in each cell of a grid over behaviour space,
+// seeded with random members and grown by mutating elites picked at random. It searches for
+// many good members spread over the descriptor's space rather than for one optimum. The
+// generation statistics are those of the elites, with the archive's QD metrics alongside,
+// and the final "population" is every elite.
+pub struct MapElites<'a, F: Objective, D: Descriptor> {
+    fitness_fn: &'a F,
+    descriptor: &'a D,
+    params: &'a MapElitesParameters,
+    archive: Archive,
+    generation: usize,
+    evaluations: usize,
+    monitor: StopMonitor,
+    // Cache counts when the run started, as the cache may outlive it
+    cache_start: Option<CacheStats>,
+    observers: Vec<Box<dyn Observer<Vec<f64>> + 'a>>,
+}
+
+impl<'a, F: Objective, D: Descriptor> MapElites<'a, F, D> {
+    pub fn new<R: Rng + ?Sized>(
+        fitness_fn: &'a F,
+        descriptor: &'a D,
+        params: &'a MapElitesParameters,
+        rng: &mut R,
+    ) -> Self {
+        let mut observers = vec![status_observer(
+            params.log_path.as_deref(),
+            "MAP-Elites",
+            params.to_map(),
+            params.seed,
+            ConsoleObserver::map_elites(params),
+        )];
+        if let Some(trace) = trace_observer(params.trace_path.as_deref()) {
+            observers.push(Box::new(trace));
+        }
+        if let Some(trace) = qd_trace_observer(params.qd_trace_path.as_deref()) {
+            observers.push(Box::new(trace));
+        }
+        let cache_start = fitness_fn.cache_stats();
+        let (low, high) = params.mem_range;
+        let members: Vec<Vec<f64>> = (0..params.initial_samples.max(1))
+            .map(|_| (0..params.mem_size).map(|_| rng.random_range(low..high)).collect())
+            .collect();
+        let mut map_elites = Self {
+            fitness_fn,
+            descriptor,
+            params,
+            archive: Archive::new(descriptor.axes(), params.cells_per_axis),
+            generation: 0,
+            evaluations: 0,
+            monitor: StopMonitor::new(&params.stopping, fitness_fn.optimum_value()),
+            cache_start,
+            observers,
+        };
+        map_elites.add(members);
+        map_elites
+    }
+
+    // Registers an observer alongside the console output
+    pub fn with_observer(mut self, observer: impl Observer<Vec<f64>> + 'a) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    // Removes all observers, including the console output, for runs driven in bulk
+    pub fn quiet(mut self) -> Self {
+        self.observers.clear();
+        self
+    }
+
+    pub fn archive(&self) -> &Archive {
+        &self.archive
+    }
+
+    // Evaluates members as one batch and offers each to the archive
+    fn add(&mut self, members: Vec<Vec<f64>>) {
+        let fitnesses = self.fitness_fn.evaluate_population(&members);
+        self.evaluations += members.len();
+        for (member, fitness) in members.into_iter().zip(fitnesses) {
+            let descriptor = self.descriptor.describe(&member);
+            self.archive.insert(member, fitness, descriptor);
+        }
+    }
+}
+
+impl<F: Objective, D: Descriptor> Algorithm for MapElites<'_, F, D> {
+    type Individual = Vec<f64>;
+
+    fn step<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<StopReason> {
+        if self.generation >= self.params.max_gens {
+            return Some(StopReason::MaxGenerations);
+        }
+        let (low, high) = self.params.mem_range;
+        let elites = self.archive.elites();
+        let offspring: Vec<Vec<f64>> = (0..self.params.batch_size)
+            .map(|_| {
+                let parent = &elites[rng.random_range(0..elites.len())].member;
+                parent
+                    .iter()
+                    .map(|gene| {
+                        let step: f64 = rng.sample(rand_distr::StandardNormal);
+                        (gene + self.params.sigma * step).clamp(low, high)
+                    })
+                    .collect()
+            })
+            .collect();
+        self.add(offspring);
+        self.generation += 1;
+
+        let fitnesses: Vec<f64> =
+            self.archive.elites().iter().map(|elite| elite.fitness).collect();
+        let (avg_fitness, std_fitness) = mean_and_std(&fitnesses);
+        let metrics = self.archive.metrics();
+        let stats = GenerationStats {
+            generation: self.generation,
+            evaluations: self.evaluations,
+            max_fitness: metrics.max_fitness,
+            avg_fitness,
+            std_fitness,
+            diversity: None,
+            cache: self.fitness_fn.cache_stats().map(|stats| stats.since(self.cache_start)),
+            boundary_hits: None,
+            violations: None,
+            qd: Some(metrics),
+        };
+        let improved = metrics.max_fitness > self.monitor.best_fitness;
+        if let Some(best) = self.archive.best() {
+            for observer in &mut self.observers {
+                observer.on_generation(&stats, &best.member);
+                if improved {
+                    observer.on_improvement(&stats, &best.member);
+                }
+            }
+        }
+        self.monitor.update(metrics.max_fitness)
+    }
+
+    fn generation(&self) -> usize {
+        self.generation
+    }
+
+    fn best_fitness(&self) -> f64 {
+        self.monitor.best_fitness
+    }
+
+    fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+
+    fn finish(mut self, reason: StopReason) -> Vec<Vec<f64>> {
+        let summary = RunSummary {
+            reason,
+            generations: self.generation,
+            evaluations: self.evaluations,
+            best_fitness: self.monitor.best_fitness,
+            stagnant_generations: self.monitor.stagnant_generations,
+        };
+        let metrics = self.archive.metrics();
+        let message = format!(
+            "Archive: {} of {} cells filled ({:.1}% coverage), QD-score {:.4}",
+            self.archive.elites().len(),
+            self.archive.total_cells(),
+            metrics.coverage * 100.0,
+            metrics.qd_score
+        );
+        let best = self.archive.best().map(|elite| elite.member.clone());
+        for observer in &mut self.observers {
+            observer.on_message(&message);
+            observer.on_termination(&summary, best.as_ref());
+        }
+        self.archive.elites.into_iter().map(|elite| elite.member).collect()
+    }
+}
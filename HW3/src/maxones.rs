@@ -0,0 +1,26 @@
+use crate::decoder::{Decoder, PerBit};
+use crate::fitness::{Fitness, Objective};
+
+pub struct MaxOnes;
+
+impl Objective for MaxOnes {
+    // Fraction of genes that are "on" (>= 0.5), so real-valued genomes work too
+    fn fitness(&self, member: &[f64]) -> f64 {
+        let ones = member.iter().filter(|&&gene| gene >= 0.5).count() as f64;
+        ones / member.len() as f64
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+
+    fn optimum_value(&self) -> Option<f64> {
+        Some(1.0)
+    }
+}
+
+impl Fitness for MaxOnes {
+    fn decoder(&self) -> impl Decoder {
+        PerBit
+    }
+}
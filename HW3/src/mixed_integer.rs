@@ -0,0 +1,148 @@
+use crate::fitness::Objective;
+use rand::prelude::*;
+use rand_distr::StandardNormal;
+
+// Type and range of one gene of a mixed-integer ES member. All genes are stored as f64;
+// integer genes hold whole numbers and categorical genes the index of their category.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GeneType {
+    Continuous { min: f64, max: f64 },
+    Integer { min: i64, max: i64 },
+    // Unordered choices 0..choices, such as a material, where neighbouring indices mean
+    // nothing
+    Categorical { choices: usize },
+}
+
+impl GeneType {
+    fn random<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        match *self {
+            GeneType::Continuous { min, max } => rng.random_range(min..max),
+            GeneType::Integer { min, max } => rng.random_range(min..=max) as f64,
+            GeneType::Categorical { choices } => rng.random_range(0..choices.max(1)) as f64,
+        }
+    }
+
+    // The nearest valid value, for genes recombined or mutated past their range
+    fn snap(&self, value: f64) -> f64 {
+        match *self {
+            GeneType::Continuous { min, max } => value.clamp(min, max),
+            GeneType::Integer { min, max } => value.round().clamp(min as f64, max as f64),
+            GeneType::Categorical { choices } => value.round().clamp(0.0, choices as f64 - 1.0),
+        }
+    }
+}
+
+// A random member's genes
+pub fn random_genes<R: Rng + ?Sized>(types: &[GeneType], rng: &mut R) -> Vec<f64> {
+    types.iter().map(|gene_type| gene_type.random(rng)).collect()
+}
+
+// Strategy parameters of a new mixed-integer member: the step size, as a fraction of each
+// gene's range, and the probability of changing each categorical gene
+pub fn initial_strategy(types: &[GeneType], sigma: f64) -> Vec<f64> {
+    vec![sigma, 1.0 / categorical_count(types).max(1) as f64]
+}
+
+fn categorical_count(types: &[GeneType]) -> usize {
+    types.iter().filter(|t| matches!(t, GeneType::Categorical { .. })).count()
+}
+
+// Recombined genes made valid again: numeric genes rounded into range, and categorical genes,
+// whose averages mean nothing, taken from the first parent
+pub fn snap_recombined(genes: &mut [f64], parent: &[f64], types: &[GeneType]) {
+    for ((gene, &from_parent), gene_type) in genes.iter_mut().zip(parent).zip(types) {
+        *gene = match gene_type {
+            GeneType::Categorical { .. } => from_parent,
+            _ => gene_type.snap(*gene),
+        };
+    }
+}
+
+// Mixed-integer mutation after Emmerich and Li's MIES. The step size and categorical
+// probability self-adapt first, log-normally and through the logistic function, then
+// continuous genes take a normal step, integer genes a step that is the difference of two
+// geometric variables (Rudolph's maximum-entropy integer analogue of the normal) with the
+// same mean size, and categorical genes switch to another category at random with the new
+// probability. Steps scale with each gene's range, so one step size serves genes of different
+// scales. Returns the child's genes followed by its strategy parameters.
+pub fn mixed_mutation<R: Rng + ?Sized>(
+    genes: &[f64],
+    strategy: &[f64],
+    types: &[GeneType],
+    tau: f64,
+    rng: &mut R,
+) -> Vec<f64> {
+    let (sigma, rate) = (strategy[0], strategy[1]);
+    let sigma = sigma * (tau * rng.sample::<f64, _>(StandardNormal)).exp();
+    let min_rate = 1.0 / (3.0 * categorical_count(types).max(1) as f64);
+    let odds = (1.0 - rate) / rate * (-tau * rng.sample::<f64, _>(StandardNormal)).exp();
+    let rate = (1.0 / (1.0 + odds)).clamp(min_rate, 0.5);
+
+    let mut child: Vec<f64> = genes
+        .iter()
+        .zip(types)
+        .map(|(&gene, gene_type)| match *gene_type {
+            GeneType::Continuous { min, max } => {
+                let step: f64 = rng.sample(StandardNormal);
+                gene_type.snap(gene + sigma * (max - min) * step)
+            }
+            GeneType::Integer { min, max } => {
+                let mean_step = sigma * (max - min) as f64;
+                let p = 1.0 - mean_step / (1.0 + (1.0 + mean_step * mean_step).sqrt());
+                let geometric = |rng: &mut R| {
+                    let u: f64 = rng.random();
+                    ((1.0 - u).ln() / (1.0 - p).ln()).floor()
+                };
+                let step = geometric(rng) - geometric(rng);
+                gene_type.snap(gene + step)
+            }
+            GeneType::Categorical { choices } => {
+                if choices > 1 && rng.random::<f64>() < rate {
+                    // Any category but the current one
+                    let other = rng.random_range(0..choices - 1) as f64;
+                    if other >= gene { other + 1.0 } else { other }
+                } else {
+                    gene
+                }
+            }
+        })
+        .collect();
+    child.push(sigma);
+    child.push(rate);
+    child
+}
+
+// Test problem for the mixed-integer ES from the MIES literature: a sphere over continuous and
+// integer genes plus a unit penalty for every categorical gene off category 0, which only
+// categorical mutation can find since the categories have no order. Fitness is in (0, 1].
+pub struct MixedSphere {
+    pub continuous: usize,
+    pub integer: usize,
+    pub categorical: usize,
+}
+
+impl MixedSphere {
+    pub fn gene_types(&self) -> Vec<GeneType> {
+        let mut types = vec![GeneType::Continuous { min: -5.0, max: 5.0 }; self.continuous];
+        types.extend(vec![GeneType::Integer { min: -10, max: 10 }; self.integer]);
+        types.extend(vec![GeneType::Categorical { choices: 5 }; self.categorical]);
+        types
+    }
+}
+
+impl Objective for MixedSphere {
+    fn fitness(&self, member: &[f64]) -> f64 {
+        let numeric = self.continuous + self.integer;
+        let sphere: f64 = member[..numeric].iter().map(|x| x * x).sum();
+        let off_category = member[numeric..].iter().filter(|&&d| d != 0.0).count() as f64;
+        1.0 / (1.0 + sphere + off_category)
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        (-5.0, 5.0)
+    }
+
+    fn optimum_value(&self) -> Option<f64> {
+        Some(1.0)
+    }
+}
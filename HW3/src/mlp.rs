@@ -0,0 +1,133 @@
+use crate::fitness::{Fitness, Objective};
+use std::error::Error;
+use std::fs;
+
+// Rows of a small CSV dataset: every column but the last is a feature and the last is the
+// target. A first line that doesn't parse as numbers is taken for a header.
+#[derive(Debug, Clone)]
+pub struct Dataset {
+    pub features: Vec<Vec<f64>>,
+    pub targets: Vec<f64>,
+}
+
+impl Dataset {
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let contents = fs::read_to_string(path)?;
+        let (mut features, mut targets) = (Vec::new(), Vec::new());
+        for (i, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let values: Result<Vec<f64>, _> =
+                line.split(',').map(|value| value.trim().parse::<f64>()).collect();
+            let mut values = match values {
+                Ok(values) => values,
+                Err(_) if i == 0 => continue,
+                Err(e) => return Err(format!("line {}: {e}", i + 1).into()),
+            };
+            // Every row as wide as the first, which has a feature and the target at least
+            let columns = features.first().map_or(values.len(), |first: &Vec<f64>| first.len() + 1);
+            if columns < 2 || values.len() != columns {
+                return Err(format!("line {}: expected {} columns", i + 1, columns.max(2)).into());
+            }
+            targets.push(values.pop().unwrap_or(0.0));
+            features.push(values);
+        }
+        if targets.is_empty() {
+            return Err("no rows".into());
+        }
+        Ok(Self { features, targets })
+    }
+
+    pub fn num_features(&self) -> usize {
+        self.features[0].len()
+    }
+
+    // Targets all 0 or 1 make a binary classification task, anything else regression
+    pub fn is_classification(&self) -> bool {
+        self.targets.iter().all(|&target| target == 0.0 || target == 1.0)
+    }
+}
+
+// Weights of a fixed-topology multilayer perceptron, one tanh hidden layer and one output,
+// fitted to a dataset: the genome is the flat weight vector, so the ES trains the network.
+// The output is a sigmoid for classification and linear for regression.
+pub struct Mlp {
+    pub dataset: Dataset,
+    pub hidden: usize,
+    classification: bool,
+}
+
+impl Mlp {
+    pub fn new(dataset: Dataset, hidden: usize) -> Self {
+        let classification = dataset.is_classification();
+        Self { dataset, hidden, classification }
+    }
+
+    pub fn is_classification(&self) -> bool {
+        self.classification
+    }
+
+    // Genes in the genome: each hidden unit's input weights and bias, then the output's
+    // weights and bias
+    pub fn num_weights(&self) -> usize {
+        self.hidden * (self.dataset.num_features() + 1) + self.hidden + 1
+    }
+
+    pub fn predict(&self, weights: &[f64], features: &[f64]) -> f64 {
+        let inputs = features.len();
+        let (hidden_weights, output_weights) = weights.split_at(self.hidden * (inputs + 1));
+        let output: f64 = hidden_weights
+            .chunks(inputs + 1)
+            .zip(output_weights)
+            .map(|(unit, weight)| {
+                let sum: f64 = unit.iter().zip(features).map(|(w, x)| w * x).sum();
+                weight * (sum + unit[inputs]).tanh()
+            })
+            .sum::<f64>()
+            + output_weights[self.hidden];
+        if self.classification { 1.0 / (1.0 + (-output).exp()) } else { output }
+    }
+
+    pub fn mean_squared_error(&self, weights: &[f64]) -> f64 {
+        let dataset = &self.dataset;
+        let total: f64 = dataset
+            .features
+            .iter()
+            .zip(&dataset.targets)
+            .map(|(features, target)| (self.predict(weights, features) - target).powi(2))
+            .sum();
+        total / dataset.targets.len() as f64
+    }
+
+    // Fraction of rows whose prediction falls on the right side of 0.5
+    pub fn accuracy(&self, weights: &[f64]) -> f64 {
+        let dataset = &self.dataset;
+        let correct = dataset
+            .features
+            .iter()
+            .zip(&dataset.targets)
+            .filter(|&(features, &target)| {
+                (self.predict(weights, features) >= 0.5) == (target == 1.0)
+            })
+            .count();
+        correct as f64 / dataset.targets.len() as f64
+    }
+}
+
+impl Objective for Mlp {
+    // Training error converted to fitness in the range (0, 1], higher is better
+    fn fitness(&self, member: &[f64]) -> f64 {
+        1.0 / (1.0 + self.mean_squared_error(member))
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        (-1.0, 1.0)
+    }
+
+    fn optimum_value(&self) -> Option<f64> {
+        Some(1.0)
+    }
+}
+
+impl Fitness for Mlp {}
@@ -0,0 +1,258 @@
+use std::f64::consts::PI;
+
+// A problem with several objectives over a real-valued phenotype. Unlike Fitness, every
+// objective is minimized, as in the literature the test problems below come from, so their
+// fronts can be compared with published ones directly.
+pub trait MultiObjective {
+    // Values of every objective for the member, num_objectives of them
+    fn objectives(&self, member: &[f64]) -> Vec<f64>;
+
+    fn num_objectives(&self) -> usize;
+
+    // Range each variable lies in
+    fn bounds(&self) -> (f64, f64);
+
+    // Objective vectors on the true Pareto front, sampled more densely for a higher
+    // resolution, or empty if the front isn't known
+    fn reference_front(&self, _resolution: usize) -> Vec<Vec<f64>> {
+        Vec::new()
+    }
+}
+
+// Whether a is no worse than b in every objective and better in at least one
+pub fn dominates(a: &[f64], b: &[f64]) -> bool {
+    let mut better = false;
+    for (x, y) in a.iter().zip(b) {
+        if x > y {
+            return false;
+        }
+        better |= x < y;
+    }
+    better
+}
+
+// Deb's fast non-dominated sort: the indices of the points front by front, the first front
+// being the non-dominated points, the second those dominated only by the first, and so on.
+// Within a front the indices are in ascending order.
+pub fn non_dominated_sort(points: &[Vec<f64>]) -> Vec<Vec<usize>> {
+    let mut dominated_by = vec![0; points.len()];
+    let mut dominating: Vec<Vec<usize>> = vec![Vec::new(); points.len()];
+    for i in 0..points.len() {
+        for j in i + 1..points.len() {
+            if dominates(&points[i], &points[j]) {
+                dominating[i].push(j);
+                dominated_by[j] += 1;
+            } else if dominates(&points[j], &points[i]) {
+                dominating[j].push(i);
+                dominated_by[i] += 1;
+            }
+        }
+    }
+    let mut fronts = Vec::new();
+    let mut front: Vec<usize> = (0..points.len()).filter(|&i| dominated_by[i] == 0).collect();
+    while !front.is_empty() {
+        let mut next = Vec::new();
+        for &i in &front {
+            for &j in &dominating[i] {
+                dominated_by[j] -= 1;
+                if dominated_by[j] == 0 {
+                    next.push(j);
+                }
+            }
+        }
+        next.sort_unstable();
+        fronts.push(front);
+        front = next;
+    }
+    fronts
+}
+
+// Mean distance from each point of a front to the nearest point of the reference front, 0.0
+// once the front lies on it
+pub fn generational_distance(front: &[Vec<f64>], reference: &[Vec<f64>]) -> f64 {
+    if front.is_empty() || reference.is_empty() {
+        return f64::INFINITY;
+    }
+    let nearest = |point: &Vec<f64>| {
+        reference
+            .iter()
+            .map(|r| point.iter().zip(r).map(|(a, b)| (a - b).powi(2)).sum::<f64>().sqrt())
+            .fold(f64::INFINITY, f64::min)
+    };
+    front.iter().map(nearest).sum::<f64>() / front.len() as f64
+}
+
+// Evenly spread points with non-negative coordinates summing to 1, every coordinate a multiple
+// of 1 / divisions (Das and Dennis' simplex lattice), as used for reference directions
+pub fn simplex_lattice(dimensions: usize, divisions: usize) -> Vec<Vec<f64>> {
+    fn fill(point: &mut Vec<usize>, left: usize, dimensions: usize, out: &mut Vec<Vec<usize>>) {
+        if point.len() + 1 == dimensions {
+            point.push(left);
+            out.push(point.clone());
+            point.pop();
+            return;
+        }
+        for part in 0..=left {
+            point.push(part);
+            fill(point, left - part, dimensions, out);
+            point.pop();
+        }
+    }
+    if dimensions == 0 {
+        return Vec::new();
+    }
+    let mut points = Vec::new();
+    fill(&mut Vec::new(), divisions, dimensions, &mut points);
+    let divisions = divisions.max(1) as f64;
+    points
+        .into_iter()
+        .map(|point| point.into_iter().map(|part| part as f64 / divisions).collect())
+        .collect()
+}
+
+// Zitzler, Deb and Thiele's two-objective problems share f1 = x1 and a distance function g over
+// the other variables, which is 1 on the front (all of them 0). Variables lie in [0, 1].
+fn zdt(member: &[f64], shape: impl Fn(f64, f64) -> f64) -> Vec<f64> {
+    let f1 = member[0];
+    let rest = &member[1..];
+    let g = 1.0 + 9.0 * rest.iter().sum::<f64>() / rest.len().max(1) as f64;
+    vec![f1, g * shape(f1, g)]
+}
+
+// The front of a ZDT problem: its shape at g = 1 over evenly spaced f1, less the points
+// another one dominates, which leaves only the disconnected pieces of ZDT3
+fn zdt_front(resolution: usize, shape: impl Fn(f64, f64) -> f64) -> Vec<Vec<f64>> {
+    let resolution = resolution.max(2);
+    let points: Vec<Vec<f64>> = (0..resolution)
+        .map(|i| {
+            let f1 = i as f64 / (resolution - 1) as f64;
+            vec![f1, shape(f1, 1.0)]
+        })
+        .collect();
+    let front = non_dominated_sort(&points).swap_remove(0);
+    front.into_iter().map(|i| points[i].clone()).collect()
+}
+
+fn zdt1_shape(f1: f64, g: f64) -> f64 {
+    1.0 - (f1 / g).sqrt()
+}
+
+fn zdt2_shape(f1: f64, g: f64) -> f64 {
+    1.0 - (f1 / g).powi(2)
+}
+
+fn zdt3_shape(f1: f64, g: f64) -> f64 {
+    1.0 - (f1 / g).sqrt() - f1 / g * (10.0 * PI * f1).sin()
+}
+
+// Convex front f2 = 1 - sqrt(f1)
+pub struct Zdt1;
+
+// Concave front f2 = 1 - f1^2
+pub struct Zdt2;
+
+// Front in five disconnected pieces
+pub struct Zdt3;
+
+impl MultiObjective for Zdt1 {
+    fn objectives(&self, member: &[f64]) -> Vec<f64> {
+        zdt(member, zdt1_shape)
+    }
+
+    fn num_objectives(&self) -> usize {
+        2
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+
+    fn reference_front(&self, resolution: usize) -> Vec<Vec<f64>> {
+        zdt_front(resolution, zdt1_shape)
+    }
+}
+
+impl MultiObjective for Zdt2 {
+    fn objectives(&self, member: &[f64]) -> Vec<f64> {
+        zdt(member, zdt2_shape)
+    }
+
+    fn num_objectives(&self) -> usize {
+        2
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+
+    fn reference_front(&self, resolution: usize) -> Vec<Vec<f64>> {
+        zdt_front(resolution, zdt2_shape)
+    }
+}
+
+impl MultiObjective for Zdt3 {
+    fn objectives(&self, member: &[f64]) -> Vec<f64> {
+        zdt(member, zdt3_shape)
+    }
+
+    fn num_objectives(&self) -> usize {
+        2
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+
+    fn reference_front(&self, resolution: usize) -> Vec<Vec<f64>> {
+        zdt_front(resolution, zdt3_shape)
+    }
+}
+
+// Deb, Thiele, Laumanns and Zitzler's DTLZ2 with any number of objectives. The first
+// objectives - 1 variables are angles placing the point on a sphere and the rest set its
+// radius 1 + g, so the front is the positive part of the unit sphere, reached with every
+// variable after the angles at 0.5. Variables lie in [0, 1]; the usual size is objectives + 9.
+pub struct Dtlz2 {
+    pub objectives: usize,
+}
+
+impl MultiObjective for Dtlz2 {
+    fn objectives(&self, member: &[f64]) -> Vec<f64> {
+        let angles = self.objectives - 1;
+        let g: f64 = member[angles..].iter().map(|x| (x - 0.5).powi(2)).sum();
+        (0..self.objectives)
+            .map(|m| {
+                // Objective m takes the cosines of the first angles - m angles and the sine of
+                // the next one, the first objective only cosines
+                let cosines = angles - m;
+                let mut value = 1.0 + g;
+                for x in &member[..cosines] {
+                    value *= (x * PI / 2.0).cos();
+                }
+                if m > 0 {
+                    value *= (member[cosines] * PI / 2.0).sin();
+                }
+                value
+            })
+            .collect()
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.objectives
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+
+    // Simplex lattice directions projected onto the unit sphere
+    fn reference_front(&self, resolution: usize) -> Vec<Vec<f64>> {
+        simplex_lattice(self.objectives, resolution.max(1))
+            .into_iter()
+            .map(|point| {
+                let norm = point.iter().map(|x| x * x).sum::<f64>().sqrt();
+                point.iter().map(|x| x / norm).collect()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,625 @@
+use crate::algorithm::Algorithm;
+use crate::logging::{status_observer, trace_observer};
+use crate::observer::{ConsoleObserver, GenerationStats, Observer, RunSummary, mean_and_std};
+use crate::speciation::{Speciation, SpeciesSet};
+use crate::stopping::{StopMonitor, StopReason, StoppingCriteria};
+use rand::prelude::*;
+use std::collections::HashMap;
+
+// A task NEAT networks are scored on. Fitness must not be negative, as species are given
+// offspring in proportion to it.
+pub trait NetworkObjective {
+    // Values fed to the input nodes
+    fn inputs(&self) -> usize;
+
+    // Values read from the output nodes
+    fn outputs(&self) -> usize;
+
+    fn fitness(&self, network: &NeatGenome) -> f64;
+
+    // Best possible fitness, if known, for the target stopping rule
+    fn optimum_value(&self) -> Option<f64> {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NeatParameters {
+    pub pop_size: usize,
+    // Chance a child's weights are mutated; each weight is then replaced by a new random one
+    // with weight_replace_rate, or else moved by a normal step of size weight_power
+    pub weight_mutation_rate: f64,
+    pub weight_power: f64,
+    pub weight_replace_rate: f64,
+    // Chances a child gains a node splitting one of its connections, or else a connection
+    // between two nodes not yet connected, in place of the weight mutation
+    pub add_node_rate: f64,
+    pub add_connection_rate: f64,
+    // Chance a child is a crossover of two parents rather than a copy of one
+    pub crossover_rate: f64,
+    // Chance a gene disabled in either parent is disabled in the child
+    pub disable_rate: f64,
+    // Compatibility distance c1 * E / N + c2 * D / N + c3 * W, for E excess and D disjoint
+    // genes, N genes in the larger genome (1 below 20 genes) and W the mean weight difference
+    // of the matching genes
+    pub excess_coefficient: f64,
+    pub disjoint_coefficient: f64,
+    pub weight_coefficient: f64,
+    // Threshold on the compatibility distance, and stagnation limit, of the species
+    pub speciation: Speciation,
+    // Fraction of each species, best first, allowed to parent its offspring
+    pub survival_threshold: f64,
+    // Species at least this big pass their champion on unchanged
+    pub champion_species_size: usize,
+    pub max_gens: usize,
+    pub stopping: StoppingCriteria,
+    // Writes the run as JSON lines to this file in place of the console status lines
+    pub log_path: Option<String>,
+    // Seed the caller's RNG was created from, recorded in the run log for replay
+    pub seed: Option<u64>,
+    // Writes a CSV of per-generation metrics to this file, alongside the other output
+    pub trace_path: Option<String>,
+}
+
+// Stanley and Miikkulainen's settings for XOR
+impl Default for NeatParameters {
+    fn default() -> Self {
+        Self {
+            pop_size: 150,
+            weight_mutation_rate: 0.8,
+            weight_power: 2.5,
+            weight_replace_rate: 0.1,
+            add_node_rate: 0.03,
+            add_connection_rate: 0.05,
+            crossover_rate: 0.75,
+            disable_rate: 0.75,
+            excess_coefficient: 1.0,
+            disjoint_coefficient: 1.0,
+            weight_coefficient: 0.4,
+            speciation: Speciation { threshold: 3.0, stagnation_limit: 15 },
+            survival_threshold: 0.2,
+            champion_species_size: 5,
+            max_gens: 100,
+            stopping: StoppingCriteria::default(),
+            log_path: None,
+            seed: None,
+            trace_path: None,
+        }
+    }
+}
+
+impl NeatParameters {
+    // Parameters as recorded in the run log
+    fn to_map(&self) -> HashMap<String, f64> {
+        HashMap::from([
+            ("pop_size".to_string(), self.pop_size as f64),
+            ("add_node_rate".to_string(), self.add_node_rate),
+            ("add_connection_rate".to_string(), self.add_connection_rate),
+            ("crossover_rate".to_string(), self.crossover_rate),
+            ("compatibility_threshold".to_string(), self.speciation.threshold),
+            ("max_gens".to_string(), self.max_gens as f64),
+        ])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NodeKind {
+    Input,
+    // Always outputs 1, standing in for the other nodes' biases
+    Bias,
+    Hidden,
+    Output,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct NodeGene {
+    id: usize,
+    kind: NodeKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ConnectionGene {
+    // Historical marking: the same connection has the same number in every genome
+    innovation: usize,
+    from: usize,
+    to: usize,
+    weight: f64,
+    enabled: bool,
+}
+
+// Innovation numbers and node ids handed out over a run, so the same structural change made
+// in different genomes is numbered the same and lines up in crossover
+struct Innovations {
+    connections: HashMap<(usize, usize), usize>,
+    // Node created by splitting each connection, by the connection's innovation number
+    splits: HashMap<usize, usize>,
+    next_node: usize,
+}
+
+impl Innovations {
+    fn connection(&mut self, from: usize, to: usize) -> usize {
+        let next = self.connections.len();
+        *self.connections.entry((from, to)).or_insert(next)
+    }
+
+    // A genome that already split the connection once, and re-enabled it through crossover,
+    // gets a new node rather than a second copy of the old one
+    fn split(&mut self, innovation: usize, genome: &NeatGenome) -> usize {
+        match self.splits.get(&innovation) {
+            Some(&node) if !genome.nodes.iter().any(|n| n.id == node) => node,
+            _ => {
+                let node = self.next_node;
+                self.next_node += 1;
+                self.splits.entry(innovation).or_insert(node);
+                node
+            }
+        }
+    }
+}
+
+// A feed-forward network as NEAT evolves it: node genes, and connection genes sorted by
+// innovation number. Connections are only added where they don't close a cycle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NeatGenome {
+    nodes: Vec<NodeGene>,
+    connections: Vec<ConnectionGene>,
+}
+
+// Sigmoid steepened as in the original NEAT, close to linear between -0.5 and 0.5
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-4.9 * x).exp())
+}
+
+impl NeatGenome {
+    // Every input and the bias connected straight to every output, with random weights, and
+    // no hidden nodes: NEAT starts minimal and grows structure only as it pays off
+    fn minimal<R: Rng + ?Sized>(
+        inputs: usize,
+        outputs: usize,
+        innovations: &mut Innovations,
+        rng: &mut R,
+    ) -> Self {
+        let kinds = [(NodeKind::Input, inputs), (NodeKind::Bias, 1), (NodeKind::Output, outputs)];
+        let nodes: Vec<NodeGene> = kinds
+            .iter()
+            .flat_map(|&(kind, count)| std::iter::repeat_n(kind, count))
+            .enumerate()
+            .map(|(id, kind)| NodeGene { id, kind })
+            .collect();
+        let mut connections = Vec::new();
+        for to in nodes.iter().filter(|node| node.kind == NodeKind::Output) {
+            for from in nodes.iter().filter(|node| node.kind != NodeKind::Output) {
+                connections.push(ConnectionGene {
+                    innovation: innovations.connection(from.id, to.id),
+                    from: from.id,
+                    to: to.id,
+                    weight: rng.random_range(-1.0..1.0),
+                    enabled: true,
+                });
+            }
+        }
+        Self { nodes, connections }
+    }
+
+    // Output values for the given inputs, propagated through the enabled connections in
+    // topological order
+    pub fn activate(&self, inputs: &[f64]) -> Vec<f64> {
+        let mut values: HashMap<usize, f64> = HashMap::new();
+        let mut input_values = inputs.iter();
+        for node in &self.nodes {
+            match node.kind {
+                NodeKind::Input => values.insert(node.id, *input_values.next().unwrap_or(&0.0)),
+                NodeKind::Bias => values.insert(node.id, 1.0),
+                NodeKind::Hidden | NodeKind::Output => None,
+            };
+        }
+
+        let enabled: Vec<&ConnectionGene> = self.connections.iter().filter(|c| c.enabled).collect();
+        let mut pending: HashMap<usize, usize> = HashMap::new();
+        for connection in &enabled {
+            *pending.entry(connection.to).or_insert(0) += 1;
+        }
+        let mut ready: Vec<usize> = self
+            .nodes
+            .iter()
+            .map(|node| node.id)
+            .filter(|id| !pending.contains_key(id))
+            .collect();
+        let mut sums: HashMap<usize, f64> = HashMap::new();
+        while let Some(node) = ready.pop() {
+            let value = *values
+                .entry(node)
+                .or_insert_with(|| sigmoid(sums.get(&node).copied().unwrap_or(0.0)));
+            for connection in enabled.iter().filter(|c| c.from == node) {
+                *sums.entry(connection.to).or_insert(0.0) += connection.weight * value;
+                let left = pending.entry(connection.to).or_insert(1);
+                *left -= 1;
+                if *left == 0 {
+                    ready.push(connection.to);
+                }
+            }
+        }
+
+        self.nodes
+            .iter()
+            .filter(|node| node.kind == NodeKind::Output)
+            .map(|node| values.get(&node.id).copied().unwrap_or(0.0))
+            .collect()
+    }
+
+    pub fn hidden_nodes(&self) -> usize {
+        self.nodes.iter().filter(|node| node.kind == NodeKind::Hidden).count()
+    }
+
+    pub fn enabled_connections(&self) -> usize {
+        self.connections.iter().filter(|c| c.enabled).count()
+    }
+
+    // Compatibility distance, the genotypic distance species are formed by
+    fn compatibility(&self, other: &Self, params: &NeatParameters) -> f64 {
+        let weights = |genome: &Self| -> HashMap<usize, f64> {
+            genome.connections.iter().map(|c| (c.innovation, c.weight)).collect()
+        };
+        let (mine, theirs) = (weights(self), weights(other));
+        let last = |genome: &Self| genome.connections.last().map(|c| c.innovation);
+        let (my_last, their_last) = (last(self), last(other));
+
+        let (mut excess, mut disjoint, mut matching, mut weight_difference) = (0, 0, 0, 0.0);
+        for (&innovation, weight) in &mine {
+            match theirs.get(&innovation) {
+                Some(their_weight) => {
+                    matching += 1;
+                    weight_difference += (weight - their_weight).abs();
+                }
+                None if their_last.is_none_or(|last| innovation > last) => excess += 1,
+                None => disjoint += 1,
+            }
+        }
+        for &innovation in theirs.keys().filter(|innovation| !mine.contains_key(innovation)) {
+            if my_last.is_none_or(|last| innovation > last) {
+                excess += 1;
+            } else {
+                disjoint += 1;
+            }
+        }
+
+        let larger = self.connections.len().max(other.connections.len());
+        let n = if larger < 20 { 1.0 } else { larger as f64 };
+        let mean_difference = if matching > 0 { weight_difference / matching as f64 } else { 0.0 };
+        params.excess_coefficient * excess as f64 / n
+            + params.disjoint_coefficient * disjoint as f64 / n
+            + params.weight_coefficient * mean_difference
+    }
+
+    // Child of two parents lined up by innovation number: matching genes come from either
+    // parent at random, disjoint and excess genes from the fitter one, so the child has the
+    // fitter parent's structure
+    fn crossover<R: Rng + ?Sized>(
+        fitter: &Self,
+        other: &Self,
+        params: &NeatParameters,
+        rng: &mut R,
+    ) -> Self {
+        let other_genes: HashMap<usize, &ConnectionGene> =
+            other.connections.iter().map(|c| (c.innovation, c)).collect();
+        let connections = fitter
+            .connections
+            .iter()
+            .map(|gene| match other_genes.get(&gene.innovation) {
+                Some(matching) => {
+                    let mut child =
+                        if rng.random_bool(0.5) { (*matching).clone() } else { gene.clone() };
+                    if !gene.enabled || !matching.enabled {
+                        child.enabled = !rng.random_bool(params.disable_rate);
+                    }
+                    child
+                }
+                None => gene.clone(),
+            })
+            .collect();
+        Self { nodes: fitter.nodes.clone(), connections }
+    }
+
+    // One structural mutation, or failing those the weight mutation
+    fn mutate<R: Rng + ?Sized>(
+        &mut self,
+        params: &NeatParameters,
+        innovations: &mut Innovations,
+        rng: &mut R,
+    ) {
+        if rng.random_bool(params.add_node_rate) {
+            self.add_node(innovations, rng);
+        } else if rng.random_bool(params.add_connection_rate) {
+            self.add_connection(innovations, rng);
+        } else if rng.random_bool(params.weight_mutation_rate) {
+            for connection in &mut self.connections {
+                if rng.random_bool(params.weight_replace_rate) {
+                    connection.weight = rng.random_range(-1.0..1.0);
+                } else {
+                    let step: f64 = rng.sample(rand_distr::StandardNormal);
+                    connection.weight += params.weight_power * step;
+                }
+            }
+        }
+    }
+
+    // Splits an enabled connection with a new hidden node: the old connection is disabled, the
+    // one into the node gets weight 1 and the one out of it the old weight, so the network
+    // computes nearly what it did before
+    fn add_node<R: Rng + ?Sized>(&mut self, innovations: &mut Innovations, rng: &mut R) {
+        let enabled: Vec<usize> = (0..self.connections.len())
+            .filter(|&i| self.connections[i].enabled)
+            .collect();
+        let Some(&split) = enabled.choose(rng) else { return };
+        self.connections[split].enabled = false;
+        let ConnectionGene { innovation, from, to, weight, .. } = self.connections[split];
+        let node = innovations.split(innovation, self);
+        self.nodes.push(NodeGene { id: node, kind: NodeKind::Hidden });
+        self.connect(from, node, 1.0, innovations);
+        self.connect(node, to, weight, innovations);
+    }
+
+    // Connects a random pair of nodes not yet connected, trying a few pairs before giving up.
+    // Connections run into hidden and output nodes only, and never close a cycle.
+    fn add_connection<R: Rng + ?Sized>(&mut self, innovations: &mut Innovations, rng: &mut R) {
+        const ATTEMPTS: usize = 20;
+        let sources: Vec<usize> = self
+            .nodes
+            .iter()
+            .filter(|node| node.kind != NodeKind::Output)
+            .map(|node| node.id)
+            .collect();
+        let targets: Vec<usize> = self
+            .nodes
+            .iter()
+            .filter(|node| matches!(node.kind, NodeKind::Hidden | NodeKind::Output))
+            .map(|node| node.id)
+            .collect();
+        for _ in 0..ATTEMPTS {
+            let (Some(&from), Some(&to)) = (sources.choose(rng), targets.choose(rng)) else {
+                return;
+            };
+            let connected = self.connections.iter().any(|c| c.from == from && c.to == to);
+            if !connected && !self.reaches(to, from) {
+                self.connect(from, to, rng.random_range(-1.0..1.0), innovations);
+                return;
+            }
+        }
+    }
+
+    fn connect(&mut self, from: usize, to: usize, weight: f64, innovations: &mut Innovations) {
+        let innovation = innovations.connection(from, to);
+        self.connections.push(ConnectionGene { innovation, from, to, weight, enabled: true });
+        self.connections.sort_by_key(|c| c.innovation);
+    }
+
+    // Whether a path of connections, enabled or not, leads from one node to the other, so
+    // that re-enabling a gene can't close a cycle either
+    fn reaches(&self, from: usize, to: usize) -> bool {
+        let mut stack = vec![from];
+        let mut seen = vec![from];
+        while let Some(node) = stack.pop() {
+            if node == to {
+                return true;
+            }
+            for connection in self.connections.iter().filter(|c| c.from == node) {
+                if !seen.contains(&connection.to) {
+                    seen.push(connection.to);
+                    stack.push(connection.to);
+                }
+            }
+        }
+        false
+    }
+}
+
+// Offspring per species in proportion to its members' summed shared fitness, rounded so they
+// add up to pop_size, the largest remainders rounding up
+fn offspring_quotas(totals: &[f64], pop_size: usize) -> Vec<usize> {
+    let sum: f64 = totals.iter().sum();
+    let shares: Vec<f64> = totals
+        .iter()
+        .map(|total| {
+            let fraction = if sum > 0.0 { total / sum } else { 1.0 / totals.len() as f64 };
+            fraction * pop_size as f64
+        })
+        .collect();
+    let mut quotas: Vec<usize> = shares.iter().map(|share| share.floor() as usize).collect();
+    let mut by_remainder: Vec<usize> = (0..shares.len()).collect();
+    let remainder = |i: usize| shares[i] - shares[i].floor();
+    by_remainder.sort_by(|&a, &b| remainder(b).total_cmp(&remainder(a)));
+    let missing = pop_size.saturating_sub(quotas.iter().sum());
+    for &i in by_remainder.iter().cycle().take(missing) {
+        quotas[i] += 1;
+    }
+    quotas
+}
+
+// NEAT (NeuroEvolution of Augmenting Topologies): networks whose structure evolves along
+// with their weights, from minimal networks up. Innovation numbers line up genes for
+// crossover and compatibility distance, and speciation shields new structure, which usually
+// costs fitness at first, from competing with the whole population.
+pub struct Neat<'a, F: NetworkObjective> {
+    objective: &'a F,
+    params: &'a NeatParameters,
+    population: Vec<NeatGenome>,
+    innovations: Innovations,
+    species: SpeciesSet<NeatGenome>,
+    generation: usize,
+    evaluations: usize,
+    monitor: StopMonitor,
+    observers: Vec<Box<dyn Observer<NeatGenome> + 'a>>,
+    best_individual: Option<NeatGenome>,
+}
+
+impl<'a, F: NetworkObjective> Neat<'a, F> {
+    pub fn new<R: Rng + ?Sized>(objective: &'a F, params: &'a NeatParameters, rng: &mut R) -> Self {
+        let (inputs, outputs) = (objective.inputs(), objective.outputs());
+        let mut innovations = Innovations {
+            connections: HashMap::new(),
+            splits: HashMap::new(),
+            next_node: inputs + 1 + outputs,
+        };
+        let population = (0..params.pop_size)
+            .map(|_| NeatGenome::minimal(inputs, outputs, &mut innovations, rng))
+            .collect();
+
+        let mut observers = vec![status_observer(
+            params.log_path.as_deref(),
+            "NEAT",
+            params.to_map(),
+            params.seed,
+            ConsoleObserver::neat(params),
+        )];
+        if let Some(trace) = trace_observer(params.trace_path.as_deref()) {
+            observers.push(Box::new(trace));
+        }
+
+        Self {
+            objective,
+            params,
+            population,
+            innovations,
+            species: SpeciesSet::new(params.speciation),
+            generation: 0,
+            evaluations: 0,
+            monitor: StopMonitor::new(&params.stopping, objective.optimum_value()),
+            observers,
+            best_individual: None,
+        }
+    }
+
+    // Removes all observers, including the console output, for runs driven in bulk
+    pub fn quiet(mut self) -> Self {
+        self.observers.clear();
+        self
+    }
+
+    // Next generation: each species breeds its quota of offspring from its best members, its
+    // champion first if the species is big enough
+    fn reproduce<R: Rng + ?Sized>(&mut self, fitnesses: &[f64], rng: &mut R) -> Vec<NeatGenome> {
+        let params = self.params;
+        let shared = self.species.shared_fitnesses(fitnesses);
+        let members = self.species.members();
+        let totals: Vec<f64> =
+            members.iter().map(|species| species.iter().map(|&i| shared[i]).sum()).collect();
+
+        let mut offspring = Vec::with_capacity(params.pop_size);
+        for (species, quota) in members.iter().zip(offspring_quotas(&totals, params.pop_size)) {
+            let mut ranked = species.to_vec();
+            ranked.sort_by(|&a, &b| fitnesses[b].total_cmp(&fitnesses[a]));
+            let mut bred = 0;
+            if quota > 0 && ranked.len() >= params.champion_species_size {
+                offspring.push(self.population[ranked[0]].clone());
+                bred += 1;
+            }
+            let survivors = (params.survival_threshold * ranked.len() as f64).ceil() as usize;
+            let parents = &ranked[..survivors.clamp(1, ranked.len())];
+            while bred < quota {
+                let first = *parents.choose(rng).unwrap();
+                let mut child = if parents.len() > 1 && rng.random_bool(params.crossover_rate) {
+                    let second = *parents.choose(rng).unwrap();
+                    let (fitter, other) = if fitnesses[first] >= fitnesses[second] {
+                        (first, second)
+                    } else {
+                        (second, first)
+                    };
+                    let (fitter, other) = (&self.population[fitter], &self.population[other]);
+                    NeatGenome::crossover(fitter, other, params, rng)
+                } else {
+                    self.population[first].clone()
+                };
+                child.mutate(params, &mut self.innovations, rng);
+                offspring.push(child);
+                bred += 1;
+            }
+        }
+        offspring
+    }
+
+    fn report(&mut self, message: &str) {
+        for observer in &mut self.observers {
+            observer.on_message(message);
+        }
+    }
+}
+
+impl<F: NetworkObjective> Algorithm for Neat<'_, F> {
+    type Individual = NeatGenome;
+
+    fn step<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<StopReason> {
+        if self.generation >= self.params.max_gens {
+            return Some(StopReason::MaxGenerations);
+        }
+        let fitnesses: Vec<f64> =
+            self.population.iter().map(|network| self.objective.fitness(network)).collect();
+        self.evaluations += fitnesses.len();
+
+        let (avg_fitness, std_fitness) = mean_and_std(&fitnesses);
+        let best_index = (0..fitnesses.len())
+            .max_by(|&a, &b| fitnesses[a].total_cmp(&fitnesses[b]))
+            .unwrap_or(0);
+        let max_fitness = fitnesses[best_index];
+        let stats = GenerationStats {
+            generation: self.generation,
+            evaluations: self.evaluations,
+            max_fitness,
+            avg_fitness,
+            std_fitness,
+            diversity: None,
+            cache: None,
+            boundary_hits: None,
+            violations: None,
+            qd: None,
+        };
+        let best = &self.population[best_index];
+        let improved = max_fitness > self.monitor.best_fitness;
+        for observer in &mut self.observers {
+            observer.on_generation(&stats, best);
+        }
+        if improved {
+            self.best_individual = Some(best.clone());
+            for observer in &mut self.observers {
+                observer.on_improvement(&stats, best);
+            }
+        }
+        let monitor_stop = self.monitor.update(max_fitness);
+        if monitor_stop.is_some() {
+            return monitor_stop;
+        }
+
+        let params = self.params;
+        self.species.speciate(&self.population, &fitnesses, |a, b| a.compatibility(b, params));
+        let description = self.species.describe();
+        self.report(&format!("Speciation: {description}"));
+        self.population = self.reproduce(&fitnesses, rng);
+        self.generation += 1;
+        None
+    }
+
+    fn generation(&self) -> usize {
+        self.generation
+    }
+
+    fn best_fitness(&self) -> f64 {
+        self.monitor.best_fitness
+    }
+
+    fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+
+    fn finish(mut self, reason: StopReason) -> Vec<NeatGenome> {
+        let summary = RunSummary {
+            reason,
+            generations: self.generation,
+            evaluations: self.evaluations,
+            best_fitness: self.monitor.best_fitness,
+            stagnant_generations: self.monitor.stagnant_generations,
+        };
+        for observer in &mut self.observers {
+            observer.on_termination(&summary, self.best_individual.as_ref());
+        }
+        self.population
+    }
+}
@@ -0,0 +1,183 @@
+use crate::fitness::Fitness;
+use crate::genome::{Genome, euclidean_distance};
+
+// Space in which niche distances are measured
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DistanceMetric {
+    // Between genomes (Hamming for bitstrings, Euclidean for real vectors)
+    Genotypic,
+    // Euclidean between decoded phenotypes
+    Phenotypic,
+}
+
+impl DistanceMetric {
+    // Distance between two genomes in this metric's space
+    pub fn between<G: Genome>(
+        &self,
+        a: &G,
+        b: &G,
+        fitness_fn: &impl Fitness,
+        num_dims: usize,
+    ) -> f64 {
+        match self {
+            DistanceMetric::Genotypic => a.distance(b),
+            DistanceMetric::Phenotypic => {
+                let decoder = fitness_fn.decoder();
+                let (a, b) = (a.phenotype(&decoder, num_dims), b.phenotype(&decoder, num_dims));
+                euclidean_distance(&a, &b)
+            }
+        }
+    }
+}
+
+// How offspring enter the next generation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Replacement {
+    // Offspring replace the whole population
+    Generational,
+    // Each offspring competes against its most similar parent and the fitter one survives,
+    // so niches persist without a sharing radius to tune
+    DeterministicCrowding { distance: DistanceMetric },
+}
+
+// Fitness sharing: each individual's fitness is divided by its niche count, so crowded
+// peaks stop attracting the whole population and several optima can be held at once
+#[derive(Debug, Clone, Copy)]
+pub struct FitnessSharing {
+    // Individuals closer than this share fitness
+    pub radius: f64,
+    // Shape of the sharing function, 1.0 for triangular
+    pub alpha: f64,
+    pub distance: DistanceMetric,
+}
+
+impl FitnessSharing {
+    // sh(d) = 1 - (d / radius)^alpha inside the radius, 0 outside
+    fn share(&self, distance: f64) -> f64 {
+        if distance < self.radius {
+            1.0 - (distance / self.radius).powf(self.alpha)
+        } else {
+            0.0
+        }
+    }
+
+    // Raw fitnesses divided by each individual's niche count
+    pub fn shared_fitnesses<G: Genome>(
+        &self,
+        population: &[G],
+        fitnesses: &[f64],
+        fitness_fn: &impl Fitness,
+        num_dims: usize,
+    ) -> Vec<f64> {
+        let phenotypes: Vec<Vec<f64>> = match self.distance {
+            DistanceMetric::Phenotypic => {
+                let decoder = fitness_fn.decoder();
+                population.iter().map(|m| m.phenotype(&decoder, num_dims)).collect()
+            }
+            DistanceMetric::Genotypic => Vec::new(),
+        };
+        let distance = |i: usize, j: usize| match self.distance {
+            DistanceMetric::Genotypic => population[i].distance(&population[j]),
+            DistanceMetric::Phenotypic => euclidean_distance(&phenotypes[i], &phenotypes[j]),
+        };
+
+        // The niche count includes the individual itself, so it is always at least 1
+        let mut niche_counts = vec![1.0; population.len()];
+        for i in 0..population.len() {
+            for j in (i + 1)..population.len() {
+                let sh = self.share(distance(i, j));
+                niche_counts[i] += sh;
+                niche_counts[j] += sh;
+            }
+        }
+
+        fitnesses
+            .iter()
+            .zip(&niche_counts)
+            .map(|(f, count)| f / count)
+            .collect()
+    }
+}
+
+// Clearing: within each niche only the best `capacity` individuals keep their fitness and the
+// others are cleared to 0, so selection spreads over the niches' winners. One pass over the
+// population sorted by fitness finds the winners, cheaper than sharing's niche counts, and
+// only the radius and capacity need tuning.
+#[derive(Debug, Clone, Copy)]
+pub struct Clearing {
+    // Individuals closer than this to a winner are in its niche
+    pub radius: f64,
+    // Winners per niche, 1 for the classic winner-takes-all clearing
+    pub capacity: usize,
+    // The ES measures distances between genes whichever metric is set
+    pub distance: DistanceMetric,
+}
+
+impl Clearing {
+    // Fitnesses after clearing, with distance(i, j) between individuals i and j. Each
+    // individual not yet cleared becomes the winner of a niche in turn, best first.
+    pub fn clear(&self, fitnesses: &[f64], distance: impl Fn(usize, usize) -> f64) -> Vec<f64> {
+        let mut order: Vec<usize> = (0..fitnesses.len()).collect();
+        order.sort_by(|&a, &b| fitnesses[b].total_cmp(&fitnesses[a]));
+        let mut cleared = fitnesses.to_vec();
+        let mut is_cleared = vec![false; fitnesses.len()];
+        for (rank, &winner) in order.iter().enumerate() {
+            if is_cleared[winner] {
+                continue;
+            }
+            let mut winners = 1;
+            for &other in &order[rank + 1..] {
+                if is_cleared[other] || distance(winner, other) >= self.radius {
+                    continue;
+                }
+                if winners < self.capacity {
+                    winners += 1;
+                } else {
+                    is_cleared[other] = true;
+                    cleared[other] = 0.0;
+                }
+            }
+        }
+        cleared
+    }
+
+    // Raw fitnesses of a GA population after clearing in the configured metric
+    pub fn cleared_fitnesses<G: Genome>(
+        &self,
+        population: &[G],
+        fitnesses: &[f64],
+        fitness_fn: &impl Fitness,
+        num_dims: usize,
+    ) -> Vec<f64> {
+        match self.distance {
+            DistanceMetric::Genotypic => {
+                self.clear(fitnesses, |i, j| population[i].distance(&population[j]))
+            }
+            DistanceMetric::Phenotypic => {
+                let decoder = fitness_fn.decoder();
+                let phenotypes: Vec<Vec<f64>> =
+                    population.iter().map(|m| m.phenotype(&decoder, num_dims)).collect();
+                self.clear(fitnesses, |i, j| euclidean_distance(&phenotypes[i], &phenotypes[j]))
+            }
+        }
+    }
+}
+
+// Deterministic crowding for one family of evaluated members: pair each child with the
+// closer parent, then keep the better of each parent-child pair (the parent wins ties)
+pub fn deterministic_crowding<G: Genome>(
+    parents: [(G, f64); 2],
+    children: [(G, f64); 2],
+    distance: impl Fn(&G, &G) -> f64,
+) -> [(G, f64); 2] {
+    let [parent1, parent2] = parents;
+    let [child1, child2] = children;
+    let straight = distance(&parent1.0, &child1.0) + distance(&parent2.0, &child2.0);
+    let crossed = distance(&parent1.0, &child2.0) + distance(&parent2.0, &child1.0);
+    let pairs = if straight <= crossed {
+        [(parent1, child1), (parent2, child2)]
+    } else {
+        [(parent1, child2), (parent2, child1)]
+    };
+    pairs.map(|(parent, child)| if child.1 > parent.1 { child } else { parent })
+}
@@ -0,0 +1,113 @@
+use crate::cache::CacheStats;
+use crate::decoder::Decoder;
+use crate::fitness::{Fitness, Objective};
+use crate::seeds::SeedSequence;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use rand_distr::Normal;
+use std::sync::Mutex;
+
+// Distribution of the additive noise applied to each fitness evaluation, validated once when
+// it is built
+#[derive(Debug, Clone, Copy)]
+pub enum NoiseModel {
+    Gaussian(Normal<f64>),
+    Uniform { half_width: f64 },
+}
+
+impl NoiseModel {
+    pub fn gaussian(std_dev: f64) -> Result<Self, String> {
+        if !std_dev.is_finite() || std_dev < 0.0 {
+            return Err(format!("Noise standard deviation must be finite and >= 0, got {std_dev}"));
+        }
+        Normal::new(0.0, std_dev).map(NoiseModel::Gaussian).map_err(|e| e.to_string())
+    }
+
+    pub fn uniform(half_width: f64) -> Result<Self, String> {
+        if !half_width.is_finite() || half_width < 0.0 {
+            return Err(format!("Noise half-width must be finite and >= 0, got {half_width}"));
+        }
+        Ok(NoiseModel::Uniform { half_width })
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        match *self {
+            NoiseModel::Gaussian(normal) => rng.sample(normal),
+            NoiseModel::Uniform { half_width } => {
+                if half_width > 0.0 {
+                    rng.random_range(-half_width..half_width)
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+// Wraps any fitness function and adds noise to every evaluation. The RNG lives behind a
+// mutex so the wrapper can still be shared across threads by the timeout runner; seeded
+// batches draw from each member's own stream instead, so parallel evaluation stays
+// reproducible.
+pub struct NoisyFitness<F: Objective> {
+    inner: F,
+    noise: NoiseModel,
+    rng: Mutex<ChaCha8Rng>,
+}
+
+impl<F: Objective> NoisyFitness<F> {
+    pub fn new(inner: F, noise: NoiseModel, seed: u64) -> Self {
+        Self {
+            inner,
+            noise,
+            rng: Mutex::new(ChaCha8Rng::seed_from_u64(seed)),
+        }
+    }
+
+    fn sample_noise(&self) -> f64 {
+        self.noise.sample(&mut *self.rng.lock().unwrap())
+    }
+}
+
+impl<F: Objective> Objective for NoisyFitness<F> {
+    fn fitness(&self, member: &[f64]) -> f64 {
+        self.inner.fitness(member) + self.sample_noise()
+    }
+
+    fn evaluate_population(&self, members: &[Vec<f64>]) -> Vec<f64> {
+        let values = self.inner.evaluate_population(members);
+        values.into_iter().map(|value| value + self.sample_noise()).collect()
+    }
+
+    fn evaluate_population_seeded(
+        &self,
+        members: &[Vec<f64>],
+        streams: &[SeedSequence],
+    ) -> Vec<f64> {
+        let values = self.inner.evaluate_population_seeded(members, streams);
+        let noise = |stream: &SeedSequence| self.noise.sample(&mut stream.operator("noise").rng());
+        values.into_iter().zip(streams).map(|(value, stream)| value + noise(stream)).collect()
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        self.inner.bounds()
+    }
+
+    fn optimum_value(&self) -> Option<f64> {
+        self.inner.optimum_value()
+    }
+
+    fn known_optima(&self, num_dims: usize) -> Vec<Vec<f64>> {
+        self.inner.known_optima(num_dims)
+    }
+
+    fn cache_stats(&self) -> Option<CacheStats> {
+        self.inner.cache_stats()
+    }
+}
+
+// Decoding is deterministic, only the evaluation is noisy
+impl<F: Fitness> Fitness for NoisyFitness<F> {
+    fn decoder(&self) -> impl Decoder {
+        self.inner.decoder()
+    }
+}
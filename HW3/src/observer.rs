@@ -0,0 +1,292 @@
+use crate::bitstring::GAParameters;
+use crate::cgp::CgpParameters;
+use crate::cache::CacheStats;
+use crate::constraint::ViolationStats;
+use crate::evol_strat::ESParameters;
+use crate::map_elites::{MapElitesParameters, QdMetrics};
+use crate::neat::NeatParameters;
+use crate::one_plus_one::OnePlusOneParameters;
+use crate::openai_es::OpenAiESParameters;
+use crate::stopping::StopReason;
+
+// Population statistics for one generation
+#[derive(Debug, Clone)]
+pub struct GenerationStats {
+    pub generation: usize,
+    pub evaluations: usize,
+    pub max_fitness: f64,
+    pub avg_fitness: f64,
+    // Population standard deviation of fitness
+    pub std_fitness: f64,
+    // Largest pairwise distance between individuals, if StatsConfig asked for it
+    pub diversity: Option<f64>,
+    // Fitness cache lookups over the run so far, if the problem is wrapped in a CachedFitness
+    pub cache: Option<CacheStats>,
+    // Genes found outside the search range so far, if the algorithm handles bounds
+    pub boundary_hits: Option<usize>,
+    // Constraint violation across the population, if the run was given constraints
+    pub violations: Option<ViolationStats>,
+    // Coverage, QD-score and best fitness of the archive, if the algorithm keeps one
+    pub qd: Option<QdMetrics>,
+}
+
+// Which population metrics are computed each generation. Fitness statistics come from the one
+// evaluation of each individual and are always on. Diversity costs O(n²) distance checks per
+// generation unless sampled and identity a hash of each member, so they are opt-in.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StatsConfig {
+    // Largest euclidean distance between two individuals in decoded space
+    pub diversity: bool,
+    // Estimate diversity from this many random pairs per generation instead of all of them
+    pub diversity_pairs: Option<usize>,
+    // Fraction of individuals with an identical copy (GA only). Convergence on identical
+    // copies is only checked when this is on.
+    pub identity: bool,
+    // Time spent in each operator, reported at the end of the run, see timing::OperatorTimer
+    pub timing: bool,
+}
+
+impl StatsConfig {
+    // Every metric, as the status lines of the default run report them
+    pub fn full() -> Self {
+        Self { diversity: true, diversity_pairs: None, identity: true, timing: false }
+    }
+}
+
+// Mean and population standard deviation, as reported in GenerationStats
+pub fn mean_and_std(values: &[f64]) -> (f64, f64) {
+    let stats = RunningStats::of(values);
+    (stats.mean(), stats.std())
+}
+
+// Fitness statistics gathered in one pass over the values: Welford's running mean and
+// variance, and the largest value with the index it was last seen at
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunningStats {
+    count: usize,
+    mean: f64,
+    m2: f64,
+    max: Option<(usize, f64)>,
+}
+
+impl RunningStats {
+    pub fn of(values: &[f64]) -> Self {
+        let mut stats = Self::default();
+        for &value in values {
+            stats.push(value);
+        }
+        stats
+    }
+
+    pub fn push(&mut self, value: f64) {
+        if self.max.is_none_or(|(_, max)| value >= max) {
+            self.max = Some((self.count, value));
+        }
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    // NaN, like the standard deviation, before any value is pushed
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 { f64::NAN } else { self.mean }
+    }
+
+    // Population standard deviation
+    pub fn std(&self) -> f64 {
+        (self.m2 / self.count as f64).sqrt()
+    }
+
+    // Index and value of the largest value, None before any is pushed
+    pub fn max(&self) -> Option<(usize, f64)> {
+        self.max
+    }
+}
+
+// End-of-run summary
+#[derive(Debug, Clone)]
+pub struct RunSummary {
+    pub reason: StopReason,
+    pub generations: usize,
+    pub evaluations: usize,
+    pub best_fitness: f64,
+    pub stagnant_generations: usize,
+}
+
+// Hooks called by the GA and ES as a run progresses. Every hook has a no-op default, so an
+// observer only implements what it needs. To stop a run early, hold the run's
+// CancellationToken and cancel it from a hook.
+pub trait Observer<I> {
+    // After each generation is evaluated, with that generation's best individual
+    fn on_generation(&mut self, _stats: &GenerationStats, _best: &I) {}
+
+    // When a generation beats the best fitness seen so far
+    fn on_improvement(&mut self, _stats: &GenerationStats, _best: &I) {}
+
+    // Once at the end of the run, with the best individual seen (None if no generation was
+    // evaluated before the run stopped)
+    fn on_termination(&mut self, _summary: &RunSummary, _best: Option<&I>) {}
+
+    // Free-form reports from operators (rate control, repair, duplicates, ...)
+    fn on_message(&mut self, _message: &str) {}
+}
+
+// Lets a caller register an observer by reference and inspect it after the run
+impl<I, O: Observer<I> + ?Sized> Observer<I> for &mut O {
+    fn on_generation(&mut self, stats: &GenerationStats, best: &I) {
+        (**self).on_generation(stats, best);
+    }
+
+    fn on_improvement(&mut self, stats: &GenerationStats, best: &I) {
+        (**self).on_improvement(stats, best);
+    }
+
+    fn on_termination(&mut self, summary: &RunSummary, best: Option<&I>) {
+        (**self).on_termination(summary, best);
+    }
+
+    fn on_message(&mut self, message: &str) {
+        (**self).on_message(message);
+    }
+}
+
+// The standard console output: one "Dejong Rosenbrock" line per generation in the format
+// the analysis scripts read, operator reports, and the final summary
+pub struct ConsoleObserver {
+    header: Option<String>,
+    prefix: String,
+    // The GA also announces convergence and running out of generations
+    announce_stop: bool,
+}
+
+impl ConsoleObserver {
+    pub fn ga(params: &GAParameters) -> Self {
+        Self {
+            header: Some(format!(
+                "Running Dejong Rosenbrock GA with Pop={} MemberSize={} Mutation={} Crossover={}",
+                params.pop_size, params.mem_size, params.mutation_rate, params.crossover_rate
+            )),
+            prefix: format!(
+                "Dejong Rosenbrock GA {} {} {} {}",
+                params.pop_size, params.pop_size, params.mutation_rate, params.crossover_rate
+            ),
+            announce_stop: true,
+        }
+    }
+
+    pub fn es(params: &ESParameters) -> Self {
+        Self {
+            header: None,
+            prefix: format!(
+                "Dejong Rosenbrock ES {} {} {} 0.0",
+                params.mu, params.lambda, params.tau
+            ),
+            announce_stop: false,
+        }
+    }
+
+    // The ES line format with mu = lambda = 1 and the success rule's factor in place of tau
+    pub fn one_plus_one(params: &OnePlusOneParameters) -> Self {
+        Self {
+            header: None,
+            prefix: format!("Dejong Rosenbrock (1+1)-ES 1 1 {} 0.0", params.factor),
+            announce_stop: false,
+        }
+    }
+
+    // The ES line format with mu = lambda = pop_size and the learning rate in place of tau
+    pub fn openai_es(params: &OpenAiESParameters) -> Self {
+        Self {
+            header: None,
+            prefix: format!(
+                "Dejong Rosenbrock OpenAI-ES {} {} {} 0.0",
+                params.pop_size, params.pop_size, params.learning_rate
+            ),
+            announce_stop: false,
+        }
+    }
+
+    // The (1+1)-ES line format with lambda offspring and the mutation rate in place of tau
+    pub fn cgp(params: &CgpParameters) -> Self {
+        Self {
+            header: None,
+            prefix: format!("CGP 1 {} {} 0.0", params.lambda, params.mutation_rate),
+            announce_stop: false,
+        }
+    }
+
+    // The ES line format with the batch in place of mu and lambda and sigma in place of tau;
+    // max and average fitness are over the archive's elites
+    pub fn map_elites(params: &MapElitesParameters) -> Self {
+        Self {
+            header: None,
+            prefix: format!(
+                "Dejong Rosenbrock MAP-Elites {} {} {} 0.0",
+                params.batch_size, params.batch_size, params.sigma
+            ),
+            announce_stop: false,
+        }
+    }
+
+    pub fn neat(params: &NeatParameters) -> Self {
+        Self {
+            header: None,
+            prefix: format!("NEAT {} {}", params.pop_size, params.speciation.threshold),
+            announce_stop: false,
+        }
+    }
+}
+
+impl<I> Observer<I> for ConsoleObserver {
+    fn on_generation(&mut self, stats: &GenerationStats, _best: &I) {
+        if let Some(header) = self.header.take() {
+            println!("{header}");
+        }
+        println!(
+            "{} {} {} {} {} {}",
+            self.prefix,
+            stats.generation,
+            stats.evaluations,
+            stats.max_fitness,
+            stats.avg_fitness,
+            stats.diversity.unwrap_or(f64::NAN)
+        );
+    }
+
+    fn on_termination(&mut self, summary: &RunSummary, _best: Option<&I>) {
+        if self.announce_stop {
+            match summary.reason {
+                StopReason::Converged => println!("Converged at generation {}", summary.generations),
+                StopReason::MaxGenerations => println!("Max iterations reached"),
+                _ => {}
+            }
+        }
+        println!(
+            "Stopped after {} generations ({}): best fitness {} with {} generations since last improvement",
+            summary.generations, summary.reason, summary.best_fitness, summary.stagnant_generations
+        );
+    }
+
+    fn on_message(&mut self, message: &str) {
+        println!("{message}");
+    }
+}
+
+// Records every improvement and the final summary, for inspecting a run after it ends
+#[derive(Debug, Default)]
+pub struct ImprovementLog {
+    // (generation, evaluations so far, new best fitness)
+    pub improvements: Vec<(usize, usize, f64)>,
+    pub summary: Option<RunSummary>,
+}
+
+impl<I> Observer<I> for ImprovementLog {
+    fn on_improvement(&mut self, stats: &GenerationStats, _best: &I) {
+        self.improvements.push((stats.generation, stats.evaluations, stats.max_fitness));
+    }
+
+    fn on_termination(&mut self, summary: &RunSummary, _best: Option<&I>) {
+        self.summary = Some(summary.clone());
+    }
+}
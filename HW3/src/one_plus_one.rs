@@ -0,0 +1,236 @@
+use crate::algorithm::Algorithm;
+use crate::cache::CacheStats;
+use crate::fitness::Objective;
+use crate::logging::{status_observer, trace_observer};
+use crate::observer::{ConsoleObserver, GenerationStats, Observer, RunSummary};
+use crate::rate_control::{RateControl, RateController};
+use crate::repair::{BoundHandling, BoundaryStats, MAX_RESAMPLES, in_range};
+use crate::stopping::{StopMonitor, StopReason, StoppingCriteria};
+use rand::prelude::*;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct OnePlusOneParameters {
+    pub mem_size: usize,
+    pub mem_range: (f64, f64),
+    pub sigma: f64,
+    // Every `window` iterations sigma is divided by factor (0 < factor < 1) if more than a
+    // fifth of the mutations succeeded, and multiplied by it otherwise
+    pub factor: f64,
+    pub window: usize,
+    // What happens to genes mutated outside mem_range
+    pub bound_handling: BoundHandling,
+    pub max_iterations: usize,
+    pub stopping: StoppingCriteria,
+    // Iterations between the rows passed to the console, log and trace, which would
+    // otherwise get one row per evaluation
+    pub report_every: usize,
+    // Writes the run as JSON lines to this file in place of the console status lines
+    pub log_path: Option<String>,
+    // Seed the caller's RNG was created from, recorded in the run log for replay
+    pub seed: Option<u64>,
+    // Writes a CSV of per-iteration metrics to this file, alongside the other output
+    pub trace_path: Option<String>,
+}
+
+impl Default for OnePlusOneParameters {
+    fn default() -> Self {
+        Self {
+            mem_size: 10,
+            mem_range: (-5.12, 5.11),
+            sigma: 1.0,
+            factor: 0.85,
+            window: 10,
+            bound_handling: BoundHandling::Ignore,
+            max_iterations: 10000,
+            stopping: StoppingCriteria::default(),
+            report_every: 1,
+            log_path: None,
+            seed: None,
+            trace_path: None,
+        }
+    }
+}
+
+impl OnePlusOneParameters {
+    // Parameters as recorded in the run log
+    fn to_map(&self) -> HashMap<String, f64> {
+        HashMap::from([
+            ("mem_size".to_string(), self.mem_size as f64),
+            ("sigma".to_string(), self.sigma),
+            ("factor".to_string(), self.factor),
+            ("window".to_string(), self.window as f64),
+            ("max_iterations".to_string(), self.max_iterations as f64),
+        ])
+    }
+}
+
+// The classic (1+1)-ES: one parent, one Gaussian mutation per iteration, the child replacing
+// the parent unless it is worse, and sigma controlled by Rechenberg's 1/5th success rule.
+// An iteration is a generation as far as the Algorithm trait and the observers are concerned,
+// so its console lines, run log and trace read like those of the ES. The final "population"
+// is the parent with its sigma appended, as ES members carry it.
+pub struct OnePlusOneES<'a, F: Objective> {
+    fitness_fn: &'a F,
+    params: &'a OnePlusOneParameters,
+    parent: Vec<f64>,
+    parent_fitness: f64,
+    iteration: usize,
+    evaluations: usize,
+    controller: RateController,
+    boundary: BoundaryStats,
+    monitor: StopMonitor,
+    // Cache counts when the run started, as the cache may outlive it
+    cache_start: Option<CacheStats>,
+    observers: Vec<Box<dyn Observer<Vec<f64>> + 'a>>,
+}
+
+impl<'a, F: Objective> OnePlusOneES<'a, F> {
+    pub fn new<R: Rng + ?Sized>(
+        fitness_fn: &'a F,
+        params: &'a OnePlusOneParameters,
+        rng: &mut R,
+    ) -> Self {
+        let mut observers = vec![status_observer(
+            params.log_path.as_deref(),
+            "(1+1)-ES",
+            params.to_map(),
+            params.seed,
+            ConsoleObserver::one_plus_one(params),
+        )];
+        if let Some(trace) = trace_observer(params.trace_path.as_deref()) {
+            observers.push(Box::new(trace));
+        }
+        let cache_start = fitness_fn.cache_stats();
+        let (low, high) = params.mem_range;
+        let parent: Vec<f64> = (0..params.mem_size).map(|_| rng.random_range(low..high)).collect();
+        let parent_fitness = fitness_fn.fitness(&parent);
+        let control = RateControl::OneFifthRule { factor: params.factor, window: params.window };
+        Self {
+            fitness_fn,
+            params,
+            parent,
+            parent_fitness,
+            iteration: 0,
+            evaluations: 1,
+            controller: RateController::new(control, params.sigma, params.max_iterations),
+            boundary: BoundaryStats::default(),
+            monitor: StopMonitor::new(&params.stopping, fitness_fn.optimum_value()),
+            cache_start,
+            observers,
+        }
+    }
+
+    // Registers an observer alongside the console output
+    pub fn with_observer(mut self, observer: impl Observer<Vec<f64>> + 'a) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    // Removes all observers, including the console output, for runs driven in bulk
+    pub fn quiet(mut self) -> Self {
+        self.observers.clear();
+        self
+    }
+}
+
+impl<F: Objective> Algorithm for OnePlusOneES<'_, F> {
+    type Individual = Vec<f64>;
+
+    fn step<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<StopReason> {
+        if self.iteration >= self.params.max_iterations {
+            return Some(StopReason::MaxGenerations);
+        }
+        let sigma = self.controller.value();
+        let mutate = |rng: &mut R| -> Vec<f64> {
+            self.parent
+                .iter()
+                .map(|gene| gene + sigma * rng.sample::<f64, _>(rand_distr::StandardNormal))
+                .collect()
+        };
+        let mut child = mutate(rng);
+        let (handling, range) = (self.params.bound_handling, self.params.mem_range);
+        if handling == BoundHandling::Resample {
+            for _ in 0..MAX_RESAMPLES {
+                if child.iter().all(|gene| in_range(*gene, range)) {
+                    break;
+                }
+                self.boundary.resamples += 1;
+                child = mutate(rng);
+            }
+        }
+        if handling != BoundHandling::Ignore {
+            self.boundary.genes += handling.apply(&mut child, range);
+        }
+        let child_fitness = self.fitness_fn.fitness(&child);
+        self.evaluations += 1;
+        self.iteration += 1;
+
+        self.controller.record_offspring(child_fitness > self.parent_fitness);
+        // Equal children are accepted, so the search can drift across plateaus
+        if child_fitness >= self.parent_fitness {
+            self.parent = child;
+            self.parent_fitness = child_fitness;
+        }
+        self.controller.end_generation(self.iteration, self.evaluations);
+
+        let improved = self.parent_fitness > self.monitor.best_fitness;
+        let reported = self.iteration.is_multiple_of(self.params.report_every.max(1));
+        if improved || reported {
+            let stats = GenerationStats {
+                generation: self.iteration,
+                evaluations: self.evaluations,
+                max_fitness: self.parent_fitness,
+                avg_fitness: self.parent_fitness,
+                std_fitness: 0.0,
+                diversity: None,
+                cache: self.fitness_fn.cache_stats().map(|stats| stats.since(self.cache_start)),
+                boundary_hits: (handling != BoundHandling::Ignore).then_some(self.boundary.genes),
+                violations: None,
+                qd: None,
+            };
+            for observer in &mut self.observers {
+                if reported {
+                    observer.on_generation(&stats, &self.parent);
+                }
+                if improved {
+                    observer.on_improvement(&stats, &self.parent);
+                }
+            }
+        }
+        self.monitor.update(self.parent_fitness)
+    }
+
+    fn generation(&self) -> usize {
+        self.iteration
+    }
+
+    fn best_fitness(&self) -> f64 {
+        self.parent_fitness
+    }
+
+    fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+
+    fn finish(mut self, reason: StopReason) -> Vec<Vec<f64>> {
+        let summary = RunSummary {
+            reason,
+            generations: self.iteration,
+            evaluations: self.evaluations,
+            best_fitness: self.monitor.best_fitness,
+            stagnant_generations: self.monitor.stagnant_generations,
+        };
+        let mut messages = vec![format!("Sigma control: {}", self.controller.describe())];
+        messages.extend(self.boundary.report(self.params.bound_handling));
+        for observer in &mut self.observers {
+            for message in &messages {
+                observer.on_message(message);
+            }
+            observer.on_termination(&summary, Some(&self.parent));
+        }
+        let mut member = self.parent;
+        member.push(self.controller.value());
+        vec![member]
+    }
+}
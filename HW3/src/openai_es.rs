@@ -0,0 +1,272 @@
+use crate::algorithm::Algorithm;
+use crate::cache::CacheStats;
+use crate::fitness::Objective;
+use crate::logging::{status_observer, trace_observer};
+use crate::observer::{ConsoleObserver, GenerationStats, Observer, RunSummary, mean_and_std};
+use crate::parallel;
+use crate::seeds::SeedSequence;
+use crate::selection::ascending_ranks;
+use crate::stopping::{StopMonitor, StopReason, StoppingCriteria};
+use rand::prelude::*;
+use rand_distr::StandardNormal;
+use std::collections::HashMap;
+use std::thread;
+
+// Adam's decay rates for the first and second moments of the gradient, and the term keeping
+// its step finite where the second moment is zero
+const BETA1: f64 = 0.9;
+const BETA2: f64 = 0.999;
+const EPSILON: f64 = 1e-8;
+
+#[derive(Debug, Clone)]
+pub struct OpenAiESParameters {
+    // Perturbations evaluated per generation, in mirrored pairs, so rounded up to even
+    pub pop_size: usize,
+    pub mem_size: usize,
+    // Range the initial mean is drawn from
+    pub mem_range: (f64, f64),
+    // Standard deviation of the perturbations, fixed over the run
+    pub sigma: f64,
+    // Step size of the Adam update of the mean along the estimated gradient
+    pub learning_rate: f64,
+    // Threads the perturbations are evaluated on, each taking an equal share of them
+    pub workers: usize,
+    pub max_gens: usize,
+    pub stopping: StoppingCriteria,
+    // Writes the run as JSON lines to this file in place of the console status lines
+    pub log_path: Option<String>,
+    // Seed the caller's RNG was created from, recorded in the run log for replay
+    pub seed: Option<u64>,
+    // Writes a CSV of per-generation metrics to this file, alongside the other output
+    pub trace_path: Option<String>,
+}
+
+impl Default for OpenAiESParameters {
+    fn default() -> Self {
+        Self {
+            pop_size: 100,
+            mem_size: 10,
+            mem_range: (-5.12, 5.11),
+            sigma: 0.1,
+            learning_rate: 0.05,
+            workers: thread::available_parallelism().map_or(1, |n| n.get()),
+            max_gens: 1000,
+            stopping: StoppingCriteria::default(),
+            log_path: None,
+            seed: None,
+            trace_path: None,
+        }
+    }
+}
+
+impl OpenAiESParameters {
+    // Parameters as recorded in the run log
+    fn to_map(&self) -> HashMap<String, f64> {
+        HashMap::from([
+            ("pop_size".to_string(), self.pop_size as f64),
+            ("mem_size".to_string(), self.mem_size as f64),
+            ("sigma".to_string(), self.sigma),
+            ("learning_rate".to_string(), self.learning_rate),
+            ("max_gens".to_string(), self.max_gens as f64),
+        ])
+    }
+}
+
+// Centered rank utilities, from -0.5 for the worst to 0.5 for the best, so the gradient
+// estimate depends only on the order of the fitnesses and outliers can't swamp it
+fn centered_ranks(fitnesses: &[f64]) -> Vec<f64> {
+    let last = fitnesses.len().saturating_sub(1).max(1) as f64;
+    ascending_ranks(fitnesses).into_iter().map(|rank| rank as f64 / last - 0.5).collect()
+}
+
+// The natural evolution strategy of Salimans et al. (OpenAI-ES): rather than selecting
+// survivors, it keeps a single search distribution, an isotropic Gaussian around a mean,
+// and moves the mean along a search gradient estimated from the fitnesses of mirrored
+// perturbations (mean + sigma * eps and mean - sigma * eps) weighted by rank-shaped
+// utilities. Evaluating a generation is embarrassingly parallel, so it is spread over a pool
+// of worker threads, which suits large populations; each member draws any randomness of the
+// fitness from its own stream, so the run is the same on any number of workers. The final
+// "population" is the best point evaluated followed by the final mean, each with sigma
+// appended, as ES members carry it.
+pub struct OpenAiES<'a, F: Objective + Sync> {
+    fitness_fn: &'a F,
+    params: &'a OpenAiESParameters,
+    mean: Vec<f64>,
+    // Adam's running moments of the gradient
+    moment: Vec<f64>,
+    second_moment: Vec<f64>,
+    generation: usize,
+    evaluations: usize,
+    monitor: StopMonitor,
+    // Cache counts when the run started, as the cache may outlive it
+    cache_start: Option<CacheStats>,
+    observers: Vec<Box<dyn Observer<Vec<f64>> + 'a>>,
+    best_individual: Option<Vec<f64>>,
+    // Streams of the evaluations, one per generation and member
+    evaluation_seeds: SeedSequence,
+}
+
+impl<'a, F: Objective + Sync> OpenAiES<'a, F> {
+    pub fn new<R: Rng + ?Sized>(
+        fitness_fn: &'a F,
+        params: &'a OpenAiESParameters,
+        rng: &mut R,
+    ) -> Self {
+        let mut observers = vec![status_observer(
+            params.log_path.as_deref(),
+            "OpenAI-ES",
+            params.to_map(),
+            params.seed,
+            ConsoleObserver::openai_es(params),
+        )];
+        if let Some(trace) = trace_observer(params.trace_path.as_deref()) {
+            observers.push(Box::new(trace));
+        }
+        let (low, high) = params.mem_range;
+        Self {
+            fitness_fn,
+            params,
+            mean: (0..params.mem_size).map(|_| rng.random_range(low..high)).collect(),
+            moment: vec![0.0; params.mem_size],
+            second_moment: vec![0.0; params.mem_size],
+            generation: 0,
+            evaluations: 0,
+            monitor: StopMonitor::new(&params.stopping, fitness_fn.optimum_value()),
+            cache_start: fitness_fn.cache_stats(),
+            observers,
+            best_individual: None,
+            evaluation_seeds: SeedSequence::new(params.seed.unwrap_or_default())
+                .operator("evaluation"),
+        }
+    }
+
+    // Removes all observers, including the console output, for runs driven in bulk
+    pub fn quiet(mut self) -> Self {
+        self.observers.clear();
+        self
+    }
+
+    // Fitness of each member, the members split into one batch per worker, each with the
+    // streams of its members
+    fn evaluate(&self, members: &[Vec<f64>]) -> Vec<f64> {
+        let streams = self.evaluation_seeds.generation(self.generation).individuals(members.len());
+        parallel::evaluate_seeded(self.fitness_fn, members, &streams, self.params.workers)
+    }
+
+    // One Adam step of the mean up the estimated gradient
+    fn update_mean(&mut self, gradient: &[f64]) {
+        let t = (self.generation + 1) as i32;
+        let rate = self.params.learning_rate * (1.0 - BETA2.powi(t)).sqrt() / (1.0 - BETA1.powi(t));
+        for (i, &g) in gradient.iter().enumerate() {
+            self.moment[i] = BETA1 * self.moment[i] + (1.0 - BETA1) * g;
+            self.second_moment[i] = BETA2 * self.second_moment[i] + (1.0 - BETA2) * g * g;
+            self.mean[i] += rate * self.moment[i] / (self.second_moment[i].sqrt() + EPSILON);
+        }
+    }
+}
+
+impl<F: Objective + Sync> Algorithm for OpenAiES<'_, F> {
+    type Individual = Vec<f64>;
+
+    fn step<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<StopReason> {
+        if self.generation >= self.params.max_gens {
+            return Some(StopReason::MaxGenerations);
+        }
+        let (pairs, sigma) = (self.params.pop_size.div_ceil(2).max(1), self.params.sigma);
+        let noise: Vec<Vec<f64>> = (0..pairs)
+            .map(|_| (0..self.mean.len()).map(|_| rng.sample(StandardNormal)).collect())
+            .collect();
+        // Each pair's positive perturbation followed by its mirror, then the mean itself
+        let mut members: Vec<Vec<f64>> = noise
+            .iter()
+            .flat_map(|eps| {
+                let shifted = |sign: f64| -> Vec<f64> {
+                    self.mean.iter().zip(eps).map(|(m, e)| m + sign * sigma * e).collect()
+                };
+                [shifted(1.0), shifted(-1.0)]
+            })
+            .collect();
+        members.push(self.mean.clone());
+        let fitnesses = self.evaluate(&members);
+        self.evaluations += fitnesses.len();
+
+        // Gradient of the expected utility: sum over pairs of (u+ - u-) * eps / (n * sigma),
+        // n the number of perturbations
+        let utilities = centered_ranks(&fitnesses[..2 * pairs]);
+        let scale = 1.0 / (2 * pairs) as f64 / sigma;
+        let mut gradient = vec![0.0; self.mean.len()];
+        for (eps, utility) in noise.iter().zip(utilities.chunks(2)) {
+            let weight = (utility[0] - utility[1]) * scale;
+            for (g, e) in gradient.iter_mut().zip(eps) {
+                *g += weight * e;
+            }
+        }
+
+        let (avg_fitness, std_fitness) = mean_and_std(&fitnesses);
+        let best_index = (0..fitnesses.len())
+            .max_by(|&a, &b| fitnesses[a].total_cmp(&fitnesses[b]))
+            .unwrap_or(0);
+        let max_fitness = fitnesses[best_index];
+        let stats = GenerationStats {
+            generation: self.generation,
+            evaluations: self.evaluations,
+            max_fitness,
+            avg_fitness,
+            std_fitness,
+            diversity: None,
+            cache: self.fitness_fn.cache_stats().map(|stats| stats.since(self.cache_start)),
+            boundary_hits: None,
+            violations: None,
+            qd: None,
+        };
+        let best = &members[best_index];
+        let improved = max_fitness > self.monitor.best_fitness;
+        for observer in &mut self.observers {
+            observer.on_generation(&stats, best);
+        }
+        if improved {
+            self.best_individual = Some(best.clone());
+            for observer in &mut self.observers {
+                observer.on_improvement(&stats, best);
+            }
+        }
+
+        self.update_mean(&gradient);
+        self.generation += 1;
+        self.monitor.update(max_fitness)
+    }
+
+    fn generation(&self) -> usize {
+        self.generation
+    }
+
+    fn best_fitness(&self) -> f64 {
+        self.monitor.best_fitness
+    }
+
+    fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+
+    fn finish(mut self, reason: StopReason) -> Vec<Vec<f64>> {
+        let summary = RunSummary {
+            reason,
+            generations: self.generation,
+            evaluations: self.evaluations,
+            best_fitness: self.monitor.best_fitness,
+            stagnant_generations: self.monitor.stagnant_generations,
+        };
+        for observer in &mut self.observers {
+            observer.on_termination(&summary, self.best_individual.as_ref());
+        }
+        let sigma = self.params.sigma;
+        self.best_individual
+            .into_iter()
+            .chain([self.mean])
+            .map(|mut member| {
+                member.push(sigma);
+                member
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,280 @@
+use crate::cache::CacheStats;
+use crate::decoder::Decoder;
+use crate::fitness::{Fitness, Objective};
+use crate::seeds::SeedSequence;
+use std::thread;
+
+// A seeded batch split into one chunk per worker thread, each chunk with its members' streams.
+// Members draw only from their own streams, so the values are those of the whole batch on one
+// thread, bit for bit, whatever the number of workers.
+pub fn evaluate_seeded(
+    fitness_fn: &(impl Objective + Sync),
+    members: &[Vec<f64>],
+    streams: &[SeedSequence],
+    workers: usize,
+) -> Vec<f64> {
+    let chunk_size = chunk_size(members.len(), workers);
+    on_threads(members.chunks(chunk_size).zip(streams.chunks(chunk_size)), |(chunk, streams)| {
+        fitness_fn.evaluate_population_seeded(chunk, streams)
+    })
+}
+
+// Members per chunk for an even split across the workers
+fn chunk_size(members: usize, workers: usize) -> usize {
+    members.div_ceil(workers.clamp(1, members.max(1))).max(1)
+}
+
+// Each chunk evaluated on a thread of its own, unless there is only one, and the values in
+// chunk order
+fn on_threads<C: Send>(
+    chunks: impl Iterator<Item = C>,
+    evaluate: impl Fn(C) -> Vec<f64> + Sync,
+) -> Vec<f64> {
+    let mut chunks = chunks.peekable();
+    let Some(first) = chunks.next() else {
+        return Vec::new();
+    };
+    if chunks.peek().is_none() {
+        return evaluate(first);
+    }
+    let evaluate = &evaluate;
+    thread::scope(|scope| {
+        let handles: Vec<_> = std::iter::once(first)
+            .chain(chunks)
+            .map(|chunk| scope.spawn(move || evaluate(chunk)))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("evaluation thread panicked"))
+            .collect()
+    })
+}
+
+// Spreads the batches of the GA and ES (whole generations, offspring populations) over a
+// number of worker threads. Seeded batches give the same values on any number of workers;
+// unseeded ones only do for deterministic objectives. Single evaluations go to the inner
+// objective as they are.
+pub struct ParallelFitness<F> {
+    inner: F,
+    workers: usize,
+}
+
+impl<F: Objective + Sync> ParallelFitness<F> {
+    pub fn new(inner: F, workers: usize) -> Self {
+        Self { inner, workers: workers.max(1) }
+    }
+}
+
+impl<F: Objective + Sync> Objective for ParallelFitness<F> {
+    fn fitness(&self, member: &[f64]) -> f64 {
+        self.inner.fitness(member)
+    }
+
+    fn evaluate_population(&self, members: &[Vec<f64>]) -> Vec<f64> {
+        let chunks = members.chunks(chunk_size(members.len(), self.workers));
+        on_threads(chunks, |chunk| self.inner.evaluate_population(chunk))
+    }
+
+    fn evaluate_population_seeded(
+        &self,
+        members: &[Vec<f64>],
+        streams: &[SeedSequence],
+    ) -> Vec<f64> {
+        evaluate_seeded(&self.inner, members, streams, self.workers)
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        self.inner.bounds()
+    }
+
+    fn optimum_value(&self) -> Option<f64> {
+        self.inner.optimum_value()
+    }
+
+    fn known_optima(&self, num_dims: usize) -> Vec<Vec<f64>> {
+        self.inner.known_optima(num_dims)
+    }
+
+    fn cache_stats(&self) -> Option<CacheStats> {
+        self.inner.cache_stats()
+    }
+}
+
+impl<F: Fitness + Sync> Fitness for ParallelFitness<F> {
+    fn decoder(&self) -> impl Decoder {
+        self.inner.decoder()
+    }
+}
+
+// Serial and parallel runs of the same seed under noise must agree bit for bit
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::Algorithm;
+    use crate::bitstring::{GAParameters, GeneticAlgorithm};
+    use crate::evol_strat::{ESParameters, EvolutionStrategy};
+    use crate::noise::{NoiseModel, NoisyFitness};
+    use crate::observer::{GenerationStats, Observer};
+    use crate::openai_es::{OpenAiES, OpenAiESParameters};
+    use crate::repair::NoRepair;
+    use crate::rosenbrock::Rosenbrock;
+    use rand::prelude::*;
+    use rand_chacha::ChaCha8Rng;
+
+    const SEED: u64 = 5000;
+    const WORKERS: usize = 4;
+
+    // Bit patterns of each generation's fitness statistics
+    #[derive(Default)]
+    struct Trace(Vec<[u64; 3]>);
+
+    impl<I> Observer<I> for Trace {
+        fn on_generation(&mut self, stats: &GenerationStats, _best: &I) {
+            let (max, avg, std) = (stats.max_fitness, stats.avg_fitness, stats.std_fitness);
+            self.0.push([max.to_bits(), avg.to_bits(), std.to_bits()]);
+        }
+    }
+
+    // Evaluates each batch one member at a time from last to first, standing in for threads
+    // that finish out of order
+    struct Reversed<F>(F);
+
+    impl<F: Objective> Objective for Reversed<F> {
+        fn fitness(&self, member: &[f64]) -> f64 {
+            self.0.fitness(member)
+        }
+
+        fn evaluate_population(&self, members: &[Vec<f64>]) -> Vec<f64> {
+            let one = |member| self.0.evaluate_population(std::slice::from_ref(member))[0];
+            let mut values: Vec<f64> = members.iter().rev().map(one).collect();
+            values.reverse();
+            values
+        }
+
+        fn evaluate_population_seeded(
+            &self,
+            members: &[Vec<f64>],
+            streams: &[SeedSequence],
+        ) -> Vec<f64> {
+            let one = |(member, stream)| {
+                let (member, stream) = (std::slice::from_ref(member), std::slice::from_ref(stream));
+                self.0.evaluate_population_seeded(member, stream)[0]
+            };
+            let mut values: Vec<f64> = members.iter().zip(streams).rev().map(one).collect();
+            values.reverse();
+            values
+        }
+
+        fn bounds(&self) -> (f64, f64) {
+            self.0.bounds()
+        }
+    }
+
+    impl<F: Fitness> Fitness for Reversed<F> {
+        fn decoder(&self) -> impl Decoder {
+            self.0.decoder()
+        }
+    }
+
+    fn noisy() -> NoisyFitness<Rosenbrock> {
+        NoisyFitness::new(Rosenbrock, NoiseModel::gaussian(0.05).unwrap(), SEED)
+    }
+
+    fn ga_trace(fitness_fn: &(impl Fitness + Sync)) -> (Vec<[u64; 3]>, Vec<String>) {
+        let params = GAParameters {
+            mem_size: 160,
+            num_dims: 10,
+            max_iters: 30,
+            resamples: 2,
+            seed: Some(SEED),
+            ..GAParameters::default()
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(SEED);
+        let mut trace = Trace::default();
+        let population =
+            GeneticAlgorithm::<String, _, _>::new(fitness_fn, &params, &NoRepair, &mut rng)
+                .quiet()
+                .with_observer(&mut trace)
+                .run(&mut rng);
+        (trace.0, population)
+    }
+
+    fn es_trace(fitness_fn: &(impl Objective + Sync)) -> (Vec<[u64; 3]>, Vec<Vec<f64>>) {
+        let params = ESParameters {
+            mem_size: 10,
+            max_gens: 30,
+            resamples: 2,
+            seed: Some(SEED),
+            ..ESParameters::default()
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(SEED);
+        let mut trace = Trace::default();
+        let population = EvolutionStrategy::new(fitness_fn, &params, &NoRepair, &mut rng)
+            .quiet()
+            .with_observer(&mut trace)
+            .run(&mut rng);
+        (trace.0, population)
+    }
+
+    // Best fitness after each generation, as OpenAI-ES doesn't take observers from outside
+    fn openai_es_trace(
+        fitness_fn: &(impl Objective + Sync),
+        workers: usize,
+    ) -> (Vec<u64>, Vec<Vec<f64>>) {
+        let params = OpenAiESParameters {
+            workers,
+            max_gens: 30,
+            seed: Some(SEED),
+            ..OpenAiESParameters::default()
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(SEED);
+        let mut es = OpenAiES::new(fitness_fn, &params, &mut rng).quiet();
+        let mut trace = Vec::new();
+        let reason = loop {
+            if let Some(reason) = es.step(&mut rng) {
+                break reason;
+            }
+            trace.push(es.best_fitness().to_bits());
+        };
+        (trace, es.finish(reason))
+    }
+
+    #[test]
+    fn ga_runs_match_on_any_number_of_workers() {
+        let serial = ga_trace(&noisy());
+        assert_eq!(serial.0.len(), 30);
+        assert_eq!(serial, ga_trace(&ParallelFitness::new(noisy(), WORKERS)));
+        assert_eq!(serial, ga_trace(&Reversed(noisy())));
+    }
+
+    #[test]
+    fn es_runs_match_on_any_number_of_workers() {
+        let serial = es_trace(&noisy());
+        assert!(!serial.0.is_empty());
+        assert_eq!(serial, es_trace(&ParallelFitness::new(noisy(), WORKERS)));
+        assert_eq!(serial, es_trace(&Reversed(noisy())));
+    }
+
+    #[test]
+    fn openai_es_runs_match_on_any_number_of_workers() {
+        let serial = openai_es_trace(&noisy(), 1);
+        assert!(!serial.0.is_empty());
+        assert_eq!(serial, openai_es_trace(&noisy(), WORKERS));
+        assert_eq!(serial, openai_es_trace(&Reversed(noisy()), 1));
+    }
+
+    #[test]
+    fn seeded_batches_match_when_split() {
+        let fitness_fn = noisy();
+        let mut rng = ChaCha8Rng::seed_from_u64(SEED);
+        let members: Vec<Vec<f64>> = (0..37)
+            .map(|_| (0..10).map(|_| rng.random_range(-2.0..2.0)).collect())
+            .collect();
+        let streams = SeedSequence::new(SEED).generation(3).individuals(members.len());
+        let bits = |values: Vec<f64>| values.iter().map(|v| v.to_bits()).collect::<Vec<u64>>();
+        let serial = bits(evaluate_seeded(&fitness_fn, &members, &streams, 1));
+        for workers in [2, 5, 64] {
+            assert_eq!(serial, bits(evaluate_seeded(&fitness_fn, &members, &streams, workers)));
+        }
+    }
+}
@@ -1,11 +1,26 @@
 use crate::bitstring::GAParameters;
+use crate::checkpoint::Checkpointer;
 use crate::evol_strat::ESParameters;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use crate::results_analyzer::{ResultsAnalyzer, ResultsWriter};
+use crate::screening::{DesignMatrix, Factor};
+use crate::seeds::SeedSequence;
+use crate::selection::SelectionMethod;
+#[cfg(feature = "cli")]
+use indicatif::{ProgressBar, ProgressStyle};
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TuningResult {
     pub algorithm: String,
+    #[serde(serialize_with = "serialize_sorted")]
     pub parameters: HashMap<String, f64>,
     pub run_id: usize,
     pub max_fitness: f64,
@@ -14,6 +29,36 @@ pub struct TuningResult {
     pub converged: bool,
     pub generations: usize,
     pub timeout_reached: bool,
+    // How the combination was chosen: "grid", "random", "lhs", "sobol" or "bayes"
+    pub design: String,
+    // Seed of the run's RNG, derived from the session's master seed
+    pub seed: u64,
+    // Evaluations the run needed to reach the session's target fitness, None if it never did
+    pub evaluations_to_target: Option<usize>,
+    // Normalized area under the run's best-so-far curve over evaluations, None for results
+    // from before it was recorded
+    pub auc: Option<f64>,
+    // Whether the best fitness ended within the session's success tolerance of the problem's
+    // optimum value, None if the problem doesn't know it or for results from before it was
+    // recorded
+    pub success: Option<bool>,
+    // Clusters the final population covered in phenotype space, None for results from before
+    // it was recorded
+    pub distinct_optima: Option<usize>,
+    // Resource use of the run, all None for results from before it was recorded. The peak
+    // resident set is the whole process's, see resources::ResourceMonitor.
+    pub evaluations_per_second: Option<f64>,
+    pub peak_rss_mb: Option<f64>,
+    pub mean_generation_ms: Option<f64>,
+    pub max_generation_ms: Option<f64>,
+}
+
+// Parameter maps are written with their names in order, so JSON output is stable
+pub fn serialize_sorted<S: Serializer>(
+    map: &HashMap<String, f64>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
 }
 
 pub struct ParameterGrid;
@@ -27,14 +72,7 @@ impl ParameterGrid {
 
         for &pop_size in &population_sizes {
             for &mutation_rate in &mutation_rates {
-                grid.push(GAParameters {
-                    pop_size,
-                    mem_size: 16 * 10, // 16 bits per dimension * 10 dimensions
-                    mutation_rate,
-                    crossover_rate: 0.75,
-                    max_iters: 1000,
-                    convergence_threshold: 0.95,
-                });
+                grid.push(Self::sga_params(pop_size, mutation_rate, 0.75));
             }
         }
 
@@ -49,32 +87,104 @@ impl ParameterGrid {
 
         for &lambda in &lambda_values {
             for &sigma in &sigma_values {
-                grid.push(ESParameters {
-                    mu: lambda / 2,
-                    lambda,
-                    mem_size: 10, // 10 dimensions
-                    mem_range: (-5.12, 5.11),
-                    sigma,
-                    tau: 1.0 / (2.0 * 10.0_f64).sqrt(),
-                    max_gens: 1000,
-                });
+                grid.push(Self::es_params(lambda, sigma));
             }
         }
 
         grid
     }
 
+    // The combination custom grids start from, before their axes and fixed values apply
+    pub fn sga_base() -> GAParameters {
+        Self::sga_params(100, 0.01, 0.75)
+    }
+
+    pub fn es_base() -> ESParameters {
+        Self::es_params(100, 1.0)
+    }
+
+    // The base combination with `values` set, which are named as in the results CSV. As in
+    // the built-in grids, an ES lambda without mu sets mu to lambda / 2, and SGA dimensions
+    // without mem_size give 16 bits to each.
+    pub fn sga_with(values: &HashMap<String, f64>) -> Option<GAParameters> {
+        let mut map = Self::params_to_map_ga(&Self::sga_base());
+        map.extend(values.iter().map(|(name, &value)| (name.clone(), value)));
+        if let Some(dims) = values.get("num_dims").filter(|_| !values.contains_key("mem_size")) {
+            map.insert("mem_size".to_string(), 16.0 * dims);
+        }
+        ResultsAnalyzer::key_to_ga_params(&map)
+    }
+
+    pub fn es_with(values: &HashMap<String, f64>) -> Option<ESParameters> {
+        let mut map = Self::params_to_map_es(&Self::es_base());
+        map.extend(values.iter().map(|(name, &value)| (name.clone(), value)));
+        if let Some(lambda) = values.get("lambda").filter(|_| !values.contains_key("mu")) {
+            map.insert("mu".to_string(), (lambda / 2.0).floor().max(1.0));
+        }
+        ResultsAnalyzer::key_to_es_params(&map)
+    }
+
+    // sga_with and es_with, with the combination named when it doesn't give parameters
+    pub fn sga_checked(values: &HashMap<String, f64>) -> Result<GAParameters, String> {
+        Self::sga_with(values)
+            .ok_or_else(|| format!("no SGA parameters from {}", describe(values)))
+    }
+
+    pub fn es_checked(values: &HashMap<String, f64>) -> Result<ESParameters, String> {
+        Self::es_with(values).ok_or_else(|| format!("no ES parameters from {}", describe(values)))
+    }
+
+    // The settings every tuned SGA shares; only the arguments vary between combinations
+    fn sga_params(pop_size: usize, mutation_rate: f64, crossover_rate: f64) -> GAParameters {
+        GAParameters {
+            pop_size,
+            mem_size: 16 * 10, // 16 bits per dimension * 10 dimensions
+            num_dims: 10,
+            mutation_rate,
+            crossover_rate,
+            max_iters: 1000,
+            convergence_threshold: 0.95,
+            ..GAParameters::default()
+        }
+    }
+
+    fn es_params(lambda: usize, sigma: f64) -> ESParameters {
+        ESParameters {
+            mu: lambda / 2,
+            lambda,
+            mem_size: 10, // 10 dimensions
+            mem_range: (-5.12, 5.11),
+            sigma,
+            tau: 1.0 / (2.0 * 10.0_f64).sqrt(),
+            max_gens: 1000,
+            ..ESParameters::default()
+        }
+    }
+
+    // Every parameter name of either algorithm, as they appear in the results CSV
+    pub fn parameter_names() -> BTreeSet<String> {
+        let ga = Self::params_to_map_ga(&GAParameters::default());
+        let es = Self::params_to_map_es(&ESParameters::default());
+        ga.into_keys().chain(es.into_keys()).collect()
+    }
+
     pub fn params_to_map_ga(params: &GAParameters) -> HashMap<String, f64> {
         let mut map = HashMap::new();
         map.insert("pop_size".to_string(), params.pop_size as f64);
         map.insert("mem_size".to_string(), params.mem_size as f64);
+        map.insert("num_dims".to_string(), params.num_dims as f64);
         map.insert("mutation_rate".to_string(), params.mutation_rate);
         map.insert("crossover_rate".to_string(), params.crossover_rate);
+        map.insert("truncation_fraction".to_string(), params.truncation_fraction);
+        if let SelectionMethod::Tournament { size } = params.selection {
+            map.insert("tournament_size".to_string(), size as f64);
+        }
         map.insert("max_iters".to_string(), params.max_iters as f64);
         map.insert(
             "convergence_threshold".to_string(),
             params.convergence_threshold,
         );
+        map.insert("resamples".to_string(), params.resamples as f64);
         map
     }
 
@@ -88,24 +198,587 @@ impl ParameterGrid {
         map.insert("sigma".to_string(), params.sigma);
         map.insert("tau".to_string(), params.tau);
         map.insert("max_gens".to_string(), params.max_gens as f64);
+        map.insert(
+            "convergence_threshold".to_string(),
+            params.convergence_threshold,
+        );
+        map.insert("resamples".to_string(), params.resamples as f64);
         map
     }
 }
 
+// Values of one parameter of a custom grid: a single fixed value, a list, or a range of
+// evenly spaced values, spaced evenly in the exponent with "log": true
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum GridAxis {
+    Fixed(f64),
+    Values(Vec<f64>),
+    Range {
+        from: f64,
+        to: f64,
+        steps: usize,
+        #[serde(default)]
+        log: bool,
+    },
+}
+
+impl GridAxis {
+    fn values(&self) -> Vec<f64> {
+        match self {
+            GridAxis::Fixed(value) => vec![*value],
+            GridAxis::Values(values) => values.clone(),
+            GridAxis::Range { from, to, steps, log } => {
+                let at = |t: f64| match log {
+                    true => (from.ln() + t * (to.ln() - from.ln())).exp(),
+                    false => from + t * (to - from),
+                };
+                match steps {
+                    0 => Vec::new(),
+                    1 => vec![*from],
+                    _ => (0..*steps).map(|i| at(i as f64 / (steps - 1) as f64)).collect(),
+                }
+            }
+        }
+    }
+}
+
+// Why `value` can't be given to the parameter `name`, if it can't: counts must be at least 1
+// (fractions of one are dropped), rates and fractions within [0, 1] and step sizes positive
+fn check_value(name: &str, value: f64) -> Result<(), String> {
+    let fine = match name {
+        "pop_size" | "mem_size" | "num_dims" | "tournament_size" | "max_iters" | "resamples"
+        | "mu" | "lambda" | "max_gens" => value >= 1.0,
+        "mutation_rate" | "crossover_rate" | "truncation_fraction" | "convergence_threshold" => {
+            (0.0..=1.0).contains(&value)
+        }
+        "sigma" | "tau" => value > 0.0,
+        _ => true,
+    };
+    match fine && value.is_finite() {
+        true => Ok(()),
+        false => Err(format!("{value} is out of range")),
+    }
+}
+
+// A combination's values in name order, for error messages
+fn describe(values: &HashMap<String, f64>) -> String {
+    let sorted: BTreeMap<&String, &f64> = values.iter().collect();
+    let pairs: Vec<String> = sorted.iter().map(|(name, value)| format!("{name} {value}")).collect();
+    pairs.join(", ")
+}
+
+// A parameter grid read from a JSON file, in place of the built-in one, e.g.
+//
+//   {"sga": {"pop_size": [50, 200], "mutation_rate": {"from": 0.001, "to": 0.2, "steps": 5,
+//            "log": true}, "tournament_size": [2, 4], "crossover_rate": 0.9},
+//    "es": {"lambda": [50, 200], "sigma": {"from": 0.1, "to": 2.0, "steps": 4}}}
+//
+// Parameters are named as in the results CSV, and the combinations of an algorithm are the
+// full factorial product of its axes, with parameters left out set as in ParameterGrid::sga_with
+// and es_with. An algorithm left out keeps the built-in grid. Screening designs try each axis
+// of several values at its lowest and highest only. Loading rejects values a parameter can't
+// take and log ranges that don't stay positive, naming the parameter.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GridSpec {
+    sga: Option<BTreeMap<String, GridAxis>>,
+    es: Option<BTreeMap<String, GridAxis>>,
+}
+
+impl GridSpec {
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let spec: Self = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let ga_names = ParameterGrid::params_to_map_ga(&ParameterGrid::sga_base());
+        let es_names = ParameterGrid::params_to_map_es(&ParameterGrid::es_base());
+        for (algorithm, axes, names) in [("sga", &spec.sga, ga_names), ("es", &spec.es, es_names)] {
+            for (name, axis) in axes.iter().flatten() {
+                if !names.contains_key(name) {
+                    return Err(format!("unknown {algorithm} parameter {name}").into());
+                }
+                if let GridAxis::Range { from, to, log: true, .. } = axis
+                    && (*from <= 0.0 || *to <= 0.0)
+                {
+                    return Err(format!(
+                        "{algorithm} parameter {name} has a log range from {from} to {to}, \
+                         which needs both ends positive"
+                    )
+                    .into());
+                }
+                let values = axis.values();
+                if values.is_empty() {
+                    return Err(format!("{algorithm} parameter {name} has no values").into());
+                }
+                for value in values {
+                    check_value(name, value)
+                        .map_err(|e| format!("{algorithm} parameter {name}: {e}"))?;
+                }
+            }
+        }
+        spec.sga_grid()?;
+        spec.es_grid()?;
+        Ok(spec)
+    }
+
+    pub fn sga_grid(&self) -> Result<Vec<GAParameters>, String> {
+        let Some(axes) = &self.sga else {
+            return Ok(ParameterGrid::generate_sga_grid());
+        };
+        Self::combinations(axes).iter().map(ParameterGrid::sga_checked).collect()
+    }
+
+    pub fn es_grid(&self) -> Result<Vec<ESParameters>, String> {
+        let Some(axes) = &self.es else {
+            return Ok(ParameterGrid::generate_es_grid());
+        };
+        Self::combinations(axes).iter().map(ParameterGrid::es_checked).collect()
+    }
+
+    // The fixed values and the factors (axes of more than one value) of a screening design
+    // over the SGA or ES axes, None for an algorithm left out
+    pub fn sga_factors(&self) -> Option<(HashMap<String, f64>, Vec<Factor>)> {
+        self.sga.as_ref().map(Self::factors)
+    }
+
+    pub fn es_factors(&self) -> Option<(HashMap<String, f64>, Vec<Factor>)> {
+        self.es.as_ref().map(Self::factors)
+    }
+
+    fn factors(axes: &BTreeMap<String, GridAxis>) -> (HashMap<String, f64>, Vec<Factor>) {
+        let (mut fixed, mut factors) = (HashMap::new(), Vec::new());
+        for (name, axis) in axes {
+            let values = axis.values();
+            let low = values.iter().copied().fold(f64::INFINITY, f64::min);
+            let high = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            if low < high {
+                factors.push(Factor { name: name.clone(), low, high });
+            } else {
+                fixed.insert(name.clone(), low);
+            }
+        }
+        (fixed, factors)
+    }
+
+    // Every combination of the axes' values, the first axis (in name order) varying slowest
+    fn combinations(axes: &BTreeMap<String, GridAxis>) -> Vec<HashMap<String, f64>> {
+        let mut combinations = vec![HashMap::new()];
+        for (name, axis) in axes {
+            let values = axis.values();
+            combinations = combinations
+                .into_iter()
+                .flat_map(|map| {
+                    values.iter().map(move |&value| {
+                        let mut map = map.clone();
+                        map.insert(name.clone(), value);
+                        map
+                    })
+                })
+                .collect();
+        }
+        combinations
+    }
+}
+
+// Range a sampled parameter is drawn from
+#[derive(Debug, Clone, Copy)]
+pub enum Distribution {
+    Uniform(f64, f64),
+    // Uniform in the exponent, for rates and step sizes spanning orders of magnitude
+    LogUniform(f64, f64),
+}
+
+impl Distribution {
+    // Maps a point of [0, 1) onto the range, so a design's spread carries over to it
+    pub fn at(&self, u: f64) -> f64 {
+        match *self {
+            Distribution::Uniform(low, high) => low + u * (high - low),
+            Distribution::LogUniform(low, high) => (low.ln() + u * (high / low).ln()).exp(),
+        }
+    }
+}
+
+// How a sampled search places its combinations in the unit cube
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sampling {
+    // Independent uniform draws
+    Random,
+    // One point per stratum of every axis
+    LatinHypercube,
+    // Digitally shifted Sobol sequence
+    Sobol,
+}
+
+impl fmt::Display for Sampling {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            Sampling::Random => "random",
+            Sampling::LatinHypercube => "lhs",
+            Sampling::Sobol => "sobol",
+        };
+        write!(f, "{text}")
+    }
+}
+
+impl Sampling {
+    pub fn points<R: Rng + ?Sized>(self, n: usize, dims: usize, rng: &mut R) -> Vec<Vec<f64>> {
+        match self {
+            Sampling::Random => (0..n).map(|_| (0..dims).map(|_| rng.random()).collect()).collect(),
+            Sampling::LatinHypercube => latin_hypercube(n, dims, rng),
+            Sampling::Sobol => sobol(n, dims, rng),
+        }
+    }
+}
+
+// Each axis is cut into n strata and every stratum holds exactly one point, at a random
+// offset; the strata are paired up across axes by independent shuffles
+fn latin_hypercube<R: Rng + ?Sized>(n: usize, dims: usize, rng: &mut R) -> Vec<Vec<f64>> {
+    let mut points = vec![vec![0.0; dims]; n];
+    for axis in 0..dims {
+        let mut strata: Vec<usize> = (0..n).collect();
+        strata.shuffle(rng);
+        for (point, stratum) in points.iter_mut().zip(strata) {
+            point[axis] = (stratum as f64 + rng.random::<f64>()) / n as f64;
+        }
+    }
+    points
+}
+
+// (degree, coefficients, initial direction numbers) of the Sobol axes after the first, from
+// Joe and Kuo's table
+const SOBOL_AXES: [(u32, u32, &[u32]); 6] = [
+    (1, 0, &[1]),
+    (2, 1, &[1, 3]),
+    (3, 1, &[1, 3, 1]),
+    (3, 2, &[1, 1, 1]),
+    (4, 1, &[1, 1, 3, 3]),
+    (4, 4, &[1, 3, 5, 13]),
+];
+
+fn direction_numbers(axis: usize) -> [u32; 32] {
+    let mut v = [0u32; 32];
+    if axis == 0 {
+        for (k, value) in v.iter_mut().enumerate() {
+            *value = 1 << (31 - k);
+        }
+        return v;
+    }
+    let (degree, coefficients, initial) = SOBOL_AXES[axis - 1];
+    let s = degree as usize;
+    for k in 0..32 {
+        v[k] = if k < s {
+            initial[k] << (31 - k)
+        } else {
+            let mut value = v[k - s] ^ (v[k - s] >> s);
+            for i in 1..s {
+                if (coefficients >> (s - 1 - i)) & 1 == 1 {
+                    value ^= v[k - i];
+                }
+            }
+            value
+        };
+    }
+    v
+}
+
+// The first n Sobol points in Gray-code order, each axis XORed with a random shift so
+// different seeds give different (but equally well spread) designs
+fn sobol<R: Rng + ?Sized>(n: usize, dims: usize, rng: &mut R) -> Vec<Vec<f64>> {
+    assert!(dims <= SOBOL_AXES.len() + 1, "Sobol design supports up to 7 parameters");
+    let directions: Vec<[u32; 32]> = (0..dims).map(direction_numbers).collect();
+    let shifts: Vec<u32> = (0..dims).map(|_| rng.random()).collect();
+    let mut state = vec![0u32; dims];
+    let mut points = Vec::with_capacity(n);
+    for i in 0..n {
+        if i > 0 {
+            let bit = (i as u32).trailing_zeros() as usize;
+            for (x, v) in state.iter_mut().zip(&directions) {
+                *x ^= v[bit];
+            }
+        }
+        let point = state
+            .iter()
+            .zip(&shifts)
+            .map(|(x, shift)| (x ^ shift) as f64 / 2f64.powi(32))
+            .collect();
+        points.push(point);
+    }
+    points
+}
+
+// Alternative to the full factorial grids: `samples` combinations per algorithm placed by a
+// random, Latin hypercube or Sobol design, so no two combinations share a value and far fewer
+// runs cover the joint parameter space
+pub struct SampledSearch {
+    pub sampling: Sampling,
+    pub samples: usize,
+    pub seed: u64,
+    pub pop_size: Distribution,
+    pub mutation_rate: Distribution,
+    pub crossover_rate: Distribution,
+    pub lambda: Distribution,
+    pub sigma: Distribution,
+}
+
+impl Default for SampledSearch {
+    fn default() -> Self {
+        Self {
+            sampling: Sampling::Random,
+            samples: 25,
+            seed: 0,
+            pop_size: Distribution::Uniform(50.0, 500.0),
+            mutation_rate: Distribution::LogUniform(0.001, 0.2),
+            crossover_rate: Distribution::Uniform(0.5, 1.0),
+            lambda: Distribution::Uniform(50.0, 500.0),
+            sigma: Distribution::LogUniform(0.05, 2.0),
+        }
+    }
+}
+
+impl SampledSearch {
+    // Number of sampled SGA and ES parameters, i.e. the dimension of their unit cubes
+    pub const SGA_AXES: usize = 3;
+    pub const ES_AXES: usize = 2;
+
+    // The SGA at a point of the unit cube: population size, mutation rate, crossover rate
+    pub fn sga_at(&self, u: &[f64]) -> GAParameters {
+        let pop_size = self.pop_size.at(u[0]).round() as usize;
+        let mutation_rate = self.mutation_rate.at(u[1]);
+        ParameterGrid::sga_params(pop_size, mutation_rate, self.crossover_rate.at(u[2]))
+    }
+
+    // The ES at a point of the unit cube: lambda, sigma
+    pub fn es_at(&self, u: &[f64]) -> ESParameters {
+        ParameterGrid::es_params(self.lambda.at(u[0]).round() as usize, self.sigma.at(u[1]))
+    }
+
+    // The sampled parameters as screening factors, between the ends of their ranges
+    pub fn sga_factors(&self) -> Vec<Factor> {
+        let factor = |name: &str, range: Distribution| Factor {
+            name: name.to_string(),
+            low: range.at(0.0),
+            high: range.at(1.0),
+        };
+        vec![
+            factor("pop_size", self.pop_size),
+            factor("mutation_rate", self.mutation_rate),
+            factor("crossover_rate", self.crossover_rate),
+        ]
+    }
+
+    pub fn es_factors(&self) -> Vec<Factor> {
+        let factor = |name: &str, range: Distribution| Factor {
+            name: name.to_string(),
+            low: range.at(0.0),
+            high: range.at(1.0),
+        };
+        vec![factor("lambda", self.lambda), factor("sigma", self.sigma)]
+    }
+
+    pub fn sample_sga(&self) -> Vec<GAParameters> {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let points = self.sampling.points(self.samples, Self::SGA_AXES, &mut rng);
+        points.iter().map(|u| self.sga_at(u)).collect()
+    }
+
+    // Seeded apart from the SGA draws so changing one sample count leaves the other's alone
+    pub fn sample_es(&self) -> Vec<ESParameters> {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed.wrapping_add(1));
+        let points = self.sampling.points(self.samples, Self::ES_AXES, &mut rng);
+        points.iter().map(|u| self.es_at(u)).collect()
+    }
+}
+
+// Stands in for indicatif's bar without the cli feature: always hidden, so progress goes to
+// stdout line by line
+#[cfg(not(feature = "cli"))]
+struct ProgressBar;
+
+#[cfg(not(feature = "cli"))]
+impl ProgressBar {
+    fn hidden() -> Self {
+        ProgressBar
+    }
+
+    fn is_hidden(&self) -> bool {
+        true
+    }
+
+    fn println(&self, _line: &str) {}
+
+    fn inc(&self, _delta: u64) {}
+
+    fn reset_eta(&self) {}
+
+    fn finish(&self) {}
+
+    fn set_message(&self, _message: String) {}
+}
+
+// Progress bar over every run of a tuning session, with the best score so far. Drawn on
+// stderr, and hidden when that isn't a terminal.
+pub struct TuningProgress {
+    bar: ProgressBar,
+    best_score: Mutex<f64>,
+}
+
+impl TuningProgress {
+    #[cfg(feature = "cli")]
+    pub fn new(total_runs: usize) -> Self {
+        let style = ProgressStyle::with_template(
+            "[{elapsed_precise}] {bar:40} {pos}/{len} runs, best score {msg}, ETA {eta}",
+        )
+        .expect("valid progress template");
+        let bar = ProgressBar::new(total_runs as u64).with_style(style);
+        bar.set_message("-");
+        Self { bar, best_score: Mutex::new(f64::NEG_INFINITY) }
+    }
+
+    #[cfg(not(feature = "cli"))]
+    pub fn new(_total_runs: usize) -> Self {
+        Self::hidden()
+    }
+
+    pub fn hidden() -> Self {
+        Self { bar: ProgressBar::hidden(), best_score: Mutex::new(f64::NEG_INFINITY) }
+    }
+
+    // Prints above the bar, or plainly when there's no bar to keep clear of
+    pub fn println(&self, line: &str) {
+        if self.bar.is_hidden() {
+            println!("{line}");
+        } else {
+            self.bar.println(line);
+        }
+    }
+
+    pub fn finish_run(&self, result: &TuningResult) {
+        self.update_best(result);
+        self.bar.inc(1);
+    }
+
+    // Runs a resumed session already has count as done, without skewing the ETA
+    pub fn skip(&self, results: &[&TuningResult]) {
+        for result in results {
+            self.update_best(result);
+        }
+        self.bar.inc(results.len() as u64);
+        self.bar.reset_eta();
+    }
+
+    pub fn finish(&self) {
+        self.bar.finish();
+    }
+
+    fn update_best(&self, result: &TuningResult) {
+        let mut best = self.best_score.lock().unwrap();
+        if result.score > *best {
+            *best = result.score;
+            self.bar.set_message(format!("{:.6} ({})", result.score, result.algorithm));
+        }
+    }
+}
+
 pub struct TuningConfig {
     pub num_runs: usize,
     pub timeout_seconds: u64,
+    // Wall-clock budget of the whole session: runs are cut short to fit what is left of it,
+    // and no run starts once it is spent
+    pub total_budget_seconds: Option<u64>,
+    // When the session, and its budget, started
+    pub started: Instant,
     pub num_dimensions: usize,
+    // Threads running grid jobs concurrently
+    pub num_workers: usize,
+    // Design the combinations came from, recorded with every result
+    pub design: String,
+    // Coded levels of the SGA and ES combinations of a screening design
+    pub design_matrices: Vec<(String, DesignMatrix)>,
+    // Directory to write a CSV trace of every run into, for plotting
+    pub trace_dir: Option<String>,
+    // Where each result is appended as soon as its run finishes
+    pub results_writer: Option<Mutex<ResultsWriter>>,
+    // Runs already in the results file of a resumed session, which aren't repeated
+    pub completed: Vec<TuningResult>,
+    pub progress: TuningProgress,
+    // Fitness a run has to reach for its evaluations to target to be recorded
+    pub target_fitness: f64,
+    // Distance from the problem's optimum value within which a run's best fitness counts as
+    // a success
+    pub success_epsilon: f64,
+    // Phenotype distance within which final members count as sitting on the same optimum
+    pub cluster_radius: f64,
+    // Snapshots the session so it can be recovered after a crash
+    pub checkpoint: Option<Checkpointer>,
+    // Run n is seeded with seeds.run(n)
+    pub seeds: SeedSequence,
     #[allow(dead_code)]
     pub bits_per_dimension: usize,
 }
 
+impl TuningConfig {
+    // The earlier result for this run, when resuming a session that already finished it
+    pub fn completed_run(
+        &self,
+        algorithm: &str,
+        parameters: &HashMap<String, f64>,
+        run_id: usize,
+    ) -> Option<&TuningResult> {
+        self.completed.iter().find(|result| {
+            result.algorithm == algorithm
+                && result.run_id == run_id
+                && result.design == self.design
+                && result.parameters == *parameters
+        })
+    }
+
+    pub fn record(&self, result: &TuningResult) {
+        if let Some(writer) = &self.results_writer {
+            writer.lock().unwrap().write(result);
+        }
+        if let Some(checkpoint) = &self.checkpoint {
+            checkpoint.record(result);
+        }
+        self.progress.finish_run(result);
+    }
+
+    // Timeout for a run starting now: the per-run timeout, shortened to what is left of the
+    // session's budget, or None once the budget is spent
+    pub fn run_timeout(&self) -> Option<Duration> {
+        let timeout = Duration::from_secs(self.timeout_seconds);
+        let Some(budget) = self.total_budget_seconds else {
+            return Some(timeout);
+        };
+        let left = Duration::from_secs(budget).saturating_sub(self.started.elapsed());
+        (!left.is_zero()).then(|| timeout.min(left))
+    }
+
+    // Trace file for one run of one grid combination, if traces were requested
+    pub fn trace_path(&self, algorithm: &str, combination: usize, run: usize) -> Option<String> {
+        let dir = self.trace_dir.as_ref()?;
+        Some(format!("{dir}/{}_{combination}_run{run}.csv", algorithm.to_lowercase()))
+    }
+}
+
 impl Default for TuningConfig {
     fn default() -> Self {
         Self {
             num_runs: 5,
             timeout_seconds: 60,
+            total_budget_seconds: None,
+            started: Instant::now(),
             num_dimensions: 10,
+            num_workers: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            design: "grid".to_string(),
+            design_matrices: Vec::new(),
+            trace_dir: None,
+            results_writer: None,
+            completed: Vec::new(),
+            progress: TuningProgress::hidden(),
+            target_fitness: 0.05,
+            success_epsilon: 0.01,
+            cluster_radius: 0.5,
+            checkpoint: None,
+            seeds: SeedSequence::new(5000),
             bits_per_dimension: 16,
         }
     }
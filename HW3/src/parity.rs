@@ -0,0 +1,48 @@
+use crate::cgp::{CgpFunction, CgpGenome, ProgramObjective};
+
+// Even parity: output 1 when an even number of the inputs are set. A standard benchmark of
+// circuit evolution, made hard by leaving XOR out of the gates, so parity has to be built
+// from AND, OR, NAND and NOR.
+#[derive(Clone, Copy)]
+pub struct EvenParity {
+    pub bits: usize,
+}
+
+// The boolean functions without XOR
+const GATES: &[CgpFunction] = CgpFunction::BOOLEAN.split_at(4).0;
+
+impl EvenParity {
+    // Every assignment of the inputs, with its target output
+    fn cases(&self) -> impl Iterator<Item = (Vec<f64>, f64)> + '_ {
+        (0..1usize << self.bits).map(|case| {
+            let inputs = (0..self.bits).map(|bit| ((case >> bit) & 1) as f64).collect();
+            let even = case.count_ones().is_multiple_of(2);
+            (inputs, if even { 1.0 } else { 0.0 })
+        })
+    }
+}
+
+impl ProgramObjective for EvenParity {
+    fn inputs(&self) -> usize {
+        self.bits
+    }
+
+    fn outputs(&self) -> usize {
+        1
+    }
+
+    fn functions(&self) -> &[CgpFunction] {
+        GATES
+    }
+
+    // Cases the program gets right, out of 2^bits
+    fn fitness(&self, program: &CgpGenome) -> f64 {
+        self.cases()
+            .filter(|(inputs, target)| program.evaluate(GATES, inputs)[0] == *target)
+            .count() as f64
+    }
+
+    fn optimum_value(&self) -> Option<f64> {
+        Some((1usize << self.bits) as f64)
+    }
+}
@@ -0,0 +1,96 @@
+use crate::bitstring::GAParameters;
+use crate::decoder::Decoder;
+use crate::genome::{Genome, hash_of};
+use rand::prelude::*;
+
+// Ordering of the items 0..n, e.g. a tour through cities
+#[derive(Debug, Clone, PartialEq)]
+pub struct Permutation(pub Vec<usize>);
+
+impl Genome for Permutation {
+    fn random<R: Rng + ?Sized>(len: usize, _bounds: (f64, f64), rng: &mut R) -> Self {
+        let mut order: Vec<usize> = (0..len).collect();
+        order.shuffle(rng);
+        Permutation(order)
+    }
+
+    // Swap mutation: each position is swapped with a random other position with probability
+    // mutation_rate
+    fn mutate<R: Rng + ?Sized>(
+        &self,
+        params: &GAParameters,
+        _bounds: (f64, f64),
+        rng: &mut R,
+    ) -> Self {
+        let mutation_rate = params.mutation_rate;
+        let mut order = self.0.clone();
+        for i in 0..order.len() {
+            let random: f64 = rng.random();
+            if random < mutation_rate {
+                let j = rng.random_range(0..order.len());
+                order.swap(i, j);
+            }
+        }
+        Permutation(order)
+    }
+
+    // Order crossover (OX1): copy a slice from one parent and fill the rest in the order the
+    // remaining items appear in the other parent
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        other: &Self,
+        crossover_rate: f64,
+        rng: &mut R,
+    ) -> (Self, Self) {
+        if self.0.len() != other.0.len() {
+            panic!("Parents must be of the same length");
+        }
+        let random: f64 = rng.random();
+        if random >= crossover_rate || self.0.len() < 2 {
+            return (self.clone(), other.clone());
+        }
+
+        let mut start = rng.random_range(0..self.0.len());
+        let mut end = rng.random_range(0..self.0.len());
+        if start > end {
+            std::mem::swap(&mut start, &mut end);
+        }
+        (
+            order_crossover(&self.0, &other.0, start, end),
+            order_crossover(&other.0, &self.0, start, end),
+        )
+    }
+
+    fn length(&self) -> usize {
+        self.0.len()
+    }
+
+    // Number of positions holding different items
+    fn distance(&self, other: &Self) -> f64 {
+        self.0.iter().zip(&other.0).filter(|(a, b)| a != b).count() as f64
+    }
+
+    fn fingerprint(&self) -> u64 {
+        hash_of(&self.0)
+    }
+
+    // Item indices as floats, decoded back with `as usize` by permutation problems
+    fn phenotype(&self, _decoder: &impl Decoder, _num_dims: usize) -> Vec<f64> {
+        self.0.iter().map(|&i| i as f64).collect()
+    }
+}
+
+fn order_crossover(keep: &[usize], fill: &[usize], start: usize, end: usize) -> Permutation {
+    let mut child: Vec<Option<usize>> = vec![None; keep.len()];
+    for i in start..=end {
+        child[i] = Some(keep[i]);
+    }
+    let kept = &keep[start..=end];
+    let mut remaining = fill.iter().filter(|item| !kept.contains(item));
+    for slot in child.iter_mut() {
+        if slot.is_none() {
+            *slot = remaining.next().copied();
+        }
+    }
+    Permutation(child.into_iter().map(|item| item.unwrap()).collect())
+}
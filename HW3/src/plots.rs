@@ -0,0 +1,449 @@
+use crate::anova::{Heatmap, MainEffect};
+use crate::curves::{CurvePoint, QdTraceRow, TraceRow, read_trace};
+use crate::ecdf::EcdfPoint;
+use crate::parameter_tuning::TuningConfig;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+use std::error::Error;
+use std::path::Path;
+
+// A line with a shaded band around it: (x, line, band low, band high) per point
+struct Band {
+    points: Vec<(f64, f64, f64, f64)>,
+}
+
+impl Band {
+    // Mean ± std across runs of one metric, per generation. Runs that stopped early simply
+    // drop out of the later generations.
+    fn mean_std<T>(runs: &[Vec<T>], generation: fn(&T) -> usize, metric: fn(&T) -> f64) -> Self {
+        let longest = runs.iter().map(Vec::len).max().unwrap_or(0);
+        let points = (0..longest)
+            .map(|i| {
+                let rows: Vec<&T> = runs.iter().filter_map(|run| run.get(i)).collect();
+                let values: Vec<f64> = rows.iter().map(|row| metric(row)).collect();
+                let n = values.len() as f64;
+                let mean = values.iter().sum::<f64>() / n;
+                let std = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+                (generation(rows[0]) as f64, mean, mean - std, mean + std)
+            })
+            .collect();
+        Self { points }
+    }
+
+    // Median with the interquartile range
+    fn quartiles(curve: &[CurvePoint]) -> Self {
+        let points = curve
+            .iter()
+            .map(|point| (point.evaluations as f64, point.median, point.q1, point.q3))
+            .collect();
+        Self { points }
+    }
+
+    // An ECDF has no spread to shade, so its band collapses onto the line
+    fn line_only(ecdf: &[EcdfPoint]) -> Self {
+        let points = ecdf
+            .iter()
+            .map(|point| {
+                let fraction = point.fraction;
+                (point.evaluations as f64, fraction, fraction, fraction)
+            })
+            .collect();
+        Self { points }
+    }
+
+    fn line(&self) -> Vec<(f64, f64)> {
+        self.points.iter().map(|&(x, y, _, _)| (x, y)).collect()
+    }
+
+    // Outline of the band: along the upper edge, then back along the lower one
+    fn outline(&self) -> Vec<(f64, f64)> {
+        let upper = self.points.iter().map(|&(x, _, _, high)| (x, high));
+        let lower = self.points.iter().rev().map(|&(x, _, low, _)| (x, low));
+        upper.chain(lower).collect()
+    }
+
+    fn x_max(&self) -> Option<f64> {
+        self.points.last().map(|point| point.0)
+    }
+
+    fn y_range(&self) -> (f64, f64) {
+        self.points.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &(_, _, l, h)| {
+            (lo.min(l), hi.max(h))
+        })
+    }
+}
+
+// Everything drawn in one colour: a solid band and optionally a dashed one, each labelled
+// "<label> <name>" in the legend
+struct BandGroup<'a> {
+    label: &'a str,
+    solid: (&'static str, Band),
+    dashed: Option<(&'static str, Band)>,
+}
+
+// Convergence plot of best (solid) and average (dashed) fitness per generation, one colour
+// per labelled group of runs, each with a ±1 std band across the group's runs. Writes SVG
+// when `output` ends in .svg and PNG otherwise.
+pub fn plot_convergence(
+    output: &str,
+    title: &str,
+    groups: &[(String, Vec<Vec<TraceRow>>)],
+) -> Result<(), Box<dyn Error>> {
+    let groups: Vec<BandGroup> = groups
+        .iter()
+        .filter(|(_, runs)| runs.iter().any(|run| !run.is_empty()))
+        .map(|(label, runs)| BandGroup {
+            label,
+            solid: ("best", Band::mean_std(runs, |row| row.generation, |row| row.best)),
+            dashed: Some(("average", Band::mean_std(runs, |row| row.generation, |row| row.mean))),
+        })
+        .collect();
+    render(output, title, ("Generation", "Fitness"), &groups)
+}
+
+// Median best-so-far fitness against evaluations with its interquartile band, one colour per
+// labelled setting
+pub fn plot_quantile_curves(
+    output: &str,
+    title: &str,
+    curves: &[(String, Vec<CurvePoint>)],
+) -> Result<(), Box<dyn Error>> {
+    let groups: Vec<BandGroup> = curves
+        .iter()
+        .filter(|(_, curve)| !curve.is_empty())
+        .map(|(label, curve)| BandGroup {
+            label,
+            solid: ("median", Band::quartiles(curve)),
+            dashed: None,
+        })
+        .collect();
+    render(output, title, ("Evaluations", "Best-so-far fitness"), &groups)
+}
+
+// Runtime ECDFs: the fraction of (run, target) pairs solved against evaluations, one line per
+// labelled setting
+pub fn plot_ecdf(
+    output: &str,
+    title: &str,
+    ecdfs: &[(String, Vec<EcdfPoint>)],
+) -> Result<(), Box<dyn Error>> {
+    let groups: Vec<BandGroup> = ecdfs
+        .iter()
+        .filter(|(_, ecdf)| !ecdf.is_empty())
+        .map(|(label, ecdf)| BandGroup {
+            label,
+            solid: ("ECDF", Band::line_only(ecdf)),
+            dashed: None,
+        })
+        .collect();
+    render(output, title, ("Evaluations", "Fraction of (run, target) pairs solved"), &groups)
+}
+
+// QD metrics of MAP-Elites runs per generation, in three panels side by side: archive
+// coverage, QD-score and archive max fitness, one colour per labelled group of runs, each
+// with a ±1 std band across the group's runs
+pub fn plot_qd_metrics(
+    output: &str,
+    title: &str,
+    groups: &[(String, Vec<Vec<QdTraceRow>>)],
+) -> Result<(), Box<dyn Error>> {
+    let is_svg = Path::new(output).extension().is_some_and(|ext| ext == "svg");
+    if is_svg {
+        draw_qd_metrics(SVGBackend::new(output, (1800, 600)).into_drawing_area(), title, groups)
+    } else {
+        draw_qd_metrics(BitMapBackend::new(output, (1800, 600)).into_drawing_area(), title, groups)
+    }
+}
+
+fn draw_qd_metrics<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    title: &str,
+    groups: &[(String, Vec<Vec<QdTraceRow>>)],
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    let groups: Vec<&(String, Vec<Vec<QdTraceRow>>)> =
+        groups.iter().filter(|(_, runs)| runs.iter().any(|run| !run.is_empty())).collect();
+    type Metric = fn(&QdTraceRow) -> f64;
+    let metrics: [(&str, Metric); 3] = [
+        ("Coverage", |row| row.coverage),
+        ("QD-score", |row| row.qd_score),
+        ("Archive max fitness", |row| row.max_fitness),
+    ];
+    root.fill(&WHITE)?;
+    let root = root.titled(title, ("sans-serif", 24))?;
+    for (panel, (name, metric)) in root.split_evenly((1, 3)).into_iter().zip(metrics) {
+        let bands: Vec<BandGroup> = groups
+            .iter()
+            .map(|(label, runs)| BandGroup {
+                label,
+                solid: ("mean", Band::mean_std(runs, |row| row.generation, metric)),
+                dashed: None,
+            })
+            .collect();
+        draw(panel, name, ("Generation", name), &bands)?;
+    }
+    Ok(())
+}
+
+fn render(
+    output: &str,
+    title: &str,
+    axes: (&str, &str),
+    groups: &[BandGroup],
+) -> Result<(), Box<dyn Error>> {
+    let is_svg = Path::new(output).extension().is_some_and(|ext| ext == "svg");
+    if is_svg {
+        draw(SVGBackend::new(output, (1024, 768)).into_drawing_area(), title, axes, groups)
+    } else {
+        draw(BitMapBackend::new(output, (1024, 768)).into_drawing_area(), title, axes, groups)
+    }
+}
+
+fn draw<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    title: &str,
+    (x_desc, y_desc): (&str, &str),
+    groups: &[BandGroup],
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    if groups.is_empty() {
+        return Err("no trace data to plot".into());
+    }
+    let bands = || {
+        groups.iter().flat_map(|group| {
+            std::iter::once(&group.solid.1).chain(group.dashed.as_ref().map(|(_, band)| band))
+        })
+    };
+
+    let x_max = bands().flat_map(Band::x_max).fold(1.0, f64::max);
+    let (y_min, y_max) = bands()
+        .map(Band::y_range)
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), (a, b)| (lo.min(a), hi.max(b)));
+    let padding = ((y_max - y_min) * 0.05).max(1e-12);
+
+    root.fill(&WHITE)?;
+    let mut chart = ChartBuilder::on(&root)
+        .caption(title, ("sans-serif", 24))
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(70)
+        .build_cartesian_2d(0.0..x_max, (y_min - padding)..(y_max + padding))?;
+    chart.configure_mesh().x_desc(x_desc).y_desc(y_desc).draw()?;
+
+    for (i, group) in groups.iter().enumerate() {
+        let color = Palette99::pick(i).to_rgba();
+        let (name, solid) = &group.solid;
+        chart.draw_series(std::iter::once(Polygon::new(solid.outline(), color.mix(0.15))))?;
+        if let Some((_, dashed)) = &group.dashed {
+            chart.draw_series(std::iter::once(Polygon::new(dashed.outline(), color.mix(0.15))))?;
+        }
+        chart
+            .draw_series(LineSeries::new(solid.line(), color.stroke_width(2)))?
+            .label(format!("{} {name}", group.label))
+            .legend(move |(x, y)| {
+                PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2))
+            });
+        if let Some((name, dashed)) = &group.dashed {
+            chart
+                .draw_series(DashedLineSeries::new(dashed.line(), 6, 4, color.into()))?
+                .label(format!("{} {name}", group.label))
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 8, y)], color));
+        }
+    }
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::LowerRight)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+    root.present()?;
+    Ok(())
+}
+
+// Main-effects plot: a panel per parameter with the mean response at each of its levels and
+// the 95% intervals as error bars. Levels are evenly spaced whatever their values, and every
+// panel shares the response axis, so the steepest panel shows the parameter that matters most.
+pub fn plot_main_effects(
+    output: &str,
+    title: &str,
+    effects: &[MainEffect],
+) -> Result<(), Box<dyn Error>> {
+    let is_svg = Path::new(output).extension().is_some_and(|ext| ext == "svg");
+    let columns = effects.len().min(3);
+    let rows = effects.len().div_ceil(3);
+    let size = (400 * columns.max(1) as u32, 60 + 340 * rows as u32);
+    if is_svg {
+        draw_main_effects(SVGBackend::new(output, size).into_drawing_area(), title, effects)
+    } else {
+        draw_main_effects(BitMapBackend::new(output, size).into_drawing_area(), title, effects)
+    }
+}
+
+fn draw_main_effects<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    title: &str,
+    effects: &[MainEffect],
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    if effects.is_empty() {
+        return Err("no factorial parameters to plot".into());
+    }
+    let (y_min, y_max) = effects
+        .iter()
+        .flat_map(|effect| &effect.levels)
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), level| {
+            (lo.min(level.ci_low), hi.max(level.ci_high))
+        });
+    let padding = ((y_max - y_min) * 0.05).max(1e-12);
+    let y_range = (y_min - padding)..(y_max + padding);
+
+    root.fill(&WHITE)?;
+    let root = root.titled(title, ("sans-serif", 24))?;
+    let panels = root.split_evenly((effects.len().div_ceil(3), effects.len().min(3)));
+    for (panel, effect) in panels.iter().zip(effects) {
+        let levels = &effect.levels;
+        let x_max = levels.len() as f64 - 0.5;
+        let mut chart = ChartBuilder::on(panel)
+            .caption(&effect.parameter, ("sans-serif", 18))
+            .margin(10)
+            .x_label_area_size(35)
+            .y_label_area_size(60)
+            .build_cartesian_2d(-0.5..x_max, y_range.clone())?;
+        let tick = |x: &f64| {
+            let index = x.round();
+            let level = (index >= 0.0 && (x - index).abs() < 1e-9)
+                .then(|| levels.get(index as usize))
+                .flatten();
+            level.map_or(String::new(), |level| level.level.to_string())
+        };
+        chart
+            .configure_mesh()
+            .disable_x_mesh()
+            .x_labels(levels.len() * 2 + 1)
+            .x_label_formatter(&tick)
+            .y_desc(format!("mean {}", effect.response))
+            .draw()?;
+        let color = Palette99::pick(0).to_rgba();
+        let points: Vec<(f64, f64)> =
+            levels.iter().enumerate().map(|(i, level)| (i as f64, level.mean)).collect();
+        chart.draw_series(LineSeries::new(points.clone(), color.stroke_width(2)))?;
+        chart.draw_series(levels.iter().enumerate().map(|(i, level)| {
+            let x = i as f64;
+            ErrorBar::new_vertical(x, level.ci_low, level.mean, level.ci_high, color, 10)
+        }))?;
+        chart.draw_series(points.iter().map(|&point| Circle::new(point, 4, color.filled())))?;
+    }
+    root.present()?;
+    Ok(())
+}
+
+// Heatmap of the mean response over two parameters' levels, one cell per pair of levels
+// coloured from the lowest mean (dark) to the highest (yellow) and labelled with its value.
+// Cells without runs are left blank.
+pub fn plot_heatmap(output: &str, title: &str, heatmap: &Heatmap) -> Result<(), Box<dyn Error>> {
+    let is_svg = Path::new(output).extension().is_some_and(|ext| ext == "svg");
+    if is_svg {
+        draw_heatmap(SVGBackend::new(output, (900, 700)).into_drawing_area(), title, heatmap)
+    } else {
+        draw_heatmap(BitMapBackend::new(output, (900, 700)).into_drawing_area(), title, heatmap)
+    }
+}
+
+fn draw_heatmap<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    title: &str,
+    heatmap: &Heatmap,
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    let (rows, columns) = (heatmap.row_levels.len(), heatmap.column_levels.len());
+    let (low, high) = heatmap
+        .means
+        .iter()
+        .flatten()
+        .flatten()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &mean| (lo.min(mean), hi.max(mean)));
+    if !low.is_finite() {
+        return Err("no runs to plot".into());
+    }
+
+    root.fill(&WHITE)?;
+    let mut chart = ChartBuilder::on(&root)
+        .caption(title, ("sans-serif", 24))
+        .margin(10)
+        .x_label_area_size(50)
+        .y_label_area_size(80)
+        .build_cartesian_2d(0.0..columns as f64, 0.0..rows as f64)?;
+    // Ticks at cell centres, labelled with the level there
+    let label = |levels: &[f64], x: f64| {
+        let index = (x - 0.5).round();
+        let centre = index >= 0.0 && (x - 0.5 - index).abs() < 1e-9;
+        centre.then(|| levels.get(index as usize)).flatten().map_or(String::new(), f64::to_string)
+    };
+    let x_label = |x: &f64| label(&heatmap.column_levels, *x);
+    let y_label = |y: &f64| label(&heatmap.row_levels, *y);
+    chart
+        .configure_mesh()
+        .disable_mesh()
+        .x_labels(columns * 2 + 1)
+        .y_labels(rows * 2 + 1)
+        .x_label_formatter(&x_label)
+        .y_label_formatter(&y_label)
+        .x_desc(heatmap.column.as_str())
+        .y_desc(heatmap.row.as_str())
+        .draw()?;
+
+    let cells = heatmap.means.iter().enumerate().flat_map(|(i, means)| {
+        means.iter().enumerate().filter_map(move |(j, mean)| Some((i, j, (*mean)?)))
+    });
+    let fraction = |mean: f64| if high > low { (mean - low) / (high - low) } else { 0.5 };
+    for (i, j, mean) in cells {
+        let (x, y) = (j as f64, i as f64);
+        let color = ViridisRGB::get_color(fraction(mean));
+        let cell = Rectangle::new([(x, y), (x + 1.0, y + 1.0)], color.filled());
+        chart.draw_series(std::iter::once(cell))?;
+        // Dark text on the light end of the scale, white on the dark end
+        let text_color = if fraction(mean) > 0.6 { BLACK } else { WHITE };
+        let centred = Pos::new(HPos::Center, VPos::Center);
+        let style = ("sans-serif", 16).into_font().color(&text_color).pos(centred);
+        let text = Text::new(format!("{mean:.4e}"), (x + 0.5, y + 0.5), style);
+        chart.draw_series(std::iter::once(text))?;
+    }
+    root.present()?;
+    Ok(())
+}
+
+// After a tuning session with traces on: one plot per grid combination, across its runs,
+// written next to the traces
+pub fn plot_tuning_traces(
+    config: &TuningConfig,
+    algorithm: &str,
+    combinations: usize,
+) -> Result<(), Box<dyn Error>> {
+    let Some(dir) = &config.trace_dir else {
+        return Ok(());
+    };
+    for combination in 0..combinations {
+        // Runs cancelled before their first generation leave only a header behind
+        let traces: Vec<Vec<TraceRow>> = (0..config.num_runs)
+            .filter_map(|run| config.trace_path(algorithm, combination, run))
+            .filter_map(|path| read_trace(&path).ok())
+            .filter(|trace| !trace.is_empty())
+            .collect();
+        if traces.is_empty() {
+            continue;
+        }
+        let output = format!("{dir}/{}_{combination}.png", algorithm.to_lowercase());
+        let title = format!("{algorithm} combination {} ({} runs)", combination + 1, traces.len());
+        plot_convergence(&output, &title, &[(algorithm.to_string(), traces)])?;
+    }
+    Ok(())
+}
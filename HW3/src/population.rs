@@ -0,0 +1,113 @@
+use crate::cancellation::CancellationToken;
+use crate::fitness::Fitness;
+use crate::genome::Genome;
+use crate::seeds::SeedSequence;
+
+// A generation's genomes, each with its fitness and whether that fitness is known yet. A
+// member that joins with a known fitness (a crowding survivor, an offspring evaluated for
+// rate-control feedback) keeps it, so evaluating the population only evaluates the others,
+// and selection, the statistics and the convergence check all read the stored values. Under
+// a noisy fitness those members keep the sample they were evaluated with.
+#[derive(Debug, Clone, Default)]
+pub struct Population<G> {
+    members: Vec<G>,
+    fitnesses: Vec<f64>,
+    evaluated: Vec<bool>,
+}
+
+impl<G: Genome> Population<G> {
+    // Members whose fitness is not known yet
+    pub fn new(members: Vec<G>) -> Self {
+        let n = members.len();
+        Self { members, fitnesses: vec![0.0; n], evaluated: vec![false; n] }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            members: Vec::with_capacity(capacity),
+            fitnesses: Vec::with_capacity(capacity),
+            evaluated: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    pub fn members(&self) -> &[G] {
+        &self.members
+    }
+
+    pub fn into_members(self) -> Vec<G> {
+        self.members
+    }
+
+    // Adds a member, with its fitness if it has been evaluated
+    pub fn push(&mut self, member: G, fitness: Option<f64>) {
+        self.members.push(member);
+        self.fitnesses.push(fitness.unwrap_or_default());
+        self.evaluated.push(fitness.is_some());
+    }
+
+    // Puts an unevaluated member in place of member i
+    pub fn replace(&mut self, i: usize, member: G) {
+        self.members[i] = member;
+        self.evaluated[i] = false;
+    }
+
+    // Empties the population, keeping its capacity, and moves its members to spare so their
+    // buffers can be reused for the next generation's offspring
+    pub fn clear_into(&mut self, spare: &mut Vec<G>) {
+        spare.append(&mut self.members);
+        self.fitnesses.clear();
+        self.evaluated.clear();
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        self.members.truncate(len);
+        self.fitnesses.truncate(len);
+        self.evaluated.truncate(len);
+    }
+
+    // Evaluates the members whose fitness isn't known, in chunks between which the token is
+    // checked, and returns how many that was. Member i draws any noise from seeds.individual(i).
+    // A cancelled run keeps the values evaluated so far and gets their count as the error.
+    pub fn evaluate(
+        &mut self,
+        fitness_fn: &impl Fitness,
+        num_dims: usize,
+        resamples: usize,
+        seeds: SeedSequence,
+        cancellation: &CancellationToken,
+    ) -> Result<usize, usize> {
+        let pending: Vec<usize> = (0..self.len()).filter(|&i| !self.evaluated[i]).collect();
+        if pending.is_empty() {
+            return Ok(0);
+        }
+        let decoder = fitness_fn.decoder();
+        let phenotypes: Vec<Vec<f64>> =
+            pending.iter().map(|&i| self.members[i].phenotype(&decoder, num_dims)).collect();
+        let streams: Vec<SeedSequence> = pending.iter().map(|&i| seeds.individual(i)).collect();
+        let result = cancellation.evaluate_batch(&phenotypes, &streams, |members, streams| {
+            fitness_fn.evaluate_population_seeded_resampled(members, streams, resamples)
+        });
+        let values = match &result {
+            Ok(values) | Err(values) => values,
+        };
+        for (&i, &value) in pending.iter().zip(values) {
+            self.fitnesses[i] = value;
+            self.evaluated[i] = true;
+        }
+        result.map(|values| values.len()).map_err(|values| values.len())
+    }
+
+    // Every member's fitness, once the population has been evaluated
+    pub fn fitnesses(&self) -> &[f64] {
+        debug_assert!(self.evaluated.iter().all(|&evaluated| evaluated));
+        &self.fitnesses
+    }
+}
@@ -0,0 +1,65 @@
+use crate::constraint::Constraint;
+use crate::fitness::Objective;
+use crate::mixed_integer::GeneType;
+use std::f64::consts::PI;
+
+// Plate thicknesses are available in multiples of 1/16 inch
+pub const PLATE_GAUGE: f64 = 0.0625;
+// Lowest cost reported in the literature for this formulation
+pub const BEST_KNOWN_COST: f64 = 6059.714;
+
+// Sandgren's pressure vessel design: choose the shell and head plate thicknesses, in whole
+// plate gauges, and the inner radius and length of a cylindrical vessel with hemispherical
+// heads, minimizing material, forming and welding cost. Genes are [shell gauges, head gauges,
+// radius, length]. Fitness is the best known cost over the cost, so 1.0 at the best known
+// design; the constraints are in PressureVesselConstraints.
+pub struct PressureVessel;
+
+impl PressureVessel {
+    pub fn gene_types(&self) -> Vec<GeneType> {
+        vec![
+            GeneType::Integer { min: 1, max: 99 },
+            GeneType::Integer { min: 1, max: 99 },
+            GeneType::Continuous { min: 10.0, max: 200.0 },
+            GeneType::Continuous { min: 10.0, max: 200.0 },
+        ]
+    }
+
+    // Thicknesses in inches, radius and length of a member's genes
+    pub fn design(member: &[f64]) -> (f64, f64, f64, f64) {
+        (member[0] * PLATE_GAUGE, member[1] * PLATE_GAUGE, member[2], member[3])
+    }
+
+    pub fn cost(member: &[f64]) -> f64 {
+        let (shell, head, radius, length) = Self::design(member);
+        0.6224 * shell * radius * length
+            + 1.7781 * head * radius.powi(2)
+            + 3.1661 * shell.powi(2) * length
+            + 19.84 * shell.powi(2) * radius
+    }
+}
+
+impl Objective for PressureVessel {
+    fn fitness(&self, member: &[f64]) -> f64 {
+        BEST_KNOWN_COST / Self::cost(member).max(f64::MIN_POSITIVE)
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        (10.0, 200.0)
+    }
+}
+
+// The ASME code limits on the thicknesses for the radius, and the required volume of
+// 750 cubic feet, each violation relative to its limit
+pub struct PressureVesselConstraints;
+
+impl Constraint for PressureVesselConstraints {
+    fn violation(&self, x: &[f64]) -> f64 {
+        let (shell, head, radius, length) = PressureVessel::design(x);
+        let volume = PI * radius.powi(2) * length + 4.0 / 3.0 * PI * radius.powi(3);
+        let required_volume = 1_296_000.0;
+        (0.0193 * radius / shell - 1.0).max(0.0)
+            + (0.00954 * radius / head - 1.0).max(0.0)
+            + (1.0 - volume / required_volume).max(0.0)
+    }
+}
@@ -0,0 +1,49 @@
+use crate::cgp::{CgpFunction, CgpGenome, ProgramObjective};
+
+// Koza's quartic polynomial x^4 + x^3 + x^2 + x, the classic symbolic regression benchmark,
+// sampled at evenly spaced points of [-1, 1] and rebuilt from +, -, * and protected /
+#[derive(Clone, Copy)]
+pub struct Quartic {
+    pub samples: usize,
+}
+
+// Error below which a sample counts as hit
+const HIT_TOLERANCE: f64 = 0.01;
+
+impl Quartic {
+    fn cases(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        let step = 2.0 / (self.samples.max(2) - 1) as f64;
+        (0..self.samples).map(move |i| {
+            let x = -1.0 + i as f64 * step;
+            (x, x.powi(4) + x.powi(3) + x.powi(2) + x)
+        })
+    }
+}
+
+impl ProgramObjective for Quartic {
+    fn inputs(&self) -> usize {
+        1
+    }
+
+    fn outputs(&self) -> usize {
+        1
+    }
+
+    fn functions(&self) -> &[CgpFunction] {
+        &CgpFunction::ARITHMETIC
+    }
+
+    // Samples hit, as in Koza's experiments, so fitness reaches its optimum on an exact fit
+    // and a program nearly right everywhere isn't mistaken for a solution
+    fn fitness(&self, program: &CgpGenome) -> f64 {
+        self.cases()
+            .filter(|&(x, y)| {
+                (program.evaluate(&CgpFunction::ARITHMETIC, &[x])[0] - y).abs() < HIT_TOLERANCE
+            })
+            .count() as f64
+    }
+
+    fn optimum_value(&self) -> Option<f64> {
+        Some(self.samples as f64)
+    }
+}
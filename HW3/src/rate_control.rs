@@ -0,0 +1,164 @@
+// Parameter control for the GA mutation and crossover rates and the ES step size (sigma). The
+// schedules are deterministic functions of time, counted in generations or evaluations.
+#[derive(Debug, Clone, Copy)]
+pub enum RateControl {
+    // Keep the initial value for the whole run
+    Constant,
+    // Move linearly from the initial value to final_value at the end of the budget
+    LinearDecay { final_value: f64 },
+    // Multiply the value by decay every generation (or evaluation)
+    ExponentialDecay { decay: f64 },
+    // Multiply the value by factor every `every` generations (or evaluations)
+    Step { factor: f64, every: usize },
+    // Rechenberg's 1/5th success rule: every window generations, divide the value by factor
+    // (0 < factor < 1) if more than a fifth of offspring beat their parents, otherwise
+    // multiply it by factor
+    OneFifthRule { factor: f64, window: usize },
+}
+
+// What the schedules count as time. The success rule always works in generations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleClock {
+    Generations,
+    Evaluations,
+}
+
+pub struct RateController {
+    control: RateControl,
+    initial: f64,
+    current: f64,
+    clock: ScheduleClock,
+    // Generations or evaluations in the run's budget, whichever the clock counts
+    horizon: usize,
+    successes: usize,
+    trials: usize,
+    last_success_rate: f64,
+}
+
+impl RateController {
+    pub fn new(control: RateControl, initial: f64, max_generations: usize) -> Self {
+        Self {
+            control,
+            initial,
+            current: initial,
+            clock: ScheduleClock::Generations,
+            horizon: max_generations,
+            successes: 0,
+            trials: 0,
+            last_success_rate: 0.0,
+        }
+    }
+
+    // Runs the schedule on evaluations, with max_evaluations in the run's budget
+    pub fn on_evaluations(mut self, max_evaluations: usize) -> Self {
+        self.clock = ScheduleClock::Evaluations;
+        self.horizon = max_evaluations;
+        self
+    }
+
+    pub fn value(&self) -> f64 {
+        self.current
+    }
+
+    pub fn is_constant(&self) -> bool {
+        matches!(self.control, RateControl::Constant)
+    }
+
+    // Only the success rule needs to know whether offspring improved on their parents
+    pub fn needs_success_feedback(&self) -> bool {
+        matches!(self.control, RateControl::OneFifthRule { .. })
+    }
+
+    pub fn record_offspring(&mut self, improved: bool) {
+        self.trials += 1;
+        if improved {
+            self.successes += 1;
+        }
+    }
+
+    // Updates the controlled value once the given number of generations has completed, having
+    // used the given number of evaluations
+    pub fn end_generation(&mut self, generations_done: usize, evaluations_done: usize) {
+        let time = match self.clock {
+            ScheduleClock::Generations => generations_done,
+            ScheduleClock::Evaluations => evaluations_done,
+        };
+        match self.control {
+            RateControl::Constant => {}
+            RateControl::LinearDecay { final_value } => {
+                let progress = (time as f64 / self.horizon.max(1) as f64).min(1.0);
+                self.current = self.initial + (final_value - self.initial) * progress;
+            }
+            RateControl::ExponentialDecay { decay } => {
+                self.current = self.initial * decay.powf(time as f64);
+            }
+            RateControl::Step { factor, every } => {
+                let steps = (time / every.max(1)).min(i32::MAX as usize) as i32;
+                self.current = self.initial * factor.powi(steps);
+            }
+            RateControl::OneFifthRule { factor, window } => {
+                if generations_done.is_multiple_of(window.max(1)) && self.trials > 0 {
+                    self.last_success_rate = self.successes as f64 / self.trials as f64;
+                    if self.last_success_rate > 0.2 {
+                        self.current /= factor;
+                    } else if self.last_success_rate < 0.2 {
+                        self.current *= factor;
+                    }
+                    self.successes = 0;
+                    self.trials = 0;
+                }
+            }
+        }
+    }
+
+    // Controller state for the per-generation log
+    pub fn describe(&self) -> String {
+        match self.control {
+            RateControl::OneFifthRule { .. } => format!(
+                "{:?} value={} success_rate={:.3}",
+                self.control, self.current, self.last_success_rate
+            ),
+            _ if self.clock == ScheduleClock::Evaluations => {
+                format!("{:?} over evaluations value={}", self.control, self.current)
+            }
+            _ => format!("{:?} value={}", self.control, self.current),
+        }
+    }
+}
+
+// Cumulative step-size adaptation (CSA) along each ES lineage, as in Ostermeier's
+// derandomized ES: a member carries an evolution path accumulating the standard normal steps
+// that led to it, and its child's sigma follows from the path length rather than from a
+// random mutation of sigma. A path longer than a random walk's means successive steps point
+// the same way and sigma grows; a shorter one means they cancel and sigma shrinks. Learning
+// rate and damping are Hansen's CMA-ES defaults for a single parent.
+pub struct EvolutionPath {
+    learning_rate: f64,
+    damping: f64,
+    // Expected length of an n-dimensional standard normal vector
+    expected_norm: f64,
+}
+
+impl EvolutionPath {
+    pub fn new(dimensions: usize) -> Self {
+        let n = dimensions.max(1) as f64;
+        let learning_rate = 3.0 / (n + 6.0);
+        Self {
+            learning_rate,
+            damping: 1.0 + learning_rate,
+            expected_norm: n.sqrt() * (1.0 - 1.0 / (4.0 * n) + 1.0 / (21.0 * n * n)),
+        }
+    }
+
+    // Folds the standard normal step a child was mutated with into the path it inherited,
+    // and returns the child's sigma
+    pub fn update(&self, path: &mut [f64], sigma: f64, normal_step: &[f64]) -> f64 {
+        let c = self.learning_rate;
+        let weight = (c * (2.0 - c)).sqrt();
+        for (value, step) in path.iter_mut().zip(normal_step) {
+            *value = (1.0 - c) * *value + weight * step;
+        }
+        let length = path.iter().map(|v| v * v).sum::<f64>().sqrt();
+        sigma * ((c / self.damping) * (length / self.expected_norm - 1.0)).exp()
+    }
+}
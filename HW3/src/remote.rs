@@ -0,0 +1,333 @@
+use crate::cache::CacheStats;
+use crate::decoder::Decoder;
+use crate::fitness::{Fitness, Objective};
+use crate::seeds::SeedSequence;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+// Wire format between master and workers: one JSON object per line in each direction. Values
+// that aren't finite go over the wire as null and come back as NaN. A seeded batch carries
+// each member's stream as its seed, so a worker draws the noise the master would have.
+#[derive(Serialize, Deserialize)]
+struct EvalRequest {
+    members: Vec<Vec<f64>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    seeds: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EvalResponse {
+    values: Vec<Option<f64>>,
+}
+
+// Serves evaluations of fitness_fn to masters connecting on addr, each connection on its own
+// thread, until the process is stopped. The delay is slept per evaluation to stand in for an
+// expensive objective.
+pub fn serve<F: Objective + Sync>(
+    addr: &str,
+    fitness_fn: &F,
+    delay: Duration,
+) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    println!("Worker listening on {}", listener.local_addr()?);
+    thread::scope(|scope| {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("Error accepting connection: {e}");
+                    continue;
+                }
+            };
+            scope.spawn(move || {
+                let peer = stream.peer_addr().map(|a| a.to_string()).unwrap_or_default();
+                match handle_connection(stream, fitness_fn, delay) {
+                    Ok(batches) => println!("{peer} disconnected after {batches} batches"),
+                    Err(e) => eprintln!("Error serving {peer}: {e}"),
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+// Answers requests until the master hangs up, returning the number of batches evaluated
+fn handle_connection(
+    stream: TcpStream,
+    fitness_fn: &impl Objective,
+    delay: Duration,
+) -> Result<usize, Box<dyn Error>> {
+    stream.set_nodelay(true)?;
+    let mut writer = stream.try_clone()?;
+    let mut batches = 0;
+    for line in BufReader::new(stream).lines() {
+        let request: EvalRequest = serde_json::from_str(&line?)?;
+        if !delay.is_zero() {
+            thread::sleep(delay * request.members.len() as u32);
+        }
+        let values = if request.seeds.is_empty() {
+            fitness_fn.evaluate_population(&request.members)
+        } else if request.seeds.len() == request.members.len() {
+            let streams: Vec<SeedSequence> =
+                request.seeds.iter().map(|&seed| SeedSequence::new(seed)).collect();
+            fitness_fn.evaluate_population_seeded(&request.members, &streams)
+        } else {
+            return Err("request has a seed count different from its member count".into());
+        };
+        let response = EvalResponse {
+            values: values.into_iter().map(|value| value.is_finite().then_some(value)).collect(),
+        };
+        // One write per line, so a response isn't split across packets
+        writer.write_all(format!("{}\n", serde_json::to_string(&response)?).as_bytes())?;
+        batches += 1;
+    }
+    Ok(batches)
+}
+
+// An open connection to one worker
+struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Connection {
+    fn open(addr: &str, timeout: Duration) -> std::io::Result<Self> {
+        let socket_addr = addr.to_socket_addrs()?.next().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "address didn't resolve")
+        })?;
+        let writer = TcpStream::connect_timeout(&socket_addr, timeout)?;
+        writer.set_read_timeout(Some(timeout))?;
+        writer.set_nodelay(true)?;
+        let reader = BufReader::new(writer.try_clone()?);
+        Ok(Self { reader, writer })
+    }
+
+    fn evaluate(
+        &mut self,
+        members: &[Vec<f64>],
+        streams: Option<&[SeedSequence]>,
+    ) -> Result<Vec<f64>, Box<dyn Error>> {
+        let seeds = streams.unwrap_or_default().iter().map(SeedSequence::seed).collect();
+        let request = serde_json::to_string(&EvalRequest { members: members.to_vec(), seeds })?;
+        self.writer.write_all(format!("{request}\n").as_bytes())?;
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err("worker closed the connection".into());
+        }
+        let response: EvalResponse = serde_json::from_str(&line)?;
+        if response.values.len() != members.len() {
+            return Err(format!(
+                "worker returned {} values for {} members",
+                response.values.len(),
+                members.len()
+            )
+            .into());
+        }
+        Ok(response.values.into_iter().map(|value| value.unwrap_or(f64::NAN)).collect())
+    }
+}
+
+// A worker's address and connection. Failures are reported when the worker goes down, not
+// again on every batch it misses.
+struct Worker {
+    addr: String,
+    connection: Mutex<Option<Connection>>,
+    down: AtomicBool,
+}
+
+// Farms population evaluations out to worker processes (`HW3 worker`) serving the same
+// problem. Each batch is split into chunks that idle workers take from a shared queue, one
+// chunk in flight per worker, so a slow worker takes fewer chunks instead of holding up a
+// backlog. A chunk whose worker fails or times out goes back on the queue for the others, and
+// the failed worker is reconnected on the next batch. If no worker is left, the rest of the
+// batch is evaluated locally. Decoding and bounds come from the local copy of the problem.
+pub struct RemoteFitness<F: Objective> {
+    inner: F,
+    workers: Vec<Worker>,
+    chunk_size: Option<usize>,
+    timeout: Duration,
+    // Whether the last batch fell back to local evaluation
+    local: AtomicBool,
+}
+
+impl<F: Objective> RemoteFitness<F> {
+    pub fn new(inner: F, addrs: &[String]) -> Self {
+        Self {
+            inner,
+            workers: addrs
+                .iter()
+                .map(|addr| Worker {
+                    addr: addr.clone(),
+                    connection: Mutex::new(None),
+                    down: AtomicBool::new(false),
+                })
+                .collect(),
+            chunk_size: None,
+            timeout: Duration::from_secs(30),
+            local: AtomicBool::new(false),
+        }
+    }
+
+    // Members per chunk; by default each batch is split evenly across the workers
+    pub fn with_chunk_size(mut self, chunk_size: Option<usize>) -> Self {
+        self.chunk_size = chunk_size.map(|size| size.max(1));
+        self
+    }
+
+    // How long to wait for a worker to connect or answer before treating it as failed
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    // Takes chunks off the queue until it is empty or the worker fails, returning false if it
+    // failed
+    fn work(
+        &self,
+        worker: &Worker,
+        (members, streams): (&[Vec<f64>], Option<&[SeedSequence]>),
+        queue: &Mutex<Vec<(usize, usize)>>,
+        values: &Mutex<Vec<f64>>,
+    ) -> bool {
+        let addr = &worker.addr;
+        let mut connection = worker.connection.lock().unwrap();
+        loop {
+            let Some((start, end)) = queue.lock().unwrap().pop() else {
+                return true;
+            };
+            let chunk = (&members[start..end], streams.map(|streams| &streams[start..end]));
+            let result = match connection.as_mut() {
+                Some(open) => open.evaluate(chunk.0, chunk.1),
+                None => Connection::open(addr, self.timeout)
+                    .map_err(Box::from)
+                    .and_then(|open| connection.insert(open).evaluate(chunk.0, chunk.1)),
+            };
+            match result {
+                Ok(chunk) => {
+                    values.lock().unwrap()[start..end].copy_from_slice(&chunk);
+                    if worker.down.swap(false, Ordering::Relaxed) {
+                        eprintln!("Worker {addr} is back");
+                    }
+                }
+                Err(e) => {
+                    if !worker.down.swap(true, Ordering::Relaxed) {
+                        eprintln!("Worker {addr} failed: {e}, requeueing its chunk");
+                    }
+                    *connection = None;
+                    queue.lock().unwrap().push((start, end));
+                    return false;
+                }
+            }
+        }
+    }
+}
+
+impl<F: Objective + Sync> RemoteFitness<F> {
+    // A batch split into chunks across the workers, each chunk with its members' streams if
+    // the batch is seeded
+    fn evaluate_remotely(
+        &self,
+        members: &[Vec<f64>],
+        streams: Option<&[SeedSequence]>,
+    ) -> Vec<f64> {
+        if members.is_empty() {
+            return Vec::new();
+        }
+        let chunk_size = self
+            .chunk_size
+            .unwrap_or_else(|| members.len().div_ceil(self.workers.len().max(1)));
+        // Reversed so workers pop the chunks in member order
+        let chunks: Vec<(usize, usize)> = (0..members.len())
+            .step_by(chunk_size)
+            .map(|start| (start, (start + chunk_size).min(members.len())))
+            .rev()
+            .collect();
+        let queue = Mutex::new(chunks);
+        let values = Mutex::new(vec![f64::NAN; members.len()]);
+        // Chunks requeued after the other workers finished get another round among the workers
+        // that are still up
+        let mut live: Vec<&Worker> = self.workers.iter().collect();
+        while !live.is_empty() && !queue.lock().unwrap().is_empty() {
+            live = thread::scope(|scope| {
+                let handles: Vec<_> = live
+                    .iter()
+                    .map(|&worker| {
+                        let (queue, values) = (&queue, &values);
+                        let batch = (members, streams);
+                        (worker, scope.spawn(move || self.work(worker, batch, queue, values)))
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .filter_map(|(worker, handle)| handle.join().unwrap_or(false).then_some(worker))
+                    .collect()
+            });
+        }
+
+        let mut values = values.into_inner().unwrap();
+        let leftover = queue.into_inner().unwrap();
+        let local = !leftover.is_empty();
+        if local != self.local.swap(local, Ordering::Relaxed) {
+            let state = if local { "No workers left, evaluating locally" } else { "Workers back" };
+            eprintln!("{state}");
+        }
+        if local {
+            for (start, end) in leftover {
+                let chunk = match streams {
+                    Some(streams) => self
+                        .inner
+                        .evaluate_population_seeded(&members[start..end], &streams[start..end]),
+                    None => self.inner.evaluate_population(&members[start..end]),
+                };
+                values[start..end].copy_from_slice(&chunk);
+            }
+        }
+        values
+    }
+}
+
+impl<F: Objective + Sync> Objective for RemoteFitness<F> {
+    fn fitness(&self, member: &[f64]) -> f64 {
+        self.evaluate_population(&[member.to_vec()])[0]
+    }
+
+    fn evaluate_population(&self, members: &[Vec<f64>]) -> Vec<f64> {
+        self.evaluate_remotely(members, None)
+    }
+
+    fn evaluate_population_seeded(
+        &self,
+        members: &[Vec<f64>],
+        streams: &[SeedSequence],
+    ) -> Vec<f64> {
+        self.evaluate_remotely(members, Some(streams))
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        self.inner.bounds()
+    }
+
+    fn optimum_value(&self) -> Option<f64> {
+        self.inner.optimum_value()
+    }
+
+    fn known_optima(&self, num_dims: usize) -> Vec<Vec<f64>> {
+        self.inner.known_optima(num_dims)
+    }
+
+    fn cache_stats(&self) -> Option<CacheStats> {
+        self.inner.cache_stats()
+    }
+}
+
+impl<F: Fitness + Sync> Fitness for RemoteFitness<F> {
+    fn decoder(&self) -> impl Decoder {
+        self.inner.decoder()
+    }
+}
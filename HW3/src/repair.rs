@@ -0,0 +1,181 @@
+// Repair operators bring infeasible offspring back into the feasible region after
+// crossover and mutation. Each method returns true if the genome had to be changed.
+pub trait Repair {
+    fn repair_bitstring(&self, _bitstring: &mut String) -> bool {
+        false
+    }
+
+    fn repair_real(&self, _genes: &mut [f64]) -> bool {
+        false
+    }
+}
+
+// Leaves every genome untouched
+pub struct NoRepair;
+
+impl Repair for NoRepair {}
+
+// Clamps real-valued genes into [bounds.0, bounds.1]
+pub struct BoundRepair {
+    pub bounds: (f64, f64),
+}
+
+impl Repair for BoundRepair {
+    fn repair_real(&self, genes: &mut [f64]) -> bool {
+        let mut repaired = false;
+        for gene in genes.iter_mut() {
+            let clamped = gene.clamp(self.bounds.0, self.bounds.1);
+            if clamped != *gene {
+                *gene = clamped;
+                repaired = true;
+            }
+        }
+        repaired
+    }
+}
+
+// How real-valued genes that mutation moved outside the search range are brought back. Unlike
+// a Repair operator this is part of the algorithm's parameters, so it can resample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundHandling {
+    // Leave genes wherever mutation put them
+    Ignore,
+    // Move the gene to the nearest bound
+    Clamp,
+    // Mirror the gene back in at the bound it crossed, repeatedly for steps wider than the range
+    Reflect,
+    // Treat the range as periodic, re-entering from the opposite bound
+    Wrap,
+    // Redraw the whole mutation until every gene is in range, clamping after MAX_RESAMPLES
+    // failed attempts
+    Resample,
+}
+
+// Attempts a Resample makes before falling back to clamping
+pub const MAX_RESAMPLES: usize = 100;
+
+pub fn in_range(gene: f64, bounds: (f64, f64)) -> bool {
+    (bounds.0..=bounds.1).contains(&gene)
+}
+
+impl BoundHandling {
+    // Brings the genes back into bounds, Resample clamping what is still outside, and returns
+    // how many were outside
+    pub fn apply(&self, genes: &mut [f64], bounds: (f64, f64)) -> usize {
+        let (low, high) = bounds;
+        let width = high - low;
+        let mut hits = 0;
+        for gene in genes.iter_mut().filter(|gene| !in_range(**gene, bounds)) {
+            hits += 1;
+            *gene = match self {
+                BoundHandling::Ignore => *gene,
+                BoundHandling::Clamp | BoundHandling::Resample => gene.clamp(low, high),
+                BoundHandling::Reflect => {
+                    let folded = (*gene - low).rem_euclid(2.0 * width);
+                    low + if folded > width { 2.0 * width - folded } else { folded }
+                }
+                BoundHandling::Wrap => low + (*gene - low).rem_euclid(width),
+            };
+        }
+        hits
+    }
+}
+
+// Genes found outside the range and mutations redrawn over a run
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BoundaryStats {
+    pub genes: usize,
+    pub resamples: usize,
+}
+
+impl BoundaryStats {
+    // End-of-run report line, if anything left the range
+    pub fn report(&self, handling: BoundHandling) -> Option<String> {
+        (self.genes + self.resamples > 0).then(|| {
+            format!(
+                "Boundary handling ({handling:?}): {} genes brought back into range, {} \
+                 mutations resampled",
+                self.genes, self.resamples
+            )
+        })
+    }
+}
+
+// Knapsack capacity repair: while the selected items are overweight, drop the selected
+// item with the worst value to weight ratio
+pub struct CapacityRepair {
+    pub weights: Vec<f64>,
+    pub values: Vec<f64>,
+    pub capacity: f64,
+}
+
+impl Repair for CapacityRepair {
+    fn repair_bitstring(&self, bitstring: &mut String) -> bool {
+        let mut bits: Vec<u8> = bitstring.bytes().collect();
+        let mut total_weight: f64 = bits
+            .iter()
+            .zip(&self.weights)
+            .filter(|&(&b, _)| b == b'1')
+            .map(|(_, w)| w)
+            .sum();
+        if total_weight <= self.capacity {
+            return false;
+        }
+
+        // Selected items ordered from worst to best value density
+        let mut selected: Vec<usize> = (0..bits.len().min(self.weights.len()))
+            .filter(|&i| bits[i] == b'1')
+            .collect();
+        selected.sort_by(|&i, &j| {
+            let ratio_i = self.values[i] / self.weights[i];
+            let ratio_j = self.values[j] / self.weights[j];
+            ratio_i.partial_cmp(&ratio_j).unwrap()
+        });
+
+        for i in selected {
+            if total_weight <= self.capacity {
+                break;
+            }
+            bits[i] = b'0';
+            total_weight -= self.weights[i];
+        }
+        *bitstring = String::from_utf8(bits).unwrap();
+        true
+    }
+}
+
+// Counts of offspring produced and how many of them needed repair
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RepairStats {
+    pub offspring: usize,
+    pub repaired: usize,
+}
+
+impl RepairStats {
+    pub fn record(&mut self, repaired: bool) {
+        self.offspring += 1;
+        if repaired {
+            self.repaired += 1;
+        }
+    }
+
+    pub fn repair_rate(&self) -> f64 {
+        if self.offspring == 0 {
+            0.0
+        } else {
+            self.repaired as f64 / self.offspring as f64
+        }
+    }
+
+    // End-of-run report line, if anything was repaired
+    pub fn report(&self) -> Option<String> {
+        (self.repaired > 0).then(|| {
+            format!(
+                "Repaired {} of {} offspring ({:.2}%)",
+                self.repaired,
+                self.offspring,
+                self.repair_rate() * 100.0
+            )
+        })
+    }
+}
@@ -0,0 +1,553 @@
+use crate::anova::Anova;
+use crate::curves::{self, CurvePoint};
+use crate::parameter_tuning::{ParameterGrid, TuningResult};
+use crate::plots;
+use crate::curves::Runs;
+use crate::results_analyzer::{ESAnalysis, ParamGroup, ResultsAnalyzer, SGAAnalysis};
+use crate::statistics::{self, Summary};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+// The pieces a report is made of, rendered as Markdown or HTML
+enum Block {
+    Heading(String),
+    Subheading(String),
+    Paragraph(String),
+    Table { header: Vec<String>, rows: Vec<Vec<String>> },
+    // Path relative to the report
+    Image { alt: String, path: String },
+}
+
+// Analysis views of a session's traces a report can add
+#[derive(Debug, Clone, Copy)]
+pub enum TraceView {
+    // Fitness distribution after this many evaluations, by default the fewest any run
+    // stopped after so every run is still comparable
+    FixedBudget(Option<usize>),
+    // Evaluations needed to reach this fitness, by default the median final best of all runs
+    FixedTarget(Option<f64>),
+}
+
+fn table(header: &[&str], rows: Vec<Vec<String>>) -> Block {
+    Block::Table { header: header.iter().map(|h| h.to_string()).collect(), rows }
+}
+
+// Readable summary of a tuning session: overview, best configurations, every parameter set
+// ranked with effect sizes against the best, the SGA-vs-ES comparison and, given the
+// session's traces, best-so-far curves of the top settings
+pub struct Report {
+    title: String,
+    blocks: Vec<Block>,
+}
+
+impl Report {
+    // Writes the report for the results in `results_file` to `output`: HTML if it ends in
+    // .html, Markdown otherwise. Curve plots are written next to it, followed by the
+    // requested views of the traces.
+    pub fn write(
+        results_file: &str,
+        output: &str,
+        trace_dir: Option<&str>,
+        views: &[TraceView],
+    ) -> Result<(), Box<dyn Error>> {
+        let results = ResultsAnalyzer::load_results_from_csv(results_file)?;
+        let mut report = Self::from_results(&results, results_file);
+        if let Some(dir) = trace_dir {
+            report.add_curves(dir, output)?;
+            let settings = curves::read_trace_dir(dir)?;
+            for &view in views {
+                report.add_view(&settings, view);
+            }
+        } else if !views.is_empty() {
+            return Err("fixed-budget and fixed-target views need the session's traces".into());
+        }
+        report.save(output)
+    }
+
+    // HTML if `output` ends in .html, Markdown otherwise
+    pub fn save(&self, output: &str) -> Result<(), Box<dyn Error>> {
+        let is_html = Path::new(output).extension().is_some_and(|ext| ext == "html");
+        let text = if is_html { self.to_html() } else { self.to_markdown() };
+        fs::write(output, text)?;
+        Ok(())
+    }
+
+    // Confirmation of a tuning session: its recommended parameter sets next to their
+    // validation reruns in `validation`, with effect sizes of the reruns over the tuning runs
+    pub fn validation(
+        sga: &SGAAnalysis,
+        es: &ESAnalysis,
+        validation: &[TuningResult],
+        source: &str,
+        (budget_factor, timeout_seconds): (usize, u64),
+    ) -> Self {
+        let mut blocks = vec![Block::Paragraph(format!(
+            "The parameter sets recommended by the effects analysis of {source}, rerun on \
+             fresh seeds with {budget_factor} times the generations and a {timeout_seconds} s \
+             timeout per run. \
+             Scores are max fitness per second, so they move with the budget; max fitness \
+             compares the runs themselves."
+        ))];
+        // Each recommended set's label, and its tuning runs when the session ran it
+        let ga_label =
+            |params| ResultsAnalyzer::ga_group_label(&ParameterGrid::params_to_map_ga(params));
+        let es_label =
+            |params| ResultsAnalyzer::es_group_label(&ParameterGrid::params_to_map_es(params));
+        let tuned = [
+            ("SGA", sga.recommended_params.as_ref().map(ga_label),
+                sga.recommended_group.map(|group| &sga.groups[group])),
+            ("ES", es.recommended_params.as_ref().map(es_label),
+                es.recommended_group.map(|group| &es.groups[group])),
+        ];
+        let reruns = |algorithm: &str| -> Vec<&TuningResult> {
+            validation.iter().filter(|r| r.algorithm == algorithm).collect()
+        };
+
+        let rows = tuned
+            .iter()
+            .filter_map(|&(name, ref label, group)| Some((name, label.as_ref()?, group)))
+            .map(|(name, label, group)| {
+                let runs = reruns(name);
+                let scores: Vec<f64> = runs.iter().map(|r| r.score).collect();
+                let fitnesses: Vec<f64> = runs.iter().map(|r| r.max_fitness).collect();
+                let (scores, fitness) = (Summary::of(&scores), Summary::of(&fitnesses));
+                let rate = |flag: fn(&TuningResult) -> bool| {
+                    let count = runs.iter().filter(|r| flag(r)).count();
+                    format!("{:.1}%", count as f64 / runs.len().max(1) as f64 * 100.0)
+                };
+                vec![
+                    name.to_string(),
+                    label.clone(),
+                    group.map_or("-".to_string(), |group| {
+                        format!("{:.6}", Summary::of(&group.fitnesses).mean)
+                    }),
+                    runs.len().to_string(),
+                    format!("{:.6}", scores.mean),
+                    format!("{:.6}", fitness.mean),
+                    format!("{:.6}", fitness.median),
+                    format!("{:.6}", fitness.std),
+                    format!("{:.6}", fitness.max),
+                    rate(|r| r.converged),
+                    rate(|r| r.timeout_reached),
+                ]
+            })
+            .collect();
+        blocks.push(Block::Heading("Validation runs".to_string()));
+        blocks.push(table(
+            &["Algorithm", "Parameters", "Tuning mean max fitness", "Runs", "Mean score",
+                "Mean max fitness", "Median", "Std", "Best", "Converged", "Timed out"],
+            rows,
+        ));
+
+        blocks.push(Block::Heading("Tuning vs validation".to_string()));
+        for (name, label, group) in tuned {
+            if label.is_none() {
+                blocks.push(Block::Paragraph(format!("No {name} parameter set was tuned.")));
+                continue;
+            }
+            let Some(group) = group else {
+                blocks.push(Block::Paragraph(format!(
+                    "The recommended {name} set was not among the tuning runs, so there is \
+                     nothing to compare its validation runs with."
+                )));
+                continue;
+            };
+            let fitnesses: Vec<f64> = reruns(name).iter().map(|r| r.max_fitness).collect();
+            blocks.push(Block::Paragraph(format!(
+                "{name} max fitness, {} validation runs over {} tuning runs: {}.",
+                fitnesses.len(),
+                group.fitnesses.len(),
+                statistics::describe_effect(&fitnesses, &group.fitnesses)
+            )));
+        }
+
+        let fitnesses = |algorithm: &str| -> Vec<f64> {
+            reruns(algorithm).iter().map(|r| r.max_fitness).collect()
+        };
+        if !fitnesses("SGA").is_empty() && !fitnesses("ES").is_empty() {
+            blocks.push(Block::Heading("SGA vs ES".to_string()));
+            blocks.push(Block::Paragraph(format!(
+                "ES validation runs over SGA validation runs, max fitness: {}.",
+                statistics::describe_effect(&fitnesses("ES"), &fitnesses("SGA"))
+            )));
+        }
+
+        Self { title: "Validation report".to_string(), blocks }
+    }
+
+    fn from_results(results: &[TuningResult], source: &str) -> Self {
+        let sga = ResultsAnalyzer::analyze_sga_results(results);
+        let es = ResultsAnalyzer::analyze_es_results(results);
+        let designs: BTreeSet<&str> = results.iter().map(|r| r.design.as_str()).collect();
+        let designs: Vec<&str> = designs.into_iter().collect();
+
+        let mut blocks = vec![Block::Paragraph(format!(
+            "{} runs from {source} ({} design).",
+            results.len(),
+            designs.join(", ")
+        ))];
+
+        blocks.push(Block::Heading("Overview".to_string()));
+        blocks.push(table(
+            &["Algorithm", "Runs", "Parameter sets", "Mean score", "Converged", "Timed out"],
+            vec![
+                overview_row("SGA", sga.total_runs, sga.param_groups, sga.avg_score,
+                    sga.convergence_rate, sga.timeout_rate),
+                overview_row("ES", es.total_runs, es.param_groups, es.avg_score,
+                    es.convergence_rate, es.timeout_rate),
+            ],
+        ));
+
+        // The effects decomposition leads: it is what the recommended sets come from
+        for (name, anovas) in [("SGA", &sga.anova), ("ES", &es.anova)] {
+            for anova in anovas {
+                blocks.push(Block::Heading(format!("{name} effects on {}", anova.response)));
+                blocks.push(Block::Paragraph(format!(
+                    "Factorial ANOVA over {} runs. Best level of each parameter: {}.",
+                    anova.runs,
+                    anova
+                        .best_levels
+                        .iter()
+                        .map(|(name, level)| format!("{name} {level}"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )));
+                blocks.push(anova_table(anova));
+            }
+        }
+
+        blocks.push(Block::Heading("Recommended and best configurations".to_string()));
+        blocks.push(table(
+            &["Algorithm", "Criterion", "Parameters", "Score"],
+            best_rows(&sga, &es),
+        ));
+
+        for (name, groups) in [("SGA", &sga.groups), ("ES", &es.groups)] {
+            if groups.is_empty() {
+                continue;
+            }
+            blocks.push(Block::Heading(format!("{name} parameter sets")));
+            blocks.push(Block::Paragraph(
+                "Ranked by mean score. The last column is the effect size of the best set over \
+                 each: A12 is the chance that a run of the best set scores higher."
+                    .to_string(),
+            ));
+            blocks.push(group_table(groups));
+        }
+
+        blocks.push(Block::Heading("SGA vs ES".to_string()));
+        blocks.push(Block::Paragraph(comparison(&sga, &es, results)));
+
+        Self { title: "Parameter tuning report".to_string(), blocks }
+    }
+
+    // Median best-so-far curves with quartile bands of each algorithm's top settings
+    fn add_curves(&mut self, trace_dir: &str, output: &str) -> Result<(), Box<dyn Error>> {
+        let settings = curves::read_trace_dir(trace_dir)?;
+        let stem = Path::new(output).with_extension("");
+        self.blocks.push(Block::Heading("Convergence".to_string()));
+        self.blocks.push(Block::Paragraph(format!(
+            "Median best-so-far fitness with interquartile bands, from the traces in {trace_dir}."
+        )));
+        for algorithm in ["sga", "es"] {
+            let prefix = format!("{algorithm}_");
+            let mut aggregated: Vec<(String, Vec<CurvePoint>)> = settings
+                .iter()
+                .filter(|(setting, _)| setting.starts_with(&prefix))
+                .map(|(setting, runs)| (setting.clone(), curves::aggregate(runs, 100)))
+                .collect();
+            if aggregated.is_empty() {
+                continue;
+            }
+            curves::top_by_final_median(&mut aggregated, 5);
+            let plot = format!("{}_{algorithm}_curves.png", stem.display());
+            let title = format!("{} best-so-far fitness", algorithm.to_uppercase());
+            plots::plot_quantile_curves(&plot, &title, &aggregated)?;
+            let file_name = Path::new(&plot).file_name().unwrap_or_default();
+            self.blocks.push(Block::Subheading(title.clone()));
+            self.blocks.push(Block::Image {
+                alt: title,
+                path: file_name.to_string_lossy().to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    fn add_view(&mut self, settings: &[(String, Runs)], view: TraceView) {
+        match view {
+            TraceView::FixedBudget(budget) => {
+                let budget = budget.unwrap_or_else(|| {
+                    let stopped = settings.iter().flat_map(|(_, runs)| runs).filter_map(|run| {
+                        run.last().map(|row| row.evaluations)
+                    });
+                    stopped.min().unwrap_or(0)
+                });
+                let rows = ResultsAnalyzer::fixed_budget(settings, budget)
+                    .into_iter()
+                    .filter(|view| !view.fitnesses.is_empty())
+                    .map(|view| {
+                        let fitness = Summary::of(&view.fitnesses);
+                        vec![
+                            view.setting,
+                            view.fitnesses.len().to_string(),
+                            format!("{:.6}", fitness.median),
+                            format!("{:.6}", statistics::quantile(&view.fitnesses, 0.25)),
+                            format!("{:.6}", statistics::quantile(&view.fitnesses, 0.75)),
+                            format!("{:.6}", fitness.mean),
+                            format!("{:.6}", fitness.max),
+                        ]
+                    })
+                    .collect();
+                self.blocks.push(Block::Heading(format!("Fixed budget: {budget} evaluations")));
+                self.blocks.push(Block::Paragraph(
+                    "Best-so-far fitness of each setting's runs after the budget, best median \
+                     first. Runs that stopped earlier count with their final best."
+                        .to_string(),
+                ));
+                self.blocks.push(table(
+                    &["Setting", "Runs", "Median", "Q1", "Q3", "Mean", "Best"],
+                    rows,
+                ));
+            }
+            TraceView::FixedTarget(target) => {
+                let target = target.unwrap_or_else(|| {
+                    let finals: Vec<f64> = settings
+                        .iter()
+                        .flat_map(|(_, runs)| runs)
+                        .filter_map(|run| curves::best_so_far(run).last().map(|point| point.1))
+                        .collect();
+                    statistics::median(&finals)
+                });
+                let rows = ResultsAnalyzer::fixed_target(settings, target)
+                    .into_iter()
+                    .map(|view| {
+                        let hits: Vec<f64> = view.hits.iter().map(|&e| e as f64).collect();
+                        let median = if hits.is_empty() {
+                            "-".to_string()
+                        } else {
+                            format!("{:.0}", statistics::median(&hits))
+                        };
+                        let ert = view
+                            .expected_running_time()
+                            .map_or("-".to_string(), |ert| format!("{ert:.0}"));
+                        vec![
+                            view.setting.clone(),
+                            (view.hits.len() + view.censored.len()).to_string(),
+                            format!("{:.1}%", view.success_rate() * 100.0),
+                            median,
+                            ert,
+                        ]
+                    })
+                    .collect();
+                self.blocks.push(Block::Heading(format!("Fixed target: fitness {target:.6}")));
+                self.blocks.push(Block::Paragraph(
+                    "Evaluations each setting's runs needed to reach the target, lowest \
+                     expected running time first. Runs that never reached it are censored: \
+                     the expected running time counts their evaluations up to where they \
+                     stopped and divides by the successes only."
+                        .to_string(),
+                ));
+                self.blocks.push(table(
+                    &["Setting", "Runs", "Reached", "Median evaluations (reached)",
+                        "Expected running time"],
+                    rows,
+                ));
+            }
+        }
+    }
+
+    fn to_markdown(&self) -> String {
+        let cell = |text: &str| text.replace('|', "\\|");
+        let mut out = format!("# {}\n", self.title);
+        for block in &self.blocks {
+            out.push('\n');
+            match block {
+                Block::Heading(text) => out.push_str(&format!("## {text}\n")),
+                Block::Subheading(text) => out.push_str(&format!("### {text}\n")),
+                Block::Paragraph(text) => out.push_str(&format!("{text}\n")),
+                Block::Table { header, rows } => {
+                    let header: Vec<String> = header.iter().map(|h| cell(h)).collect();
+                    out.push_str(&format!("| {} |\n", header.join(" | ")));
+                    out.push_str(&format!("|{}\n", "---|".repeat(header.len())));
+                    for row in rows {
+                        let row: Vec<String> = row.iter().map(|c| cell(c)).collect();
+                        out.push_str(&format!("| {} |\n", row.join(" | ")));
+                    }
+                }
+                Block::Image { alt, path } => out.push_str(&format!("![{alt}]({path})\n")),
+            }
+        }
+        out
+    }
+
+    fn to_html(&self) -> String {
+        let escape = |text: &str| {
+            text.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;")
+        };
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n\
+             <style>body {{ font-family: sans-serif; max-width: 70em; margin: 2em auto; }} \
+             table {{ border-collapse: collapse; }} \
+             th, td {{ border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; }} \
+             img {{ max-width: 100%; }}</style>\n</head>\n<body>\n<h1>{0}</h1>\n",
+            escape(&self.title)
+        );
+        for block in &self.blocks {
+            match block {
+                Block::Heading(text) => out.push_str(&format!("<h2>{}</h2>\n", escape(text))),
+                Block::Subheading(text) => out.push_str(&format!("<h3>{}</h3>\n", escape(text))),
+                Block::Paragraph(text) => out.push_str(&format!("<p>{}</p>\n", escape(text))),
+                Block::Table { header, rows } => {
+                    out.push_str("<table>\n<tr>");
+                    for h in header {
+                        out.push_str(&format!("<th>{}</th>", escape(h)));
+                    }
+                    out.push_str("</tr>\n");
+                    for row in rows {
+                        out.push_str("<tr>");
+                        for c in row {
+                            out.push_str(&format!("<td>{}</td>", escape(c)));
+                        }
+                        out.push_str("</tr>\n");
+                    }
+                    out.push_str("</table>\n");
+                }
+                Block::Image { alt, path } => out.push_str(&format!(
+                    "<p><img src=\"{}\" alt=\"{}\"></p>\n",
+                    escape(path),
+                    escape(alt)
+                )),
+            }
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+fn overview_row(
+    name: &str,
+    runs: usize,
+    param_groups: usize,
+    avg_score: f64,
+    convergence_rate: f64,
+    timeout_rate: f64,
+) -> Vec<String> {
+    vec![
+        name.to_string(),
+        runs.to_string(),
+        param_groups.to_string(),
+        format!("{avg_score:.6}"),
+        format!("{:.1}%", convergence_rate * 100.0),
+        format!("{:.1}%", timeout_rate * 100.0),
+    ]
+}
+
+fn best_rows(sga: &SGAAnalysis, es: &ESAnalysis) -> Vec<Vec<String>> {
+    let ga_label =
+        |params| ResultsAnalyzer::ga_group_label(&ParameterGrid::params_to_map_ga(params));
+    let es_label =
+        |params| ResultsAnalyzer::es_group_label(&ParameterGrid::params_to_map_es(params));
+    let mut rows = Vec::new();
+    if let Some(params) = &sga.recommended_params {
+        rows.push(vec!["SGA".into(), "Recommended".into(), ga_label(params),
+            tuning_average(&sga.groups, sga.recommended_group)]);
+    }
+    if let Some(params) = &sga.best_single_run_params {
+        rows.push(vec!["SGA".into(), "Best single run".into(), ga_label(params),
+            format!("{:.6}", sga.best_single_run_score)]);
+    }
+    if let Some(params) = &es.recommended_params {
+        rows.push(vec!["ES".into(), "Recommended".into(), es_label(params),
+            tuning_average(&es.groups, es.recommended_group)]);
+    }
+    if let Some(params) = &es.best_single_run_params {
+        rows.push(vec!["ES".into(), "Best single run".into(), es_label(params),
+            format!("{:.6}", es.best_single_run_score)]);
+    }
+    rows
+}
+
+fn anova_table(anova: &Anova) -> Block {
+    let optional = |value: Option<f64>, precision: usize| {
+        value.map_or("-".to_string(), |value| format!("{value:.precision$}"))
+    };
+    let mut rows: Vec<Vec<String>> = anova
+        .effects
+        .iter()
+        .map(|effect| {
+            vec![
+                effect.source.clone(),
+                effect.df.to_string(),
+                format!("{:.4e}", effect.sum_sq),
+                optional(effect.f, 2),
+                optional(effect.p, 4),
+                format!("{:.3}", effect.eta_squared),
+            ]
+        })
+        .collect();
+    rows.push(vec![
+        "residual".to_string(),
+        anova.residual_df.to_string(),
+        format!("{:.4e}", anova.residual_sum_sq),
+        "-".to_string(),
+        "-".to_string(),
+        "-".to_string(),
+    ]);
+    table(&["Source", "df", "Sum sq", "F", "p", "Eta^2"], rows)
+}
+
+// The average score of a recommended set, "-" when the session never ran it
+fn tuning_average(groups: &[ParamGroup], group: Option<usize>) -> String {
+    group.map_or("-".to_string(), |group| format!("{:.6}", groups[group].avg_score))
+}
+
+fn group_table(groups: &[ParamGroup]) -> Block {
+    let best = &groups[0];
+    let rows = groups
+        .iter()
+        .enumerate()
+        .map(|(rank, group)| {
+            let scores = Summary::of(&group.scores);
+            let fitness = Summary::of(&group.fitnesses);
+            let effect = if rank == 0 {
+                "-".to_string()
+            } else {
+                statistics::describe_effect(&best.scores, &group.scores)
+            };
+            vec![
+                (rank + 1).to_string(),
+                group.label.clone(),
+                group.scores.len().to_string(),
+                format!("{:.6}", scores.mean),
+                format!("{:.6}", scores.median),
+                format!("{:.6}", scores.std),
+                format!("{:.6}", fitness.mean),
+                effect,
+            ]
+        })
+        .collect();
+    table(
+        &["Rank", "Parameters", "Runs", "Mean score", "Median", "Std", "Mean max fitness",
+            "Best vs this"],
+        rows,
+    )
+}
+
+fn comparison(sga: &SGAAnalysis, es: &ESAnalysis, results: &[TuningResult]) -> String {
+    let (Some(sga_best), Some(es_best)) = (sga.groups.first(), es.groups.first()) else {
+        return "Both algorithms need results to be compared.".to_string();
+    };
+    let scores = |algorithm: &str| -> Vec<f64> {
+        results.iter().filter(|r| r.algorithm == algorithm).map(|r| r.score).collect()
+    };
+    format!(
+        "All ES runs over all SGA runs: {}. Best ES set over best SGA set: {}.",
+        statistics::describe_effect(&scores("ES"), &scores("SGA")),
+        statistics::describe_effect(&es_best.scores, &sga_best.scores)
+    )
+}
@@ -0,0 +1,95 @@
+use crate::parameter_tuning::TuningResult;
+use serde::Serialize;
+use std::fs;
+use std::time::{Duration, Instant};
+
+// Resident set size of this process in megabytes, from the VmRSS line of /proc/self/status.
+// None where that file doesn't exist, as off Linux.
+pub fn resident_set_mb() -> Option<f64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kilobytes: f64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kilobytes / 1024.0)
+}
+
+// Wall time of each generation and the largest resident set seen during a run. The resident
+// set belongs to the whole process, so with several workers it includes the other runs in
+// flight; it is sampled at most every 50ms, as reading it costs more than a small generation.
+pub struct ResourceMonitor {
+    generation_ms: Vec<f64>,
+    peak_rss_mb: Option<f64>,
+    last_sample: Instant,
+}
+
+const RSS_SAMPLE_INTERVAL: Duration = Duration::from_millis(50);
+
+impl ResourceMonitor {
+    pub fn start() -> Self {
+        Self {
+            generation_ms: Vec::new(),
+            peak_rss_mb: resident_set_mb(),
+            last_sample: Instant::now(),
+        }
+    }
+
+    // Records a generation that started at `started`
+    pub fn generation(&mut self, started: Instant) {
+        self.generation_ms.push(started.elapsed().as_secs_f64() * 1000.0);
+        if self.last_sample.elapsed() >= RSS_SAMPLE_INTERVAL {
+            self.sample();
+        }
+    }
+
+    // Samples the resident set now, as at the end of a run while its population is alive
+    pub fn sample(&mut self) {
+        self.last_sample = Instant::now();
+        if let Some(rss) = resident_set_mb() {
+            self.peak_rss_mb = Some(self.peak_rss_mb.map_or(rss, |peak| peak.max(rss)));
+        }
+    }
+
+    pub fn peak_rss_mb(&self) -> Option<f64> {
+        self.peak_rss_mb
+    }
+
+    // Mean and longest generation, both 0 for a run cancelled before its first
+    pub fn generation_ms(&self) -> (f64, f64) {
+        if self.generation_ms.is_empty() {
+            return (0.0, 0.0);
+        }
+        let mean = self.generation_ms.iter().sum::<f64>() / self.generation_ms.len() as f64;
+        (mean, self.generation_ms.iter().copied().fold(0.0, f64::max))
+    }
+}
+
+// Resource use across a set of runs, over those that recorded it
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceUsage {
+    pub mean_evaluations_per_second: f64,
+    pub mean_generation_ms: f64,
+    pub max_generation_ms: f64,
+    // None if no run could read its resident set
+    pub peak_rss_mb: Option<f64>,
+}
+
+impl ResourceUsage {
+    // None if no run recorded its resource use, as in results from before it was recorded
+    pub fn of<'a>(results: impl IntoIterator<Item = &'a TuningResult>) -> Option<Self> {
+        let results: Vec<&TuningResult> =
+            results.into_iter().filter(|r| r.evaluations_per_second.is_some()).collect();
+        if results.is_empty() {
+            return None;
+        }
+        let mean = |values: Vec<f64>| values.iter().sum::<f64>() / values.len() as f64;
+        let max = |values: Vec<f64>| values.into_iter().fold(f64::NEG_INFINITY, f64::max);
+        let peaks: Vec<f64> = results.iter().filter_map(|r| r.peak_rss_mb).collect();
+        Some(Self {
+            mean_evaluations_per_second: mean(
+                results.iter().filter_map(|r| r.evaluations_per_second).collect(),
+            ),
+            mean_generation_ms: mean(results.iter().filter_map(|r| r.mean_generation_ms).collect()),
+            max_generation_ms: max(results.iter().filter_map(|r| r.max_generation_ms).collect()),
+            peak_rss_mb: (!peaks.is_empty()).then(|| max(peaks)),
+        })
+    }
+}
@@ -1,10 +1,152 @@
-use crate::parameter_tuning::TuningResult;
+use crate::anova::{self, Anova, Heatmap, MainEffect};
+use crate::parameter_tuning::{ParameterGrid, TuningResult, serialize_sorted};
 use crate::bitstring::GAParameters;
+use crate::curves::{QdTraceRow, Runs, best_so_far};
+use crate::ecdf;
 use crate::evol_strat::ESParameters;
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::Write;
+use crate::selection::SelectionMethod;
+use crate::multi_objective::non_dominated_sort;
+use crate::resources::ResourceUsage;
+use crate::statistics::{self, Summary};
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::iter::Peekable;
 use std::path::Path;
+use std::str::Lines;
+
+// Leading columns of the results CSV, followed by one column per parameter
+const RESULT_COLUMNS: &str = "algorithm,run_id,max_fitness,execution_time,score,converged,\
+    generations,timeout_reached,design,seed,evaluations_to_target,auc,success,distinct_optima,\
+    evaluations_per_second,peak_rss_mb,mean_generation_ms,max_generation_ms";
+
+// Files from before designs were recorded stop after timeout_reached and their runs were grid
+// runs; files from before seeds were recorded stop after design. Both seeded run n with 5000 + n.
+// Files from before evaluations to target were recorded stop after seed, files from before
+// anytime performance was recorded after evaluations_to_target, files from before success
+// flags were recorded after auc, files from before optima were counted after success, and
+// files from before resource use was recorded after distinct_optima.
+const LEGACY_COLUMNS: usize = 8;
+
+// The leading columns followed by the given parameter columns
+fn result_columns(param_names: impl IntoIterator<Item = String>) -> Vec<String> {
+    RESULT_COLUMNS.split(',').map(str::to_string).chain(param_names).collect()
+}
+
+// Column names of a results file and how many of them are leading columns, which must be the
+// current ones or a legacy prefix of them
+fn read_header<'a>(
+    lines: &mut Peekable<Lines<'a>>,
+    filename: &str,
+) -> Result<(Vec<&'a str>, usize), Box<dyn std::error::Error>> {
+    let mut header: Vec<&str> = lines.next().ok_or("empty results file")?.split(',').collect();
+    // Older files put the parameter names on a second header line starting with a comma
+    if let Some(continuation) = lines.next_if(|line| line.starts_with(',')) {
+        header.extend(continuation.split(',').skip(1));
+    }
+    let leading = RESULT_COLUMNS.split(',').zip(&header).take_while(|(a, b)| a == *b).count();
+    if leading < LEGACY_COLUMNS {
+        return Err(format!("{filename}: unexpected header").into());
+    }
+    Ok((header, leading))
+}
+
+// One CSV row of `result`, with a cell per column and parameters it doesn't have left empty
+fn format_row(result: &TuningResult, columns: &[String]) -> String {
+    let optional = |value: Option<f64>, precision: usize| {
+        value.map_or(String::new(), |value| format!("{value:.precision$}"))
+    };
+    let cells: Vec<String> = columns
+        .iter()
+        .map(|column| match column.as_str() {
+            "algorithm" => result.algorithm.clone(),
+            "run_id" => result.run_id.to_string(),
+            "max_fitness" => format!("{:.6}", result.max_fitness),
+            "execution_time" => format!("{:.6}", result.execution_time),
+            "score" => format!("{:.6}", result.score),
+            "converged" => result.converged.to_string(),
+            "generations" => result.generations.to_string(),
+            "timeout_reached" => result.timeout_reached.to_string(),
+            "design" => result.design.clone(),
+            "seed" => result.seed.to_string(),
+            "evaluations_to_target" => {
+                result.evaluations_to_target.map_or(String::new(), |evals| evals.to_string())
+            }
+            "auc" => result.auc.map_or(String::new(), |auc| format!("{auc:.6}")),
+            "success" => result.success.map_or(String::new(), |success| success.to_string()),
+            "distinct_optima" => {
+                result.distinct_optima.map_or(String::new(), |optima| optima.to_string())
+            }
+            "evaluations_per_second" => optional(result.evaluations_per_second, 1),
+            "peak_rss_mb" => optional(result.peak_rss_mb, 1),
+            "mean_generation_ms" => optional(result.mean_generation_ms, 4),
+            "max_generation_ms" => optional(result.max_generation_ms, 4),
+            param_name => result.parameters.get(param_name).map_or(String::new(), f64::to_string),
+        })
+        .collect();
+    cells.join(",")
+}
+
+// A numeric leading column that may be empty or, in older files, missing
+fn optional_field(
+    fields: &[&str],
+    fixed_columns: usize,
+    index: usize,
+) -> Result<Option<f64>, std::num::ParseFloatError> {
+    match fields.get(index) {
+        Some(value) if fixed_columns > index && !value.is_empty() => value.parse().map(Some),
+        _ => Ok(None),
+    }
+}
+
+// Appends each result to the results CSV as soon as its run finishes, so a session that dies
+// part way can be resumed from the file
+pub struct ResultsWriter {
+    file: File,
+    columns: Vec<String>,
+    path: String,
+    // Set after the first write error so it's reported once, not after every run
+    failed: bool,
+}
+
+impl ResultsWriter {
+    // Starts `path` afresh with a header covering every SGA and ES parameter
+    pub fn create(path: &str) -> io::Result<Self> {
+        let columns = result_columns(ParameterGrid::parameter_names());
+        let mut file = File::create(path)?;
+        writeln!(file, "{}", columns.join(","))?;
+        Ok(Self { file, columns, path: path.to_string(), failed: false })
+    }
+    
+    // Continues an existing results file, writing rows in the column order of its header
+    pub fn append(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let contents = std::fs::read_to_string(path)?;
+        let columns = read_header(&mut contents.lines().peekable(), path)?
+            .0
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        let mut file = OpenOptions::new().append(true).open(path)?;
+        // A session killed mid-write can leave the last row without its newline
+        if !contents.is_empty() && !contents.ends_with('\n') {
+            writeln!(file)?;
+        }
+        Ok(Self { file, columns, path: path.to_string(), failed: false })
+    }
+    
+    pub fn write(&mut self, result: &TuningResult) {
+        if self.failed {
+            return;
+        }
+        // One write per row, so an interrupted session loses at most the row in flight
+        let row = format!("{}\n", format_row(result, &self.columns));
+        if let Err(e) = self.file.write_all(row.as_bytes()) {
+            eprintln!("Error writing results {}: {e}", self.path);
+            self.failed = true;
+        }
+    }
+}
 
 pub struct ResultsAnalyzer;
 
@@ -13,47 +155,172 @@ impl ResultsAnalyzer {
         let path = Path::new(filename);
         let mut file = File::create(path)?;
         
-        // Write header
-        let header = "algorithm,run_id,max_fitness,execution_time,score,converged,generations,timeout_reached";
-        writeln!(file, "{header}")?;
+        // Parameter columns: every name used by any result, since SGA and ES differ
+        let all_param_names: BTreeSet<String> = results
+            .iter()
+            .flat_map(|result| result.parameters.keys().cloned())
+            .collect();
+        let columns = result_columns(all_param_names);
         
-        // Write parameter headers (get all unique parameter names)
-        let mut all_param_names = Vec::new();
-        if !results.is_empty() {
-            let first_result = &results[0];
-            for key in first_result.parameters.keys() {
-                all_param_names.push(key.clone());
+        // Write header, then the data rows
+        writeln!(file, "{}", columns.join(","))?;
+        for result in results {
+            writeln!(file, "{}", format_row(result, &columns))?;
+        }
+        
+        Ok(())
+    }
+    
+    // Reads results written by save_results_to_csv or a ResultsWriter
+    pub fn load_results_from_csv(filename: &str) -> Result<Vec<TuningResult>, Box<dyn std::error::Error>> {
+        let contents = std::fs::read_to_string(filename)?;
+        let mut lines = contents.lines().peekable();
+        let (header, fixed_columns) = read_header(&mut lines, filename)?;
+        let param_names = &header[fixed_columns..];
+        
+        let mut results = Vec::new();
+        for line in lines.filter(|line| !line.is_empty()) {
+            let fields: Vec<&str> = line.split(',').collect();
+            if fields.len() != header.len() {
+                return Err(format!("{filename}: expected {} fields in '{line}'", header.len()).into());
             }
-            all_param_names.sort();
+            let parameters = param_names
+                .iter()
+                .zip(&fields[fixed_columns..])
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(name, value)| Ok((name.to_string(), value.parse()?)))
+                .collect::<Result<HashMap<String, f64>, std::num::ParseFloatError>>()?;
+            let run_id = fields[1].parse()?;
+            results.push(TuningResult {
+                algorithm: fields[0].to_string(),
+                parameters,
+                run_id,
+                max_fitness: fields[2].parse()?,
+                execution_time: fields[3].parse()?,
+                score: fields[4].parse()?,
+                converged: fields[5].parse()?,
+                generations: fields[6].parse()?,
+                timeout_reached: fields[7].parse()?,
+                design: if fixed_columns > 8 { fields[8] } else { "grid" }.to_string(),
+                seed: if fixed_columns > 9 { fields[9].parse()? } else { 5000 + run_id as u64 },
+                evaluations_to_target: match fields.get(10) {
+                    Some(evals) if fixed_columns > 10 && !evals.is_empty() => Some(evals.parse()?),
+                    _ => None,
+                },
+                auc: if fixed_columns > 11 { Some(fields[11].parse()?) } else { None },
+                success: match fields.get(12) {
+                    Some(flag) if fixed_columns > 12 && !flag.is_empty() => Some(flag.parse()?),
+                    _ => None,
+                },
+                distinct_optima: if fixed_columns > 13 { Some(fields[13].parse()?) } else { None },
+                evaluations_per_second: optional_field(&fields, fixed_columns, 14)?,
+                peak_rss_mb: optional_field(&fields, fixed_columns, 15)?,
+                mean_generation_ms: optional_field(&fields, fixed_columns, 16)?,
+                max_generation_ms: optional_field(&fields, fixed_columns, 17)?,
+            });
         }
+        Ok(results)
+    }
+    
+    // One row per parameter combination (per algorithm, design and full parameter set) with
+    // score and fitness statistics across its runs, in the order combinations first appear
+    pub fn save_combinations_to_csv(results: &[TuningResult], filename: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut combinations: Vec<(String, Vec<&TuningResult>)> = Vec::new();
+        for result in results {
+            let parameters: BTreeMap<_, _> = result.parameters.iter().collect();
+            let key = format!("{}|{}|{parameters:?}", result.algorithm, result.design);
+            match combinations.iter_mut().find(|(existing, _)| *existing == key) {
+                Some((_, runs)) => runs.push(result),
+                None => combinations.push((key, vec![result])),
+            }
+        }
+        let param_names: BTreeSet<String> = results
+            .iter()
+            .flat_map(|result| result.parameters.keys().cloned())
+            .collect();
         
-        // Write parameter headers
-        for param_name in &all_param_names {
+        let mut file = BufWriter::new(File::create(filename)?);
+        write!(file, "algorithm,design,runs")?;
+        for metric in ["score", "max_fitness"] {
+            for stat in ["mean", "median", "std", "min", "max"] {
+                write!(file, ",{metric}_{stat}")?;
+            }
+        }
+        write!(file, ",convergence_rate,timeout_rate,auc_mean,success_rate,distinct_optima_mean")?;
+        write!(file, ",evaluations_per_second_mean,generation_ms_mean,generation_ms_max")?;
+        write!(file, ",peak_rss_mb_max")?;
+        for param_name in &param_names {
             write!(file, ",{param_name}")?;
         }
         writeln!(file)?;
         
-        // Write data rows
-        for result in results {
-            write!(file, "{},{},{:.6},{:.6},{:.6},{},{},{}",
-                result.algorithm,
-                result.run_id,
-                result.max_fitness,
-                result.execution_time,
-                result.score,
-                result.converged,
-                result.generations,
-                result.timeout_reached
-            )?;
-            
-            // Write parameter values
-            for param_name in &all_param_names {
-                let value = result.parameters.get(param_name).unwrap_or(&0.0);
-                write!(file, ",{value}")?;
+        for (_, runs) in &combinations {
+            let n = runs.len() as f64;
+            write!(file, "{},{},{}", runs[0].algorithm, runs[0].design, runs.len())?;
+            let scores: Vec<f64> = runs.iter().map(|r| r.score).collect();
+            let fitnesses: Vec<f64> = runs.iter().map(|r| r.max_fitness).collect();
+            for summary in [Summary::of(&scores), Summary::of(&fitnesses)] {
+                write!(file, ",{:.6},{:.6},{:.6},{:.6},{:.6}",
+                    summary.mean, summary.median, summary.std, summary.min, summary.max)?;
+            }
+            let converged = runs.iter().filter(|r| r.converged).count() as f64;
+            let timed_out = runs.iter().filter(|r| r.timeout_reached).count() as f64;
+            write!(file, ",{:.4},{:.4}", converged / n, timed_out / n)?;
+            let aucs: Vec<f64> = runs.iter().filter_map(|r| r.auc).collect();
+            match mean_auc(&aucs) {
+                Some(auc) => write!(file, ",{auc:.4}")?,
+                None => write!(file, ",")?,
+            }
+            match success_rate(runs.iter().copied()) {
+                Some(rate) => write!(file, ",{rate:.4}")?,
+                None => write!(file, ",")?,
+            }
+            match mean_optima(runs.iter().copied()) {
+                Some(optima) => write!(file, ",{optima:.2}")?,
+                None => write!(file, ",")?,
+            }
+            match ResourceUsage::of(runs.iter().copied()) {
+                Some(usage) => write!(
+                    file,
+                    ",{:.1},{:.4},{:.4},{}",
+                    usage.mean_evaluations_per_second,
+                    usage.mean_generation_ms,
+                    usage.max_generation_ms,
+                    usage.peak_rss_mb.map_or(String::new(), |rss| format!("{rss:.1}"))
+                )?,
+                None => write!(file, ",,,,")?,
+            }
+            for param_name in &param_names {
+                match runs[0].parameters.get(param_name) {
+                    Some(value) => write!(file, ",{value}")?,
+                    None => write!(file, ",")?,
+                }
             }
             writeln!(file)?;
         }
-        
+        file.flush()?;
+        Ok(())
+    }
+    
+    // The results as a JSON array, one object per run
+    pub fn save_results_to_json(results: &[TuningResult], filename: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = BufWriter::new(File::create(filename)?);
+        serde_json::to_writer_pretty(&mut file, results)?;
+        writeln!(file)?;
+        file.flush()?;
+        Ok(())
+    }
+    
+    // Both analyses as one JSON object with "sga" and "es" members
+    pub fn write_analysis_json(
+        sga_analysis: &SGAAnalysis,
+        es_analysis: &ESAnalysis,
+        mut writer: impl Write,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let analysis = serde_json::json!({ "sga": sga_analysis, "es": es_analysis });
+        serde_json::to_writer_pretty(&mut writer, &analysis)?;
+        writeln!(writer)?;
+        writer.flush()?;
         Ok(())
     }
     
@@ -66,6 +333,7 @@ impl ResultsAnalyzer {
         let mut best_score = 0.0;
         let mut best_params: Option<GAParameters> = None;
         let mut score_sum = 0.0;
+        let mut aucs = Vec::new();
         let mut convergence_count = 0;
         let mut timeout_count = 0;
         
@@ -77,6 +345,7 @@ impl ResultsAnalyzer {
             param_groups.entry(param_key).or_default().push(result);
             
             score_sum += result.score;
+            aucs.extend(result.auc);
             if result.converged {
                 convergence_count += 1;
             }
@@ -90,28 +359,30 @@ impl ResultsAnalyzer {
             }
         }
         
-        // Find best average performing parameter set
-        let mut best_avg_score = 0.0;
-        let mut best_avg_params: Option<GAParameters> = None;
-        
-        for group_results in param_groups.values() {
-            let avg_score: f64 = group_results.iter().map(|r| r.score).sum::<f64>() / group_results.len() as f64;
-            if avg_score > best_avg_score {
-                best_avg_score = avg_score;
-                best_avg_params = Self::key_to_ga_params(&group_results[0].parameters);
-            }
-        }
+        let groups = Self::rank_groups(&param_groups, Self::ga_group_label);
+        let pareto = pareto_ranking(&groups);
+        let anova = effects_anova(&sga_results);
+        let (recommended, recommended_group) = recommended(&sga_results, &anova, &groups);
         
         SGAAnalysis {
             total_runs: sga_results.len(),
             best_single_run_score: best_score,
             best_single_run_params: best_params,
-            best_avg_score,
-            best_avg_params,
+            recommended_params: recommended.as_ref().and_then(Self::key_to_ga_params),
+            recommended_group,
             avg_score: score_sum / sga_results.len() as f64,
+            avg_auc: mean_auc(&aucs),
+            success_rate: success_rate(sga_results.iter().copied()),
+            avg_distinct_optima: mean_optima(sga_results.iter().copied()),
+            resources: ResourceUsage::of(sga_results.iter().copied()),
             convergence_rate: convergence_count as f64 / sga_results.len() as f64,
             timeout_rate: timeout_count as f64 / sga_results.len() as f64,
             param_groups: param_groups.len(),
+            groups,
+            pareto,
+            anova,
+            main_effects: anova::main_effects(&sga_results, "score", |result| result.score),
+            heatmaps: anova::heatmaps(&sga_results, "score", |result| result.score),
         }
     }
     
@@ -124,6 +395,7 @@ impl ResultsAnalyzer {
         let mut best_score = 0.0;
         let mut best_params: Option<ESParameters> = None;
         let mut score_sum = 0.0;
+        let mut aucs = Vec::new();
         let mut convergence_count = 0;
         let mut timeout_count = 0;
         
@@ -135,6 +407,7 @@ impl ResultsAnalyzer {
             param_groups.entry(param_key).or_default().push(result);
             
             score_sum += result.score;
+            aucs.extend(result.auc);
             if result.converged {
                 convergence_count += 1;
             }
@@ -148,28 +421,30 @@ impl ResultsAnalyzer {
             }
         }
         
-        // Find best average performing parameter set
-        let mut best_avg_score = 0.0;
-        let mut best_avg_params: Option<ESParameters> = None;
-        
-        for group_results in param_groups.values() {
-            let avg_score: f64 = group_results.iter().map(|r| r.score).sum::<f64>() / group_results.len() as f64;
-            if avg_score > best_avg_score {
-                best_avg_score = avg_score;
-                best_avg_params = Self::key_to_es_params(&group_results[0].parameters);
-            }
-        }
+        let groups = Self::rank_groups(&param_groups, Self::es_group_label);
+        let pareto = pareto_ranking(&groups);
+        let anova = effects_anova(&es_results);
+        let (recommended, recommended_group) = recommended(&es_results, &anova, &groups);
         
         ESAnalysis {
             total_runs: es_results.len(),
             best_single_run_score: best_score,
             best_single_run_params: best_params,
-            best_avg_score,
-            best_avg_params,
+            recommended_params: recommended.as_ref().and_then(Self::key_to_es_params),
+            recommended_group,
             avg_score: score_sum / es_results.len() as f64,
+            avg_auc: mean_auc(&aucs),
+            success_rate: success_rate(es_results.iter().copied()),
+            avg_distinct_optima: mean_optima(es_results.iter().copied()),
+            resources: ResourceUsage::of(es_results.iter().copied()),
             convergence_rate: convergence_count as f64 / es_results.len() as f64,
             timeout_rate: timeout_count as f64 / es_results.len() as f64,
             param_groups: param_groups.len(),
+            groups,
+            pareto,
+            anova,
+            main_effects: anova::main_effects(&es_results, "score", |result| result.score),
+            heatmaps: anova::heatmaps(&es_results, "score", |result| result.score),
         }
     }
     
@@ -181,85 +456,343 @@ impl ResultsAnalyzer {
         println!("\n--- SGA Results ---");
         println!("Total runs: {}", sga_analysis.total_runs);
         println!("Average score: {:.6}", sga_analysis.avg_score);
+        if let Some(auc) = sga_analysis.avg_auc {
+            println!("Average anytime AUC: {auc:.3}");
+        }
+        if let Some(rate) = sga_analysis.success_rate {
+            println!("Success rate: {:.2}%", rate * 100.0);
+        }
+        if let Some(optima) = sga_analysis.avg_distinct_optima {
+            println!("Average distinct optima covered: {optima:.2}");
+        }
+        if let Some(usage) = &sga_analysis.resources {
+            Self::print_resources(usage);
+        }
         println!("Convergence rate: {:.2}%", sga_analysis.convergence_rate * 100.0);
         println!("Timeout rate: {:.2}%", sga_analysis.timeout_rate * 100.0);
         println!("Parameter combinations tested: {}", sga_analysis.param_groups);
         
-        if let Some(ref params) = sga_analysis.best_single_run_params {
-            println!("\nBest single run parameters:");
+        // The effects decomposition and the parameters it recommends lead; single runs and
+        // the ranking of parameter sets follow as detail
+        sga_analysis.anova.iter().for_each(anova::print);
+        if let Some(ref params) = sga_analysis.recommended_params {
+            println!("\nRecommended parameters (best level of each effect):");
             println!("  Population size: {}", params.pop_size);
-            println!("  Mutation rate: {:.3}", params.mutation_rate);
-            println!("  Score: {:.6}", sga_analysis.best_single_run_score);
+            println!("  Mutation rate: {:.4}", params.mutation_rate);
+            println!("  Crossover rate: {:.3}", params.crossover_rate);
+            Self::print_recommended_runs(&sga_analysis.groups, sga_analysis.recommended_group);
         }
         
-        if let Some(ref params) = sga_analysis.best_avg_params {
-            println!("\nBest average parameters:");
+        if let Some(ref params) = sga_analysis.best_single_run_params {
+            println!("\nBest single run parameters:");
             println!("  Population size: {}", params.pop_size);
-            println!("  Mutation rate: {:.3}", params.mutation_rate);
-            println!("  Average score: {:.6}", sga_analysis.best_avg_score);
+            println!("  Mutation rate: {:.4}", params.mutation_rate);
+            println!("  Crossover rate: {:.3}", params.crossover_rate);
+            println!("  Score: {:.6}", sga_analysis.best_single_run_score);
         }
+        Self::print_top_groups(&sga_analysis.groups);
+        Self::print_pareto_front(&sga_analysis.pareto);
         
         println!("\n--- ES Results ---");
         println!("Total runs: {}", es_analysis.total_runs);
         println!("Average score: {:.6}", es_analysis.avg_score);
+        if let Some(auc) = es_analysis.avg_auc {
+            println!("Average anytime AUC: {auc:.3}");
+        }
+        if let Some(rate) = es_analysis.success_rate {
+            println!("Success rate: {:.2}%", rate * 100.0);
+        }
+        if let Some(optima) = es_analysis.avg_distinct_optima {
+            println!("Average distinct optima covered: {optima:.2}");
+        }
+        if let Some(usage) = &es_analysis.resources {
+            Self::print_resources(usage);
+        }
         println!("Convergence rate: {:.2}%", es_analysis.convergence_rate * 100.0);
         println!("Timeout rate: {:.2}%", es_analysis.timeout_rate * 100.0);
         println!("Parameter combinations tested: {}", es_analysis.param_groups);
         
-        if let Some(ref params) = es_analysis.best_single_run_params {
-            println!("\nBest single run parameters:");
+        es_analysis.anova.iter().for_each(anova::print);
+        if let Some(ref params) = es_analysis.recommended_params {
+            println!("\nRecommended parameters (best level of each effect):");
             println!("  Mu: {}, Lambda: {}", params.mu, params.lambda);
             println!("  Sigma: {:.3}", params.sigma);
-            println!("  Score: {:.6}", es_analysis.best_single_run_score);
+            Self::print_recommended_runs(&es_analysis.groups, es_analysis.recommended_group);
         }
         
-        if let Some(ref params) = es_analysis.best_avg_params {
-            println!("\nBest average parameters:");
+        if let Some(ref params) = es_analysis.best_single_run_params {
+            println!("\nBest single run parameters:");
             println!("  Mu: {}, Lambda: {}", params.mu, params.lambda);
             println!("  Sigma: {:.3}", params.sigma);
-            println!("  Average score: {:.6}", es_analysis.best_avg_score);
+            println!("  Score: {:.6}", es_analysis.best_single_run_score);
         }
+        Self::print_top_groups(&es_analysis.groups);
+        Self::print_pareto_front(&es_analysis.pareto);
         
-        // Compare algorithms
+        // Compare algorithms over all their tuning runs rather than their luckiest cells
         println!("\n--- Algorithm Comparison ---");
-        if sga_analysis.best_avg_score > es_analysis.best_avg_score {
-            println!("SGA performs better on average");
+        let all_scores = |groups: &[ParamGroup]| -> Vec<f64> {
+            groups.iter().flat_map(|group| group.scores.iter().copied()).collect()
+        };
+        let sga_scores = all_scores(&sga_analysis.groups);
+        let es_scores = all_scores(&es_analysis.groups);
+        if sga_analysis.avg_score > es_analysis.avg_score {
+            println!("SGA performs better on average over all tuning runs");
             println!("SGA avg score: {:.6} vs ES avg score: {:.6}", 
-                sga_analysis.best_avg_score, es_analysis.best_avg_score);
+                sga_analysis.avg_score, es_analysis.avg_score);
         } else {
-            println!("ES performs better on average");
+            println!("ES performs better on average over all tuning runs");
             println!("ES avg score: {:.6} vs SGA avg score: {:.6}", 
-                es_analysis.best_avg_score, sga_analysis.best_avg_score);
+                es_analysis.avg_score, sga_analysis.avg_score);
+        }
+        
+        // From the winner's side
+        if !sga_scores.is_empty() && !es_scores.is_empty() {
+            let effect = if sga_analysis.avg_score > es_analysis.avg_score {
+                statistics::describe_effect(&sga_scores, &es_scores)
+            } else {
+                statistics::describe_effect(&es_scores, &sga_scores)
+            };
+            println!("Effect size of the better over the worse: {effect}");
         }
     }
     
-    // Helper functions for parameter key conversion
-    fn ga_params_to_key(params: &HashMap<String, f64>) -> String {
-        format!("{}_{:.3}", 
+    // How the recommended parameter set did in the tuning runs, if the design ran it
+    fn print_recommended_runs(groups: &[ParamGroup], group: Option<usize>) {
+        match group.map(|group| &groups[group]) {
+            Some(group) => println!(
+                "  Tuning average score: {:.6} over {} runs",
+                group.avg_score,
+                group.scores.len()
+            ),
+            None => println!("  Not a combination the session ran; `validate` runs it"),
+        }
+    }
+    
+    fn print_resources(usage: &ResourceUsage) {
+        println!(
+            "Throughput: {:.0} evaluations/s, {:.3} ms per generation ({:.3} at worst)",
+            usage.mean_evaluations_per_second, usage.mean_generation_ms, usage.max_generation_ms
+        );
+        if let Some(rss) = usage.peak_rss_mb {
+            println!("Peak resident set: {rss:.1} MB");
+        }
+    }
+    
+    // Best average first; ties go by label so the order doesn't depend on hashing
+    fn rank_groups(
+        param_groups: &HashMap<String, Vec<&TuningResult>>,
+        label: fn(&HashMap<String, f64>) -> String,
+    ) -> Vec<ParamGroup> {
+        let mut groups: Vec<ParamGroup> = param_groups
+            .values()
+            .map(|results| {
+                let scores: Vec<f64> = results.iter().map(|r| r.score).collect();
+                ParamGroup {
+                    label: label(&results[0].parameters),
+                    parameters: results[0].parameters.clone(),
+                    avg_score: scores.iter().sum::<f64>() / scores.len() as f64,
+                    scores,
+                    fitnesses: results.iter().map(|r| r.max_fitness).collect(),
+                    aucs: results.iter().filter_map(|r| r.auc).collect(),
+                    success_rate: success_rate(results.iter().copied()),
+                    distinct_optima: results.iter().filter_map(|r| r.distinct_optima).collect(),
+                    times: results.iter().map(|r| r.execution_time).collect(),
+                    evaluations_to_target: results
+                        .iter()
+                        .map(|r| r.evaluations_to_target)
+                        .collect(),
+                }
+            })
+            .collect();
+        groups.sort_by(|a, b| {
+            b.avg_score.total_cmp(&a.avg_score).then_with(|| a.label.cmp(&b.label))
+        });
+        groups
+    }
+    
+    pub fn ga_group_label(params: &HashMap<String, f64>) -> String {
+        let base = ParameterGrid::params_to_map_ga(&ParameterGrid::sga_base());
+        format!("pop {}, mutation {:.4}, crossover {:.3}{}",
             params.get("pop_size").unwrap_or(&0.0),
-            params.get("mutation_rate").unwrap_or(&0.0)
+            params.get("mutation_rate").unwrap_or(&0.0),
+            params.get("crossover_rate").unwrap_or(&0.0),
+            other_parameters(params, &base, &["pop_size", "mutation_rate", "crossover_rate"])
         )
     }
     
-    fn es_params_to_key(params: &HashMap<String, f64>) -> String {
-        format!("{}_{:.3}", 
+    pub fn es_group_label(params: &HashMap<String, f64>) -> String {
+        let base = ParameterGrid::params_to_map_es(&ParameterGrid::es_base());
+        format!("lambda {}, sigma {:.3}{}",
             params.get("lambda").unwrap_or(&0.0),
-            params.get("sigma").unwrap_or(&0.0)
+            params.get("sigma").unwrap_or(&0.0),
+            other_parameters(params, &base, &["lambda", "sigma", "mu"])
         )
     }
     
-    fn key_to_ga_params(params: &HashMap<String, f64>) -> Option<GAParameters> {
+    // The top parameter sets with the effect size of the best one over each, since a few
+    // runs per set make close averages easy to over-read
+    fn print_top_groups(groups: &[ParamGroup]) {
+        let Some(best) = groups.first() else {
+            return;
+        };
+        println!("\nTop parameter sets (effect size of the best over each):");
+        for (rank, group) in groups.iter().take(5).enumerate() {
+            let runs = group.scores.len();
+            print!("  {}. {}: avg {:.6} over {runs} runs", rank + 1, group.label, group.avg_score);
+            if let Some(auc) = mean_auc(&group.aucs) {
+                print!(", AUC {auc:.3}");
+            }
+            if let Some(rate) = group.success_rate {
+                print!(", success {:.0}%", rate * 100.0);
+            }
+            if !group.distinct_optima.is_empty() {
+                let total: usize = group.distinct_optima.iter().sum();
+                print!(", optima {:.1}", total as f64 / group.distinct_optima.len() as f64);
+            }
+            if rank > 0 {
+                print!(", {}", statistics::describe_effect(&best.scores, &group.scores));
+            }
+            println!();
+        }
+    }
+    
+    // The non-dominated parameter sets, which no other set beats on fitness, time and
+    // evaluations to target at once
+    fn print_pareto_front(pareto: &[ParetoEntry]) {
+        let front: Vec<&ParetoEntry> = pareto.iter().filter(|entry| entry.rank == 1).collect();
+        if front.is_empty() {
+            return;
+        }
+        println!("\nPareto front over fitness, time and evaluations to target:");
+        for entry in front {
+            let evals = entry
+                .evaluations_to_target
+                .map_or("target not reached".to_string(), |evals| format!("{evals:.0} evals"));
+            println!("  {}: fitness {:.6}, {:.3}s, {evals}",
+                entry.label, entry.mean_fitness, entry.mean_time);
+        }
+    }
+    
+    // Fixed-budget view of the traces of each setting: the best fitness each run had found
+    // within `budget` evaluations, best median first. Runs that stopped earlier keep their
+    // final best; runs that hadn't finished their first generation are left out.
+    pub fn fixed_budget(settings: &[(String, Runs)], budget: usize) -> Vec<FixedBudget> {
+        let mut views: Vec<FixedBudget> = settings
+            .iter()
+            .map(|(setting, runs)| FixedBudget {
+                setting: setting.clone(),
+                fitnesses: runs
+                    .iter()
+                    .filter_map(|run| {
+                        let curve = best_so_far(run);
+                        let reached = curve.partition_point(|&(evals, _)| evals <= budget);
+                        reached.checked_sub(1).map(|last| curve[last].1)
+                    })
+                    .collect(),
+            })
+            .collect();
+        let median = |view: &FixedBudget| {
+            let median = statistics::median(&view.fitnesses);
+            if median.is_nan() { f64::NEG_INFINITY } else { median }
+        };
+        views.sort_by(|a, b| median(b).total_cmp(&median(a)));
+        views
+    }
+    
+    // Fixed-target view of the traces of each setting: the evaluations each run needed to
+    // reach `target`, with the runs that never did censored at the evaluations they stopped
+    // after. Lowest expected running time first, settings that never reached it last.
+    pub fn fixed_target(settings: &[(String, Runs)], target: f64) -> Vec<FixedTarget> {
+        let mut views: Vec<FixedTarget> = settings
+            .iter()
+            .map(|(setting, runs)| {
+                let runtimes = ecdf::runtimes(runs, target);
+                let (mut hits, mut censored) = (Vec::new(), Vec::new());
+                for (runtime, run) in runtimes.into_iter().zip(runs) {
+                    match runtime {
+                        Some(evaluations) => hits.push(evaluations),
+                        None => censored.push(run.last().map_or(0, |row| row.evaluations)),
+                    }
+                }
+                FixedTarget { setting: setting.clone(), hits, censored }
+            })
+            .collect();
+        let ert = |view: &FixedTarget| view.expected_running_time().unwrap_or(f64::INFINITY);
+        views.sort_by(|a, b| ert(a).total_cmp(&ert(b)));
+        views
+    }
+    
+    // Final archive metrics of each MAP-Elites run from its QD trace; runs that ended before
+    // their first generation are left out
+    pub fn analyze_qd(runs: &[Vec<QdTraceRow>]) -> QdAnalysis {
+        let finals: Vec<&QdTraceRow> = runs.iter().filter_map(|run| run.last()).collect();
+        QdAnalysis {
+            runs: finals.len(),
+            evaluations: finals.iter().map(|row| row.evaluations).collect(),
+            coverage: finals.iter().map(|row| row.coverage).collect(),
+            qd_score: finals.iter().map(|row| row.qd_score).collect(),
+            max_fitness: finals.iter().map(|row| row.max_fitness).collect(),
+        }
+    }
+    
+    pub fn print_qd_summary(analysis: &QdAnalysis) {
+        println!("\n--- MAP-Elites Archives ---");
+        println!("Runs: {}", analysis.runs);
+        if analysis.runs == 0 {
+            return;
+        }
+        let evaluations: Vec<f64> =
+            analysis.evaluations.iter().map(|&evals| evals as f64).collect();
+        println!(
+            "Final archives after {:.0} evaluations on average:",
+            Summary::of(&evaluations).mean
+        );
+        let metrics = [
+            ("Coverage", &analysis.coverage),
+            ("QD-score", &analysis.qd_score),
+            ("Max fitness", &analysis.max_fitness),
+        ];
+        for (name, values) in metrics {
+            let summary = Summary::of(values);
+            println!(
+                "  {name}: mean {:.6} (std {:.6}), median {:.6}, best {:.6}",
+                summary.mean, summary.std, summary.median, summary.max
+            );
+        }
+    }
+    
+    // Helper functions for parameter key conversion
+    // Full precision: randomly sampled rates can agree to three decimals and still differ
+    // Every parameter goes into the key, as custom grids may vary any of them
+    fn ga_params_to_key(params: &HashMap<String, f64>) -> String {
+        params_key(params)
+    }
+    
+    fn es_params_to_key(params: &HashMap<String, f64>) -> String {
+        params_key(params)
+    }
+    
+    pub fn key_to_ga_params(params: &HashMap<String, f64>) -> Option<GAParameters> {
         Some(GAParameters {
             pop_size: *params.get("pop_size")? as usize,
             mem_size: *params.get("mem_size")? as usize,
+            num_dims: params.get("num_dims").map_or(10, |&d| d as usize),
             mutation_rate: *params.get("mutation_rate")?,
             crossover_rate: *params.get("crossover_rate")?,
+            truncation_fraction: params.get("truncation_fraction").map_or(0.5, |&t| t),
+            selection: params.get("tournament_size").map_or(
+                GAParameters::default().selection,
+                |&size| SelectionMethod::Tournament { size: size as usize },
+            ),
             max_iters: *params.get("max_iters")? as usize,
             convergence_threshold: *params.get("convergence_threshold")?,
+            resamples: params.get("resamples").map_or(1, |&r| r as usize),
+            ..Default::default()
         })
     }
     
-    fn key_to_es_params(params: &HashMap<String, f64>) -> Option<ESParameters> {
+    pub fn key_to_es_params(params: &HashMap<String, f64>) -> Option<ESParameters> {
         Some(ESParameters {
             mu: *params.get("mu")? as usize,
             lambda: *params.get("lambda")? as usize,
@@ -271,32 +804,281 @@ impl ResultsAnalyzer {
             sigma: *params.get("sigma")?,
             tau: *params.get("tau")?,
             max_gens: *params.get("max_gens")? as usize,
+            convergence_threshold: params.get("convergence_threshold").map_or(0.99, |&t| t),
+            resamples: params.get("resamples").map_or(1, |&r| r as usize),
+            ..Default::default()
         })
     }
 }
 
-#[derive(Debug)]
+// A parameter map as a key, its names in order so equal maps give equal keys
+fn params_key(params: &HashMap<String, f64>) -> String {
+    let sorted: BTreeMap<&String, &f64> = params.iter().collect();
+    sorted.iter().map(|(name, value)| format!("{name}={value}")).collect::<Vec<_>>().join("_")
+}
+
+// ", name value" for each parameter a custom grid set away from the base combination, besides
+// those a label already shows, so combinations differing only there can be told apart
+fn other_parameters(
+    params: &HashMap<String, f64>,
+    base: &HashMap<String, f64>,
+    shown: &[&str],
+) -> String {
+    let sorted: BTreeMap<&String, &f64> = params.iter().collect();
+    sorted
+        .into_iter()
+        .filter(|(name, value)| !shown.contains(&name.as_str()) && base.get(*name) != Some(*value))
+        .map(|(name, value)| format!(", {name} {value}"))
+        .collect()
+}
+
+// Factorial ANOVAs of score and of max fitness, see anova::factorial_anova
+fn effects_anova(results: &[&TuningResult]) -> Vec<Anova> {
+    let score = anova::factorial_anova(results, "score", |result| result.score);
+    let fitness = anova::factorial_anova(results, "max_fitness", |result| result.max_fitness);
+    score.into_iter().chain(fitness).collect()
+}
+
+// The parameter set the effects on score point to: every factor at its level of the best
+// marginal mean, the other parameters as the runs had them. The design may never have run
+// that combination, so the index of its group among `groups` comes with it when it did. With
+// a single parameter set there are no effects to decompose and that set is the answer.
+fn recommended(
+    results: &[&TuningResult],
+    anova: &[Anova],
+    groups: &[ParamGroup],
+) -> (Option<HashMap<String, f64>>, Option<usize>) {
+    let parameters = match anova.iter().find(|anova| anova.response == "score") {
+        Some(anova) => results.first().map(|result| {
+            let mut parameters = result.parameters.clone();
+            parameters.extend(anova.best_levels.iter().cloned());
+            parameters
+        }),
+        None => groups.first().map(|group| group.parameters.clone()),
+    };
+    let group = parameters
+        .as_ref()
+        .and_then(|parameters| groups.iter().position(|group| group.parameters == *parameters));
+    (parameters, group)
+}
+
+fn mean_auc(aucs: &[f64]) -> Option<f64> {
+    (!aucs.is_empty()).then(|| aucs.iter().sum::<f64>() / aucs.len() as f64)
+}
+
+// Mean number of optima covered by the runs that counted them, None if none did
+fn mean_optima<'a>(results: impl IntoIterator<Item = &'a TuningResult>) -> Option<f64> {
+    let counts: Vec<usize> = results.into_iter().filter_map(|r| r.distinct_optima).collect();
+    (!counts.is_empty()).then(|| counts.iter().sum::<usize>() as f64 / counts.len() as f64)
+}
+
+// Fraction of the runs with a success flag that succeeded, None if none has one
+fn success_rate<'a>(results: impl IntoIterator<Item = &'a TuningResult>) -> Option<f64> {
+    let flags: Vec<bool> = results.into_iter().filter_map(|r| r.success).collect();
+    (!flags.is_empty())
+        .then(|| flags.iter().filter(|&&success| success).count() as f64 / flags.len() as f64)
+}
+
+// Best parameters in the analyses are written as the same name-value maps as the results
+fn serialize_ga_params<S: Serializer>(
+    params: &Option<GAParameters>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let map = params.as_ref().map(ParameterGrid::params_to_map_ga);
+    map.map(|map| map.into_iter().collect::<BTreeMap<_, _>>()).serialize(serializer)
+}
+
+fn serialize_es_params<S: Serializer>(
+    params: &Option<ESParameters>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let map = params.as_ref().map(ParameterGrid::params_to_map_es);
+    map.map(|map| map.into_iter().collect::<BTreeMap<_, _>>()).serialize(serializer)
+}
+
+// The runs of one parameter set
+#[derive(Debug, Serialize)]
+pub struct ParamGroup {
+    pub label: String,
+    #[serde(serialize_with = "serialize_sorted")]
+    pub parameters: HashMap<String, f64>,
+    pub avg_score: f64,
+    pub scores: Vec<f64>,
+    pub fitnesses: Vec<f64>,
+    // Anytime performance of the runs that recorded it
+    pub aucs: Vec<f64>,
+    // Fraction of the runs that recorded a success flag which succeeded
+    pub success_rate: Option<f64>,
+    // Clusters each run's final population covered, for the runs that recorded it
+    pub distinct_optima: Vec<usize>,
+    pub times: Vec<f64>,
+    pub evaluations_to_target: Vec<Option<usize>>,
+}
+
+// Best-so-far fitness of one setting's runs at a fixed evaluation budget
+#[derive(Debug, Serialize)]
+pub struct FixedBudget {
+    pub setting: String,
+    pub fitnesses: Vec<f64>,
+}
+
+// Evaluations one setting's runs needed to reach a fixed target
+#[derive(Debug, Serialize)]
+pub struct FixedTarget {
+    pub setting: String,
+    // Evaluations of the runs that reached the target
+    pub hits: Vec<usize>,
+    // Evaluations of the runs that didn't, up to where they stopped
+    pub censored: Vec<usize>,
+}
+
+impl FixedTarget {
+    pub fn success_rate(&self) -> f64 {
+        self.hits.len() as f64 / (self.hits.len() + self.censored.len()).max(1) as f64
+    }
+    
+    // Expected running time: the evaluations of every run, failures counted up to where
+    // they stopped, over the number of successes. None if no run reached the target.
+    pub fn expected_running_time(&self) -> Option<f64> {
+        let total: usize = self.hits.iter().chain(&self.censored).sum();
+        (!self.hits.is_empty()).then(|| total as f64 / self.hits.len() as f64)
+    }
+}
+
+// QD metrics of the final archive of each of a set of MAP-Elites runs
+#[derive(Debug, Serialize)]
+pub struct QdAnalysis {
+    pub runs: usize,
+    pub evaluations: Vec<usize>,
+    pub coverage: Vec<f64>,
+    pub qd_score: Vec<f64>,
+    pub max_fitness: Vec<f64>,
+}
+
+// A parameter set's position on the quality-time trade-off. Evaluations to target is the
+// expected running time: the mean over the runs that reached the target divided by the
+// fraction that did, None if none did.
+#[derive(Debug, Serialize)]
+pub struct ParetoEntry {
+    pub label: String,
+    #[serde(serialize_with = "serialize_sorted")]
+    pub parameters: HashMap<String, f64>,
+    pub mean_fitness: f64,
+    pub mean_time: f64,
+    pub evaluations_to_target: Option<f64>,
+    // Non-dominated sorting front, 1 for the Pareto front itself
+    pub rank: usize,
+}
+
+impl ParetoEntry {
+    fn of(group: &ParamGroup) -> Self {
+        let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len().max(1) as f64;
+        let successes: Vec<f64> =
+            group.evaluations_to_target.iter().flatten().map(|&evals| evals as f64).collect();
+        let success_rate = successes.len() as f64 / group.evaluations_to_target.len() as f64;
+        Self {
+            label: group.label.clone(),
+            parameters: group.parameters.clone(),
+            mean_fitness: mean(&group.fitnesses),
+            mean_time: mean(&group.times),
+            evaluations_to_target: (!successes.is_empty())
+                .then(|| mean(&successes) / success_rate),
+            rank: 0,
+        }
+    }
+    
+    // The objectives, all minimized: fitness is negated, and a target never reached is worse
+    // than any count of evaluations
+    fn objectives(&self) -> Vec<f64> {
+        let evals = self.evaluations_to_target.unwrap_or(f64::INFINITY);
+        vec![-self.mean_fitness, self.mean_time, evals]
+    }
+}
+
+// Every parameter set with its non-dominated sorting rank, front by front and within a front
+// in the groups' order
+fn pareto_ranking(groups: &[ParamGroup]) -> Vec<ParetoEntry> {
+    let entries: Vec<ParetoEntry> = groups.iter().map(ParetoEntry::of).collect();
+    let objectives: Vec<Vec<f64>> = entries.iter().map(ParetoEntry::objectives).collect();
+    let mut entries: Vec<Option<ParetoEntry>> = entries.into_iter().map(Some).collect();
+    let mut ranked = Vec::with_capacity(entries.len());
+    for (rank, front) in non_dominated_sort(&objectives).into_iter().enumerate() {
+        for i in front {
+            let entry = entries[i].take().unwrap();
+            ranked.push(ParetoEntry { rank: rank + 1, ..entry });
+        }
+    }
+    ranked
+}
+
+#[derive(Debug, Serialize)]
 pub struct SGAAnalysis {
     pub total_runs: usize,
     pub best_single_run_score: f64,
+    #[serde(serialize_with = "serialize_ga_params")]
     pub best_single_run_params: Option<GAParameters>,
-    pub best_avg_score: f64,
-    pub best_avg_params: Option<GAParameters>,
+    // Each factorial parameter at its level of the best mean score, see `recommended`
+    #[serde(serialize_with = "serialize_ga_params")]
+    pub recommended_params: Option<GAParameters>,
+    // Index in `groups` of the recommended set's tuning runs, None if the session never ran it
+    pub recommended_group: Option<usize>,
     pub avg_score: f64,
+    // Mean normalized area under the best-so-far curves, None if no run recorded it
+    pub avg_auc: Option<f64>,
+    // Fraction of successful runs among those that recorded it, None if none did
+    pub success_rate: Option<f64>,
+    // Mean clusters covered by the final populations, None if no run counted them
+    pub avg_distinct_optima: Option<f64>,
+    // Throughput, generation times and peak memory, None if no run recorded them
+    pub resources: Option<ResourceUsage>,
     pub convergence_rate: f64,
     pub timeout_rate: f64,
     pub param_groups: usize,
+    // Every parameter set, best average score first
+    pub groups: Vec<ParamGroup>,
+    // Every parameter set by Pareto rank over fitness, time and evaluations to target
+    pub pareto: Vec<ParetoEntry>,
+    // Effects of the factorial parameters on score and on max fitness, empty if the session
+    // varied none factorially
+    pub anova: Vec<Anova>,
+    // Mean score at each level of the same parameters, with confidence intervals
+    pub main_effects: Vec<MainEffect>,
+    // Mean score over each pair of those parameters' levels
+    pub heatmaps: Vec<Heatmap>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct ESAnalysis {
     pub total_runs: usize,
     pub best_single_run_score: f64,
+    #[serde(serialize_with = "serialize_es_params")]
     pub best_single_run_params: Option<ESParameters>,
-    pub best_avg_score: f64,
-    pub best_avg_params: Option<ESParameters>,
+    // Each factorial parameter at its level of the best mean score, see `recommended`
+    #[serde(serialize_with = "serialize_es_params")]
+    pub recommended_params: Option<ESParameters>,
+    // Index in `groups` of the recommended set's tuning runs, None if the session never ran it
+    pub recommended_group: Option<usize>,
     pub avg_score: f64,
+    // Mean normalized area under the best-so-far curves, None if no run recorded it
+    pub avg_auc: Option<f64>,
+    // Fraction of successful runs among those that recorded it, None if none did
+    pub success_rate: Option<f64>,
+    // Mean clusters covered by the final populations, None if no run counted them
+    pub avg_distinct_optima: Option<f64>,
+    // Throughput, generation times and peak memory, None if no run recorded them
+    pub resources: Option<ResourceUsage>,
     pub convergence_rate: f64,
     pub timeout_rate: f64,
     pub param_groups: usize,
+    // Every parameter set, best average score first
+    pub groups: Vec<ParamGroup>,
+    // Every parameter set by Pareto rank over fitness, time and evaluations to target
+    pub pareto: Vec<ParetoEntry>,
+    // Effects of the factorial parameters on score and on max fitness, empty if the session
+    // varied none factorially
+    pub anova: Vec<Anova>,
+    // Mean score at each level of the same parameters, with confidence intervals
+    pub main_effects: Vec<MainEffect>,
+    // Mean score over each pair of those parameters' levels
+    pub heatmaps: Vec<Heatmap>,
 }
\ No newline at end of file
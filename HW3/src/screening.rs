@@ -0,0 +1,142 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+// Two-level screening designs: each factor is tried only at a low and a high value, and
+// far fewer combinations than the full factorial still estimate every main effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Screening {
+    // A 2^(k-p) fraction of resolution IV at least, so main effects are clear of two-factor
+    // interactions
+    Fractional,
+    // Plackett-Burman: the smallest run count (a multiple of 4) that fits the factors, main
+    // effects only, partly aliased with interactions
+    PlackettBurman,
+}
+
+impl fmt::Display for Screening {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            Screening::Fractional => "fractional",
+            Screening::PlackettBurman => "plackett-burman",
+        };
+        write!(f, "{text}")
+    }
+}
+
+// A parameter a screening design varies, named as in the results CSV
+#[derive(Debug, Clone, PartialEq)]
+pub struct Factor {
+    pub name: String,
+    pub low: f64,
+    pub high: f64,
+}
+
+// Coded levels of a design, -1 for a factor's low value and +1 for its high one, with one
+// row per combination
+#[derive(Debug, Clone)]
+pub struct DesignMatrix {
+    pub factors: Vec<Factor>,
+    pub rows: Vec<Vec<f64>>,
+}
+
+impl DesignMatrix {
+    // The factors' values in each row
+    pub fn combinations(&self) -> Vec<HashMap<String, f64>> {
+        self.rows
+            .iter()
+            .map(|row| {
+                self.factors
+                    .iter()
+                    .zip(row)
+                    .map(|(factor, &level)| {
+                        let value = if level < 0.0 { factor.low } else { factor.high };
+                        (factor.name.clone(), value)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+// Cyclic generators of the Plackett-Burman designs in 4, 8, 12, 16, 20 and 24 runs: each
+// row is the previous one shifted right, and a row of minuses ends the design
+const PLACKETT_BURMAN: [&str; 6] = [
+    "++-",
+    "+++-+--",
+    "++-+++---+-",
+    "++++-+-++--+---",
+    "++--++++-+-+----++-",
+    "+++++-+-++--++--+-+----",
+];
+
+impl Screening {
+    pub fn design(self, factors: Vec<Factor>) -> Result<DesignMatrix, String> {
+        let rows = match self {
+            Screening::Fractional => fractional(factors.len()),
+            Screening::PlackettBurman => plackett_burman(factors.len())?,
+        };
+        Ok(DesignMatrix { factors, rows })
+    }
+}
+
+// A 2^(k-p) design: a full factorial in the fewest base factors b with 2^b >= 2k, the other
+// factors set to products of odd sets of three or more base columns. Every defining word then
+// has four letters or more, which is resolution IV.
+fn fractional(k: usize) -> Vec<Vec<f64>> {
+    let base = (0..=k).find(|&b| 1usize << b >= 2 * k).unwrap_or(k).min(k);
+    let mut generators: Vec<usize> = (0..1usize << base)
+        .filter(|subset| subset.count_ones() >= 3 && subset.count_ones() % 2 == 1)
+        .collect();
+    // Highest-order interactions first, whose aliasing matters least
+    generators.sort_by_key(|subset| std::cmp::Reverse(subset.count_ones()));
+    (0..1usize << base)
+        .map(|run| {
+            let level = |factor: usize| if (run >> factor) & 1 == 1 { 1.0 } else { -1.0 };
+            let added = generators.iter().take(k - base).map(|&subset| {
+                (0..base).filter(|&factor| (subset >> factor) & 1 == 1).map(level).product()
+            });
+            (0..base).map(level).chain(added).collect()
+        })
+        .collect()
+}
+
+fn plackett_burman(k: usize) -> Result<Vec<Vec<f64>>, String> {
+    let generator = PLACKETT_BURMAN
+        .iter()
+        .find(|generator| generator.len() >= k)
+        .ok_or_else(|| format!("Plackett-Burman designs here take up to 23 factors, not {k}"))?;
+    let signs: Vec<f64> =
+        generator.chars().map(|sign| if sign == '+' { 1.0 } else { -1.0 }).collect();
+    let n = signs.len();
+    let mut rows: Vec<Vec<f64>> =
+        (0..n).map(|shift| (0..k).map(|j| signs[(j + n - shift) % n]).collect()).collect();
+    rows.push(vec![-1.0; k]);
+    Ok(rows)
+}
+
+// Writes the coded levels of each algorithm's design as CSV, one row per combination with
+// its index in the algorithm's grid, and a column per factor of either design
+pub fn save_design_matrices(matrices: &[(String, DesignMatrix)], path: &str) -> io::Result<()> {
+    let names: BTreeSet<&str> = matrices
+        .iter()
+        .flat_map(|(_, matrix)| matrix.factors.iter().map(|factor| factor.name.as_str()))
+        .collect();
+    let mut file = BufWriter::new(File::create(path)?);
+    let header: Vec<&str> = ["algorithm", "combination"].into_iter().chain(names.clone()).collect();
+    writeln!(file, "{}", header.join(","))?;
+    for (algorithm, matrix) in matrices {
+        for (combination, row) in matrix.rows.iter().enumerate() {
+            let cells: Vec<String> = names
+                .iter()
+                .map(|name| {
+                    let column = matrix.factors.iter().position(|factor| factor.name == *name);
+                    column.map_or(String::new(), |column| row[column].to_string())
+                })
+                .collect();
+            writeln!(file, "{algorithm},{combination},{}", cells.join(","))?;
+        }
+    }
+    file.flush()
+}
@@ -0,0 +1,84 @@
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+// Derives every seed of an experiment from one master seed: run n of a session, island i of a
+// run, each operator's own stream and the stream of member i of generation g get seeds that
+// are unrelated to each other but always the same for the same master seed and path, so any
+// of them can be re-derived to replay it, e.g. SeedSequence::new(5000).run(3).island(1) for
+// island 1 of run 3.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeedSequence {
+    seed: u64,
+}
+
+// Kinds of child, mixed into the derivation so run 1 and island 1 get different seeds
+const RUN: u64 = 1;
+const ISLAND: u64 = 2;
+const OPERATOR: u64 = 3;
+const GENERATION: u64 = 4;
+const INDIVIDUAL: u64 = 5;
+const SAMPLE: u64 = 6;
+
+impl SeedSequence {
+    pub fn new(master: u64) -> Self {
+        Self { seed: master }
+    }
+
+    // The seed this sequence seeds an RNG with, as recorded in results and run logs
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn rng(&self) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(self.seed)
+    }
+
+    pub fn run(&self, run: usize) -> Self {
+        self.child(RUN, run as u64)
+    }
+
+    pub fn island(&self, island: usize) -> Self {
+        self.child(ISLAND, island as u64)
+    }
+
+    // Operators are told apart by name, so adding one doesn't shift the others' seeds
+    pub fn operator(&self, name: &str) -> Self {
+        // FNV-1a, which unlike the std hasher is the same on every platform and release
+        let hash = name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100_0000_01b3)
+        });
+        self.child(OPERATOR, hash)
+    }
+
+    pub fn generation(&self, generation: usize) -> Self {
+        self.child(GENERATION, generation as u64)
+    }
+
+    // One stream per member of a batch, so whatever draws a member's random numbers draws the
+    // same ones whichever thread it runs on and in whatever order, in place of one shared RNG
+    pub fn individuals(&self, count: usize) -> Vec<Self> {
+        (0..count).map(|index| self.individual(index)).collect()
+    }
+
+    pub fn individual(&self, index: usize) -> Self {
+        self.child(INDIVIDUAL, index as u64)
+    }
+
+    // Stream of the nth of several evaluations of the same member, when fitness is resampled
+    pub fn sample(&self, sample: usize) -> Self {
+        self.child(SAMPLE, sample as u64)
+    }
+
+    fn child(&self, kind: u64, index: u64) -> Self {
+        let key = splitmix64(kind.wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ index);
+        Self { seed: splitmix64(self.seed ^ key) }
+    }
+}
+
+// The SplitMix64 finalizer: consecutive inputs give well-spread, uncorrelated outputs
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
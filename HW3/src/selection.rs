@@ -0,0 +1,257 @@
+use crate::bitstring::GAParameters;
+use crate::constraint::feasibility_order;
+use rand::prelude::*;
+
+// Parent selection operators for the GA
+#[derive(Debug, Clone, Copy)]
+pub enum SelectionMethod {
+    // Best of size uniformly drawn individuals
+    Tournament { size: usize },
+    // Fitness proportional (roulette wheel)
+    Roulette,
+    // Fitness proportional, with every parent drawn by one spin of evenly spaced pointers
+    StochasticUniversal,
+    // Linear ranking with selection pressure between 1.0 (uniform) and 2.0 (best gets twice
+    // the average share)
+    LinearRank { pressure: f64 },
+    // Truncation: the best fraction of the population (GAParameters::truncation_fraction)
+    // become parents with equal probability
+    Truncation,
+    // Exponential ranking: the individual at rank r from the top gets weight base^r, with
+    // 0 < base < 1
+    ExponentialRank { base: f64 },
+}
+
+// Transformation of raw fitness before proportional selection. With 1/(1+f) most of the
+// population sits just below 1.0 late in a run, and scaling restores the differences.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FitnessScaling {
+    None,
+    // Goldberg's linear scaling: the average is kept and the best gets multiple times the
+    // average, clamped so no scaled fitness goes negative
+    Linear { multiple: f64 },
+    // f - (avg - c * std_dev), truncated at 0
+    SigmaTruncation { c: f64 },
+}
+
+impl FitnessScaling {
+    pub fn apply(&self, fitnesses: &[f64]) -> Vec<f64> {
+        let n = fitnesses.len() as f64;
+        let avg = fitnesses.iter().sum::<f64>() / n;
+        match *self {
+            FitnessScaling::None => fitnesses.to_vec(),
+            FitnessScaling::Linear { multiple } => {
+                let max = fitnesses.iter().cloned().fold(f64::MIN, f64::max);
+                let min = fitnesses.iter().cloned().fold(f64::MAX, f64::min);
+                if max - avg <= f64::EPSILON {
+                    return vec![1.0; fitnesses.len()];
+                }
+                // Scale so the best gets multiple * avg, unless that pushes the worst below 0
+                let (a, b) = if min > (multiple * avg - max) / (multiple - 1.0) {
+                    let a = (multiple - 1.0) * avg / (max - avg);
+                    (a, avg * (1.0 - a))
+                } else {
+                    let a = avg / (avg - min);
+                    (a, -min * a)
+                };
+                fitnesses.iter().map(|f| (a * f + b).max(0.0)).collect()
+            }
+            FitnessScaling::SigmaTruncation { c } => {
+                let variance = fitnesses.iter().map(|f| (f - avg).powi(2)).sum::<f64>() / n;
+                let floor = avg - c * variance.sqrt();
+                fitnesses.iter().map(|f| (f - floor).max(0.0)).collect()
+            }
+        }
+    }
+}
+
+// Selection probability of each individual for the wheel-based methods
+pub fn selection_probabilities(params: &GAParameters, fitnesses: &[f64]) -> Vec<f64> {
+    let n = fitnesses.len();
+    let weights: Vec<f64> = match params.selection {
+        SelectionMethod::Tournament { .. }
+        | SelectionMethod::Roulette
+        | SelectionMethod::StochasticUniversal => params
+            .scaling
+            .apply(fitnesses)
+            .iter()
+            .map(|&f| f.max(0.0))
+            .collect(),
+        SelectionMethod::LinearRank { pressure } => {
+            let ranks = ascending_ranks(fitnesses);
+            ranks
+                .iter()
+                .map(|&rank| {
+                    if n < 2 {
+                        1.0
+                    } else {
+                        (2.0 - pressure) / n as f64
+                            + 2.0 * rank as f64 * (pressure - 1.0) / (n * (n - 1)) as f64
+                    }
+                })
+                .collect()
+        }
+        SelectionMethod::Truncation => {
+            let ranks = ascending_ranks(fitnesses);
+            let parents = ((params.truncation_fraction * n as f64).ceil() as usize).clamp(1, n);
+            ranks
+                .iter()
+                .map(|&rank| if rank >= n - parents { 1.0 } else { 0.0 })
+                .collect()
+        }
+        SelectionMethod::ExponentialRank { base } => {
+            let ranks = ascending_ranks(fitnesses);
+            ranks
+                .iter()
+                .map(|&rank| base.powi((n - 1 - rank) as i32))
+                .collect()
+        }
+    };
+
+    let total: f64 = weights.iter().sum();
+    if total <= 0.0 {
+        return vec![1.0 / n as f64; n];
+    }
+    weights.iter().map(|w| w / total).collect()
+}
+
+// Rank of each individual, 0 for the worst and n - 1 for the best
+pub fn ascending_ranks(fitnesses: &[f64]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..fitnesses.len()).collect();
+    order.sort_by(|&i, &j| fitnesses[i].partial_cmp(&fitnesses[j]).unwrap());
+    let mut ranks = vec![0; fitnesses.len()];
+    for (rank, &i) in order.iter().enumerate() {
+        ranks[i] = rank;
+    }
+    ranks
+}
+
+// Cumulative form of the probabilities, ready for spinning
+pub fn cumulative(probabilities: &[f64]) -> Vec<f64> {
+    let mut total = 0.0;
+    probabilities
+        .iter()
+        .map(|p| {
+            total += p;
+            total
+        })
+        .collect()
+}
+
+// One spin of the roulette wheel
+pub fn spin<R: Rng + ?Sized>(wheel: &[f64], rng: &mut R) -> usize {
+    let pick: f64 = rng.random_range(0.0..*wheel.last().unwrap());
+    wheel
+        .partition_point(|&edge| edge <= pick)
+        .min(wheel.len() - 1)
+}
+
+// Stochastic universal sampling: count evenly spaced pointers from a single random offset,
+// so each individual is picked within one of its expected number of copies
+pub fn stochastic_universal<R: Rng + ?Sized>(
+    wheel: &[f64],
+    count: usize,
+    rng: &mut R,
+) -> Vec<usize> {
+    let spacing = wheel.last().unwrap() / count as f64;
+    let start: f64 = rng.random_range(0.0..spacing);
+    let mut picks = Vec::with_capacity(count);
+    let mut index = 0;
+    for i in 0..count {
+        let pointer = start + i as f64 * spacing;
+        while index < wheel.len() - 1 && wheel[index] <= pointer {
+            index += 1;
+        }
+        picks.push(index);
+    }
+    picks
+}
+
+// Tournament over precomputed fitnesses, returning the winner's index
+pub fn tournament_index<R: Rng + ?Sized>(fitnesses: &[f64], size: usize, rng: &mut R) -> usize {
+    let mut best_index = 0;
+    let mut best_fitness = f64::MIN;
+    for _ in 0..size {
+        let random_index = rng.random_range(0..fitnesses.len());
+        if fitnesses[random_index] > best_fitness {
+            best_fitness = fitnesses[random_index];
+            best_index = random_index;
+        }
+    }
+    best_index
+}
+
+// Tournament under Deb's feasibility rules, for constrained problems: the winner is the
+// feasible member with the best fitness, or the least violating one if none is feasible
+pub fn feasibility_tournament_index<R: Rng + ?Sized>(
+    fitnesses: &[f64],
+    violations: &[f64],
+    size: usize,
+    rng: &mut R,
+) -> usize {
+    let mut best_index = rng.random_range(0..fitnesses.len());
+    for _ in 1..size {
+        let i = rng.random_range(0..fitnesses.len());
+        let challenger = (fitnesses[i], violations[i]);
+        if feasibility_order(challenger, (fitnesses[best_index], violations[best_index])).is_gt() {
+            best_index = i;
+        }
+    }
+    best_index
+}
+
+// Parent source for one generation built from the population's fitness: repeated spins for
+// roulette and ranking, a shuffled mating pool drawn up front for SUS, or tournaments
+pub enum ParentSelector {
+    Spin(Vec<f64>),
+    Pool(Vec<usize>),
+    // Tournaments compare by feasibility first when the members' violations are known
+    Tournament { size: usize, fitnesses: Vec<f64>, violations: Option<Vec<f64>> },
+}
+
+impl ParentSelector {
+    pub fn new<R: Rng + ?Sized>(params: &GAParameters, fitnesses: &[f64], rng: &mut R) -> Self {
+        if let SelectionMethod::Tournament { size } = params.selection {
+            return ParentSelector::Tournament {
+                size,
+                fitnesses: fitnesses.to_vec(),
+                violations: None,
+            };
+        }
+        let wheel = cumulative(&selection_probabilities(params, fitnesses));
+        match params.selection {
+            SelectionMethod::StochasticUniversal => {
+                // Parents come in pairs, so an odd population still needs an even number
+                let parents_needed = params.pop_size + params.pop_size % 2;
+                let mut pool = stochastic_universal(&wheel, parents_needed, rng);
+                // Shuffle so the pairing of parents is random rather than by position
+                pool.shuffle(rng);
+                ParentSelector::Pool(pool)
+            }
+            _ => ParentSelector::Spin(wheel),
+        }
+    }
+
+    // Decides tournaments by the members' total constraint violations as well as fitness. The
+    // wheel-based methods need a single value per member and keep selecting on fitness.
+    pub fn with_violations(mut self, member_violations: Vec<f64>) -> Self {
+        if let ParentSelector::Tournament { violations, .. } = &mut self {
+            *violations = Some(member_violations);
+        }
+        self
+    }
+
+    // Index of the next parent
+    pub fn next<R: Rng + ?Sized>(&mut self, rng: &mut R) -> usize {
+        match self {
+            ParentSelector::Spin(wheel) => spin(wheel, rng),
+            ParentSelector::Pool(pool) => pool.pop().expect("mating pool sized for the generation"),
+            ParentSelector::Tournament { size, fitnesses, violations: None } => {
+                tournament_index(fitnesses, *size, rng)
+            }
+            ParentSelector::Tournament { size, fitnesses, violations: Some(violations) } => {
+                feasibility_tournament_index(fitnesses, violations, *size, rng)
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use crate::bitstring::GAParameters;
+use crate::decoder::Decoder;
+use crate::genome::{Genome, hash_of, hash_reals};
+use crate::observer::{GenerationStats, Observer};
+use crate::repair::Repair;
+use rand::prelude::*;
+use rand_distr::StandardNormal;
+
+// Smallest rate self-adaptation can reach, so a rate that collapsed can still grow back
+const MIN_RATE: f64 = 1e-4;
+// Largest mutation rate; past a half, flipping more bits only makes offspring more alike again
+const MAX_MUTATION_RATE: f64 = 0.5;
+
+// A genome carrying its own mutation and crossover rates, the way ES members carry sigma.
+// Mutation first perturbs the rates log-normally with learning rate params.rate_tau and then
+// mutates the genes with the new mutation rate, so rates that produce fitter offspring spread
+// through the population with them. The rates of params are ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfAdaptive<G> {
+    pub genome: G,
+    pub mutation_rate: f64,
+    pub crossover_rate: f64,
+}
+
+impl<G: Genome> Genome for SelfAdaptive<G> {
+    const VARIABLE_LENGTH: bool = G::VARIABLE_LENGTH;
+
+    // Mutation rates start log-uniform between one and ten changes per genome, crossover rates
+    // uniform between 0.5 and 1
+    fn random<R: Rng + ?Sized>(len: usize, bounds: (f64, f64), rng: &mut R) -> Self {
+        let low = (1.0 / len.max(1) as f64).min(MAX_MUTATION_RATE);
+        let high = (10.0 * low).min(MAX_MUTATION_RATE);
+        Self {
+            genome: G::random(len, bounds, rng),
+            mutation_rate: low * (high / low).powf(rng.random()),
+            crossover_rate: rng.random_range(0.5..1.0),
+        }
+    }
+
+    fn mutate<R: Rng + ?Sized>(
+        &self,
+        params: &GAParameters,
+        bounds: (f64, f64),
+        rng: &mut R,
+    ) -> Self {
+        self.mutate_with_loci(params, bounds, rng).0
+    }
+
+    fn mutate_with_loci<R: Rng + ?Sized>(
+        &self,
+        params: &GAParameters,
+        bounds: (f64, f64),
+        rng: &mut R,
+    ) -> (Self, Option<Vec<usize>>) {
+        let mut perturb = |rate: f64| {
+            let step: f64 = rng.sample(StandardNormal);
+            rate * (params.rate_tau * step).exp()
+        };
+        let mutation_rate = perturb(self.mutation_rate).clamp(MIN_RATE, MAX_MUTATION_RATE);
+        let crossover_rate = perturb(self.crossover_rate).clamp(MIN_RATE, 1.0);
+        let gene_params = GAParameters { mutation_rate, ..params.clone() };
+        let (genome, loci) = self.genome.mutate_with_loci(&gene_params, bounds, rng);
+        (Self { genome, mutation_rate, crossover_rate }, loci)
+    }
+
+    // Parents recombine with the mean of their crossover rates. The rates sit at the end of
+    // the genome, so when the parents do recombine each child takes the rates of the parent
+    // its tail came from.
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        other: &Self,
+        _crossover_rate: f64,
+        rng: &mut R,
+    ) -> (Self, Self) {
+        self.recombine(other, rng, |a, b, rng| a.crossover(b, 1.0, rng))
+    }
+
+    // As crossover, with the inner genome recombined by the operator in the parameters
+    fn crossover_with_params<R: Rng + ?Sized>(
+        &self,
+        other: &Self,
+        params: &GAParameters,
+        rng: &mut R,
+    ) -> (Self, Self) {
+        let inner = GAParameters { crossover_rate: 1.0, ..params.clone() };
+        self.recombine(other, rng, |a, b, rng| a.crossover_with_params(b, &inner, rng))
+    }
+
+    fn length(&self) -> usize {
+        self.genome.length()
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        self.genome.distance(&other.genome)
+    }
+
+    // The rates are part of the genome, so copies must carry the same rates
+    fn fingerprint(&self) -> u64 {
+        let rates = hash_reals(&[self.mutation_rate, self.crossover_rate]);
+        hash_of(&[self.genome.fingerprint(), rates])
+    }
+
+    fn phenotype(&self, decoder: &impl Decoder, num_dims: usize) -> Vec<f64> {
+        self.genome.phenotype(decoder, num_dims)
+    }
+
+    fn repair(&mut self, repair: &impl Repair) -> bool {
+        self.genome.repair(repair)
+    }
+}
+
+impl<G: Genome> SelfAdaptive<G> {
+    // Parents recombine with the mean of their crossover rates; `genes` recombines the inner
+    // genomes whenever they do
+    fn recombine<R: Rng + ?Sized>(
+        &self,
+        other: &Self,
+        rng: &mut R,
+        genes: impl FnOnce(&G, &G, &mut R) -> (G, G),
+    ) -> (Self, Self) {
+        let crossover_rate = 0.5 * (self.crossover_rate + other.crossover_rate);
+        if rng.random::<f64>() >= crossover_rate {
+            return (self.clone(), other.clone());
+        }
+        let (genome1, genome2) = genes(&self.genome, &other.genome, rng);
+        let child = |genome, rates: &Self| Self {
+            genome,
+            mutation_rate: rates.mutation_rate,
+            crossover_rate: rates.crossover_rate,
+        };
+        (child(genome1, other), child(genome2, self))
+    }
+}
+
+// Records the rates of the generation's best individual every `every` generations, to show
+// how the rates evolve over a run
+#[derive(Debug)]
+pub struct RateLog {
+    pub every: usize,
+    // (generation, mutation rate, crossover rate)
+    pub samples: Vec<(usize, f64, f64)>,
+}
+
+impl RateLog {
+    pub fn new(every: usize) -> Self {
+        Self { every: every.max(1), samples: Vec::new() }
+    }
+}
+
+impl<G> Observer<SelfAdaptive<G>> for RateLog {
+    fn on_generation(&mut self, stats: &GenerationStats, best: &SelfAdaptive<G>) {
+        if stats.generation.is_multiple_of(self.every) {
+            self.samples.push((stats.generation, best.mutation_rate, best.crossover_rate));
+        }
+    }
+}
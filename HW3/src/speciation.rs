@@ -0,0 +1,129 @@
+// Species formation: members are grouped by genotypic (compatibility) distance to each
+// species' representative, share fitness within their species, and species that stop
+// improving are culled, as in NEAT. The GA measures Genome::distance; NEAT supplies its
+// compatibility distance between networks.
+#[derive(Debug, Clone, Copy)]
+pub struct Speciation {
+    // Members closer than this to a species' representative belong to it
+    pub threshold: f64,
+    // Generations a species may go without improving its best fitness before it is culled
+    pub stagnation_limit: usize,
+}
+
+// One species as of the last generation speciated
+#[derive(Debug, Clone)]
+struct Species<G> {
+    // Best member of the previous generation, which new members are compared against
+    representative: G,
+    // Indices of the current generation's members
+    members: Vec<usize>,
+    best_fitness: f64,
+    stagnant_generations: usize,
+}
+
+// Species carried between generations, with what changed in the last speciation
+pub struct SpeciesSet<G> {
+    config: Speciation,
+    species: Vec<Species<G>>,
+    founded: usize,
+    extinct: usize,
+    culled: usize,
+}
+
+impl<G: Clone> SpeciesSet<G> {
+    pub fn new(config: Speciation) -> Self {
+        Self { config, species: Vec::new(), founded: 0, extinct: 0, culled: 0 }
+    }
+
+    // Places each member in the first species whose representative is within the threshold,
+    // founding a new species when none is. Species left without members go extinct, and ones
+    // stagnant beyond the limit are culled, except the one with the best fitness so far.
+    pub fn speciate(
+        &mut self,
+        population: &[G],
+        fitnesses: &[f64],
+        distance: impl Fn(&G, &G) -> f64,
+    ) {
+        let previous = self.species.len();
+        for species in &mut self.species {
+            species.members.clear();
+        }
+        self.founded = 0;
+        for (i, member) in population.iter().enumerate() {
+            let threshold = self.config.threshold;
+            let home = self
+                .species
+                .iter_mut()
+                .find(|species| distance(member, &species.representative) < threshold);
+            match home {
+                Some(species) => species.members.push(i),
+                None => {
+                    self.species.push(Species {
+                        representative: member.clone(),
+                        members: vec![i],
+                        best_fitness: f64::NEG_INFINITY,
+                        stagnant_generations: 0,
+                    });
+                    self.founded += 1;
+                }
+            }
+        }
+        self.species.retain(|species| !species.members.is_empty());
+        self.extinct = previous + self.founded - self.species.len();
+
+        // Track each species' best, and make its best member the next representative
+        let by_fitness = |a: &&usize, b: &&usize| fitnesses[**a].total_cmp(&fitnesses[**b]);
+        for species in &mut self.species {
+            let Some(&best) = species.members.iter().max_by(by_fitness) else { continue };
+            if fitnesses[best] > species.best_fitness {
+                species.best_fitness = fitnesses[best];
+                species.stagnant_generations = 0;
+            } else {
+                species.stagnant_generations += 1;
+            }
+            species.representative = population[best].clone();
+        }
+
+        let leader = self
+            .species
+            .iter()
+            .map(|species| species.best_fitness)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let before = self.species.len();
+        let limit = self.config.stagnation_limit;
+        self.species.retain(|species| {
+            species.stagnant_generations <= limit || species.best_fitness >= leader
+        });
+        self.culled = before - self.species.len();
+    }
+
+    // Members of each species, by index into the population last speciated
+    pub fn members(&self) -> Vec<&[usize]> {
+        self.species.iter().map(|species| species.members.as_slice()).collect()
+    }
+
+    // Explicit fitness sharing: each member's fitness divided by the size of its species.
+    // Members of culled species get 0, so they leave no offspring.
+    pub fn shared_fitnesses(&self, fitnesses: &[f64]) -> Vec<f64> {
+        let mut shared = vec![0.0; fitnesses.len()];
+        for species in &self.species {
+            let size = species.members.len() as f64;
+            for &i in &species.members {
+                shared[i] = fitnesses[i] / size;
+            }
+        }
+        shared
+    }
+
+    pub fn describe(&self) -> String {
+        let largest = self.species.iter().map(|species| species.members.len()).max();
+        format!(
+            "{} species (largest {}), {} founded, {} extinct, {} culled",
+            self.species.len(),
+            largest.unwrap_or(0),
+            self.founded,
+            self.extinct,
+            self.culled
+        )
+    }
+}
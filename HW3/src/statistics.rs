@@ -0,0 +1,214 @@
+use crate::observer::mean_and_std;
+
+// Vargha-Delaney A12: the probability that a run from `a` scores higher than a run from `b`,
+// counting ties as half. 0.5 means no difference.
+pub fn vargha_delaney_a12(a: &[f64], b: &[f64]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.5;
+    }
+    let wins: f64 = a
+        .iter()
+        .flat_map(|x| b.iter().map(move |y| (x, y)))
+        .map(|(x, y)| if x > y { 1.0 } else if x == y { 0.5 } else { 0.0 })
+        .sum();
+    wins / (a.len() * b.len()) as f64
+}
+
+// Cliff's delta: P(a > b) - P(a < b), in [-1, 1]
+pub fn cliffs_delta(a: &[f64], b: &[f64]) -> f64 {
+    2.0 * vargha_delaney_a12(a, b) - 1.0
+}
+
+// Vargha and Delaney's thresholds on |A12 - 0.5|
+pub fn a12_magnitude(a12: f64) -> &'static str {
+    match (a12 - 0.5).abs() {
+        d if d < 0.06 => "negligible",
+        d if d < 0.14 => "small",
+        d if d < 0.21 => "medium",
+        _ => "large",
+    }
+}
+
+// Romano et al.'s thresholds on |delta|
+pub fn cliffs_delta_magnitude(delta: f64) -> &'static str {
+    match delta.abs() {
+        d if d < 0.147 => "negligible",
+        d if d < 0.33 => "small",
+        d if d < 0.474 => "medium",
+        _ => "large",
+    }
+}
+
+// "A12 0.72 (large), Cliff's delta 0.44 (medium)" for a over b
+pub fn describe_effect(a: &[f64], b: &[f64]) -> String {
+    let a12 = vargha_delaney_a12(a, b);
+    let delta = cliffs_delta(a, b);
+    format!(
+        "A12 {a12:.2} ({}), Cliff's delta {delta:.2} ({})",
+        a12_magnitude(a12),
+        cliffs_delta_magnitude(delta)
+    )
+}
+
+// Mean, median, (population) std, min and max of a sample
+#[derive(Debug, Clone, Copy)]
+pub struct Summary {
+    pub mean: f64,
+    pub median: f64,
+    pub std: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Summary {
+    pub fn of(values: &[f64]) -> Self {
+        let (mean, std) = mean_and_std(values);
+        Self {
+            mean,
+            median: median(values),
+            std,
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+pub fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let n = sorted.len();
+    match n {
+        0 => f64::NAN,
+        _ if n % 2 == 1 => sorted[n / 2],
+        _ => (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0,
+    }
+}
+
+// The q-quantile (0 <= q <= 1), interpolating linearly between order statistics
+pub fn quantile(values: &[f64], q: f64) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+    let position = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+// Normalized area under a best-so-far curve of (evaluations, best fitness) points: the mean
+// over the evaluations after the first point of how far the best had come from its first value
+// towards its last, holding each value until the next point. 1 means the run had its final
+// fitness straight away, about 0.5 steady improvement and near 0 a late jump, so runs that
+// converge quickly and plateau can be told apart from slow steady improvers with the same
+// final fitness. A run without improvement counts as 1 and an empty curve as 0.
+pub fn normalized_auc(curve: &[(usize, f64)]) -> f64 {
+    let (Some(&(first_evals, first)), Some(&(last_evals, last))) = (curve.first(), curve.last())
+    else {
+        return 0.0;
+    };
+    let (range, span) = (last - first, last_evals.saturating_sub(first_evals));
+    if range <= 0.0 || span == 0 {
+        return 1.0;
+    }
+    let area: f64 = curve
+        .windows(2)
+        .map(|pair| (pair[0].1 - first) * (pair[1].0 - pair[0].0) as f64)
+        .sum();
+    area / (range * span as f64)
+}
+
+// Upper tail probability P(F > f) of the F distribution with (d1, d2) degrees of freedom
+pub fn f_test_p_value(f: f64, d1: f64, d2: f64) -> f64 {
+    if f <= 0.0 || !f.is_finite() {
+        return if f.is_infinite() { 0.0 } else { 1.0 };
+    }
+    regularized_beta(d2 / (d2 + d1 * f), d2 / 2.0, d1 / 2.0)
+}
+
+// Two-sided critical value of Student's t with `df` degrees of freedom at `confidence`
+// (0.95 for a 95% interval), by bisection on P(|T| > t) = P(F(1, df) > t^2)
+pub fn t_critical(confidence: f64, df: f64) -> f64 {
+    let alpha = 1.0 - confidence;
+    let (mut low, mut high) = (0.0, 1.0);
+    while f_test_p_value(high * high, 1.0, df) > alpha && high < 1e6 {
+        high *= 2.0;
+    }
+    for _ in 0..100 {
+        let mid = (low + high) / 2.0;
+        if f_test_p_value(mid * mid, 1.0, df) > alpha {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+// Natural log of the gamma function, by the Lanczos approximation (g = 7, 9 terms)
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // Reflection, for the small shape parameters of one degree of freedom
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (x + i as f64 + 1.0));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+// The regularized incomplete beta function I_x(a, b), by its continued fraction (Lentz's
+// method), on the side of x where that converges quickly
+fn regularized_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    if x > (a + 1.0) / (a + b + 2.0) {
+        return 1.0 - regularized_beta(1.0 - x, b, a);
+    }
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln())
+        .exp()
+        / a;
+    let tiny = 1e-300;
+    let (mut c, mut d) = (1.0, 1.0 - (a + b) * x / (a + 1.0));
+    d = 1.0 / if d.abs() < tiny { tiny } else { d };
+    let mut fraction = d;
+    for m in 1..300 {
+        let m = m as f64;
+        // The even and odd terms of the fraction
+        for numerator in [
+            m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m)),
+            -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0)),
+        ] {
+            d = 1.0 + numerator * d;
+            d = 1.0 / if d.abs() < tiny { tiny } else { d };
+            c = 1.0 + numerator / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            fraction *= c * d;
+        }
+        if (c * d - 1.0).abs() < 1e-12 {
+            break;
+        }
+    }
+    front * fraction
+}
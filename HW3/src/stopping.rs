@@ -0,0 +1,86 @@
+use std::fmt;
+
+// Optional stopping rules shared by the GA and the ES, on top of their generation limits
+#[derive(Debug, Clone, Default)]
+pub struct StoppingCriteria {
+    // Stop once the best-so-far fitness has not improved for this many generations
+    pub stagnation_generations: Option<usize>,
+    // Stop once the best fitness is within this distance of the problem's known optimum
+    pub target_epsilon: Option<f64>,
+}
+
+// Restarts from a fresh random population when a run stagnates or converges, instead of
+// stopping, as in IPOP-CMA-ES. The run keeps its generation and evaluation counts and its
+// best-ever member across restarts, so it is still reported as one run. Stagnation is only
+// detected with stagnation_generations set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RestartPolicy {
+    pub max_restarts: usize,
+    // Factor the population (mu and lambda) grows by at each restart: 2 for IPOP, 1 to
+    // restart at the same size
+    pub population_factor: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopReason {
+    MaxGenerations,
+    Converged,
+    Stagnation,
+    TargetReached,
+    // Stopped from outside through a cancellation token, e.g. on timeout
+    Cancelled,
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            StopReason::MaxGenerations => "max generations reached",
+            StopReason::Converged => "converged",
+            StopReason::Stagnation => "stagnated",
+            StopReason::TargetReached => "target fitness reached",
+            StopReason::Cancelled => "cancelled",
+        };
+        write!(f, "{text}")
+    }
+}
+
+// Tracks the best-so-far fitness across generations and applies the stopping criteria
+pub struct StopMonitor {
+    criteria: StoppingCriteria,
+    optimum: Option<f64>,
+    pub best_fitness: f64,
+    pub stagnant_generations: usize,
+}
+
+impl StopMonitor {
+    pub fn new(criteria: &StoppingCriteria, optimum: Option<f64>) -> Self {
+        Self {
+            criteria: criteria.clone(),
+            optimum,
+            best_fitness: f64::NEG_INFINITY,
+            stagnant_generations: 0,
+        }
+    }
+
+    // Records the best fitness of the current generation and returns why to stop, if at all
+    pub fn update(&mut self, generation_best: f64) -> Option<StopReason> {
+        if generation_best > self.best_fitness {
+            self.best_fitness = generation_best;
+            self.stagnant_generations = 0;
+        } else {
+            self.stagnant_generations += 1;
+        }
+
+        if let (Some(epsilon), Some(optimum)) = (self.criteria.target_epsilon, self.optimum)
+            && (optimum - self.best_fitness).abs() <= epsilon
+        {
+            return Some(StopReason::TargetReached);
+        }
+        if let Some(limit) = self.criteria.stagnation_generations
+            && self.stagnant_generations >= limit
+        {
+            return Some(StopReason::Stagnation);
+        }
+        None
+    }
+}
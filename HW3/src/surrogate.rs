@@ -0,0 +1,229 @@
+use crate::bayesian_tuning::GaussianProcess;
+use std::fmt;
+
+// Model of the fitness landscape fitted to the true evaluations so far. Both are fitted on
+// the search box scaled to the unit cube.
+#[derive(Debug, Clone, Copy)]
+pub enum SurrogateModel {
+    // Gaussian radial basis interpolation of the evaluated points, ranking offspring by
+    // predicted fitness alone
+    Rbf { width: f64 },
+    // Kriging (Gaussian process regression), ranking offspring by the upper confidence bound
+    // mean + exploration * std so unexplored regions get a chance too
+    Kriging { length_scale: f64, noise: f64, exploration: f64 },
+}
+
+// Model management for surrogate-assisted evolution
+#[derive(Debug, Clone, Copy)]
+pub struct SurrogateParameters {
+    pub model: SurrogateModel,
+    // Fraction of each generation's offspring sent to the true fitness function, the ones the
+    // model rates highest; the rest go into selection with the model's prediction
+    pub evaluated_fraction: f64,
+    // Generations between refits of the model to the archive of true evaluations
+    pub retrain_interval: usize,
+    // True evaluations needed before the model is used at all
+    pub min_points: usize,
+    // Most recent true evaluations the model is fitted to, which bounds the cubic cost of a fit
+    pub max_points: usize,
+    // Rank correlation between predicted and true fitness of the evaluated offspring below
+    // which the model is distrusted: the next generation is evaluated in full and the model
+    // is refitted before it screens again
+    pub trust_threshold: f64,
+}
+
+impl Default for SurrogateParameters {
+    fn default() -> Self {
+        Self {
+            model: SurrogateModel::Kriging { length_scale: 0.2, noise: 1e-6, exploration: 1.0 },
+            evaluated_fraction: 0.25,
+            retrain_interval: 1,
+            min_points: 50,
+            max_points: 200,
+            trust_threshold: 0.3,
+        }
+    }
+}
+
+// How much work the surrogate saved, for the end-of-run report
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SurrogateStats {
+    pub screened: usize,
+    pub evaluated: usize,
+    pub fits: usize,
+    pub distrusted: usize,
+    pub last_correlation: Option<f64>,
+}
+
+impl fmt::Display for SurrogateStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} screened offspring evaluated, {} fits, distrusted {} times",
+            self.evaluated, self.screened, self.fits, self.distrusted
+        )?;
+        if let Some(correlation) = self.last_correlation {
+            write!(f, ", last rank correlation {correlation:.3}")?;
+        }
+        Ok(())
+    }
+}
+
+enum FittedModel {
+    Rbf(GaussianProcess),
+    Kriging { process: GaussianProcess, exploration: f64 },
+}
+
+// Archive of true evaluations and the model fitted to it. Points are the genes only, in the
+// units of the search box given at creation.
+pub struct Surrogate {
+    params: SurrogateParameters,
+    bounds: (f64, f64),
+    points: Vec<Vec<f64>>,
+    values: Vec<f64>,
+    model: Option<FittedModel>,
+    generations_since_fit: usize,
+    trusted: bool,
+    pub stats: SurrogateStats,
+}
+
+impl Surrogate {
+    pub fn new(params: SurrogateParameters, bounds: (f64, f64)) -> Self {
+        Self {
+            params,
+            bounds,
+            points: Vec::new(),
+            values: Vec::new(),
+            model: None,
+            generations_since_fit: 0,
+            trusted: true,
+            stats: SurrogateStats::default(),
+        }
+    }
+
+    // Adds true evaluations to the archive. Exact repeats of archived points (parents that
+    // are evaluated again) and values that aren't finite are left out, as either would
+    // break the fit.
+    pub fn record(&mut self, points: &[Vec<f64>], values: &[f64]) {
+        for (point, &value) in points.iter().zip(values) {
+            let scaled = self.scale(point);
+            if value.is_finite() && !self.points.contains(&scaled) {
+                self.points.push(scaled);
+                self.values.push(value);
+            }
+        }
+        let excess = self.points.len().saturating_sub(self.params.max_points);
+        self.points.drain(..excess);
+        self.values.drain(..excess);
+    }
+
+    // Which candidates to evaluate for real, and the predicted fitness of all of them, or None
+    // when the whole generation should be evaluated: too few points, or a model that was just
+    // distrusted. Refits first when the retraining interval is up.
+    pub fn screen(&mut self, candidates: &[Vec<f64>]) -> Option<(Vec<usize>, Vec<f64>)> {
+        if !self.trusted {
+            self.trusted = true;
+            self.model = None;
+            return None;
+        }
+        if self.points.len() < self.params.min_points.max(2) {
+            return None;
+        }
+        self.generations_since_fit += 1;
+        if self.model.is_none() || self.generations_since_fit >= self.params.retrain_interval {
+            self.fit();
+        }
+        let model = self.model.as_ref()?;
+        let (predicted, scores): (Vec<f64>, Vec<f64>) = candidates
+            .iter()
+            .map(|candidate| {
+                let x = self.scale(candidate);
+                match model {
+                    FittedModel::Rbf(process) => {
+                        let mean = process.mean(&x);
+                        (mean, mean)
+                    }
+                    FittedModel::Kriging { process, exploration } => {
+                        let (mean, std) = process.predict(&x);
+                        (mean, mean + exploration * std)
+                    }
+                }
+            })
+            .unzip();
+        let count = (self.params.evaluated_fraction * candidates.len() as f64).ceil() as usize;
+        let mut order: Vec<usize> = (0..candidates.len()).collect();
+        order.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+        order.truncate(count.clamp(1, candidates.len()));
+        self.stats.screened += candidates.len();
+        self.stats.evaluated += order.len();
+        Some((order, predicted))
+    }
+
+    // Compares the predictions for the evaluated candidates with their true fitness and
+    // distrusts the model if they rank too differently, returning true if it did
+    pub fn check(&mut self, predicted: &[f64], actual: &[f64]) -> bool {
+        let Some(correlation) = rank_correlation(predicted, actual) else {
+            return false;
+        };
+        self.stats.last_correlation = Some(correlation);
+        if correlation < self.params.trust_threshold {
+            self.trusted = false;
+            self.stats.distrusted += 1;
+        }
+        !self.trusted
+    }
+
+    fn fit(&mut self) {
+        let process = |length_scale, noise| {
+            GaussianProcess::fit(&self.points, &self.values, length_scale, noise)
+        };
+        self.model = Some(match self.params.model {
+            // A tiny nugget keeps the interpolation matrix factorizable
+            SurrogateModel::Rbf { width } => FittedModel::Rbf(process(width, 1e-8)),
+            SurrogateModel::Kriging { length_scale, noise, exploration } => {
+                FittedModel::Kriging { process: process(length_scale, noise), exploration }
+            }
+        });
+        self.generations_since_fit = 0;
+        self.stats.fits += 1;
+    }
+
+    fn scale(&self, point: &[f64]) -> Vec<f64> {
+        let (low, high) = self.bounds;
+        point.iter().map(|x| (x - low) / (high - low)).collect()
+    }
+}
+
+// Spearman's rank correlation, None for fewer than three pairs or a constant side
+fn rank_correlation(a: &[f64], b: &[f64]) -> Option<f64> {
+    if a.len() < 3 {
+        return None;
+    }
+    let (rank_a, rank_b) = (ranks(a), ranks(b));
+    let n = a.len() as f64;
+    let mean = (n - 1.0) / 2.0;
+    let covariance: f64 = rank_a.iter().zip(&rank_b).map(|(x, y)| (x - mean) * (y - mean)).sum();
+    let spread = |ranks: &[f64]| ranks.iter().map(|x| (x - mean).powi(2)).sum::<f64>().sqrt();
+    let denominator = spread(&rank_a) * spread(&rank_b);
+    (denominator > 0.0).then(|| covariance / denominator)
+}
+
+// Rank of each value, ties sharing their mean rank
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        let rank = (start + end - 1) as f64 / 2.0;
+        for &i in &order[start..end] {
+            ranks[i] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
@@ -0,0 +1,23 @@
+use crate::fitness::{Fitness, Objective};
+
+// Toy variable-length problem: choose values in [0, 1] that add up to target with as few
+// values as possible. The best size is not known in advance, so it has to evolve.
+pub struct TargetSum {
+    pub target: f64,
+    // Fitness is divided by (1 + length_penalty * number of values)
+    pub length_penalty: f64,
+}
+
+impl Objective for TargetSum {
+    fn fitness(&self, member: &[f64]) -> f64 {
+        let sum: f64 = member.iter().map(|v| v.clamp(0.0, 1.0)).sum();
+        let accuracy = 1.0 / (1.0 + (sum - self.target).abs());
+        accuracy / (1.0 + self.length_penalty * member.len() as f64)
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+}
+
+impl Fitness for TargetSum {}
@@ -1,10 +1,21 @@
-use crate::bitstring::GAParameters;
-use crate::evol_strat::ESParameters;
-use crate::fitness::Fitness;
-use crate::parameter_tuning::{TuningResult, TuningConfig};
-use rand::prelude::*;
+use crate::algorithm::Algorithm;
+use crate::basins::distinct_optima;
+use crate::bitstring::{GAParameters, GeneticAlgorithm};
+use crate::cancellation::CancellationToken;
+use crate::evol_strat::{ESParameters, EvolutionStrategy};
+use crate::decoder::{BinaryDecoder, Encoding};
+use crate::fitness::{Decoded, Fitness};
+use crate::logging::trace_observer;
+use crate::parameter_tuning::{ParameterGrid, TuningResult, TuningConfig};
+use crate::repair::NoRepair;
+use crate::resources::ResourceMonitor;
+use crate::seeds::SeedSequence;
+use crate::statistics;
+use crate::stopping::StopReason;
 use rand_chacha::ChaCha8Rng;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
 use std::thread;
 use std::time::{Duration, Instant};
 
@@ -15,84 +26,124 @@ pub struct ExecutionStats {
     pub converged: bool,
     pub timeout_reached: bool,
     pub execution_time: f64,
+    // Evaluations used by the end of the first generation that reached the target fitness
+    pub evaluations_to_target: Option<usize>,
+    // Normalized area under the best-so-far curve, see statistics::normalized_auc
+    pub auc: f64,
+    // Optima the final population covers, see basins::distinct_optima
+    pub distinct_optima: usize,
+    pub evaluations_per_second: f64,
+    // None where the resident set can't be read
+    pub peak_rss_mb: Option<f64>,
+    pub mean_generation_ms: f64,
+    pub max_generation_ms: f64,
 }
 
 pub struct TimeoutRunner;
 
+// Optima the phenotypes of a final population cover, see basins::distinct_optima
+fn count_optima(fitness_fn: &impl Fitness, members: Vec<Vec<f64>>, radius: f64) -> usize {
+    let fitnesses = fitness_fn.evaluate_population(&members);
+    distinct_optima(&members, &fitnesses, radius)
+}
+
+// One run of one parameter combination in a tuning sweep
+#[derive(Debug, Clone, Copy)]
+enum TuningJob {
+    Sga { combination: usize, run: usize },
+    Es { combination: usize, run: usize },
+}
+
+impl TuningJob {
+    fn combination(&self) -> usize {
+        match *self {
+            TuningJob::Sga { combination, .. } | TuningJob::Es { combination, .. } => combination,
+        }
+    }
+
+    fn run(&self) -> usize {
+        match *self {
+            TuningJob::Sga { run, .. } | TuningJob::Es { run, .. } => run,
+        }
+    }
+
+    // The algorithm name and parameter map its result is recorded under
+    fn parameters(
+        &self,
+        sga_grid: &[GAParameters],
+        es_grid: &[ESParameters],
+    ) -> (&'static str, HashMap<String, f64>) {
+        match *self {
+            TuningJob::Sga { combination, .. } => {
+                ("SGA", ParameterGrid::params_to_map_ga(&sga_grid[combination]))
+            }
+            TuningJob::Es { combination, .. } => {
+                ("ES", ParameterGrid::params_to_map_es(&es_grid[combination]))
+            }
+        }
+    }
+
+    fn grid_len(&self, sga_grid: &[GAParameters], es_grid: &[ESParameters]) -> usize {
+        match self {
+            TuningJob::Sga { .. } => sga_grid.len(),
+            TuningJob::Es { .. } => es_grid.len(),
+        }
+    }
+}
+
 impl TimeoutRunner {
     pub fn run_sga_with_timeout<F: Fitness + Send + Sync + 'static>(
         fitness_fn: F,
         params: GAParameters,
         config: &TuningConfig,
         run_id: usize,
+        trace_path: Option<String>,
     ) -> TuningResult {
-        let fitness_fn = Arc::new(fitness_fn);
-        let timeout_duration = Duration::from_secs(config.timeout_seconds);
-        let params_clone = params.clone();
+        let optimum = fitness_fn.optimum_value();
+        let timeout_duration = config.run_timeout().unwrap_or_default();
         let params_for_result = params.clone();
-        let num_dimensions = config.num_dimensions;
+        let seed = config.seeds.run(run_id).seed();
+        let target_fitness = config.target_fitness;
+        let cluster_radius = config.cluster_radius;
+        let run_params = GAParameters {
+            num_dims: config.num_dimensions,
+            seed: Some(seed),
+            ..params
+        };
+        // The combination's genome length decides the precision the problem's bounds are
+        // decoded at
+        let bits_per_dim = run_params.mem_size / run_params.num_dims.max(1);
+        let decoder = BinaryDecoder::new(fitness_fn.bounds(), bits_per_dim, Encoding::Binary)
+            .unwrap_or_else(|e| panic!("SGA combination can't be decoded: {e}"));
+        let fitness_fn = Arc::new(Decoded { objective: fitness_fn, decoder });
+        let token = CancellationToken::new();
+        let run_token = token.clone();
+        let (done_tx, done_rx) = mpsc::channel();
         
         let handle = thread::spawn(move || {
             let start_time = Instant::now();
-            let mut rng = ChaCha8Rng::seed_from_u64(5000 + run_id as u64);
-            
-            // Run SGA with timeout checking
-            let mut current_gen = 0;
-            let mut max_fitness = 0.0;
-            let mut converged = false;
-            
-            // Initialize population
-            let mut population = Self::init_population(&params_clone, &mut rng);
-            
-            while current_gen < params.max_iters {
-                // Check timeout
-                if start_time.elapsed() >= timeout_duration {
-                    break;
-                }
-                
-                // Calculate fitnesses
-                let fitnesses: Vec<f64> = population
+            let mut rng = SeedSequence::new(seed).rng();
+            let mut algorithm: GeneticAlgorithm<String, _, _> =
+                GeneticAlgorithm::new(&*fitness_fn, &run_params, &NoRepair, &mut rng)
+                    .with_cancellation(run_token)
+                    .quiet();
+            if let Some(trace) = trace_observer(trace_path.as_deref()) {
+                algorithm = algorithm.with_observer(trace);
+            }
+            let optima_of = |population: Vec<String>| {
+                let members = population
                     .iter()
-                    .map(|m| fitness_fn.fitness_bitstring(m, num_dimensions))
+                    .map(|member| fitness_fn.decode_bitstring(member, run_params.num_dims))
                     .collect();
-                
-                max_fitness = fitnesses.iter().cloned().fold(0.0, f64::max);
-                let avg_fitness = fitnesses.iter().sum::<f64>() / population.len() as f64;
-                
-                // Check convergence
-                if avg_fitness >= params.convergence_threshold {
-                    converged = true;
-                    break;
-                }
-                
-                // Create new generation (simplified version)
-                population = Self::create_next_generation(&population, &params_clone, &*fitness_fn, num_dimensions, &mut rng);
-                current_gen += 1;
-            }
-            
-            let execution_time = start_time.elapsed().as_secs_f64();
-            let timeout_reached = start_time.elapsed() >= timeout_duration;
-            
-            ExecutionStats {
-                max_fitness,
-                generations: current_gen,
-                converged,
-                timeout_reached,
-                execution_time,
-            }
+                count_optima(&*fitness_fn, members, cluster_radius)
+            };
+            let stats = Self::drive(algorithm, &mut rng, start_time, target_fitness, optima_of);
+            let _ = done_tx.send(());
+            stats
         });
         
         // Wait for completion or timeout
-        let execution_stats = match handle.join() {
-            Ok(stats) => stats,
-            Err(_) => ExecutionStats {
-                max_fitness: 0.0,
-                generations: 0,
-                converged: false,
-                timeout_reached: true,
-                execution_time: config.timeout_seconds as f64,
-            },
-        };
+        let execution_stats = Self::wait_or_cancel(handle, done_rx, &token, timeout_duration, config);
         
         let score = if execution_stats.execution_time > 0.0 {
             execution_stats.max_fitness / execution_stats.execution_time
@@ -102,7 +153,7 @@ impl TimeoutRunner {
         
         TuningResult {
             algorithm: "SGA".to_string(),
-            parameters: crate::parameter_tuning::ParameterGrid::params_to_map_ga(&params_for_result),
+            parameters: ParameterGrid::params_to_map_ga(&params_for_result),
             run_id,
             max_fitness: execution_stats.max_fitness,
             execution_time: execution_stats.execution_time,
@@ -110,6 +161,16 @@ impl TimeoutRunner {
             converged: execution_stats.converged,
             generations: execution_stats.generations,
             timeout_reached: execution_stats.timeout_reached,
+            design: config.design.clone(),
+            seed,
+            evaluations_to_target: execution_stats.evaluations_to_target,
+            auc: Some(execution_stats.auc),
+            success: Self::success(optimum, execution_stats.max_fitness, config),
+            distinct_optima: Some(execution_stats.distinct_optima),
+            evaluations_per_second: Some(execution_stats.evaluations_per_second),
+            peak_rss_mb: execution_stats.peak_rss_mb,
+            mean_generation_ms: Some(execution_stats.mean_generation_ms),
+            max_generation_ms: Some(execution_stats.max_generation_ms),
         }
     }
     
@@ -118,73 +179,41 @@ impl TimeoutRunner {
         params: ESParameters,
         config: &TuningConfig,
         run_id: usize,
+        trace_path: Option<String>,
     ) -> TuningResult {
+        let optimum = fitness_fn.optimum_value();
         let fitness_fn = Arc::new(fitness_fn);
-        let timeout_duration = Duration::from_secs(config.timeout_seconds);
-        
-        let params_clone = params.clone();
+        let timeout_duration = config.run_timeout().unwrap_or_default();
         let params_for_result = params.clone();
+        let token = CancellationToken::new();
+        let run_token = token.clone();
+        let (done_tx, done_rx) = mpsc::channel();
+        let seed = config.seeds.run(run_id).seed();
+        let target_fitness = config.target_fitness;
+        let cluster_radius = config.cluster_radius;
+        let params = ESParameters { seed: Some(seed), ..params };
+        
         let handle = thread::spawn(move || {
             let start_time = Instant::now();
-            let mut rng = ChaCha8Rng::seed_from_u64(5000 + run_id as u64);
-            
-            // Run ES with timeout checking
-            let mut current_gen = 0;
-            let mut max_fitness = 0.0;
-            let mut converged = false;
-            
-            // Initialize population
-            let mut population = Self::init_es_population(&params_clone, &mut rng);
-            
-            while current_gen < params.max_gens {
-                // Check timeout
-                if start_time.elapsed() >= timeout_duration {
-                    break;
-                }
-                
-                // Evaluate fitness
-                let fitnesses: Vec<f64> = population
-                    .iter()
-                    .map(|member| fitness_fn.fitness(&member[0..params.mem_size]))
-                    .collect();
-                
-                max_fitness = fitnesses.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-                let avg_fitness = fitnesses.iter().sum::<f64>() / params.mu as f64;
-                
-                // Check convergence
-                if avg_fitness > 0.99 {
-                    converged = true;
-                    break;
-                }
-                
-                // Create offspring (simplified version)
-                population = Self::create_es_offspring(&population, &params_clone, &*fitness_fn, &mut rng);
-                current_gen += 1;
-            }
-            
-            let execution_time = start_time.elapsed().as_secs_f64();
-            let timeout_reached = start_time.elapsed() >= timeout_duration;
-            
-            ExecutionStats {
-                max_fitness,
-                generations: current_gen,
-                converged,
-                timeout_reached,
-                execution_time,
+            let mut rng = SeedSequence::new(seed).rng();
+            let mut algorithm = EvolutionStrategy::new(&*fitness_fn, &params, &NoRepair, &mut rng)
+                .with_cancellation(run_token)
+                .quiet();
+            if let Some(trace) = trace_observer(trace_path.as_deref()) {
+                algorithm = algorithm.with_observer(trace);
             }
+            let optima_of = |population: Vec<Vec<f64>>| {
+                let members =
+                    population.iter().map(|member| member[0..params.mem_size].to_vec()).collect();
+                count_optima(&*fitness_fn, members, cluster_radius)
+            };
+            let stats = Self::drive(algorithm, &mut rng, start_time, target_fitness, optima_of);
+            let _ = done_tx.send(());
+            stats
         });
         
         // Wait for completion or timeout
-        let execution_stats = match handle.join() {
-            Ok(stats) => stats,
-            Err(_) => ExecutionStats {
-                max_fitness: 0.0,
-                generations: 0,
-                converged: false,
-                timeout_reached: true,
-                execution_time: config.timeout_seconds as f64,
-            },
-        };
+        let execution_stats = Self::wait_or_cancel(handle, done_rx, &token, timeout_duration, config);
         
         let score = if execution_stats.execution_time > 0.0 {
             execution_stats.max_fitness / execution_stats.execution_time
@@ -194,7 +223,7 @@ impl TimeoutRunner {
         
         TuningResult {
             algorithm: "ES".to_string(),
-            parameters: crate::parameter_tuning::ParameterGrid::params_to_map_es(&params_for_result),
+            parameters: ParameterGrid::params_to_map_es(&params_for_result),
             run_id,
             max_fitness: execution_stats.max_fitness,
             execution_time: execution_stats.execution_time,
@@ -202,179 +231,245 @@ impl TimeoutRunner {
             converged: execution_stats.converged,
             generations: execution_stats.generations,
             timeout_reached: execution_stats.timeout_reached,
+            design: config.design.clone(),
+            seed,
+            evaluations_to_target: execution_stats.evaluations_to_target,
+            auc: Some(execution_stats.auc),
+            success: Self::success(optimum, execution_stats.max_fitness, config),
+            distinct_optima: Some(execution_stats.distinct_optima),
+            evaluations_per_second: Some(execution_stats.evaluations_per_second),
+            peak_rss_mb: execution_stats.peak_rss_mb,
+            mean_generation_ms: Some(execution_stats.mean_generation_ms),
+            max_generation_ms: Some(execution_stats.max_generation_ms),
         }
     }
     
-    // Helper functions for simplified algorithm execution
-    fn init_population(params: &GAParameters, rng: &mut ChaCha8Rng) -> Vec<String> {
-        let mut population = Vec::new();
-        for _ in 0..params.pop_size {
-            let mut member: String = String::new();
-            for _ in 0..params.mem_size {
-                let bit = if rng.random() { '1' } else { '0' };
-                member.push(bit);
-            }
-            population.push(member);
-        }
-        population
+    // Whether a run's best fitness is within the success tolerance of the optimum value
+    fn success(optimum: Option<f64>, max_fitness: f64, config: &TuningConfig) -> Option<bool> {
+        optimum.map(|optimum| (optimum - max_fitness).abs() <= config.success_epsilon)
     }
-    
-    fn create_next_generation(
-        population: &[String],
-        params: &GAParameters,
-        fitness_fn: &impl Fitness,
-        num_dims: usize,
+
+    // Steps an algorithm until it stops on its own or its cancellation token fires, timing
+    // each generation, then counts the optima its final population covers
+    fn drive<A: Algorithm>(
+        mut algorithm: A,
         rng: &mut ChaCha8Rng,
-    ) -> Vec<String> {
-        let mut new_population = Vec::new();
-        
-        while new_population.len() < params.pop_size {
-            // Tournament selection
-            let parent1 = Self::tournament_selection(population, fitness_fn, num_dims, rng);
-            let parent2 = Self::tournament_selection(population, fitness_fn, num_dims, rng);
-            
-            // Crossover
-            let (mut child1, mut child2) = Self::crossover(&parent1, &parent2, params.crossover_rate, rng);
-            
-            // Mutation
-            child1 = Self::mutate(&child1, params.mutation_rate, rng);
-            child2 = Self::mutate(&child2, params.mutation_rate, rng);
-            
-            new_population.push(child1);
-            if new_population.len() < params.pop_size {
-                new_population.push(child2);
+        start_time: Instant,
+        target_fitness: f64,
+        optima_of: impl FnOnce(Vec<A::Individual>) -> usize,
+    ) -> ExecutionStats {
+        let mut evaluations_to_target = None;
+        // Best-so-far curve over evaluations, one point per generation
+        let mut curve = Vec::new();
+        let mut resources = ResourceMonitor::start();
+        let reason = loop {
+            let generation_start = Instant::now();
+            let stop = algorithm.step(rng);
+            resources.generation(generation_start);
+            let best_fitness = algorithm.best_fitness();
+            if evaluations_to_target.is_none() && best_fitness >= target_fitness {
+                evaluations_to_target = Some(algorithm.evaluations());
             }
-        }
-        
-        new_population
-    }
-    
-    fn tournament_selection(
-        population: &[String],
-        fitness_fn: &impl Fitness,
-        num_dims: usize,
-        rng: &mut ChaCha8Rng,
-    ) -> String {
-        let tournament_size = 3;
-        let mut best_individual = String::new();
-        let mut best_fitness = f64::MIN;
-        
-        for _ in 0..tournament_size {
-            let random_index = rng.random_range(0..population.len());
-            let individual = &population[random_index];
-            let fitness = fitness_fn.fitness_bitstring(individual, num_dims);
-            
-            if fitness > best_fitness {
-                best_fitness = fitness;
-                best_individual = individual.clone();
+            // Nothing has been evaluated yet if the run was cancelled before its first step
+            if best_fitness.is_finite() {
+                curve.push((algorithm.evaluations(), best_fitness));
             }
-        }
+            if let Some(reason) = stop {
+                break reason;
+            }
+        };
         
-        best_individual
-    }
-    
-    fn crossover(parent1: &str, parent2: &str, crossover_rate: f64, rng: &mut ChaCha8Rng) -> (String, String) {
-        let random: f64 = rng.random();
-        if random >= crossover_rate {
-            return (parent1.to_string(), parent2.to_string());
+        let (max_fitness, generations) = (algorithm.best_fitness(), algorithm.generation());
+        let execution_time = start_time.elapsed().as_secs_f64();
+        let evaluations_per_second = if execution_time > 0.0 {
+            algorithm.evaluations() as f64 / execution_time
+        } else {
+            0.0
+        };
+        resources.sample();
+        let (mean_generation_ms, max_generation_ms) = resources.generation_ms();
+        let population = algorithm.finish(reason);
+        ExecutionStats {
+            // No generation was evaluated if the run was cancelled straight away
+            max_fitness: max_fitness.max(0.0),
+            generations,
+            converged: reason == StopReason::Converged,
+            timeout_reached: reason == StopReason::Cancelled,
+            execution_time,
+            evaluations_to_target,
+            auc: statistics::normalized_auc(&curve),
+            distinct_optima: optima_of(population),
+            evaluations_per_second,
+            peak_rss_mb: resources.peak_rss_mb(),
+            mean_generation_ms,
+            max_generation_ms,
         }
-        
-        let crossover_point = rng.random_range(1..parent1.len());
-        let offspring1 = format!("{}{}", &parent1[..crossover_point], &parent2[crossover_point..]);
-        let offspring2 = format!("{}{}", &parent2[..crossover_point], &parent1[crossover_point..]);
-        
-        (offspring1, offspring2)
     }
     
-    fn mutate(bitstring: &str, mutation_rate: f64, rng: &mut ChaCha8Rng) -> String {
-        let mut mutated = String::new();
-        for c in bitstring.chars() {
-            let random: f64 = rng.random();
-            let bit = if random < mutation_rate {
-                if c == '1' { '0' } else { '1' }
-            } else {
-                c
-            };
-            mutated.push(bit);
+    // Runs every (combination, run_id) pair of both grids on config.num_workers threads.
+    // Workers pull jobs in serial order and each run keeps its own seed, so the returned
+    // results are in the same order, with the same seeds, as a serial sweep. Under a session
+    // budget the jobs go round the combinations a run at a time instead, so a budget spent
+    // early still leaves every combination with some runs, and a comparable analysis.
+    pub fn run_grid<F: Fitness + Clone + Send + Sync + 'static>(
+        fitness_fn: F,
+        sga_grid: &[GAParameters],
+        es_grid: &[ESParameters],
+        config: &TuningConfig,
+    ) -> Vec<TuningResult> {
+        let mut jobs = Vec::new();
+        if config.total_budget_seconds.is_some() {
+            for run in 0..config.num_runs {
+                let sga = (0..sga_grid.len()).map(|combination| TuningJob::Sga { combination, run });
+                let es = (0..es_grid.len()).map(|combination| TuningJob::Es { combination, run });
+                jobs.extend(sga.chain(es));
+            }
+            return Self::run_jobs(fitness_fn, sga_grid, es_grid, &jobs, config);
         }
-        mutated
-    }
-    
-    fn init_es_population(params: &ESParameters, rng: &mut ChaCha8Rng) -> Vec<Vec<f64>> {
-        let mut population = Vec::new();
-        for _ in 0..params.mu {
-            let mut member: Vec<f64> = Vec::new();
-            for _ in 0..params.mem_size {
-                let gene: f64 = rng.random_range(params.mem_range.0..params.mem_range.1);
-                member.push(gene);
+        for combination in 0..sga_grid.len() {
+            for run in 0..config.num_runs {
+                jobs.push(TuningJob::Sga { combination, run });
+            }
+        }
+        for combination in 0..es_grid.len() {
+            for run in 0..config.num_runs {
+                jobs.push(TuningJob::Es { combination, run });
             }
-            member.push(params.sigma);
-            population.push(member);
         }
-        population
+        Self::run_jobs(fitness_fn, sga_grid, es_grid, &jobs, config)
     }
-    
-    fn create_es_offspring(
-        population: &[Vec<f64>],
-        params: &ESParameters,
-        fitness_fn: &impl Fitness,
-        rng: &mut ChaCha8Rng,
-    ) -> Vec<Vec<f64>> {
-        // Evaluate current population
-        let fitnesses: Vec<f64> = population
+
+    // The config.num_runs runs of one SGA combination, for tuners that choose combinations
+    // one at a time. Traces are numbered by the combination's index in `sga_grid`.
+    pub fn run_sga_combination<F: Fitness + Clone + Send + Sync + 'static>(
+        fitness_fn: F,
+        sga_grid: &[GAParameters],
+        combination: usize,
+        config: &TuningConfig,
+    ) -> Vec<TuningResult> {
+        let jobs: Vec<TuningJob> =
+            (0..config.num_runs).map(|run| TuningJob::Sga { combination, run }).collect();
+        Self::run_jobs(fitness_fn, sga_grid, &[], &jobs, config)
+    }
+
+    pub fn run_es_combination<F: Fitness + Clone + Send + Sync + 'static>(
+        fitness_fn: F,
+        es_grid: &[ESParameters],
+        combination: usize,
+        config: &TuningConfig,
+    ) -> Vec<TuningResult> {
+        let jobs: Vec<TuningJob> =
+            (0..config.num_runs).map(|run| TuningJob::Es { combination, run }).collect();
+        Self::run_jobs(fitness_fn, &[], es_grid, &jobs, config)
+    }
+
+    fn run_jobs<F: Fitness + Clone + Send + Sync + 'static>(
+        fitness_fn: F,
+        sga_grid: &[GAParameters],
+        es_grid: &[ESParameters],
+        jobs: &[TuningJob],
+        config: &TuningConfig,
+    ) -> Vec<TuningResult> {
+        // A resumed session takes runs it already finished from the results file
+        let results: Vec<Option<TuningResult>> = jobs
             .iter()
-            .map(|member| fitness_fn.fitness(&member[0..params.mem_size]))
+            .map(|job| {
+                let (algorithm, parameters) = job.parameters(sga_grid, es_grid);
+                config.completed_run(algorithm, &parameters, job.run()).cloned()
+            })
             .collect();
-        
-        // Create lambda offspring
-        let mut offspring = Vec::new();
-        for _ in 0..params.lambda {
-            // Tournament selection
-            let parent_idx = (0..params.mu)
-                .choose_multiple(rng, 2)
-                .into_iter()
-                .max_by(|&i, &j| fitnesses[i].partial_cmp(&fitnesses[j]).unwrap())
-                .unwrap();
-            let parent = &population[parent_idx];
-            
-            // Mutate
-            let mut child = Vec::new();
-            let genes = &parent[0..params.mem_size];
-            let sigma_val = parent[params.mem_size];
-            
-            for &gene in genes {
-                let mutation: f64 = rng.sample::<f64, _>(rand_distr::Normal::new(0.0, sigma_val).unwrap());
-                let mutated_gene = gene + mutation;
-                child.push(mutated_gene);
+        let pending: Vec<usize> = (0..jobs.len()).filter(|&i| results[i].is_none()).collect();
+        if pending.len() < jobs.len() {
+            let skipped: Vec<&TuningResult> = results.iter().flatten().collect();
+            config.progress.println(&format!(
+                "Skipping {} of {} runs already in the results",
+                skipped.len(),
+                jobs.len()
+            ));
+            config.progress.skip(&skipped);
+        }
+
+        let next_job = AtomicUsize::new(0);
+        let results = Mutex::new(results);
+        let workers = config.num_workers.clamp(1, pending.len().max(1));
+
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| {
+                    loop {
+                        let next = next_job.fetch_add(1, Ordering::Relaxed);
+                        let Some(&index) = pending.get(next) else {
+                            break;
+                        };
+                        // Once the session's budget is spent the remaining jobs are left out
+                        if config.run_timeout().is_none() {
+                            break;
+                        }
+                        let job = &jobs[index];
+                        let result = match *job {
+                            TuningJob::Sga { combination, run } => Self::run_sga_with_timeout(
+                                fitness_fn.clone(),
+                                sga_grid[combination].clone(),
+                                config,
+                                run,
+                                config.trace_path("SGA", combination, run),
+                            ),
+                            TuningJob::Es { combination, run } => Self::run_es_with_timeout(
+                                fitness_fn.clone(),
+                                es_grid[combination].clone(),
+                                config,
+                                run,
+                                config.trace_path("ES", combination, run),
+                            ),
+                        };
+                        config.progress.println(&format!(
+                            "Finished {} combination {}/{} run {} (max fitness {:.6})",
+                            result.algorithm,
+                            job.combination() + 1,
+                            job.grid_len(sga_grid, es_grid),
+                            result.run_id,
+                            result.max_fitness
+                        ));
+                        config.record(&result);
+                        results.lock().unwrap()[index] = Some(result);
+                    }
+                });
             }
-            
-            // Mutate sigma
-            let sigma_mutation: f64 = rng.sample::<f64, _>(rand_distr::Normal::new(0.0, 1.0).unwrap());
-            let new_sigma = sigma_val * (params.tau * sigma_mutation).exp();
-            child.push(new_sigma);
-            
-            offspring.push(child);
+        });
+
+        // Jobs left out by a spent session budget have no result
+        results.into_inner().unwrap().into_iter().flatten().collect()
+    }
+
+    // Waits up to the timeout for the run to finish, then cancels it. A cancelled run checks
+    // its token every MEMBERS_PER_CHECK members it evaluates (each of them resampled) and
+    // between offspring pairs, so the join that follows waits at most for one such chunk.
+    fn wait_or_cancel(
+        handle: thread::JoinHandle<ExecutionStats>,
+        done_rx: mpsc::Receiver<()>,
+        token: &CancellationToken,
+        timeout_duration: Duration,
+        config: &TuningConfig,
+    ) -> ExecutionStats {
+        if done_rx.recv_timeout(timeout_duration).is_err() {
+            token.cancel();
         }
-        
-        // Select best mu from lambda offspring
-        let offspring_fitnesses: Vec<f64> = offspring
-            .iter()
-            .map(|member| fitness_fn.fitness(&member[0..params.mem_size]))
-            .collect();
-        
-        let mut indexed: Vec<(f64, usize)> = offspring_fitnesses
-            .iter()
-            .enumerate()
-            .map(|(i, &f)| (f, i))
-            .collect();
-        indexed.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
-        
-        let selected_indices: Vec<usize> = indexed.into_iter().take(params.mu).map(|(_, i)| i).collect();
-        let mut new_population = Vec::new();
-        for &idx in &selected_indices {
-            new_population.push(offspring[idx].clone());
+        match handle.join() {
+            Ok(stats) => stats,
+            Err(_) => ExecutionStats {
+                max_fitness: 0.0,
+                generations: 0,
+                converged: false,
+                timeout_reached: true,
+                execution_time: config.timeout_seconds as f64,
+                evaluations_to_target: None,
+                auc: 0.0,
+                distinct_optima: 0,
+                evaluations_per_second: 0.0,
+                peak_rss_mb: None,
+                mean_generation_ms: 0.0,
+                max_generation_ms: 0.0,
+            },
         }
-        
-        new_population
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,99 @@
+use std::time::{Duration, Instant};
+
+// The parts of a generation whose time is accounted separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Selection,
+    Crossover,
+    Mutation,
+    Evaluation,
+    Statistics,
+}
+
+impl Phase {
+    const ALL: [Phase; 5] =
+        [Phase::Selection, Phase::Crossover, Phase::Mutation, Phase::Evaluation, Phase::Statistics];
+
+    fn name(self) -> &'static str {
+        match self {
+            Phase::Selection => "selection",
+            Phase::Crossover => "crossover",
+            Phase::Mutation => "mutation",
+            Phase::Evaluation => "evaluation",
+            Phase::Statistics => "statistics",
+        }
+    }
+}
+
+// Time a run spends in each phase, when StatsConfig::timing turns it on; off, it never reads
+// the clock. A generation runs from one step to the next, so whatever it spends outside the
+// phases (repair, replacement, rate control, the caller's loop) is reported as other.
+#[derive(Debug, Clone, Default)]
+pub struct OperatorTimer {
+    enabled: bool,
+    phases: [Duration; 5],
+    total: Duration,
+    generations: usize,
+    generation_start: Option<Instant>,
+}
+
+impl OperatorTimer {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled, ..Self::default() }
+    }
+
+    // Clock reading at the start of a phase, None when timing is off
+    pub fn start(&self) -> Option<Instant> {
+        self.enabled.then(Instant::now)
+    }
+
+    pub fn stop(&mut self, phase: Phase, started: Option<Instant>) {
+        if let Some(started) = started {
+            self.phases[phase as usize] += started.elapsed();
+        }
+    }
+
+    // Ends the previous generation, if any, and starts the next
+    pub fn start_generation(&mut self) {
+        self.end_generation();
+        self.generation_start = self.start();
+    }
+
+    fn end_generation(&mut self) {
+        if let Some(started) = self.generation_start.take() {
+            self.total += started.elapsed();
+            self.generations += 1;
+        }
+    }
+
+    // Ends the last generation and describes the breakdown, largest share first; None when
+    // timing is off or no generation ran
+    pub fn finish(&mut self) -> Option<String> {
+        self.end_generation();
+        if !self.enabled || self.generations == 0 {
+            return None;
+        }
+        let total = self.total.as_secs_f64();
+        let timed: Duration = self.phases.iter().sum();
+        let mut parts: Vec<(&str, f64)> = Phase::ALL
+            .iter()
+            .map(|&phase| (phase.name(), self.phases[phase as usize].as_secs_f64()))
+            .collect();
+        parts.push(("other", total - timed.as_secs_f64().min(total)));
+        parts.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let share = |seconds: f64| if total > 0.0 { 100.0 * seconds / total } else { 0.0 };
+        let per_generation = |seconds: f64| 1000.0 * seconds / self.generations as f64;
+        let parts: Vec<String> = parts
+            .iter()
+            .map(|&(name, seconds)| {
+                let (share, ms) = (share(seconds), per_generation(seconds));
+                format!("{name} {share:.1}% ({ms:.3} ms/gen)")
+            })
+            .collect();
+        Some(format!(
+            "Operator time over {} generations ({total:.3} s): {}",
+            self.generations,
+            parts.join(", ")
+        ))
+    }
+}
@@ -0,0 +1,31 @@
+use crate::fitness::{Fitness, Objective};
+
+// Travelling salesman over points in the plane. The phenotype is the visiting order, and
+// fitness is 1 / (1 + tour length) so shorter closed tours are better.
+pub struct Tsp {
+    pub cities: Vec<(f64, f64)>,
+}
+
+impl Tsp {
+    pub fn tour_length(&self, order: &[f64]) -> f64 {
+        let mut length = 0.0;
+        for i in 0..order.len() {
+            let (x1, y1) = self.cities[order[i] as usize];
+            let (x2, y2) = self.cities[order[(i + 1) % order.len()] as usize];
+            length += ((x1 - x2).powi(2) + (y1 - y2).powi(2)).sqrt();
+        }
+        length
+    }
+}
+
+impl Objective for Tsp {
+    fn fitness(&self, member: &[f64]) -> f64 {
+        1.0 / (1.0 + self.tour_length(member))
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        (0.0, self.cities.len() as f64)
+    }
+}
+
+impl Fitness for Tsp {}
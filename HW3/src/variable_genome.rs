@@ -0,0 +1,105 @@
+use crate::bitstring::GAParameters;
+use crate::decoder::Decoder;
+use crate::genome::{Genome, euclidean_distance, hash_reals};
+use rand::prelude::*;
+use rand_distr::Normal;
+
+// Real-valued genome whose length is itself evolved, for problems where the size of the
+// solution is unknown (rule sets, feature subsets). Random genomes start with between one
+// and the requested number of genes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableGenome(pub Vec<f64>);
+
+impl Genome for VariableGenome {
+    const VARIABLE_LENGTH: bool = true;
+
+    fn random<R: Rng + ?Sized>(len: usize, bounds: (f64, f64), rng: &mut R) -> Self {
+        let initial_len = rng.random_range(1..=len.max(1));
+        VariableGenome(
+            (0..initial_len)
+                .map(|_| rng.random_range(bounds.0..bounds.1))
+                .collect(),
+        )
+    }
+
+    // Gaussian creep on each gene with probability mutation_rate, then at most one insertion
+    // of a random gene and one deletion, with probabilities insertion_rate and deletion_rate.
+    // Genomes never shrink below one gene.
+    fn mutate<R: Rng + ?Sized>(
+        &self,
+        params: &GAParameters,
+        bounds: (f64, f64),
+        rng: &mut R,
+    ) -> Self {
+        let step = Normal::new(0.0, 0.1 * (bounds.1 - bounds.0)).unwrap();
+        let mut genes: Vec<f64> = self
+            .0
+            .iter()
+            .map(|&gene| {
+                let random: f64 = rng.random();
+                if random < params.mutation_rate {
+                    gene + rng.sample::<f64, _>(step)
+                } else {
+                    gene
+                }
+            })
+            .collect();
+
+        let random: f64 = rng.random();
+        if random < params.insertion_rate {
+            let position = rng.random_range(0..=genes.len());
+            genes.insert(position, rng.random_range(bounds.0..bounds.1));
+        }
+        let random: f64 = rng.random();
+        if random < params.deletion_rate && genes.len() > 1 {
+            let position = rng.random_range(0..genes.len());
+            genes.remove(position);
+        }
+        VariableGenome(genes)
+    }
+
+    // Cut-and-splice: each parent is cut at its own random point and the tails are swapped,
+    // so children can be longer or shorter than either parent
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        other: &Self,
+        crossover_rate: f64,
+        rng: &mut R,
+    ) -> (Self, Self) {
+        let random: f64 = rng.random();
+        if random >= crossover_rate {
+            return (self.clone(), other.clone());
+        }
+
+        let cut1 = rng.random_range(0..=self.0.len());
+        let cut2 = rng.random_range(0..=other.0.len());
+        let mut offspring1 = [&self.0[..cut1], &other.0[cut2..]].concat();
+        let mut offspring2 = [&other.0[..cut2], &self.0[cut1..]].concat();
+
+        // Keep at least one gene in each child
+        if offspring1.is_empty() {
+            offspring1.push(self.0[0]);
+        }
+        if offspring2.is_empty() {
+            offspring2.push(other.0[0]);
+        }
+        (VariableGenome(offspring1), VariableGenome(offspring2))
+    }
+
+    fn length(&self) -> usize {
+        self.0.len()
+    }
+
+    // Euclidean distance over the shared prefix plus one per unmatched gene
+    fn distance(&self, other: &Self) -> f64 {
+        euclidean_distance(&self.0, &other.0) + self.0.len().abs_diff(other.0.len()) as f64
+    }
+
+    fn fingerprint(&self) -> u64 {
+        hash_reals(&self.0)
+    }
+
+    fn phenotype(&self, _decoder: &impl Decoder, _num_dims: usize) -> Vec<f64> {
+        self.0.clone()
+    }
+}
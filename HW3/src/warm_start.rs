@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+
+// Individuals to warm-start runs from, as JSON: bitstrings for the GA and real vectors for
+// the ES, each either the genes alone or the genes followed by the member's strategy
+// parameters, such as its sigma. Saved final populations are written best first, so seeding
+// from the front of a list takes the best.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SavedPopulation {
+    #[serde(default)]
+    pub ga: Vec<String>,
+    #[serde(default)]
+    pub es: Vec<Vec<f64>>,
+}
+
+impl SavedPopulation {
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+}
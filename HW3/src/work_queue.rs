@@ -0,0 +1,233 @@
+use crate::bitstring::GAParameters;
+use crate::evol_strat::ESParameters;
+use crate::fitness::Fitness;
+use crate::parameter_tuning::{ParameterGrid, TuningConfig, TuningResult, serialize_sorted};
+use crate::results_analyzer::{ResultsAnalyzer, ResultsWriter};
+use crate::seeds::SeedSequence;
+use crate::timeout_runner::TimeoutRunner;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Every run of one parameter combination, as handed to a tuning worker. It carries all a
+// worker needs, so workers on other machines don't have to regenerate the session's design.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedJob {
+    pub algorithm: String,
+    pub combination: usize,
+    // Number of combinations of the algorithm in the session
+    pub grid_len: usize,
+    #[serde(serialize_with = "serialize_sorted")]
+    pub parameters: HashMap<String, f64>,
+    pub design: String,
+    pub num_runs: usize,
+    pub timeout_seconds: u64,
+    // Jobs queued before targets were recorded use the default target
+    #[serde(default = "default_target_fitness")]
+    pub target_fitness: f64,
+    // Likewise for the success tolerance
+    #[serde(default = "default_success_epsilon")]
+    pub success_epsilon: f64,
+    // Master seed of the session, so run n gets the same seed as in a local session
+    pub seed: u64,
+}
+
+fn default_target_fitness() -> f64 {
+    TuningConfig::default().target_fitness
+}
+
+fn default_success_epsilon() -> f64 {
+    TuningConfig::default().success_epsilon
+}
+
+impl QueuedJob {
+    // File name in every directory of the queue, e.g. sga_0007 for SGA combination 7
+    fn name(&self) -> String {
+        format!("{}_{:04}", self.algorithm.to_lowercase(), self.combination)
+    }
+
+    // SGA combinations first, as in a local session
+    fn order(&self) -> (bool, usize) {
+        (self.algorithm != "SGA", self.combination)
+    }
+
+    pub fn run<F: Fitness + Clone + Send + Sync + 'static>(
+        &self,
+        fitness_fn: F,
+        config: &TuningConfig,
+    ) -> Result<Vec<TuningResult>, Box<dyn Error>> {
+        let parameters = &self.parameters;
+        let unreadable = || format!("{}: incomplete {} parameters", self.name(), self.algorithm);
+        // Placed at its index in a grid of the session's size, so the progress lines number
+        // the combination as a local session would
+        let (combination, grid_len) = (self.combination, self.grid_len.max(self.combination + 1));
+        match self.algorithm.as_str() {
+            "SGA" => {
+                let params = ResultsAnalyzer::key_to_ga_params(parameters).ok_or_else(unreadable)?;
+                let grid = vec![params; grid_len];
+                Ok(TimeoutRunner::run_sga_combination(fitness_fn, &grid, combination, config))
+            }
+            "ES" => {
+                let params = ResultsAnalyzer::key_to_es_params(parameters).ok_or_else(unreadable)?;
+                let grid = vec![params; grid_len];
+                Ok(TimeoutRunner::run_es_combination(fitness_fn, &grid, combination, config))
+            }
+            other => Err(format!("{}: unknown algorithm {other}", self.name()).into()),
+        }
+    }
+}
+
+// How far a queue has got
+#[derive(Debug, Default, Clone, Copy)]
+pub struct QueueStatus {
+    pub pending: usize,
+    pub claimed: usize,
+    pub done: usize,
+}
+
+// A tuning session spread over machines through a shared directory. The coordinator writes
+// one job file per combination into pending/; a worker claims a job by renaming it into
+// claimed/, which only one worker can do, writes its results to results/<job>.csv and then
+// moves the job to done/. Claims of workers that died stay in claimed/ until requeued.
+pub struct WorkQueue {
+    dir: PathBuf,
+}
+
+impl WorkQueue {
+    pub fn new(dir: &str) -> Self {
+        Self { dir: PathBuf::from(dir) }
+    }
+
+    fn subdir(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+
+    // Fills the queue with one job per combination of both grids
+    pub fn create(
+        &self,
+        sga_grid: &[GAParameters],
+        es_grid: &[ESParameters],
+        config: &TuningConfig,
+    ) -> Result<usize, Box<dyn Error>> {
+        for name in ["pending", "claimed", "done", "results"] {
+            fs::create_dir_all(self.subdir(name))?;
+        }
+        let sga = sga_grid.iter().map(ParameterGrid::params_to_map_ga).enumerate();
+        let es = es_grid.iter().map(ParameterGrid::params_to_map_es).enumerate();
+        let sga = sga.map(|(combination, map)| ("SGA", combination, sga_grid.len(), map));
+        let es = es.map(|(combination, map)| ("ES", combination, es_grid.len(), map));
+        let mut count = 0;
+        for (algorithm, combination, grid_len, parameters) in sga.chain(es) {
+            let job = QueuedJob {
+                algorithm: algorithm.to_string(),
+                combination,
+                grid_len,
+                parameters,
+                design: config.design.clone(),
+                num_runs: config.num_runs,
+                timeout_seconds: config.timeout_seconds,
+                target_fitness: config.target_fitness,
+                success_epsilon: config.success_epsilon,
+                seed: config.seeds.seed(),
+            };
+            let path = self.subdir("pending").join(format!("{}.json", job.name()));
+            fs::write(path, serde_json::to_string_pretty(&job)?)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    // Claims the next pending job, or None once nothing is left to claim
+    pub fn claim(&self) -> Result<Option<QueuedJob>, Box<dyn Error>> {
+        for path in sorted_files(&self.subdir("pending"), "json")? {
+            let claimed = self.subdir("claimed").join(path.file_name().unwrap_or_default());
+            // Another worker renamed it first
+            if fs::rename(&path, &claimed).is_err() {
+                continue;
+            }
+            return Ok(Some(serde_json::from_str(&fs::read_to_string(&claimed)?)?));
+        }
+        Ok(None)
+    }
+
+    // Runs a claimed job, writing its results as they finish, and marks it done
+    pub fn complete<F: Fitness + Clone + Send + Sync + 'static>(
+        &self,
+        job: &QueuedJob,
+        fitness_fn: F,
+        num_workers: usize,
+    ) -> Result<Vec<TuningResult>, Box<dyn Error>> {
+        let name = job.name();
+        // Written under a temporary name, so merging never picks up half a job
+        let partial = self.subdir("results").join(format!("{name}.csv.partial"));
+        let config = TuningConfig {
+            num_runs: job.num_runs,
+            timeout_seconds: job.timeout_seconds,
+            target_fitness: job.target_fitness,
+            success_epsilon: job.success_epsilon,
+            num_workers,
+            design: job.design.clone(),
+            seeds: SeedSequence::new(job.seed),
+            results_writer: Some(ResultsWriter::create(&path_str(&partial)?)?.into()),
+            ..TuningConfig::default()
+        };
+        let results = job.run(fitness_fn, &config)?;
+        drop(config);
+        let results_path = self.subdir("results").join(format!("{name}.csv"));
+        ResultsAnalyzer::save_results_to_csv(&results, &path_str(&results_path)?)?;
+        fs::remove_file(&partial)?;
+        let file = format!("{name}.json");
+        fs::rename(self.subdir("claimed").join(&file), self.subdir("done").join(&file))?;
+        Ok(results)
+    }
+
+    // Puts the jobs of workers that died back in pending/, returning how many there were
+    pub fn requeue_claimed(&self) -> Result<usize, Box<dyn Error>> {
+        let claimed = sorted_files(&self.subdir("claimed"), "json")?;
+        for path in &claimed {
+            let pending = self.subdir("pending").join(path.file_name().unwrap_or_default());
+            fs::rename(path, pending)?;
+        }
+        Ok(claimed.len())
+    }
+
+    pub fn status(&self) -> Result<QueueStatus, Box<dyn Error>> {
+        Ok(QueueStatus {
+            pending: sorted_files(&self.subdir("pending"), "json")?.len(),
+            claimed: sorted_files(&self.subdir("claimed"), "json")?.len(),
+            done: sorted_files(&self.subdir("done"), "json")?.len(),
+        })
+    }
+
+    // Results of every finished job, in the order a local session would have produced them
+    pub fn merge(&self) -> Result<Vec<TuningResult>, Box<dyn Error>> {
+        let mut jobs: Vec<QueuedJob> = sorted_files(&self.subdir("done"), "json")?
+            .iter()
+            .map(|path| Ok(serde_json::from_str(&fs::read_to_string(path)?)?))
+            .collect::<Result<_, Box<dyn Error>>>()?;
+        jobs.sort_by_key(QueuedJob::order);
+        let mut results = Vec::new();
+        for job in &jobs {
+            let path = self.subdir("results").join(format!("{}.csv", job.name()));
+            results.extend(ResultsAnalyzer::load_results_from_csv(&path_str(&path)?)?);
+        }
+        Ok(results)
+    }
+}
+
+// Files with the given extension in dir, by name
+fn sorted_files(dir: &Path, extension: &str) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("{}: {e}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == extension))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn path_str(path: &Path) -> Result<String, Box<dyn Error>> {
+    path.to_str().map(str::to_string).ok_or_else(|| format!("{}: not UTF-8", path.display()).into())
+}
@@ -0,0 +1,34 @@
+use crate::neat::{NeatGenome, NetworkObjective};
+
+// Inputs and target output of each case of exclusive or
+pub const XOR_CASES: [([f64; 2], f64); 4] =
+    [([0.0, 0.0], 0.0), ([0.0, 1.0], 1.0), ([1.0, 0.0], 1.0), ([1.0, 1.0], 0.0)];
+
+// Exclusive or, the classic first test of NEAT: it is not linearly separable, so no network
+// without a hidden node solves it and NEAT has to grow one
+#[derive(Clone, Copy)]
+pub struct Xor;
+
+impl NetworkObjective for Xor {
+    fn inputs(&self) -> usize {
+        2
+    }
+
+    fn outputs(&self) -> usize {
+        1
+    }
+
+    // (4 - total absolute error)^2, as in Stanley and Miikkulainen's experiments, so a network
+    // that gets closer on cases it already has right is still rewarded
+    fn fitness(&self, network: &NeatGenome) -> f64 {
+        let error: f64 = XOR_CASES
+            .iter()
+            .map(|(inputs, target)| (network.activate(inputs)[0] - target).abs())
+            .sum();
+        (4.0 - error).powi(2)
+    }
+
+    fn optimum_value(&self) -> Option<f64> {
+        Some(16.0)
+    }
+}